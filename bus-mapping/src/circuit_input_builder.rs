@@ -3,7 +3,9 @@
 
 mod access;
 mod block;
+mod bundle;
 mod call;
+mod divergence;
 mod execution;
 mod input_state_ref;
 #[cfg(test)]
@@ -13,39 +15,45 @@ mod withdrawal;
 
 use self::access::gen_state_access_trace;
 use crate::{
-    error::Error,
+    error::{Error, ExecError},
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
-    operation::{CallContextField, Operation, RWCounter, StartOp, RW},
+    operation::{CallContextField, Operation, RWCounter, StartOp, TxLogField, RW},
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
 pub use access::{Access, AccessSet, AccessValue, CodeSource};
 pub use block::{Block, BlockContext};
+pub use bundle::BlockWitnessBundle;
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
+pub use divergence::{Divergence, DivergenceKind};
 use eth_types::{
-    self, geth_types,
+    self,
+    evm_types::Hardfork,
+    geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Address, GethExecStep, GethExecTrace, ToWord, Word,
+    Address, GethExecStep, GethExecTrace, ToAddress, ToBigEndian, ToWord, Word, H256,
 };
 use ethers_providers::JsonRpcClient;
 pub use execution::{
-    CopyDataType, CopyEvent, CopyStep, ExecState, ExecStep, ExpEvent, ExpStep, NumberOrHash,
+    CopyDataType, CopyEvent, CopyMemoryWords, CopyStep, ExecState, ExecStep, ExpEvent, ExpStep,
+    NumberOrHash,
 };
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 use log::warn;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     ops::Deref,
 };
-pub use transaction::{Transaction, TransactionContext};
+pub use transaction::{TraceSummary, Transaction, TransactionContext};
 pub use withdrawal::{Withdrawal, WithdrawalContext};
 
 /// Runtime Config
 ///
 /// Default to mainnet block
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FeatureConfig {
     /// Zero difficulty
     pub zero_difficulty: bool,
@@ -59,6 +67,9 @@ pub struct FeatureConfig {
     /// shouldn't be included in a mainnet block. However, rollup developers might want to
     /// include invalid tx in the L2 block to support forced exit feature.
     pub invalid_tx: bool,
+    /// Hardfork whose rules are used for opcode availability, the access list
+    /// and gas costs
+    pub hardfork: Hardfork,
 }
 
 impl Default for FeatureConfig {
@@ -68,6 +79,7 @@ impl Default for FeatureConfig {
             free_first_tx: false,
             enable_eip1559: true,
             invalid_tx: false,
+            hardfork: Hardfork::Shanghai,
         }
     }
 }
@@ -75,12 +87,16 @@ impl Default for FeatureConfig {
 impl FeatureConfig {
     /// Check if we are mainnet config
     pub fn is_mainnet(&self) -> bool {
-        self.zero_difficulty && !self.free_first_tx && self.enable_eip1559 && !self.invalid_tx
+        self.zero_difficulty
+            && !self.free_first_tx
+            && self.enable_eip1559
+            && !self.invalid_tx
+            && self.hardfork == Hardfork::Shanghai
     }
 }
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FixedCParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// nummber of rows). This must be at least the number of rw operations
@@ -170,7 +186,7 @@ impl Default for FixedCParams {
 ///
 /// The generated bus-mapping operations are:
 /// [`StackOp`](crate::operation::StackOp)s,
-/// [`MemoryOp`](crate::operation::MemoryOp)s and
+/// [`MemoryWordOp`](crate::operation::MemoryWordOp)s and
 /// [`StorageOp`](crate::operation::StorageOp), which correspond to each
 /// [`OpcodeId`](crate::evm::OpcodeId)s used in each `ExecTrace` step so that
 /// the State Proof witnesses are already generated on a structured manner and
@@ -189,6 +205,10 @@ pub struct CircuitInputBuilder<C: CircuitsParams> {
     pub block_ctx: BlockContext,
     /// Feature config
     pub feature_config: FeatureConfig,
+    /// Compare the post-state of each step, as predicted by the generated
+    /// operations, with the next step of the geth trace, and fail with
+    /// [`Error::TraceDivergence`] at the first difference.
+    pub check_trace_divergence: bool,
 }
 
 impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
@@ -208,6 +228,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             circuits_params: params,
             block_ctx: BlockContext::new(),
             feature_config,
+            check_trace_divergence: false,
         }
     }
 
@@ -227,6 +248,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             tx,
             tx_ctx,
             max_rws: self.circuits_params.max_rws(),
+            feature_config: self.feature_config,
         }
     }
 
@@ -281,6 +303,61 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
         }
     }
 
+    /// Check that the logs written by each transaction of the handled block
+    /// are the logs of its receipt.
+    pub fn check_receipt_logs(
+        &self,
+        receipts: &[eth_types::TransactionReceipt],
+    ) -> Result<(), Error> {
+        if receipts.len() != self.block.eth_block.transactions.len() {
+            return Err(Error::InternalError("missing receipt of transaction"));
+        }
+        for (idx, (tx, receipt)) in self
+            .block
+            .eth_block
+            .transactions
+            .iter()
+            .zip(receipts)
+            .enumerate()
+        {
+            // Transaction and log ids start from 1
+            let mut logs: Vec<(Address, Vec<H256>, Vec<u8>)> = Vec::new();
+            for op in self.block.container.tx_log.iter() {
+                let op = op.op();
+                if op.tx_id != idx + 1 {
+                    continue;
+                }
+                if logs.len() < op.log_id {
+                    logs.resize(op.log_id, Default::default());
+                }
+                let log = &mut logs[op.log_id - 1];
+                match op.field {
+                    TxLogField::Address => log.0 = op.value.to_address(),
+                    TxLogField::Topic => {
+                        if log.1.len() <= op.index {
+                            log.1.resize(op.index + 1, H256::zero());
+                        }
+                        log.1[op.index] = H256(op.value.to_be_bytes());
+                    }
+                    TxLogField::Data => {
+                        if log.2.len() <= op.index {
+                            log.2.resize(op.index + 1, 0);
+                        }
+                        log.2[op.index] = op.value.as_u64() as u8;
+                    }
+                }
+            }
+            let receipt_logs = receipt
+                .logs
+                .iter()
+                .map(|log| (log.address, log.topics.clone(), log.data.to_vec()));
+            if !logs.into_iter().eq(receipt_logs) {
+                return Err(Error::ReceiptMismatch(tx.hash, "logs"));
+            }
+        }
+        Ok(())
+    }
+
     /// Handle a transaction with its corresponding execution trace to generate
     /// all the associated operations.  Each operation is registered in
     /// `self.block.container`, and each step stores the
@@ -293,28 +370,169 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
         is_last_tx: bool,
         tx_index: u64,
     ) -> Result<(), Error> {
-        let mut tx = self.new_tx(tx_index, eth_tx, !geth_trace.failed)?;
-        let mut tx_ctx = TransactionContext::new(eth_tx, geth_trace, is_last_tx)?;
+        let summary = TraceSummary::from_trace(geth_trace)?;
+        let (mut tx, mut tx_ctx) = self.begin_tx(eth_tx, &summary, is_last_tx, tx_index)?;
+        if !summary.invalid {
+            for index in 0..geth_trace.struct_logs.len() {
+                self.handle_geth_step(
+                    &mut tx,
+                    &mut tx_ctx,
+                    index,
+                    &geth_trace.struct_logs[index..],
+                )?;
+            }
+        }
+        self.end_tx(tx, tx_ctx, summary.invalid)
+    }
+
+    /// Handle a transaction like [`Self::handle_tx`], with its steps fed one at
+    /// a time, e.g. from a streaming deserializer or a tracer callback.  The
+    /// steps are read once and only the current and the next step are kept in
+    /// memory, so the `is_success` of the calls whose callee executes code is
+    /// taken from the call trace of the summary, see
+    /// [`TraceSummary::with_call_trace`].
+    fn handle_tx_stream(
+        &mut self,
+        eth_tx: &eth_types::Transaction,
+        mut summary: TraceSummary,
+        geth_steps: impl IntoIterator<Item = GethExecStep>,
+        is_last_tx: bool,
+        tx_index: u64,
+    ) -> Result<(), Error> {
+        let (mut tx, mut tx_ctx) = self.begin_tx(eth_tx, &summary, is_last_tx, tx_index)?;
+        if !summary.invalid {
+            let mut window = StepWindow::new(geth_steps.into_iter());
+            let mut index = 0;
+            while !window.is_empty() {
+                let geth_steps = window.steps();
+                summary.memory_enabled |= geth_steps.iter().any(|step| !step.memory.is_empty());
+                tx_ctx.memory_enabled = summary.memory_enabled;
+                self.push_stream_call_is_success(&mut tx, &mut tx_ctx, &mut summary, geth_steps)?;
+                self.handle_geth_step(&mut tx, &mut tx_ctx, index, geth_steps)?;
+                window.advance();
+                index += 1;
+            }
+        }
+        self.end_tx(tx, tx_ctx, summary.invalid)
+    }
+
+    /// Push the `is_success` of the call made by the first step in
+    /// `geth_steps`, if it makes one, before the steps of its callee are read:
+    /// it's at the top of the stack of the next step when the callee doesn't
+    /// execute code, and in the call trace of the summary otherwise.
+    fn push_stream_call_is_success(
+        &mut self,
+        tx: &mut Transaction,
+        tx_ctx: &mut TransactionContext,
+        summary: &mut TraceSummary,
+        geth_steps: &[GethExecStep],
+    ) -> Result<(), Error> {
+        let (step, next_step) = match geth_steps {
+            [step, next_step, ..] if CallKind::try_from(step.op).is_ok() => (step, next_step),
+            _ => return Ok(()),
+        };
+        let is_success = if next_step.depth == step.depth + 1 {
+            summary.next_call_trace_is_success()?
+        } else if next_step.depth == step.depth {
+            let is_success = !next_step.stack.last()?.is_zero();
+            // The calls failing their precheck aren't in the call trace
+            let is_traced = is_success
+                || !matches!(
+                    self.state_ref(tx, tx_ctx)
+                        .get_step_err(step, Some(next_step))?,
+                    Some(
+                        ExecError::Depth(_)
+                            | ExecError::InsufficientBalance(_)
+                            | ExecError::NonceUintOverflow(_)
+                            | ExecError::ContractAddressCollision
+                    )
+                );
+            if is_traced && summary.next_call_trace_is_success()? != is_success {
+                return Err(Error::InvalidGethExecTrace(
+                    "call trace doesn't match the steps",
+                ));
+            }
+            is_success
+        } else {
+            // The call step fails without making the call
+            return Ok(());
+        };
+        tx_ctx.call_is_success.push(is_success);
+        Ok(())
+    }
+
+    /// Create the transaction and its context, and generate the BeginTx step of
+    /// a valid transaction.
+    fn begin_tx(
+        &mut self,
+        eth_tx: &eth_types::Transaction,
+        summary: &TraceSummary,
+        is_last_tx: bool,
+        tx_index: u64,
+    ) -> Result<(Transaction, TransactionContext), Error> {
+        let mut tx = self.new_tx(tx_index, eth_tx, !summary.failed)?;
+        let mut tx_ctx = TransactionContext::from_summary(eth_tx, summary, is_last_tx)?;
 
-        if !geth_trace.invalid {
+        if !summary.invalid {
             // Generate BeginTx step
             let begin_tx_step = gen_associated_steps(
                 &mut self.state_ref(&mut tx, &mut tx_ctx),
                 ExecState::BeginTx,
             )?;
             tx.steps_mut().push(begin_tx_step);
+        }
+        Ok((tx, tx_ctx))
+    }
 
-            for (index, geth_step) in geth_trace.struct_logs.iter().enumerate() {
-                let mut state_ref = self.state_ref(&mut tx, &mut tx_ctx);
-                log::trace!("handle {}th opcode {:?} ", index, geth_step.op);
-                let exec_steps = gen_associated_ops(
-                    &geth_step.op,
-                    &mut state_ref,
-                    &geth_trace.struct_logs[index..],
-                )?;
-                tx.steps_mut().extend(exec_steps);
+    /// Generate the execution steps of the first step in `geth_steps`, which
+    /// must be followed by the next step of the trace if there is one.
+    /// `index` is the position of the step in the trace.
+    fn handle_geth_step(
+        &mut self,
+        tx: &mut Transaction,
+        tx_ctx: &mut TransactionContext,
+        index: usize,
+        geth_steps: &[GethExecStep],
+    ) -> Result<(), Error> {
+        let mut state_ref = self.state_ref(tx, tx_ctx);
+        log::trace!(
+            "handle opcode {:?} at pc {}",
+            geth_steps[0].op,
+            geth_steps[0].pc
+        );
+        let exec_steps = gen_associated_ops(&geth_steps[0].op, &mut state_ref, geth_steps)?;
+        // The call stack may be empty after the last step of the trace
+        if self.check_trace_divergence && geth_steps.len() > 1 {
+            let call_id = tx.calls()[tx_ctx.call_index()?].call_id;
+            if let Some(kind) = divergence::check_step(
+                &self.block.container,
+                tx_ctx,
+                call_id,
+                geth_steps,
+                &exec_steps,
+            ) {
+                return Err(Error::TraceDivergence(Box::new(Divergence {
+                    tx_id: tx_ctx.id(),
+                    step_index: index,
+                    pc: geth_steps[0].pc,
+                    opcode: geth_steps[0].op,
+                    kind,
+                })));
             }
+        }
+        tx.steps_mut().extend(exec_steps);
+        Ok(())
+    }
 
+    /// Generate the EndTx step of a valid transaction, or the InvalidTx step of
+    /// an invalid one, and add the transaction to the block.
+    fn end_tx(
+        &mut self,
+        mut tx: Transaction,
+        mut tx_ctx: TransactionContext,
+        invalid: bool,
+    ) -> Result<(), Error> {
+        if !invalid {
             // Generate EndTx step
             let end_tx_step =
                 gen_associated_steps(&mut self.state_ref(&mut tx, &mut tx_ctx), ExecState::EndTx)?;
@@ -337,6 +555,35 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
     }
 }
 
+/// The current and the next step of a streamed trace, the only steps kept in
+/// memory while handling it.
+struct StepWindow<I: Iterator<Item = GethExecStep>> {
+    steps: I,
+    window: VecDeque<GethExecStep>,
+}
+
+impl<I: Iterator<Item = GethExecStep>> StepWindow<I> {
+    fn new(mut steps: I) -> Self {
+        let window = steps.by_ref().take(2).collect();
+        Self { steps, window }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// The current step followed by the next one if there is one.
+    fn steps(&mut self) -> &[GethExecStep] {
+        self.window.make_contiguous()
+    }
+
+    /// Move to the next step, reading the one after it.
+    fn advance(&mut self) {
+        self.window.pop_front();
+        self.window.extend(self.steps.next());
+    }
+}
+
 impl CircuitInputBuilder<FixedCParams> {
     /// Handle a block by handling each transaction to generate all the
     /// associated operations.
@@ -351,6 +598,20 @@ impl CircuitInputBuilder<FixedCParams> {
         Ok(self)
     }
 
+    /// Handle a block like [`Self::handle_block`], with the trace of each
+    /// transaction given as a [`TraceSummary::with_call_trace`] of its flags and
+    /// call trace, and a stream of its steps, read in one pass, so that memory
+    /// use doesn't grow with the length of the transactions.
+    pub fn handle_block_stream<S: IntoIterator<Item = GethExecStep>>(
+        &mut self,
+        eth_block: &EthBlock,
+        tx_traces: impl IntoIterator<Item = (TraceSummary, S)>,
+    ) -> Result<&CircuitInputBuilder<FixedCParams>, Error> {
+        self.begin_handle_block_stream(eth_block, tx_traces)?;
+        self.set_end_block(self.circuits_params.max_rws)?;
+        Ok(self)
+    }
+
     fn set_end_block(&mut self, max_rws: usize) -> Result<(), Error> {
         let mut end_block_not_last = self.block.block_steps.end_block_not_last.clone();
         let mut end_block_last = self.block.block_steps.end_block_last.clone();
@@ -428,10 +689,43 @@ impl<C: CircuitsParams> CircuitInputBuilder<C> {
                 tx_id as u64,
             )?;
         }
+        self.finish_block_txs(eth_block);
+        Ok(())
+    }
+
+    /// First part of handle_block_stream, the streaming counterpart of
+    /// [`Self::begin_handle_block`]. Each transaction comes with the summary of
+    /// its trace, completed from the steps of its trace as they are yielded.
+    /// The call trace of the summary gives the `is_success` of the calls
+    /// executing code before their steps are read.
+    pub fn begin_handle_block_stream<S: IntoIterator<Item = GethExecStep>>(
+        &mut self,
+        eth_block: &EthBlock,
+        tx_traces: impl IntoIterator<Item = (TraceSummary, S)>,
+    ) -> Result<(), Error> {
+        let mut tx_traces = tx_traces.into_iter();
+        for (idx, tx) in eth_block.transactions.iter().enumerate() {
+            let (summary, geth_steps) = tx_traces
+                .next()
+                .ok_or(Error::InternalError("missing trace of transaction"))?;
+            // Transaction index starts from 1
+            let tx_id = idx + 1;
+            self.handle_tx_stream(
+                tx,
+                summary,
+                geth_steps,
+                tx_id == eth_block.transactions.len(),
+                tx_id as u64,
+            )?;
+        }
+        self.finish_block_txs(eth_block);
+        Ok(())
+    }
+
+    fn finish_block_txs(&mut self, eth_block: &EthBlock) {
         // set eth_block
         self.block.eth_block = eth_block.clone();
         self.set_value_ops_call_context_rwc_eor();
-        Ok(())
     }
 }
 impl CircuitInputBuilder<DynamicCParams> {
@@ -444,7 +738,28 @@ impl CircuitInputBuilder<DynamicCParams> {
         geth_traces: &[eth_types::GethExecTrace],
     ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
         self.begin_handle_block(eth_block, geth_traces)?;
+        self.end_handle_block(eth_block)
+    }
+
+    /// Handle a block like [`Self::handle_block`], with the trace of each
+    /// transaction given as a [`TraceSummary::with_call_trace`] of its flags and
+    /// call trace, and a stream of its steps, read in one pass, so that memory
+    /// use doesn't grow with the length of the transactions.
+    pub fn handle_block_stream<S: IntoIterator<Item = GethExecStep>>(
+        mut self,
+        eth_block: &EthBlock,
+        tx_traces: impl IntoIterator<Item = (TraceSummary, S)>,
+    ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
+        self.begin_handle_block_stream(eth_block, tx_traces)?;
+        self.end_handle_block(eth_block)
+    }
 
+    /// Derive the optimal circuit parameters from the handled block and set its
+    /// end block steps.
+    fn end_handle_block(
+        self,
+        eth_block: &EthBlock,
+    ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
         // Compute subcircuits parameters
         let c_params = {
             let max_txs = eth_block.transactions.len();
@@ -468,7 +783,7 @@ impl CircuitInputBuilder<DynamicCParams> {
                 .block
                 .copy_events
                 .iter()
-                .fold(0, |acc, c| acc + c.bytes.len())
+                .fold(0, |acc, c| acc + c.steps().len())
                 * 2
                 + 4; // disabled and unused rows.
 
@@ -506,6 +821,7 @@ impl CircuitInputBuilder<DynamicCParams> {
             circuits_params: c_params,
             block_ctx: self.block_ctx,
             feature_config: self.feature_config,
+            check_trace_divergence: self.check_trace_divergence,
         };
 
         cib.set_end_block(c_params.max_rws)?;
@@ -642,6 +958,92 @@ pub fn get_state_accesses(
     Ok(AccessSet::from(block_access_trace))
 }
 
+/// Re-execute a block with the in-repo tracer from the pre-state of its
+/// transactions reported by geth's `prestateTracer`, with the rules of
+/// `hardfork`, to obtain its TxExecTraces.  The status and the gas used of
+/// each re-executed transaction are checked against its receipt, the logs are
+/// checked once the block is handled with
+/// [`CircuitInputBuilder::check_receipt_logs`].
+#[cfg(feature = "prestate")]
+pub fn trace_from_prestate(
+    chain_id: Word,
+    eth_block: &EthBlock,
+    prestates: &[eth_types::GethPrestateTrace],
+    receipts: &[eth_types::TransactionReceipt],
+    history_hashes: Vec<Word>,
+    hardfork: Hardfork,
+) -> Result<Vec<eth_types::GethExecTrace>, Error> {
+    if receipts.len() != eth_block.transactions.len() {
+        return Err(Error::InternalError("missing receipt of transaction"));
+    }
+
+    // The earliest pre-state reported for an account or a storage slot is its
+    // state before the block.
+    let mut accounts: HashMap<Address, geth_types::Account> = HashMap::new();
+    for prestate in prestates {
+        for (address, prestate_account) in prestate {
+            let account = accounts
+                .entry(*address)
+                .or_insert_with(|| geth_types::Account {
+                    address: *address,
+                    nonce: prestate_account.nonce.into(),
+                    balance: prestate_account.balance,
+                    code: prestate_account.code.clone(),
+                    storage: HashMap::new(),
+                });
+            for (key, value) in prestate_account.storage.iter() {
+                account
+                    .storage
+                    .entry(key.to_word())
+                    .or_insert_with(|| value.to_word());
+            }
+        }
+    }
+
+    let trace_config = external_tracer::TraceConfig {
+        chain_id,
+        history_hashes,
+        block_constants: geth_types::BlockConstants::try_from(eth_block)?,
+        accounts,
+        transactions: eth_block
+            .transactions
+            .iter()
+            .map(geth_types::Transaction::from)
+            .collect(),
+        withdrawals: eth_block
+            .withdrawals
+            .iter()
+            .flatten()
+            .map(|withdrawal| geth_types::Withdrawal {
+                id: withdrawal.index.as_u64(),
+                validator_id: withdrawal.validator_index.as_u64(),
+                address: withdrawal.address,
+                amount: withdrawal.amount.as_u64(),
+            })
+            .collect(),
+        logger_config: external_tracer::LoggerConfig::default(),
+        hardfork,
+    };
+    let geth_traces = external_tracer::trace(&trace_config)?;
+
+    // The gas used reported in the traces is after refunds, like in the
+    // receipts.
+    for ((tx, geth_trace), receipt) in eth_block
+        .transactions
+        .iter()
+        .zip(geth_traces.iter())
+        .zip(receipts)
+    {
+        if geth_trace.invalid || receipt.status != Some((!geth_trace.failed as u64).into()) {
+            return Err(Error::ReceiptMismatch(tx.hash, "status"));
+        }
+        if receipt.gas_used != Some(geth_trace.gas.into()) {
+            return Err(Error::ReceiptMismatch(tx.hash, "gas used"));
+        }
+    }
+    Ok(geth_traces)
+}
+
 /// Build a partial StateDB from step 3
 pub fn build_state_code_db(
     proofs: Vec<eth_types::EIP1186ProofResponse>,
@@ -701,7 +1103,65 @@ impl<P: JsonRpcClient> BuilderClient<P> {
     ) -> Result<(EthBlock, Vec<eth_types::GethExecTrace>, Vec<Word>, Word), Error> {
         let eth_block = self.cli.get_block_by_number(block_num.into()).await?;
         let geth_traces = self.cli.trace_block_by_number(block_num.into()).await?;
+        let (history_hashes, prev_state_root) =
+            self.get_block_history(&eth_block, block_num).await?;
+
+        Ok((eth_block, geth_traces, history_hashes, prev_state_root))
+    }
+
+    /// Step 1 for nodes that don't expose struct logs. Query geth for Block,
+    /// the `prestateTracer` output and the receipts of its Txs, history block
+    /// hashes and previous state root, and obtain the TxExecTraces by
+    /// re-executing the block locally from its pre-state.
+    #[cfg(feature = "prestate")]
+    pub async fn get_block_from_prestate(
+        &self,
+        block_num: u64,
+    ) -> Result<
+        (
+            EthBlock,
+            Vec<eth_types::GethExecTrace>,
+            Vec<eth_types::TransactionReceipt>,
+            Vec<Word>,
+            Word,
+        ),
+        Error,
+    > {
+        let eth_block = self.cli.get_block_by_number(block_num.into()).await?;
+        let prestates = self
+            .cli
+            .trace_block_prestate_by_number(block_num.into())
+            .await?;
+        let mut receipts = Vec::with_capacity(eth_block.transactions.len());
+        for tx in eth_block.transactions.iter() {
+            receipts.push(self.cli.get_transaction_receipt(tx.hash).await?);
+        }
+        let (history_hashes, prev_state_root) =
+            self.get_block_history(&eth_block, block_num).await?;
+        let geth_traces = trace_from_prestate(
+            self.chain_id,
+            &eth_block,
+            &prestates,
+            &receipts,
+            history_hashes.clone(),
+            self.feature_config.hardfork,
+        )?;
+
+        Ok((
+            eth_block,
+            geth_traces,
+            receipts,
+            history_hashes,
+            prev_state_root,
+        ))
+    }
 
+    /// Query geth for the history block hashes and the previous state root.
+    async fn get_block_history(
+        &self,
+        eth_block: &EthBlock,
+        block_num: u64,
+    ) -> Result<(Vec<Word>, Word), Error> {
         // fetch up to 256 blocks
         let mut n_blocks = std::cmp::min(256, block_num as usize);
         let mut next_hash = eth_block.parent_hash;
@@ -729,12 +1189,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             next_hash = header.parent_hash;
         }
 
-        Ok((
-            eth_block,
-            geth_traces,
-            history_hashes,
-            prev_state_root.unwrap_or_default(),
-        ))
+        Ok((history_hashes, prev_state_root.unwrap_or_default()))
     }
 
     /// Step 2. Get State Accesses from TxExecTraces
@@ -812,6 +1267,80 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         Ok(builder)
     }
 
+    /// Check the post-state of the accounts and storage slots in `access_set`,
+    /// as left in `sdb` by handling the block, against the state of the chain
+    /// after the block.
+    pub async fn check_post_state(
+        &self,
+        block_num: u64,
+        eth_block: &EthBlock,
+        sdb: &StateDB,
+        access_set: AccessSet,
+    ) -> Result<(), Error> {
+        // Withdrawals are not applied by the CircuitInputBuilder.
+        let mut withdrawn: HashMap<Address, Word> = HashMap::new();
+        for withdrawal in eth_block.withdrawals.iter().flatten() {
+            // The amount is in Gwei.
+            *withdrawn.entry(withdrawal.address).or_default() +=
+                Word::from(withdrawal.amount.as_u64()) * Word::from(1_000_000_000u64);
+        }
+
+        for (address, key_set) in access_set.state {
+            let mut keys: Vec<Word> = key_set.iter().cloned().collect();
+            keys.sort();
+            let proof = self.cli.get_proof(address, keys, block_num.into()).await?;
+            let (_, account) = sdb.get_account(&address);
+            let balance = account.balance + withdrawn.get(&address).copied().unwrap_or_default();
+            if account.nonce != proof.nonce.as_u64() {
+                return Err(Error::PostStateMismatch(address, "nonce"));
+            }
+            if balance != proof.balance {
+                return Err(Error::PostStateMismatch(address, "balance"));
+            }
+            // Nodes differ in the code hash they report for missing accounts.
+            if !account.is_empty() && account.code_hash != proof.code_hash {
+                return Err(Error::PostStateMismatch(address, "code hash"));
+            }
+            for storage_proof in proof.storage_proof {
+                let (_, value) = sdb.get_storage(&address, &storage_proof.key);
+                if *value != storage_proof.value {
+                    return Err(Error::PostStateMismatch(address, "storage"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Perform steps 1 to 3 and collect their results into a bundle, which
+    /// can be saved and replayed later without a node.
+    pub async fn gen_witness_bundle(&self, block_num: u64) -> Result<BlockWitnessBundle, Error> {
+        let (eth_block, geth_traces, history_hashes, prev_state_root) =
+            self.get_block(block_num).await?;
+        let access_set = Self::get_state_accesses(&eth_block, &geth_traces)?;
+        let (proofs, codes) = self.get_state(block_num, access_set).await?;
+        Ok(BlockWitnessBundle {
+            chain_id: self.chain_id,
+            block: eth_block,
+            traces: geth_traces,
+            history_hashes,
+            prev_state_root,
+            proofs,
+            codes: codes
+                .into_iter()
+                .map(|(address, code)| (address, code.into()))
+                .collect(),
+        })
+    }
+
+    /// Perform steps 4 and 5 from a bundle, with the parameters of this
+    /// client.
+    pub fn gen_inputs_from_bundle(
+        &self,
+        bundle: &BlockWitnessBundle,
+    ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
+        bundle.gen_inputs(self.circuits_params, self.feature_config)
+    }
+
     /// Perform all the steps to generate the circuit inputs
     pub async fn gen_inputs(
         &self,
@@ -823,19 +1352,41 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         ),
         Error,
     > {
-        let (eth_block, geth_traces, history_hashes, prev_state_root) =
-            self.get_block(block_num).await?;
+        let bundle = self.gen_witness_bundle(block_num).await?;
+        let builder = self.gen_inputs_from_bundle(&bundle)?;
+        Ok((builder, bundle.block))
+    }
+
+    /// Perform all the steps to generate the circuit inputs from the
+    /// `prestateTracer` output of the block instead of its struct logs, and
+    /// check the resulting receipts and post-state against the chain.
+    #[cfg(feature = "prestate")]
+    pub async fn gen_inputs_from_prestate(
+        &self,
+        block_num: u64,
+    ) -> Result<
+        (
+            CircuitInputBuilder<FixedCParams>,
+            eth_types::Block<eth_types::Transaction>,
+        ),
+        Error,
+    > {
+        let (eth_block, geth_traces, receipts, history_hashes, prev_state_root) =
+            self.get_block_from_prestate(block_num).await?;
         let access_set = Self::get_state_accesses(&eth_block, &geth_traces)?;
-        let (proofs, codes) = self.get_state(block_num, access_set).await?;
-        let (state_db, code_db) = Self::build_state_code_db(proofs, codes);
+        let (proofs, codes) = self.get_state(block_num, access_set.clone()).await?;
+        let (sdb, code_db) = Self::build_state_code_db(proofs, codes);
         let builder = self.gen_inputs_from_state(
-            state_db,
+            sdb,
             code_db,
             &eth_block,
             &geth_traces,
             history_hashes,
             prev_state_root,
         )?;
+        builder.check_receipt_logs(&receipts)?;
+        self.check_post_state(block_num, &eth_block, &builder.sdb, access_set)
+            .await?;
         Ok((builder, eth_block))
     }
 }
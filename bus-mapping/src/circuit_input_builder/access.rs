@@ -57,7 +57,7 @@ fn get_call_result(trace: &[GethExecStep]) -> Option<Word> {
 }
 
 /// State and Code Access set.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AccessSet {
     /// Set of accounts
     pub state: HashMap<Address, HashSet<Word>>,
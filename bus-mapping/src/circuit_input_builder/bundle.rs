@@ -0,0 +1,116 @@
+//! Offline bundle of the node data needed to build the circuit inputs of a
+//! block.
+
+use super::{
+    build_state_code_db, Block, CircuitInputBuilder, DynamicCParams, EthBlock, FeatureConfig,
+    FixedCParams,
+};
+use crate::{
+    error::Error,
+    state_db::{CodeDB, StateDB},
+};
+use eth_types::{Address, Bytes, EIP1186ProofResponse, GethExecTrace, Word};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// Everything [`BuilderClient`](super::BuilderClient) fetches from the node
+/// to build the circuit inputs of a block: the block with its traces, the
+/// history hashes, the previous state root, and the `eth_getProof` responses
+/// and codes of all the state accesses.
+///
+/// A bundle can be archived and replayed later without a node, which is
+/// useful to rerun problematic blocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockWitnessBundle {
+    /// Chain id
+    pub chain_id: Word,
+    /// The block with its transactions
+    pub block: EthBlock,
+    /// Execution trace of each transaction of the block
+    pub traces: Vec<GethExecTrace>,
+    /// Hashes of up to 256 previous blocks, the latest one last
+    pub history_hashes: Vec<Word>,
+    /// State root of the parent block
+    pub prev_state_root: Word,
+    /// Proofs of the accounts and storage slots accessed by the block
+    pub proofs: Vec<EIP1186ProofResponse>,
+    /// Code of the accounts accessed by the block
+    pub codes: HashMap<Address, Bytes>,
+}
+
+impl BlockWitnessBundle {
+    /// Read a JSON encoded bundle.
+    pub fn read<R: Read>(reader: R) -> Result<Self, Error> {
+        serde_json::from_reader(reader).map_err(Error::SerdeError)
+    }
+
+    /// Write the bundle JSON encoded.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        serde_json::to_writer(writer, self).map_err(Error::SerdeError)
+    }
+
+    /// Load a bundle from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = File::open(path).map_err(Error::IoError)?;
+        Self::read(BufReader::new(file))
+    }
+
+    /// Save the bundle to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let file = File::create(path).map_err(Error::IoError)?;
+        let mut writer = BufWriter::new(file);
+        self.write(&mut writer)?;
+        writer.flush().map_err(Error::IoError)
+    }
+
+    /// Build the partial StateDB and the CodeDB of the block.
+    pub fn state_code_db(&self) -> (StateDB, CodeDB) {
+        let codes = self
+            .codes
+            .iter()
+            .map(|(address, code)| (*address, code.to_vec()))
+            .collect();
+        build_state_code_db(self.proofs.clone(), codes)
+    }
+
+    fn circuit_input_block(&self) -> Result<Block, Error> {
+        Block::new(
+            self.chain_id,
+            self.history_hashes.clone(),
+            self.prev_state_root,
+            &self.block,
+        )
+    }
+
+    /// Generate the circuit inputs of the block with the given circuit
+    /// parameters.
+    pub fn gen_inputs(
+        &self,
+        circuits_params: FixedCParams,
+        feature_config: FeatureConfig,
+    ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
+        let (sdb, code_db) = self.state_code_db();
+        let block = self.circuit_input_block()?;
+        let mut builder =
+            CircuitInputBuilder::new(sdb, code_db, block, circuits_params, feature_config);
+        builder.handle_block(&self.block, &self.traces)?;
+        Ok(builder)
+    }
+
+    /// Generate the circuit inputs of the block with the smallest circuit
+    /// parameters fitting it.
+    pub fn gen_inputs_dynamic(
+        &self,
+        feature_config: FeatureConfig,
+    ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
+        let (sdb, code_db) = self.state_code_db();
+        let block = self.circuit_input_block()?;
+        CircuitInputBuilder::new(sdb, code_db, block, DynamicCParams {}, feature_config)
+            .handle_block(&self.block, &self.traces)
+    }
+}
@@ -138,6 +138,9 @@ pub struct CallContext {
     pub memory: Memory,
     /// return data buffer
     pub return_data: Vec<u8>,
+    /// memory of the last callee when it returned, which holds the return
+    /// data buffer
+    pub last_callee_memory: Memory,
 }
 
 impl CallContext {
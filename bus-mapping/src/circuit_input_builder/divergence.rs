@@ -0,0 +1,480 @@
+//! Comparison of the post-state predicted by the operations generated for a
+//! step with the next step of the geth trace.
+
+use super::{ExecStep, TransactionContext};
+use crate::operation::{OperationContainer, Target, RW};
+use eth_types::{
+    evm_types::{
+        gas_utils::{memory_copier_gas_cost, memory_expansion_gas_cost},
+        GasCost, OpcodeId, StackAddress,
+    },
+    GethExecStep, Word,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+/// Part of the post-state of a step in which bus-mapping and geth disagree.
+/// Each variant holds the value found in the geth trace and the value
+/// predicted by bus-mapping.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// Call depth
+    Depth {
+        /// Depth of the next geth step
+        geth: usize,
+        /// Depth of the bus-mapping call stack
+        bus_mapping: usize,
+    },
+    /// Gas left
+    GasLeft {
+        /// Gas left at the next geth step
+        geth: u64,
+        /// Gas left of the step minus the gas cost computed from its opcode,
+        /// its stack inputs and the memory expansion of the call context
+        bus_mapping: u64,
+    },
+    /// Stack pointer
+    StackPointer {
+        /// Stack pointer of the next geth step
+        geth: StackAddress,
+        /// Stack pointer after the generated stack operations
+        bus_mapping: StackAddress,
+    },
+    /// Value written to the stack
+    StackValue {
+        /// Stack address of the value
+        address: StackAddress,
+        /// Value in the next geth step
+        geth: Word,
+        /// Value of the generated stack write
+        bus_mapping: Word,
+    },
+    /// Memory size in bytes
+    MemorySize {
+        /// Memory size of the next geth step
+        geth: usize,
+        /// Memory size of the bus-mapping call context
+        bus_mapping: usize,
+    },
+    /// Value written to storage
+    Storage {
+        /// Storage key
+        key: Word,
+        /// Value in the next geth step
+        geth: Word,
+        /// Value of the generated storage write
+        bus_mapping: Word,
+    },
+}
+
+/// First step of a transaction whose generated operations do not lead to the
+/// post-state found in the geth trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Transaction id, which is the transaction index plus one
+    pub tx_id: usize,
+    /// Index of the step in the struct logs of the transaction
+    pub step_index: usize,
+    /// Program counter of the step
+    pub pc: u64,
+    /// Opcode of the step
+    pub opcode: OpcodeId,
+    /// Part of the post-state that differs
+    pub kind: DivergenceKind,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "tx {} step {} ({:?} at pc {}): ",
+            self.tx_id, self.step_index, self.opcode, self.pc
+        )?;
+        match &self.kind {
+            DivergenceKind::Depth { geth, bus_mapping } => {
+                write!(
+                    f,
+                    "depth is {} in geth, {} in bus-mapping",
+                    geth, bus_mapping
+                )
+            }
+            DivergenceKind::GasLeft { geth, bus_mapping } => {
+                write!(
+                    f,
+                    "gas left is {} in geth, {} in bus-mapping",
+                    geth, bus_mapping
+                )
+            }
+            DivergenceKind::StackPointer { geth, bus_mapping } => write!(
+                f,
+                "stack pointer is {:?} in geth, {:?} in bus-mapping",
+                geth, bus_mapping
+            ),
+            DivergenceKind::StackValue {
+                address,
+                geth,
+                bus_mapping,
+            } => write!(
+                f,
+                "stack {:?} is {:#x} in geth, {:#x} in bus-mapping",
+                address, geth, bus_mapping
+            ),
+            DivergenceKind::MemorySize { geth, bus_mapping } => write!(
+                f,
+                "memory size is {} in geth, {} in bus-mapping",
+                geth, bus_mapping
+            ),
+            DivergenceKind::Storage {
+                key,
+                geth,
+                bus_mapping,
+            } => write!(
+                f,
+                "storage {:#x} is {:#x} in geth, {:#x} in bus-mapping",
+                key, geth, bus_mapping
+            ),
+        }
+    }
+}
+
+/// Compare the state after `exec_steps`, generated for `geth_steps[0]`, with
+/// `geth_steps[1]`.  The depth is always compared; the rest of the state only
+/// when the next step runs in the same call, with `call_id`.
+pub(crate) fn check_step(
+    container: &OperationContainer,
+    tx_ctx: &TransactionContext,
+    call_id: usize,
+    geth_steps: &[GethExecStep],
+    exec_steps: &[ExecStep],
+) -> Option<DivergenceKind> {
+    let (step, next_step) = match geth_steps {
+        [step, next_step, ..] => (step, next_step),
+        _ => return None,
+    };
+
+    let depth = tx_ctx.calls().len();
+    if usize::from(next_step.depth) != depth {
+        return Some(DivergenceKind::Depth {
+            geth: next_step.depth.into(),
+            bus_mapping: depth,
+        });
+    }
+    if next_step.depth != step.depth {
+        return None;
+    }
+
+    if let Some(exec_step) = exec_steps.first().filter(|step| step.error.is_none()) {
+        let memory_word_size = (exec_step.memory_size as u64 + 31) / 32;
+        let next_memory_word_size = tx_ctx
+            .calls()
+            .last()
+            .map_or(0, |call| (call.memory.len() as u64 + 31) / 32);
+        if let Some(gas_cost) = gas_cost(step, memory_word_size, next_memory_word_size) {
+            let gas_left = step.gas.saturating_sub(gas_cost);
+            if next_step.gas != gas_left {
+                return Some(DivergenceKind::GasLeft {
+                    geth: next_step.gas,
+                    bus_mapping: gas_left,
+                });
+            }
+        }
+    }
+
+    let op_refs = exec_steps
+        .iter()
+        .flat_map(|exec_step| exec_step.bus_mapping_instance.iter());
+
+    let mut stack_pointer = step.stack.stack_pointer();
+    let mut lowest_write = None;
+    let mut highest_read = None;
+    let mut stack_writes = BTreeMap::new();
+    let mut storage_writes = BTreeMap::new();
+    for op_ref in op_refs {
+        match op_ref.target() {
+            Target::Stack => {
+                let operation = &container.stack[op_ref.as_usize()];
+                let op = operation.op();
+                if op.call_id != call_id {
+                    continue;
+                }
+                if operation.rw() == RW::WRITE {
+                    lowest_write = Some(
+                        lowest_write.map_or(op.address, |addr: StackAddress| addr.min(op.address)),
+                    );
+                    stack_writes.insert(op.address, op.value);
+                } else {
+                    highest_read = highest_read.max(Some(op.address));
+                }
+            }
+            Target::Storage => {
+                let operation = &container.storage[op_ref.as_usize()];
+                if operation.rw() == RW::WRITE {
+                    storage_writes.insert(operation.op().key, operation.op().value);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(address) = lowest_write {
+        stack_pointer = address;
+    } else if let Some(address) = highest_read {
+        stack_pointer = address.map(|addr| addr + 1);
+    }
+
+    if next_step.stack.stack_pointer() != stack_pointer {
+        return Some(DivergenceKind::StackPointer {
+            geth: next_step.stack.stack_pointer(),
+            bus_mapping: stack_pointer,
+        });
+    }
+    for (address, value) in stack_writes {
+        let geth_value = next_step.stack.0[1023 - address.0];
+        if geth_value != value {
+            return Some(DivergenceKind::StackValue {
+                address,
+                geth: geth_value,
+                bus_mapping: value,
+            });
+        }
+    }
+
+    if tx_ctx.memory_enabled {
+        let memory_size = tx_ctx.calls().last().map_or(0, |call| call.memory.len());
+        if next_step.memory.len() != memory_size {
+            return Some(DivergenceKind::MemorySize {
+                geth: next_step.memory.len(),
+                bus_mapping: memory_size,
+            });
+        }
+    }
+
+    // Storage is only compared for the keys the tracer reports, as the
+    // storage capture may be disabled.
+    for (key, value) in storage_writes {
+        match next_step.storage.get(&key) {
+            Some(geth_value) if *geth_value != value => {
+                return Some(DivergenceKind::Storage {
+                    key,
+                    geth: *geth_value,
+                    bus_mapping: value,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Gas cost of `step` from its opcode, its stack inputs and the memory word
+/// sizes of the bus-mapping call context before and after the step.  Returns
+/// `None` for the opcodes whose cost also depends on the access list, the
+/// storage or the gas forwarded to a callee.
+fn gas_cost(step: &GethExecStep, memory_word_size: u64, next_memory_word_size: u64) -> Option<u64> {
+    let memory_expansion = memory_expansion_gas_cost(memory_word_size, next_memory_word_size);
+    let dynamic_cost = match step.op {
+        OpcodeId::MLOAD | OpcodeId::MSTORE | OpcodeId::MSTORE8 => memory_expansion,
+        OpcodeId::CALLDATACOPY | OpcodeId::CODECOPY | OpcodeId::RETURNDATACOPY => {
+            let length = step.stack.nth_last(2).ok()?;
+            memory_copier_gas_cost(memory_word_size, next_memory_word_size, length.low_u64())
+        }
+        OpcodeId::SHA3 => {
+            let length = step.stack.nth_last(1).ok()?;
+            (length.low_u64() + 31) / 32 * GasCost::COPY_SHA3 + memory_expansion
+        }
+        OpcodeId::EXP => {
+            let exponent = step.stack.nth_last(1).ok()?;
+            (exponent.bits() as u64 + 7) / 8 * GasCost::EXP_BYTE_TIMES
+        }
+        OpcodeId::BALANCE
+        | OpcodeId::EXTCODESIZE
+        | OpcodeId::EXTCODECOPY
+        | OpcodeId::EXTCODEHASH
+        | OpcodeId::SLOAD
+        | OpcodeId::SSTORE
+        | OpcodeId::LOG0
+        | OpcodeId::LOG1
+        | OpcodeId::LOG2
+        | OpcodeId::LOG3
+        | OpcodeId::LOG4
+        | OpcodeId::CREATE
+        | OpcodeId::CREATE2
+        | OpcodeId::CALL
+        | OpcodeId::CALLCODE
+        | OpcodeId::DELEGATECALL
+        | OpcodeId::STATICCALL
+        | OpcodeId::RETURN
+        | OpcodeId::REVERT
+        | OpcodeId::SELFDESTRUCT => return None,
+        _ => 0,
+    };
+
+    Some(step.op.constant_gas_cost() + dynamic_cost)
+}
+
+#[cfg(test)]
+mod divergence_tests {
+    use super::*;
+    use crate::{error::Error, mock::BlockData};
+    use eth_types::{bytecode, geth_types::GethData, Bytes};
+    use mock::{TestContext, MOCK_ACCOUNTS};
+
+    fn block() -> GethData {
+        let callee = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let caller = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            PUSH1(0x00)
+            MSTORE
+            .op_call(0x1_0000, MOCK_ACCOUNTS[1], 0, 0, 0x20, 0, 0)
+            STOP
+        };
+        TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(caller);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(callee);
+                accs[2]
+                    .address(MOCK_ACCOUNTS[2])
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into()
+    }
+
+    fn handle_block(block: &GethData) -> Result<(), Error> {
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder.check_trace_divergence = true;
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn trace_without_divergence() {
+        handle_block(&block()).unwrap();
+    }
+
+    #[test]
+    fn trace_with_gas_divergence() {
+        let mut block = block();
+        let struct_logs = &mut block.geth_traces[0].struct_logs;
+        // MSTORE is preceded by three PUSH1 and an ADD
+        let index = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::MSTORE)
+            .unwrap();
+        assert_eq!(index, 4);
+        let gas = struct_logs[index].gas;
+        struct_logs[index].gas = gas - 1;
+
+        match handle_block(&block) {
+            Err(Error::TraceDivergence(divergence)) => assert_eq!(
+                *divergence,
+                Divergence {
+                    tx_id: 1,
+                    step_index: index - 1,
+                    pc: 5,
+                    opcode: OpcodeId::PUSH1,
+                    kind: DivergenceKind::GasLeft {
+                        geth: gas - 1,
+                        bus_mapping: gas,
+                    },
+                }
+            ),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn trace_with_stack_divergence() {
+        let code = bytecode! {
+            PUSH1(0x00)
+            CALLDATALOAD
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code);
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .input(Bytes::from(vec![0x01; 32]));
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        // bus-mapping writes the word it reads from the call data of the
+        // transaction, which no longer matches the one geth pushed.  The
+        // intrinsic gas is unchanged as every byte stays non-zero.
+        block.eth_block.transactions[0].input = Bytes::from(vec![0x02; 32]);
+
+        match handle_block(&block) {
+            Err(Error::TraceDivergence(divergence)) => assert_eq!(
+                *divergence,
+                Divergence {
+                    tx_id: 1,
+                    step_index: 1,
+                    pc: 2,
+                    opcode: OpcodeId::CALLDATALOAD,
+                    kind: DivergenceKind::StackValue {
+                        address: StackAddress(1023),
+                        geth: Word::from_big_endian(&[0x01; 32]),
+                        bus_mapping: Word::from_big_endian(&[0x02; 32]),
+                    },
+                }
+            ),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn trace_with_storage_divergence() {
+        let mut block = block();
+        let struct_logs = &mut block.geth_traces[0].struct_logs;
+        let index = struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::SSTORE)
+            .unwrap();
+        struct_logs[index + 1]
+            .storage
+            .0
+            .insert(Word::zero(), Word::from(0x2b));
+
+        match handle_block(&block) {
+            Err(Error::TraceDivergence(divergence)) => {
+                assert_eq!(divergence.step_index, index);
+                assert_eq!(divergence.opcode, OpcodeId::SSTORE);
+                assert_eq!(
+                    divergence.kind,
+                    DivergenceKind::Storage {
+                        key: Word::zero(),
+                        geth: Word::from(0x2b),
+                        bus_mapping: Word::from(0x2a),
+                    }
+                );
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
@@ -7,7 +7,10 @@ use crate::{
     operation::RWCounter,
     precompile::PrecompileCalls,
 };
-use eth_types::{evm_types::OpcodeId, GethExecStep, Word, H256};
+use eth_types::{
+    evm_types::{Memory, MemoryAddress, OpcodeId},
+    GethExecStep, Word, H256,
+};
 use gadgets::impl_expr;
 use halo2_proofs::plonk::Expression;
 use strum_macros::EnumIter;
@@ -230,13 +233,110 @@ impl_expr!(CopyDataType);
 
 /// Defines a single copy step in a copy event. This type is unified over the
 /// source/destination row in the copy table.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct CopyStep {
-    /// Byte value copied in this step.
+    /// Address of the copied byte, or of the next copied byte for a masked
+    /// step.
+    pub addr: u64,
+    /// Byte value copied in this step, or the memory byte that a masked step
+    /// leaves untouched.
     pub value: u8,
+    /// Byte value in memory before this step. It only differs from `value`
+    /// for a copied byte written to memory.
+    pub value_prev: u8,
     /// Optional field which is enabled only for the source being `bytecode`,
     /// and represents whether or not the byte is an opcode.
     pub is_code: Option<bool>,
+    /// Whether the step is not part of the copied bytes, but only completes a
+    /// memory word or pads the shorter side of the event.
+    pub mask: bool,
+    /// Whether the step is a masked step before the first copied byte.
+    pub front_mask: bool,
+    /// Whether the step reads past the end of the source and copies a zero.
+    pub is_pad: bool,
+    /// For memory, the aligned address of the word containing the step and
+    /// the index of the byte in that word.
+    pub word: Option<(u64, usize)>,
+    /// Whether the step looks up a read-write operation, which is the case
+    /// for the last byte of a memory word and for every byte of a tx log.
+    pub has_rw: bool,
+}
+
+/// The 32-byte aligned memory words touched by one side of a copy event, as
+/// they were before the copy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CopyMemoryWords {
+    /// Bytes of the first word before the copied bytes.
+    pub front: Vec<u8>,
+    /// Previous value of the copied bytes at the destination. Empty at the
+    /// source, whose copied bytes are the bytes of the copy event.
+    pub overwritten: Vec<u8>,
+    /// Bytes of the last word after the copied bytes.
+    pub back: Vec<u8>,
+}
+
+impl CopyMemoryWords {
+    /// Take the words of `memory` that hold the `length` bytes at `address`,
+    /// for the destination of a copy.
+    pub fn new(memory: &Memory, address: u64, length: u64) -> Self {
+        if length == 0 {
+            return Self::default();
+        }
+        let start = address - address % 32;
+        let end = (address + length + 31) / 32 * 32;
+        let read = |from: u64, to: u64| {
+            memory.read_chunk(
+                MemoryAddress(from as usize),
+                MemoryAddress((to - from) as usize),
+            )
+        };
+        Self {
+            front: read(start, address),
+            overwritten: read(address, address + length),
+            back: read(address + length, end),
+        }
+    }
+
+    /// Take the words of `memory` that hold the `length` bytes at `address`,
+    /// for the source of a copy. Only the bytes around the copied ones are
+    /// kept.
+    pub fn source(memory: &Memory, address: u64, length: u64) -> Self {
+        Self {
+            overwritten: Vec::new(),
+            ..Self::new(memory, address, length)
+        }
+    }
+
+    /// Steps over the words, masking the bytes around the copied bytes at
+    /// `start`, given as (value, previous value) pairs.
+    fn steps(&self, start: u64, copied: impl Iterator<Item = (u8, u8)>) -> Vec<CopyStep> {
+        let word_start = start - self.front.len() as u64;
+        let front = self.front.iter().map(|&byte| (false, byte, byte));
+        let copied = copied.map(|(value, value_prev)| (true, value, value_prev));
+        let back = self.back.iter().map(|&byte| (false, byte, byte));
+        let mut addr = start;
+        front
+            .chain(copied)
+            .chain(back)
+            .enumerate()
+            .map(|(index, (is_copied, value, value_prev))| {
+                let address = word_start + index as u64;
+                let step = CopyStep {
+                    addr,
+                    value,
+                    value_prev,
+                    is_code: None,
+                    mask: !is_copied,
+                    front_mask: address < start,
+                    is_pad: false,
+                    word: Some((address - address % 32, index % 32)),
+                    has_rw: index % 32 == 31,
+                };
+                addr += is_copied as u64;
+                step
+            })
+            .collect()
+    }
 }
 
 /// Defines an enum type that can hold either a number or a hash value.
@@ -248,6 +348,17 @@ pub enum NumberOrHash {
     Hash(H256),
 }
 
+impl NumberOrHash {
+    /// Return the number of the [`NumberOrHash::Number`] variant, such as a
+    /// call id or a tx id.
+    pub fn as_number(&self) -> usize {
+        match self {
+            NumberOrHash::Number(number) => *number,
+            NumberOrHash::Hash(_) => panic!("expected a number, found a hash"),
+        }
+    }
+}
+
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
@@ -274,43 +385,106 @@ pub struct CopyEvent {
     pub rw_counter_start: RWCounter,
     /// Represents the list of (bytes, is_code) copied during this copy event
     pub bytes: Vec<(u8, bool)>,
+    /// Memory words around the copied bytes at the source, when it is memory.
+    pub src_words: CopyMemoryWords,
+    /// Memory words at the destination before the copy, when it is memory.
+    pub dst_words: CopyMemoryWords,
 }
 
 impl CopyEvent {
-    /// rw counter at step index
-    pub fn rw_counter(&self, step_index: usize) -> u64 {
-        u64::try_from(self.rw_counter_start.0).unwrap() + self.rw_counter_increase(step_index)
-    }
-
-    /// rw counter increase left at step index
-    pub fn rw_counter_increase_left(&self, step_index: usize) -> u64 {
-        self.rw_counter(self.bytes.len() * 2) - self.rw_counter(step_index)
+    /// The read and write steps of this copy event, one pair per row pair of
+    /// the copy circuit. A memory side steps over whole words, and the
+    /// shorter side is padded with masked steps.
+    pub fn steps(&self) -> Vec<(CopyStep, CopyStep)> {
+        let read_steps = self.read_steps();
+        let write_steps = self.write_steps();
+        let len = read_steps.len().max(write_steps.len());
+        pad_copy_steps(read_steps, len)
+            .into_iter()
+            .zip(pad_copy_steps(write_steps, len))
+            .collect()
     }
 
     /// Number of rw operations performed by this copy event
     pub fn rw_counter_delta(&self) -> u64 {
-        self.rw_counter_increase(self.bytes.len() * 2)
+        self.steps()
+            .iter()
+            .map(|(read, write)| read.has_rw as u64 + write.has_rw as u64)
+            .sum()
     }
 
-    // increase in rw counter from the start of the copy event to step index
-    fn rw_counter_increase(&self, step_index: usize) -> u64 {
-        let source_rw_increase = match self.src_type {
-            CopyDataType::Bytecode | CopyDataType::TxCalldata | CopyDataType::RlcAcc => 0,
-            CopyDataType::Memory => std::cmp::min(
-                u64::try_from(step_index + 1).unwrap() / 2,
-                self.src_addr_end
-                    .checked_sub(self.src_addr)
-                    .unwrap_or_default(),
-            ),
+    fn read_steps(&self) -> Vec<CopyStep> {
+        let length = self.bytes.len() as u64;
+        match self.src_type {
+            CopyDataType::Memory => {
+                // Only the bytes before the end of the source are read from memory, the others
+                // are padded with zeros.
+                let read_length = self.src_addr_end.saturating_sub(self.src_addr).min(length);
+                let mut steps = self.src_words.steps(
+                    self.src_addr,
+                    self.bytes[..read_length as usize]
+                        .iter()
+                        .map(|&(value, _)| (value, value)),
+                );
+                steps.extend((read_length..length).map(|index| CopyStep {
+                    addr: self.src_addr + index,
+                    is_pad: true,
+                    ..Default::default()
+                }));
+                steps
+            }
             CopyDataType::TxLog | CopyDataType::Padding => unreachable!(),
-        };
-        let destination_rw_increase = match self.dst_type {
-            CopyDataType::RlcAcc | CopyDataType::Bytecode => 0,
-            CopyDataType::TxLog | CopyDataType::Memory => u64::try_from(step_index).unwrap() / 2,
+            _ => self.byte_steps(self.src_type, self.src_addr, true),
+        }
+    }
+
+    fn write_steps(&self) -> Vec<CopyStep> {
+        match self.dst_type {
+            CopyDataType::Memory => self.dst_words.steps(
+                self.dst_addr,
+                self.bytes
+                    .iter()
+                    .zip(&self.dst_words.overwritten)
+                    .map(|(&(value, _), &value_prev)| (value, value_prev)),
+            ),
             CopyDataType::TxCalldata | CopyDataType::Padding => unreachable!(),
-        };
-        source_rw_increase + destination_rw_increase
+            _ => self.byte_steps(self.dst_type, self.dst_addr, false),
+        }
     }
+
+    // one step per copied byte for a side that is not memory
+    fn byte_steps(&self, tag: CopyDataType, start: u64, is_read: bool) -> Vec<CopyStep> {
+        self.bytes
+            .iter()
+            .enumerate()
+            .map(|(index, &(value, is_code))| {
+                let addr = start + index as u64;
+                CopyStep {
+                    addr,
+                    value,
+                    value_prev: value,
+                    is_code: (tag == CopyDataType::Bytecode).then_some(is_code),
+                    is_pad: is_read && addr >= self.src_addr_end,
+                    has_rw: tag == CopyDataType::TxLog,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+// Pad the steps of one side of a copy event with masked steps up to `len`.
+fn pad_copy_steps(mut steps: Vec<CopyStep>, len: usize) -> Vec<CopyStep> {
+    let addr = steps.last().map_or(0, |step| step.addr + !step.mask as u64);
+    steps.resize(
+        len,
+        CopyStep {
+            addr,
+            mask: true,
+            ..Default::default()
+        },
+    );
+    steps
 }
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
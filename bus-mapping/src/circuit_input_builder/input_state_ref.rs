@@ -2,16 +2,16 @@
 
 use super::{
     get_call_memory_offset_length, get_create_init_code, Block, BlockContext, Call, CallContext,
-    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, Transaction,
+    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, FeatureConfig, Transaction,
     TransactionContext,
 };
 use crate::{
-    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError},
+    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
     exec_trace::OperationRef,
     operation::{
-        AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        AccountField, AccountOp, CallContextField, CallContextOp, MemoryWordOp, Op, OpEnum,
+        Operation, StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField,
+        TxReceiptOp, RW,
     },
     precompile::{is_precompiled, PrecompileCalls},
     state_db::{CodeDB, StateDB},
@@ -43,6 +43,8 @@ pub struct CircuitInputStateRef<'a> {
     pub tx_ctx: &'a mut TransactionContext,
     /// Max rw number limit
     pub max_rws: Option<usize>,
+    /// Feature config
+    pub feature_config: FeatureConfig,
 }
 
 impl<'a> CircuitInputStateRef<'a> {
@@ -233,71 +235,53 @@ impl<'a> CircuitInputStateRef<'a> {
         self.check_rw_num_limit()
     }
 
-    /// Push a read type [`MemoryOp`] into the
+    /// Push a read type [`MemoryWordOp`] for the 32-byte aligned word at
+    /// `address` into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter) and `call_id`, and then
     /// adds a reference to the stored operation ([`OperationRef`]) inside
     /// the bus-mapping instance of the current [`ExecStep`].  Then increase
     /// the `block_ctx` [`RWCounter`](crate::operation::RWCounter) by one.
-    pub fn memory_read(
+    pub fn memory_read_word(
         &mut self,
         step: &mut ExecStep,
         address: MemoryAddress,
-    ) -> Result<u8, Error> {
-        let byte = &self.call_ctx()?.memory.read_chunk(address, 1.into())[0];
+    ) -> Result<Word, Error> {
+        let value = self.call_ctx()?.memory.read_word(address);
         let call_id = self.call()?.call_id;
-        self.push_op(step, RW::READ, MemoryOp::new(call_id, address, *byte))?;
-        Ok(*byte)
-    }
-
-    /// Almost the same as above memory_read, but read from the caller's context instead.
-    pub fn memory_read_caller(
-        &mut self,
-        step: &mut ExecStep,
-        address: MemoryAddress, // Caution: make sure this address = slot passing
-    ) -> Result<u8, Error> {
-        let byte = &self.caller_ctx()?.memory.read_chunk(address, 1.into())[0];
-        let call_id = self.call()?.caller_id;
-        self.push_op(step, RW::READ, MemoryOp::new(call_id, address, *byte))?;
-        Ok(*byte)
+        self.push_op(
+            step,
+            RW::READ,
+            MemoryWordOp::new_read(call_id, address, value),
+        )?;
+        Ok(value)
     }
 
-    /// Push a write type [`MemoryOp`] into the
+    /// Push a write type [`MemoryWordOp`] for the 32-byte aligned word at
+    /// `address` into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter) and `call_id`, and then
     /// adds a reference to the stored operation ([`OperationRef`]) inside
     /// the bus-mapping instance of the current [`ExecStep`].  Then increase
-    /// the `block_ctx` [`RWCounter`](crate::operation::RWCounter)  by one.
-    pub fn memory_write(
+    /// the `block_ctx` [`RWCounter`](crate::operation::RWCounter) by one.
+    /// The memory must already be expanded to hold the word.
+    pub fn memory_write_word(
         &mut self,
         step: &mut ExecStep,
         address: MemoryAddress,
-        value: u8,
-    ) -> Result<u8, Error> {
+        value: Word,
+    ) -> Result<Word, Error> {
         let call_id = self.call()?.call_id;
 
         let mem = &mut self.call_ctx_mut()?.memory;
-        let value_prev = mem.read_chunk(address, 1.into())[0];
-        mem.write_chunk(address, &[value]);
+        let value_prev = mem.read_word(address);
+        mem.write_chunk(address, &value.to_be_bytes());
 
-        self.push_op(step, RW::WRITE, MemoryOp::new(call_id, address, value))?;
-        Ok(value_prev)
-    }
-
-    /// Almost the same as above memory_write, but write to the caller's context instead.
-    pub fn memory_write_caller(
-        &mut self,
-        step: &mut ExecStep,
-        address: MemoryAddress, // Caution: make sure this address = slot passing
-        value: u8,
-    ) -> Result<u8, Error> {
-        let call_id = self.call()?.caller_id;
-
-        let mem = &mut self.caller_ctx_mut()?.memory;
-        let value_prev = mem.read_chunk(address, 1.into())[0];
-        mem.write_chunk(address, &[value_prev]);
-
-        self.push_op(step, RW::WRITE, MemoryOp::new(call_id, address, value))?;
+        self.push_op(
+            step,
+            RW::WRITE,
+            MemoryWordOp::new(call_id, address, value, value_prev),
+        )?;
         Ok(value_prev)
     }
 
@@ -1212,6 +1196,12 @@ impl<'a> CircuitInputStateRef<'a> {
             }
         }
 
+        // Keep the memory of the call for the copies of its return data.
+        let callee_memory = std::mem::take(&mut self.call_ctx_mut()?.memory);
+        if let Ok(caller_ctx) = self.caller_ctx_mut() {
+            caller_ctx.last_callee_memory = callee_memory;
+        }
+
         self.tx_ctx.pop_call_ctx();
 
         Ok(())
@@ -1393,10 +1383,56 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok(())
     }
 
-    /// Push a copy event to the state.
-    pub fn push_copy(&mut self, step: &mut ExecStep, event: CopyEvent) {
-        step.copy_rw_counter_delta += event.rw_counter_delta();
+    /// Push a copy event to the state, along with the memory word and tx log
+    /// operations that the copy circuit looks up for it. The memory itself
+    /// is left to the caller to update.
+    pub fn push_copy(&mut self, step: &mut ExecStep, event: CopyEvent) -> Result<(), Error> {
+        debug_assert_eq!(event.rw_counter_start, self.block_ctx.rwc);
+        let rw_counter_start = self.block_ctx.rwc.0;
+
+        let (mut read_word, mut write_word, mut write_word_prev) = (vec![], vec![], vec![]);
+        for (read, write) in event.steps() {
+            if let Some((address, _)) = read.word {
+                read_word.push(read.value);
+                if read.has_rw {
+                    let op = MemoryWordOp::new_read(
+                        event.src_id.as_number(),
+                        MemoryAddress(address as usize),
+                        Word::from_big_endian(&read_word),
+                    );
+                    self.push_op(step, RW::READ, op)?;
+                    read_word.clear();
+                }
+            }
+            if let Some((address, _)) = write.word {
+                write_word.push(write.value);
+                write_word_prev.push(write.value_prev);
+                if write.has_rw {
+                    let op = MemoryWordOp::new(
+                        event.dst_id.as_number(),
+                        MemoryAddress(address as usize),
+                        Word::from_big_endian(&write_word),
+                        Word::from_big_endian(&write_word_prev),
+                    );
+                    self.push_op(step, RW::WRITE, op)?;
+                    write_word.clear();
+                    write_word_prev.clear();
+                }
+            } else if write.has_rw {
+                self.tx_log_write(
+                    step,
+                    event.dst_id.as_number(),
+                    event.log_id.expect("tx log copy event has a log id") as usize,
+                    TxLogField::Data,
+                    write.addr as usize,
+                    Word::from(write.value),
+                )?;
+            }
+        }
+
+        step.copy_rw_counter_delta += (self.block_ctx.rwc.0 - rw_counter_start) as u64;
         self.block.add_copy_event(event);
+        Ok(())
     }
 
     /// Push a exponentiation event to the state.
@@ -1413,10 +1449,18 @@ impl<'a> CircuitInputStateRef<'a> {
             return Ok(Some(error));
         }
 
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        if !self.feature_config.hardfork.is_opcode_enabled(step.op) {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
+        // EIP-3860: an init code larger than the limit of the hardfork fails
+        // like a gas overflow.
+        if matches!(step.op, OpcodeId::CREATE | OpcodeId::CREATE2)
+            && step.stack.nth_last(2)? > self.feature_config.hardfork.max_init_code_size().into()
+        {
+            return Ok(Some(ExecError::OutOfGas(OogError::Create)));
+        }
+
         let call = self.call()?;
 
         if matches!(next_step, None) {
@@ -1680,119 +1724,39 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Generate copy steps for bytecode.
     pub(crate) fn gen_copy_steps_for_bytecode(
-        &mut self,
-        exec_step: &mut ExecStep,
+        &self,
         bytecode: &Bytecode,
         src_addr: u64,
-        dst_addr: u64,
         src_addr_end: u64,
         bytes_left: u64,
-    ) -> Result<Vec<(u8, bool)>, Error> {
-        let mut copy_steps = Vec::with_capacity(bytes_left as usize);
-        for idx in 0..bytes_left {
-            let addr = src_addr.checked_add(idx).unwrap_or(src_addr_end);
-            let step = bytecode.get(addr as usize).unwrap_or_default();
-            copy_steps.push(step);
-            self.memory_write(exec_step, (dst_addr + idx).into(), step.0)?;
-        }
-
-        Ok(copy_steps)
-    }
-
-    pub(crate) fn gen_copy_steps_for_precompile_calldata(
-        &mut self,
-        exec_step: &mut ExecStep,
-        src_addr: u64,
-        copy_length: u64,
-    ) -> Result<Vec<u8>, Error> {
-        let mut input_bytes: Vec<u8> = vec![];
-
-        if copy_length != 0 {
-            let start_byte_index = src_addr;
-            for i in 0..copy_length {
-                let b = self.memory_read_caller(exec_step, (start_byte_index + i).into())?;
-                input_bytes.push(b);
-            }
-        }
-
-        Ok(input_bytes)
-    }
-
-    pub(crate) fn gen_copy_steps_for_precompile_callee_memory(
-        &mut self,
-        exec_step: &mut ExecStep,
-        result: &[u8],
-    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
-        if result.is_empty() {
-            Ok((vec![], vec![]))
-        } else {
-            let mut prev_bytes = vec![];
-            for (byte_index, byte) in result.iter().enumerate() {
-                let prev_byte = self.memory_write(exec_step, byte_index.into(), *byte)?;
-                prev_bytes.push(prev_byte);
-            }
-
-            Ok((result.into(), prev_bytes))
-        }
-    }
-
-    pub(crate) fn gen_copy_steps_for_precompile_returndata(
-        &mut self,
-        exec_step: &mut ExecStep,
-        dst_addr: impl Into<MemoryAddress>,
-        copy_length: impl Into<MemoryAddress>,
-        result: &[u8],
-    ) -> Result<Vec<u8>, Error> {
-        let copy_length = copy_length.into().0;
-        let mut return_bytes: Vec<u8> = vec![];
-
-        if copy_length != 0 {
-            assert!(copy_length <= result.len());
-
-            let mut dst_byte_index: usize = dst_addr.into().0;
-
-            for (src_byte_index, b) in result.iter().take(copy_length).enumerate() {
-                self.memory_read(exec_step, src_byte_index.into())?;
-                self.memory_write_caller(exec_step, dst_byte_index.into(), *b)?;
-                dst_byte_index += 1;
-
-                return_bytes.push(*b);
-            }
-        }
-
-        Ok(return_bytes)
+    ) -> Vec<(u8, bool)> {
+        (0..bytes_left)
+            .map(|idx| {
+                let addr = src_addr.checked_add(idx).unwrap_or(src_addr_end);
+                bytecode.get(addr as usize).unwrap_or_default()
+            })
+            .collect()
     }
 
     /// Generate copy steps for call data.
     pub(crate) fn gen_copy_steps_for_call_data(
-        &mut self,
-        exec_step: &mut ExecStep,
+        &self,
         src_addr: u64,
-        dst_addr: u64,
         src_addr_end: u64,
         bytes_left: u64,
     ) -> Result<Vec<(u8, bool)>, Error> {
-        let mut copy_steps = Vec::with_capacity(bytes_left as usize);
-        for idx in 0..bytes_left {
-            let addr = src_addr.checked_add(idx).unwrap_or(src_addr_end);
-            let value = if addr < src_addr_end {
-                let byte =
-                    self.call_ctx()?.call_data[(addr - self.call()?.call_data_offset) as usize];
-                if !self.call()?.is_root {
-                    self.push_op(
-                        exec_step,
-                        RW::READ,
-                        MemoryOp::new(self.call()?.caller_id, addr.into(), byte),
-                    )?;
-                }
-                byte
-            } else {
-                0
-            };
-            copy_steps.push((value, false));
-            self.memory_write(exec_step, (dst_addr + idx).into(), value)?;
-        }
-
-        Ok(copy_steps)
+        let call_data_offset = self.call()?.call_data_offset;
+        let call_data = &self.call_ctx()?.call_data;
+        Ok((0..bytes_left)
+            .map(|idx| {
+                let addr = src_addr.checked_add(idx).unwrap_or(src_addr_end);
+                let value = if addr < src_addr_end {
+                    call_data[(addr - call_data_offset) as usize]
+                } else {
+                    0
+                };
+                (value, false)
+            })
+            .collect())
     }
 }
@@ -10,9 +10,9 @@ use crate::{
 };
 use eth_types::{
     address, bytecode,
-    evm_types::{stack::Stack, OpcodeId, INVALID_INIT_CODE_FIRST_BYTE},
+    evm_types::{stack::Stack, Hardfork, OpcodeId, INVALID_INIT_CODE_FIRST_BYTE},
     geth_types::GethData,
-    word, Bytecode, Hash, ToAddress, ToWord, Word,
+    word, Bytecode, GethCallTrace, Hash, ToAddress, ToWord, Word,
 };
 use lazy_static::lazy_static;
 use mock::{
@@ -1577,6 +1577,40 @@ fn tracer_err_invalid_opcode() {
     );
 }
 
+#[test]
+fn tracer_err_invalid_opcode_by_hardfork() {
+    // PUSH0 is only defined from Shanghai
+    let code = bytecode! {
+        PC
+        PUSH0
+        STOP
+    };
+    let block: GethData = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(code),
+        tx_from_1_to_0,
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+    .into();
+
+    let index = 1; // PUSH0
+    let step = &block.geth_traces[0].struct_logs[index];
+    let next_step = block.geth_traces[0].struct_logs.get(index + 1);
+    assert_eq!(step.op, OpcodeId::PUSH0);
+
+    let mut builder = CircuitInputBuilderTx::new(&block, step);
+    assert_eq!(
+        builder.state_ref().get_step_err(step, next_step).unwrap(),
+        None
+    );
+    builder.builder.feature_config.hardfork = Hardfork::London;
+    assert_eq!(
+        builder.state_ref().get_step_err(step, next_step).unwrap(),
+        Some(ExecError::InvalidOpcode)
+    );
+}
+
 #[test]
 fn test_tracer_err_write_protection() {
     // test write_protection error happens in sstore
@@ -2264,3 +2298,361 @@ fn test_gen_access_trace_create_push_call_stack() {
         }
     )
 }
+
+// Block whose first tx calls a reverting callee, and whose second tx calls the
+// reverting code directly.
+fn gen_stream_block() -> GethData {
+    let code_a = bytecode! {
+        PUSH1(0x20) // retLength
+        PUSH1(0x0) // retOffset
+        PUSH1(0x0) // argsLength
+        PUSH1(0x0) // argsOffset
+        PUSH1(0x0) // value
+        PUSH32(*WORD_ADDR_B) // addr
+        PUSH32(0x1_0000) // gas
+        CALL
+        PUSH1(0x40)
+        MSTORE
+        STOP
+    };
+    let code_b = bytecode! {
+        PUSH1(0x01) // value
+        PUSH1(0x02) // key
+        SSTORE
+        PUSH1(0x20)
+        PUSH1(0x0)
+        REVERT
+    };
+    TestContext::<3, 2>::new_with_logger_config(
+        None,
+        |accs| {
+            accs[0].address(*ADDR_A).code(code_a);
+            accs[1].address(*ADDR_B).code(code_b);
+            accs[2]
+                .address(address!("0x000000000000000000000000000000000cafe002"))
+                .balance(Word::from(1u64 << 30));
+        },
+        |mut txs, accs| {
+            txs[0].to(accs[0].address).from(accs[2].address);
+            txs[1].to(accs[1].address).from(accs[2].address);
+        },
+        |block, _tx| block.number(0xcafeu64),
+        LoggerConfig::enable_memory(),
+    )
+    .unwrap()
+    .into()
+}
+
+// Call traces of the txs of `gen_stream_block`, as given by geth's
+// `callTracer`.
+fn gen_stream_call_traces() -> Vec<GethCallTrace> {
+    let reverted_call = GethCallTrace {
+        call_type: "CALL".to_string(),
+        error: Some("execution reverted".to_string()),
+        calls: vec![],
+    };
+    vec![
+        GethCallTrace {
+            call_type: "CALL".to_string(),
+            error: None,
+            calls: vec![reverted_call],
+        },
+        GethCallTrace {
+            call_type: "CALL".to_string(),
+            error: Some("execution reverted".to_string()),
+            calls: vec![],
+        },
+    ]
+}
+
+#[test]
+fn handle_block_stream_matches_handle_block() {
+    let block = gen_stream_block();
+    let block_data = crate::mock::BlockData::new_from_geth_data(block.clone());
+
+    let builder = block_data
+        .new_circuit_input_builder()
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    let call_traces = gen_stream_call_traces();
+    let tx_traces =
+        block
+            .geth_traces
+            .iter()
+            .zip(call_traces.iter())
+            .map(|(geth_trace, call_trace)| {
+                (
+                    TraceSummary::with_call_trace(
+                        geth_trace.failed,
+                        geth_trace.invalid,
+                        call_trace,
+                    ),
+                    geth_trace.struct_logs.iter().cloned(),
+                )
+            });
+    let stream_builder = block_data
+        .new_circuit_input_builder()
+        .handle_block_stream(&block.eth_block, tx_traces)
+        .unwrap();
+
+    assert_eq!(stream_builder.block.container, builder.block.container);
+    assert_eq!(
+        format!("{:?}", stream_builder.block.txs()),
+        format!("{:?}", builder.block.txs())
+    );
+}
+
+#[test]
+fn handle_block_stream_reads_steps_once() {
+    let block = gen_stream_block();
+    let block_data = crate::mock::BlockData::new_from_geth_data(block.clone());
+
+    let builder = block_data
+        .new_circuit_input_builder()
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    // The steps of each trace can only be received once from their channel.
+    let steps_read = &std::cell::Cell::new(0);
+    let call_traces = gen_stream_call_traces();
+    let tx_traces =
+        block
+            .geth_traces
+            .iter()
+            .zip(call_traces.iter())
+            .map(move |(geth_trace, call_trace)| {
+                let (sender, receiver) = std::sync::mpsc::channel();
+                for geth_step in geth_trace.struct_logs.iter() {
+                    sender.send(geth_step.clone()).unwrap();
+                }
+                drop(sender);
+                (
+                    TraceSummary::with_call_trace(
+                        geth_trace.failed,
+                        geth_trace.invalid,
+                        call_trace,
+                    ),
+                    receiver
+                        .into_iter()
+                        .inspect(move |_| steps_read.set(steps_read.get() + 1)),
+                )
+            });
+    let stream_builder = block_data
+        .new_circuit_input_builder()
+        .handle_block_stream(&block.eth_block, tx_traces)
+        .unwrap();
+
+    let num_steps: usize = block
+        .geth_traces
+        .iter()
+        .map(|geth_trace| geth_trace.struct_logs.len())
+        .sum();
+    assert_eq!(steps_read.get(), num_steps);
+    assert_eq!(stream_builder.block.container, builder.block.container);
+    assert_eq!(
+        format!("{:?}", stream_builder.block.txs()),
+        format!("{:?}", builder.block.txs())
+    );
+}
+
+#[test]
+fn handle_block_stream_keeps_two_steps() {
+    let block = gen_stream_block();
+    // The steps of the callee are only read after its call is handled.
+    let struct_logs = &block.geth_traces[0].struct_logs;
+    let call_index = struct_logs
+        .iter()
+        .position(|step| step.op == OpcodeId::CALL)
+        .unwrap();
+    assert!(struct_logs[call_index + 1..]
+        .iter()
+        .any(|step| step.depth > struct_logs[call_index].depth));
+
+    let steps_read = &std::cell::Cell::new(0);
+    let mut window = StepWindow::new(
+        struct_logs
+            .iter()
+            .cloned()
+            .inspect(move |_| steps_read.set(steps_read.get() + 1)),
+    );
+    let mut index = 0;
+    while !window.is_empty() {
+        assert_eq!(window.steps()[0], struct_logs[index]);
+        assert_eq!(window.steps().len(), 2.min(struct_logs.len() - index));
+        assert_eq!(steps_read.get(), (index + 2).min(struct_logs.len()));
+        window.advance();
+        index += 1;
+    }
+    assert_eq!(index, struct_logs.len());
+}
+
+#[test]
+fn handle_block_stream_checks_call_trace() {
+    let block = gen_stream_block();
+    let block_data = crate::mock::BlockData::new_from_geth_data(block.clone());
+
+    // The call trace is missing the call of the first tx.
+    let call_traces = [GethCallTrace::default(), GethCallTrace::default()];
+    let tx_traces =
+        block
+            .geth_traces
+            .iter()
+            .zip(call_traces.iter())
+            .map(|(geth_trace, call_trace)| {
+                (
+                    TraceSummary::with_call_trace(
+                        geth_trace.failed,
+                        geth_trace.invalid,
+                        call_trace,
+                    ),
+                    geth_trace.struct_logs.iter().cloned(),
+                )
+            });
+    assert!(matches!(
+        block_data
+            .new_circuit_input_builder()
+            .handle_block_stream(&block.eth_block, tx_traces),
+        Err(Error::InvalidGethExecTrace(_))
+    ));
+}
+
+#[cfg(feature = "prestate")]
+#[test]
+fn trace_from_prestate_matches_struct_logs() {
+    use eth_types::{
+        GethPrestateAccount, GethPrestateTrace, ToBigEndian, TransactionReceipt, H256,
+    };
+
+    // Both txs are sent by the same account, increment the same slot and emit
+    // a log.
+    let contract = address!("0x000000000000000000000000000000000cafe001");
+    let code = bytecode! {
+        PUSH1(0x00)
+        SLOAD
+        PUSH1(0x01)
+        ADD
+        PUSH1(0x00)
+        SSTORE
+        PUSH1(0x2a)
+        PUSH1(0x00)
+        MSTORE
+        PUSH1(0x07) // topic
+        PUSH1(0x20) // length
+        PUSH1(0x00) // offset
+        LOG1
+        STOP
+    };
+    let block: GethData = TestContext::<2, 2>::new(
+        None,
+        |accs| {
+            accs[0]
+                .address(contract)
+                .balance(eth(10))
+                .code(code)
+                .storage(vec![(Word::zero(), Word::from(0x2a))].into_iter());
+            accs[1]
+                .address(address!("0x000000000000000000000000000000000cafe002"))
+                .balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0].to(accs[0].address).from(accs[1].address);
+            txs[1].to(accs[0].address).from(accs[1].address);
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+    .into();
+    let mut receipts: Vec<TransactionReceipt> = block
+        .eth_block
+        .transactions
+        .iter()
+        .zip(block.geth_traces.iter())
+        .map(|(tx, geth_trace)| TransactionReceipt {
+            transaction_hash: tx.hash,
+            status: Some(1.into()),
+            gas_used: Some(geth_trace.gas.into()),
+            logs: vec![eth_types::Log {
+                address: contract,
+                topics: vec![H256::from_low_u64_be(0x07)],
+                data: H256::from_low_u64_be(0x2a).as_bytes().to_vec().into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+        .collect();
+
+    let before_block: GethPrestateTrace = block
+        .accounts
+        .iter()
+        .map(|account| {
+            let prestate_account = GethPrestateAccount {
+                balance: account.balance,
+                nonce: account.nonce.as_u64(),
+                code: account.code.clone(),
+                storage: account
+                    .storage
+                    .iter()
+                    .map(|(key, value)| (H256(key.to_be_bytes()), H256(value.to_be_bytes())))
+                    .collect(),
+            };
+            (account.address, prestate_account)
+        })
+        .collect();
+    // The second tx touches the sender and the slot again, as left by the first
+    // tx. Their state before the block must be taken from the first tx.
+    let mut after_first_tx = before_block.clone();
+    let first_tx = &block.eth_block.transactions[0];
+    let sender = after_first_tx.get_mut(&first_tx.from).unwrap();
+    sender.nonce += 1;
+    sender.balance -= first_tx.gas_price.unwrap() * block.geth_traces[0].gas;
+    after_first_tx
+        .get_mut(&contract)
+        .unwrap()
+        .storage
+        .insert(H256::zero(), H256::from_low_u64_be(0x2b));
+
+    let prestates = [before_block, after_first_tx];
+    let geth_traces = trace_from_prestate(
+        block.chain_id,
+        &block.eth_block,
+        &prestates,
+        &receipts,
+        block.history_hashes.clone(),
+        Hardfork::Shanghai,
+    )
+    .unwrap();
+
+    let block_data = crate::mock::BlockData::new_from_geth_data(block.clone());
+    let builder = block_data
+        .new_circuit_input_builder()
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let prestate_builder = block_data
+        .new_circuit_input_builder()
+        .handle_block(&block.eth_block, &geth_traces)
+        .unwrap();
+
+    assert_eq!(prestate_builder.block.container, builder.block.container);
+    prestate_builder.check_receipt_logs(&receipts).unwrap();
+
+    // The first tx diverging from its receipt is reported.
+    let tx_hash = block.eth_block.transactions[1].hash;
+    receipts[1].logs[0].topics[0] = H256::from_low_u64_be(0x08);
+    assert!(matches!(
+        prestate_builder.check_receipt_logs(&receipts),
+        Err(Error::ReceiptMismatch(hash, "logs")) if hash == tx_hash
+    ));
+    receipts[1].gas_used = Some(0.into());
+    assert!(matches!(
+        trace_from_prestate(
+            block.chain_id,
+            &block.eth_block,
+            &prestates,
+            &receipts,
+            block.history_hashes.clone(),
+            Hardfork::Shanghai,
+        ),
+        Err(Error::ReceiptMismatch(hash, "gas used")) if hash == tx_hash
+    ));
+}
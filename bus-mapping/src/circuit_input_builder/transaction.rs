@@ -1,8 +1,11 @@
 //! Transaction & TransactionContext utility module.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
-use eth_types::{evm_types::Memory, geth_types, GethExecTrace};
+use eth_types::{
+    evm_types::{Memory, OpcodeId},
+    geth_types, GethCallTrace, GethExecStep, GethExecTrace,
+};
 use ethers_core::utils::get_contract_address;
 
 use crate::{
@@ -31,6 +34,8 @@ pub struct TransactionContext {
     /// in the inner most revert (which we track with the last element in
     /// the reversion groups stack), and skip it in the outer revert.
     pub(crate) reversion_groups: Vec<ReversionGroup>,
+    /// Whether the trace captured the memory of each step.
+    pub(crate) memory_enabled: bool,
 }
 
 impl TransactionContext {
@@ -40,32 +45,19 @@ impl TransactionContext {
         geth_trace: &GethExecTrace,
         is_last_tx: bool,
     ) -> Result<Self, Error> {
-        // Iterate over geth_trace to inspect and collect each call's is_success, which
-        // is at the top of stack at the step after a call.
-        let call_is_success = {
-            let mut call_is_success_map = BTreeMap::new();
-            let mut call_indices = Vec::new();
-            for (index, geth_step) in geth_trace.struct_logs.iter().enumerate() {
-                if let Some(geth_next_step) = geth_trace.struct_logs.get(index + 1) {
-                    // Dive into call
-                    if geth_step.depth + 1 == geth_next_step.depth {
-                        call_indices.push(index);
-                    // Emerge from call
-                    } else if geth_step.depth - 1 == geth_next_step.depth {
-                        let is_success = !geth_next_step.stack.last()?.is_zero();
-                        call_is_success_map.insert(call_indices.pop().unwrap(), is_success);
-                    // Callee with empty code
-                    } else if CallKind::try_from(geth_step.op).is_ok() {
-                        let is_success = !geth_next_step.stack.last()?.is_zero();
-                        call_is_success_map.insert(index, is_success);
-                    }
-                }
-            }
+        Self::from_summary(eth_tx, &TraceSummary::from_trace(geth_trace)?, is_last_tx)
+    }
 
-            std::iter::once(!geth_trace.failed)
-                .chain(call_is_success_map.into_values())
-                .collect()
-        };
+    /// Create a new Self from the summary of the transaction trace, so that the
+    /// steps of the trace can be handled as they are streamed.
+    pub fn from_summary(
+        eth_tx: &eth_types::Transaction,
+        summary: &TraceSummary,
+        is_last_tx: bool,
+    ) -> Result<Self, Error> {
+        let call_is_success = std::iter::once(!summary.failed)
+            .chain(summary.call_is_success.values().copied())
+            .collect();
 
         let mut tx_ctx = Self {
             id: eth_tx
@@ -78,6 +70,7 @@ impl TransactionContext {
             call_is_success,
             calls: Vec::new(),
             reversion_groups: Vec::new(),
+            memory_enabled: summary.memory_enabled,
         };
         tx_ctx.push_call_ctx(0, eth_tx.input.to_vec());
 
@@ -161,6 +154,7 @@ impl TransactionContext {
             call_data,
             memory: Memory::default(),
             return_data: vec![],
+            last_callee_memory: Memory::default(),
         });
     }
 
@@ -176,6 +170,96 @@ impl TransactionContext {
     }
 }
 
+/// The summary of a transaction trace, collected from its steps as they are
+/// read with [`Self::push_step`] while only keeping the current call stack, so
+/// that a transaction never has to be materialized as a whole
+/// [`GethExecTrace`]. The `is_success` of an internal call is only known once
+/// the steps up to its return have been read, so a trace whose steps are
+/// streamed takes the `is_success` of the calls that execute code from the
+/// [`GethCallTrace`] of the transaction instead.
+#[derive(Debug, Clone, Default)]
+pub struct TraceSummary {
+    /// Whether the transaction failed
+    pub failed: bool,
+    /// Whether the transaction is invalid
+    pub invalid: bool,
+    /// Whether the trace captured the memory of the steps read so far
+    pub memory_enabled: bool,
+    /// `is_success` of the internal calls indexed by the step making them.
+    call_is_success: BTreeMap<usize, bool>,
+    /// Indices of the steps making the calls we are currently in.
+    call_indices: Vec<usize>,
+    /// Index, opcode and depth of the last pushed step.
+    last_step: Option<(usize, OpcodeId, u16)>,
+    /// `is_success` of the calls of the call trace not made yet, in order.
+    call_trace_is_success: VecDeque<bool>,
+}
+
+impl TraceSummary {
+    /// Create a new Self for a trace whose steps are pushed afterwards.
+    pub fn new(failed: bool, invalid: bool) -> Self {
+        Self {
+            failed,
+            invalid,
+            ..Default::default()
+        }
+    }
+
+    /// Create a new Self for a trace whose steps are streamed, with the call
+    /// trace of the transaction given by geth's `callTracer`.
+    pub fn with_call_trace(failed: bool, invalid: bool, call_trace: &GethCallTrace) -> Self {
+        Self {
+            call_trace_is_success: call_trace.calls_is_success().into(),
+            ..Self::new(failed, invalid)
+        }
+    }
+
+    /// Summarize a materialized transaction trace.
+    pub(crate) fn from_trace(geth_trace: &GethExecTrace) -> Result<Self, Error> {
+        let mut summary = Self::new(geth_trace.failed, geth_trace.invalid);
+        for geth_step in geth_trace.struct_logs.iter() {
+            summary.push_step(geth_step)?;
+        }
+        Ok(summary)
+    }
+
+    /// Push the next step of the trace. Each call's is_success is at the top of
+    /// the stack at the step after the call.
+    pub(crate) fn push_step(&mut self, geth_next_step: &GethExecStep) -> Result<(), Error> {
+        self.memory_enabled |= !geth_next_step.memory.is_empty();
+        let index = match self.last_step {
+            Some((index, op, depth)) => {
+                // Dive into call
+                if depth + 1 == geth_next_step.depth {
+                    self.call_indices.push(index);
+                // Emerge from call
+                } else if depth - 1 == geth_next_step.depth {
+                    let is_success = !geth_next_step.stack.last()?.is_zero();
+                    self.call_is_success
+                        .insert(self.call_indices.pop().unwrap(), is_success);
+                // Callee with empty code
+                } else if CallKind::try_from(op).is_ok() {
+                    let is_success = !geth_next_step.stack.last()?.is_zero();
+                    self.call_is_success.insert(index, is_success);
+                }
+                index + 1
+            }
+            None => 0,
+        };
+        self.last_step = Some((index, geth_next_step.op, geth_next_step.depth));
+        Ok(())
+    }
+
+    /// Take the `is_success` of the next call of the call trace.
+    pub(crate) fn next_call_trace_is_success(&mut self) -> Result<bool, Error> {
+        self.call_trace_is_success
+            .pop_front()
+            .ok_or(Error::InvalidGethExecTrace(
+                "call trace has fewer calls than the steps",
+            ))
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 /// Result of the parsing of an Ethereum Transaction.
 pub struct Transaction {
@@ -294,6 +378,13 @@ impl Transaction {
         self.steps.is_empty()
     }
 
+    /// Return a mutable reference to the raw transaction, to tamper with the
+    /// witness in tests.
+    #[cfg(feature = "test")]
+    pub fn tx_mut(&mut self) -> &mut geth_types::Transaction {
+        &mut self.tx
+    }
+
     /// Constructor for padding tx in tx circuit
     pub fn padding_tx(id: usize) -> Self {
         Self {
@@ -5,9 +5,12 @@ use eth_types::{evm_types::OpcodeId, Address, GethExecStep, Word, H256};
 use ethers_providers::ProviderError;
 use std::error::Error as StdError;
 
-use crate::geth_errors::{
-    GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
-    GETH_ERR_STACK_UNDERFLOW,
+use crate::{
+    circuit_input_builder::Divergence,
+    geth_errors::{
+        GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
+        GETH_ERR_STACK_UNDERFLOW,
+    },
 };
 
 /// Error type for any BusMapping related failure.
@@ -15,6 +18,8 @@ use crate::geth_errors::{
 pub enum Error {
     /// Serde de/serialization error.
     SerdeError(serde_json::error::Error),
+    /// IO error.
+    IoError(std::io::Error),
     /// JSON-RPC related error.
     JSONRpcError(ProviderError),
     /// OpcodeId is not a call type.
@@ -42,6 +47,27 @@ pub enum Error {
     InternalError(&'static str),
     /// Rw number overflow
     RwsNotEnough(usize, usize),
+    /// The post-state of an account re-executed locally differs from the
+    /// chain in the given field.
+    PostStateMismatch(Address, &'static str),
+    /// The transaction with the given hash re-executed locally differs from
+    /// its receipt in the given field.
+    ReceiptMismatch(H256, &'static str),
+    /// The operations generated for a step do not lead to the state of the
+    /// next step of the geth trace.
+    TraceDivergence(Box<Divergence>),
+    /// The circuits do not support the opcode, which is only defined from
+    /// Cancun.
+    UnsupportedOpcode(OpcodeId),
+    /// The circuits do not support the precompiled contract at the address,
+    /// which is the point evaluation precompile of Cancun.
+    UnsupportedPrecompile(Address),
+    /// The circuits only encode legacy, EIP-2930 and EIP-1559 transactions:
+    /// id and EIP-2718 type of a transaction of another type.
+    TxTypeNotSupported(u64, u64),
+    /// The circuits do not warm up the entries of access lists: id of a
+    /// transaction with a non-empty access list.
+    AccessListNotSupported(u64),
 }
 
 impl From<eth_types::Error> for Error {
@@ -116,7 +142,8 @@ impl From<&OpcodeId> for OogError {
             OpcodeId::CALLDATACOPY
             | OpcodeId::CODECOPY
             | OpcodeId::EXTCODECOPY
-            | OpcodeId::RETURNDATACOPY => OogError::MemoryCopy,
+            | OpcodeId::RETURNDATACOPY
+            | OpcodeId::MCOPY => OogError::MemoryCopy,
             OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
                 OogError::AccountAccess
             }
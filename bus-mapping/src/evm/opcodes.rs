@@ -4,6 +4,7 @@ use crate::{
     error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
     evm::OpcodeId,
     operation::{AccountField, AccountOp, TxAccessListAccountOp},
+    precompile::unsupported_precompile,
     Error,
 };
 use core::fmt::Debug;
@@ -118,7 +119,7 @@ pub use crate::precompile::PrecompileCallArgs;
 /// or multiple [`ExecStep`](crate::circuit_input_builder::ExecStep) depending
 /// of the [`OpcodeId`] it contains.
 pub trait Opcode: Debug {
-    /// Generate the associated [`MemoryOp`](crate::operation::MemoryOp)s,
+    /// Generate the associated [`MemoryWordOp`](crate::operation::MemoryWordOp)s,
     /// [`StackOp`](crate::operation::StackOp)s, and
     /// [`StorageOp`](crate::operation::StorageOp)s associated to the Opcode
     /// is implemented for.
@@ -350,14 +351,14 @@ fn fn_gen_error_state_associated_ops(
 }
 #[allow(clippy::collapsible_else_if)]
 /// Generate the associated operations according to the particular
-/// [`OpcodeId`].
+/// [`OpcodeId`]. `geth_steps` starts at the step of the opcode, and only the
+/// next step, if any, is looked at besides it.
 pub fn gen_associated_ops(
     opcode_id: &OpcodeId,
     state: &mut CircuitInputStateRef,
     geth_steps: &[GethExecStep],
 ) -> Result<Vec<ExecStep>, Error> {
-    let memory_enabled = !geth_steps.iter().all(|s| s.memory.is_empty());
-    if memory_enabled {
+    if state.tx_ctx.memory_enabled {
         assert_eq!(
             &state.call_ctx()?.memory,
             &geth_steps[0].memory,
@@ -366,8 +367,10 @@ pub fn gen_associated_ops(
         );
     }
 
-    // check if have error
     let geth_step = &geth_steps[0];
+    check_supported(state, geth_step)?;
+
+    // check if have error
     let mut exec_step = state.new_step(geth_step)?;
     let next_step = if geth_steps.len() > 1 {
         Some(&geth_steps[1])
@@ -410,6 +413,37 @@ pub fn gen_associated_ops(
     fn_gen_associated_ops(state, geth_steps)
 }
 
+/// Fail on the features of the hardfork that the circuits do not support: the
+/// opcodes added in Cancun besides `PUSH0`, and the point evaluation
+/// precompile.
+fn check_supported(state: &CircuitInputStateRef, geth_step: &GethExecStep) -> Result<(), Error> {
+    let hardfork = state.feature_config.hardfork;
+    if hardfork.is_opcode_enabled(geth_step.op)
+        && matches!(
+            geth_step.op,
+            OpcodeId::TLOAD
+                | OpcodeId::TSTORE
+                | OpcodeId::MCOPY
+                | OpcodeId::BLOBHASH
+                | OpcodeId::BLOBBASEFEE
+        )
+    {
+        return Err(Error::UnsupportedOpcode(geth_step.op));
+    }
+    if matches!(
+        geth_step.op,
+        OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL
+    ) {
+        // A call with a stack underflow does not reach its code address.
+        if let Ok(code_address) = geth_step.stack.nth_last(1) {
+            if let Some(address) = unsupported_precompile(hardfork, &code_address.to_address()) {
+                return Err(Error::UnsupportedPrecompile(address));
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn gen_associated_steps(
     state: &mut CircuitInputStateRef,
     execution_step: ExecState,
@@ -468,33 +502,41 @@ fn dummy_gen_selfdestruct_ops(
     let sender_account = &sender_account.clone();
     let value = sender_account.balance;
 
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Balance,
-            value: Word::zero(),
-            value_prev: value,
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Nonce,
-            value: Word::zero(),
-            value_prev: sender_account.nonce.into(),
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::CodeHash,
-            value: Word::zero(),
-            value_prev: sender_account.code_hash.to_word(),
-        },
-    )?;
+    // After EIP-6780, an account not created in the transaction is kept, and
+    // only sends its balance to another receiver.
+    let is_deleted = !state.feature_config.hardfork.is_selfdestruct_restricted()
+        || state.sdb.is_account_created_in_tx(&sender);
+    if is_deleted || receiver != sender {
+        state.push_op_reversible(
+            &mut exec_step,
+            AccountOp {
+                address: sender,
+                field: AccountField::Balance,
+                value: Word::zero(),
+                value_prev: value,
+            },
+        )?;
+    }
+    if is_deleted {
+        state.push_op_reversible(
+            &mut exec_step,
+            AccountOp {
+                address: sender,
+                field: AccountField::Nonce,
+                value: Word::zero(),
+                value_prev: sender_account.nonce.into(),
+            },
+        )?;
+        state.push_op_reversible(
+            &mut exec_step,
+            AccountOp {
+                address: sender,
+                field: AccountField::CodeHash,
+                value: Word::zero(),
+                value_prev: sender_account.code_hash.to_word(),
+            },
+        )?;
+    }
     if receiver != sender {
         state.transfer_to(
             &mut exec_step,
@@ -506,7 +548,7 @@ fn dummy_gen_selfdestruct_ops(
         )?;
     }
 
-    if state.call()?.is_persistent {
+    if is_deleted && state.call()?.is_persistent {
         state.sdb.destruct_account(sender);
     }
 
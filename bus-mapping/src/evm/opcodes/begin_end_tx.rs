@@ -2,11 +2,12 @@ use super::TxExecSteps;
 use crate::{
     circuit_input_builder::{Call, CircuitInputStateRef, ExecState, ExecStep},
     operation::{AccountField, AccountOp, CallContextField, TxReceiptField, TxRefundOp, RW},
+    precompile::unsupported_precompile,
     state_db::CodeDB,
     Error,
 };
 use eth_types::{
-    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED, PRECOMPILE_COUNT},
+    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
     ToWord, Word,
 };
 use ethers_core::utils::get_contract_address;
@@ -32,6 +33,20 @@ impl TxExecSteps for BeginEndTx {
 fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
     let mut exec_step = state.new_begin_tx_step();
     let call = state.call()?.clone();
+    if let Some(address) = unsupported_precompile(state.feature_config.hardfork, &call.address) {
+        return Err(Error::UnsupportedPrecompile(address));
+    }
+    if state.tx.transaction_type.as_u64() > 2 {
+        return Err(Error::TxTypeNotSupported(
+            state.tx.id,
+            state.tx.transaction_type.as_u64(),
+        ));
+    }
+    if let Some(access_list) = &state.tx.access_list {
+        if !access_list.0.is_empty() {
+            return Err(Error::AccessListNotSupported(state.tx.id));
+        }
+    }
 
     begin_tx(state, &mut exec_step, &call)?;
 
@@ -62,7 +77,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     )?;
 
     // Add precompile contract address to access list
-    for address in 1..=PRECOMPILE_COUNT {
+    for address in 1..=state.feature_config.hardfork.precompile_count() {
         let address = eth_types::Address::from_low_u64_be(address);
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
@@ -75,7 +90,12 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     }
 
     // Add caller, callee and coinbase (for EIP-3651) to access list.
-    for address in [call.caller_address, call.address, state.block.coinbase] {
+    let coinbase = state.block.coinbase;
+    let warm_coinbase = state.feature_config.hardfork.is_coinbase_warm();
+    for address in [call.caller_address, call.address]
+        .into_iter()
+        .chain(warm_coinbase.then_some(coinbase))
+    {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
             &mut exec_step,
@@ -88,7 +108,8 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
 
     let init_code_gas_cost = if state.tx.is_create() {
         // Calculate gas cost of init code for EIP-3860.
-        (state.tx.call_data.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
+        (state.tx.call_data.len() as u64 + 31) / 32
+            * state.feature_config.hardfork.init_code_word_gas()
     } else {
         0
     };
@@ -167,6 +188,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
                     value_prev: 0.into(),
                 },
             )?;
+            state.sdb.create_account_in_tx(call.address);
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
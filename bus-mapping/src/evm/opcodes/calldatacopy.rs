@@ -1,7 +1,7 @@
 use super::Opcode;
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecStep, NumberOrHash,
     },
     operation::CallContextField,
     Error,
@@ -19,6 +19,8 @@ impl Opcode for Calldatacopy {
         let geth_step = &geth_steps[0];
         let mut exec_steps = vec![gen_calldatacopy_step(state, geth_step)?];
 
+        let copy_event = gen_copy_event(state, geth_step)?;
+
         // reconstruction
         let memory_offset = geth_step.stack.nth_last(0)?;
         let data_offset = geth_step.stack.nth_last(1)?;
@@ -28,8 +30,7 @@ impl Opcode for Calldatacopy {
 
         memory.copy_from(memory_offset, data_offset, length, &call_ctx.call_data);
 
-        let copy_event = gen_copy_event(state, geth_step, &mut exec_steps[0])?;
-        state.push_copy(&mut exec_steps[0], copy_event);
+        state.push_copy(&mut exec_steps[0], copy_event)?;
         Ok(exec_steps)
     }
 }
@@ -95,7 +96,6 @@ fn gen_calldatacopy_step(
 fn gen_copy_event(
     state: &mut CircuitInputStateRef,
     geth_step: &GethExecStep,
-    exec_step: &mut ExecStep,
 ) -> Result<CopyEvent, Error> {
     let rw_counter_start = state.block_ctx.rwc;
 
@@ -118,13 +118,24 @@ fn gen_copy_event(
         .unwrap_or(src_addr_end)
         .min(src_addr_end);
 
-    let copy_steps =
-        state.gen_copy_steps_for_call_data(exec_step, src_addr, dst_addr, src_addr_end, length)?;
+    let copy_steps = state.gen_copy_steps_for_call_data(src_addr, src_addr_end, length)?;
 
-    let (src_type, src_id) = if state.call()?.is_root {
-        (CopyDataType::TxCalldata, state.tx_ctx.id())
+    let (src_type, src_id, src_words) = if state.call()?.is_root {
+        (
+            CopyDataType::TxCalldata,
+            state.tx_ctx.id(),
+            CopyMemoryWords::default(),
+        )
     } else {
-        (CopyDataType::Memory, state.call()?.caller_id)
+        (
+            CopyDataType::Memory,
+            state.call()?.caller_id,
+            CopyMemoryWords::source(
+                &state.caller_ctx()?.memory,
+                src_addr,
+                length.min(src_addr_end - src_addr),
+            ),
+        )
     };
 
     Ok(CopyEvent {
@@ -138,6 +149,8 @@ fn gen_copy_event(
         log_id: None,
         rw_counter_start,
         bytes: copy_steps,
+        src_words,
+        dst_words: CopyMemoryWords::new(&state.call_ctx()?.memory, dst_addr, length),
     })
 }
 
@@ -146,7 +159,7 @@ mod calldatacopy_tests {
     use crate::{
         circuit_input_builder::{ExecState, NumberOrHash},
         mock::BlockData,
-        operation::{CallContextField, CallContextOp, MemoryOp, StackOp, RW},
+        operation::{CallContextField, CallContextOp, MemoryWordOp, StackOp, RW},
     };
     use eth_types::{
         bytecode,
@@ -224,8 +237,8 @@ mod calldatacopy_tests {
         let caller_id = builder.block.txs()[0].calls()[step.call_index].caller_id;
         let expected_call_id = builder.block.txs()[0].calls()[step.call_index].call_id;
 
-        // 3 stack reads + 3 call context reads + `copy_size` x 2 memory r/w
-        assert_eq!(step.bus_mapping_instance.len(), 6 + copy_size * 2);
+        // 3 stack reads + 3 call context reads + 1 memory word read + 1 memory word write
+        assert_eq!(step.bus_mapping_instance.len(), 8);
 
         // 3 stack reads.
         assert_eq!(
@@ -284,39 +297,36 @@ mod calldatacopy_tests {
 
         // Memory reads/writes.
         //
-        // 1. First `call_data_length` memory ops are RW::WRITE and come from the `CALL`
-        // opcode. We skip checking those.
+        // 1. First 2 memory ops are RW::WRITE and come from the `MSTORE` of the
+        // pushdata in code A. We skip checking those.
         //
-        // 2. Following that, we should have tuples of (RW::READ and RW::WRITE) where
-        // the caller memory is read and the current call's memory is written to.
-        assert_eq!(
-            builder.block.container.memory.len(),
-            call_data_length + 2 * copy_size
+        // 2. Following that, the caller memory word holding the call data is read and
+        // the current call's memory word is written to.
+        assert_eq!(builder.block.container.memory.len(), 4);
+        let mut copied = [0; 32];
+        copied[..copy_size].copy_from_slice(
+            &memory_a[offset + call_data_offset..offset + call_data_offset + copy_size],
         );
         assert_eq!(
-            (call_data_length..(call_data_length + (2 * copy_size)))
+            (2..4)
                 .map(|idx| &builder.block.container.memory[idx])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            {
-                let mut memory_ops = Vec::with_capacity(2 * copy_size);
-                (0..copy_size).for_each(|idx| {
-                    let value = if offset + call_data_offset + idx < memory_a.len() {
-                        memory_a[offset + call_data_offset + idx]
-                    } else {
-                        0
-                    };
-                    memory_ops.push((
-                        RW::READ,
-                        MemoryOp::new(caller_id, (call_data_offset + offset + idx).into(), value),
-                    ));
-                    memory_ops.push((
-                        RW::WRITE,
-                        MemoryOp::new(expected_call_id, (dst_offset + idx).into(), value),
-                    ));
-                });
-                memory_ops
-            },
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            [
+                (
+                    RW::READ,
+                    MemoryWordOp::new_read(caller_id, 0.into(), Word::from_big_endian(&memory_a))
+                ),
+                (
+                    RW::WRITE,
+                    MemoryWordOp::new(
+                        expected_call_id,
+                        dst_offset.into(),
+                        Word::from_big_endian(&copied),
+                        Word::zero()
+                    )
+                ),
+            ],
         );
 
         let copy_events = builder.block.copy_events.clone();
@@ -428,8 +438,8 @@ mod calldatacopy_tests {
             .unwrap();
 
         let expected_call_id = builder.block.txs()[0].calls()[step.call_index].call_id;
-        // 3 stack reads + 2 call context reads + `size` memory write
-        assert_eq!(step.bus_mapping_instance.len(), 5 + size);
+        // 3 stack reads + 2 call context reads + `size / 32` memory word writes
+        assert_eq!(step.bus_mapping_instance.len(), 5 + size / 32);
 
         assert_eq!(
             [0, 1, 2]
@@ -479,28 +489,33 @@ mod calldatacopy_tests {
         // Memory reads/writes.
         //
         // 1. Since its a root call, we should only have memory RW::WRITE where the
-        // current call's memory is written to.
-        assert_eq!(builder.block.container.memory.len(), size);
+        // current call's memory words are written to.
+        assert_eq!(builder.block.container.memory.len(), size / 32);
         assert_eq!(
-            (0..size)
+            (0..size / 32)
                 .map(|idx| &builder.block.container.memory[idx])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            {
-                let mut memory_ops = Vec::with_capacity(size);
-                (0..size).for_each(|idx| {
-                    let value = if offset + idx < calldata_len {
-                        calldata[offset + idx]
-                    } else {
-                        0
-                    };
-                    memory_ops.push((
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            (0..size / 32)
+                .map(|idx| {
+                    let mut word = [0; 32];
+                    for (i, byte) in word.iter_mut().enumerate() {
+                        *byte = calldata
+                            .get(offset + 32 * idx + i)
+                            .copied()
+                            .unwrap_or_default();
+                    }
+                    (
                         RW::WRITE,
-                        MemoryOp::new(expected_call_id, (dst_offset + idx).into(), value),
-                    ));
-                });
-                memory_ops
-            },
+                        MemoryWordOp::new(
+                            expected_call_id,
+                            (dst_offset + 32 * idx).into(),
+                            Word::from_big_endian(&word),
+                            Word::zero(),
+                        ),
+                    )
+                })
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
         );
 
         let copy_events = builder.block.copy_events.clone();
@@ -1,9 +1,9 @@
 use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecStep},
-    operation::{CallContextField, MemoryOp, RW},
+    operation::{CallContextField, MemoryWordOp, RW},
     Error,
 };
-use eth_types::{GethExecStep, U256};
+use eth_types::{evm_types::MemoryAddress, GethExecStep, U256};
 
 use super::Opcode;
 
@@ -70,24 +70,31 @@ impl Opcode for Calldataload {
                 state.call_ctx()?.call_data.to_vec(),
             );
 
+            // An internal call reads the two caller memory words that hold the 32 bytes at the
+            // offset.
+            if !is_root {
+                let word_addr = src_addr - src_addr % 32;
+                for word in 0..2 {
+                    let address = MemoryAddress((word_addr + 32 * word) as usize);
+                    let value = state.caller_ctx()?.memory.read_word(address);
+                    state.push_op(
+                        &mut exec_step,
+                        RW::READ,
+                        MemoryWordOp::new_read(caller_id, address, value),
+                    )?;
+                }
+            }
+
             let calldata: Vec<u8> = (0..32)
-                .map(|idx| -> Result<u8, Error> {
+                .map(|idx| {
                     let addr = src_addr.checked_add(idx).unwrap_or(src_addr_end);
                     if addr < src_addr_end {
-                        let byte = call_data[(addr - call_data_offset) as usize];
-                        if !is_root {
-                            state.push_op(
-                                &mut exec_step,
-                                RW::READ,
-                                MemoryOp::new(caller_id, (src_addr + idx).into(), byte),
-                            )?;
-                        }
-                        Ok(byte)
+                        call_data[(addr - call_data_offset) as usize]
                     } else {
-                        Ok(0)
+                        0
                     }
                 })
-                .collect::<Result<Vec<u8>, Error>>()?;
+                .collect();
 
             U256::from_big_endian(&calldata)
         } else {
@@ -183,12 +190,12 @@ mod calldataload_tests {
         let call_id = builder.block.txs()[0].calls()[step.call_index].call_id;
         let caller_id = builder.block.txs()[0].calls()[step.call_index].caller_id;
 
-        // 1 stack read, 3 call context reads, 32 memory reads and 1 stack write.
-        assert_eq!(step.bus_mapping_instance.len(), 37);
+        // 1 stack read, 3 call context reads, 2 memory word reads and 1 stack write.
+        assert_eq!(step.bus_mapping_instance.len(), 7);
 
         // stack read and write.
         assert_eq!(
-            [0, 36]
+            [0, 6]
                 .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
                 .map(|op| (op.rw(), op.op())),
             [
@@ -237,25 +244,36 @@ mod calldataload_tests {
             ],
         );
 
-        // 32 memory reads from caller memory
+        // 2 memory word reads from caller memory
+        let word_addr = (call_data_offset + offset) / 32 * 32;
         assert_eq!(
-            (0..32)
+            (0..2)
                 .map(|idx| &builder.block.container.memory
                     [step.bus_mapping_instance[4 + idx].as_usize()])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            (0..32)
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            (0..2)
                 .map(|idx| {
+                    let address = word_addr + 32 * idx;
+                    // memory_a holds the caller memory from the call data offset on, which is
+                    // zero before it.
+                    let mut word = [0; 32];
+                    for (i, byte) in word.iter_mut().enumerate() {
+                        *byte = (address + i)
+                            .checked_sub(call_data_offset)
+                            .and_then(|addr| memory_a.get(addr).copied())
+                            .unwrap_or_default();
+                    }
                     (
                         RW::READ,
-                        MemoryOp::new(
+                        MemoryWordOp::new_read(
                             caller_id,
-                            (call_data_offset + offset + idx).into(),
-                            memory_a[offset + idx],
+                            address.into(),
+                            Word::from_big_endian(&word),
                         ),
                     )
                 })
-                .collect::<Vec<(RW, MemoryOp)>>(),
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
         );
     }
 
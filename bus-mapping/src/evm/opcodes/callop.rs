@@ -1,7 +1,8 @@
 use super::Opcode;
 use crate::{
     circuit_input_builder::{
-        CallKind, CircuitInputStateRef, CodeSource, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CallKind, CircuitInputStateRef, CodeSource, CopyDataType, CopyEvent, CopyMemoryWords,
+        ExecStep, NumberOrHash,
     },
     evm::opcodes::{
         error_oog_precompile::ErrorOOGPrecompile,
@@ -330,6 +331,12 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     state.call_context_write(&mut exec_step, call.call_id, field, value)?;
                 }
 
+                // the caller memory words that the returned bytes are copied to, before the copy.
+                let return_dst_words = CopyMemoryWords::new(
+                    &state.caller_ctx()?.memory,
+                    call.return_data_offset,
+                    length as u64,
+                );
                 let caller_ctx = state.caller_ctx_mut()?;
                 caller_ctx.memory.0[ret_offset..ret_offset + length]
                     .copy_from_slice(&result[..length]);
@@ -369,11 +376,14 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                         call.call_data_length as usize
                     };
 
-                    let input_bytes = state.gen_copy_steps_for_precompile_calldata(
-                        &mut exec_step,
+                    let caller_memory = &state.caller_ctx()?.memory;
+                    let input_bytes = caller_memory
+                        .read_chunk(call.call_data_offset.into(), n_input_bytes.into());
+                    let src_words = CopyMemoryWords::source(
+                        caller_memory,
                         call.call_data_offset,
                         n_input_bytes as u64,
-                    )?;
+                    );
 
                     state.push_copy(
                         &mut exec_step,
@@ -388,15 +398,18 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             log_id: None,
                             rw_counter_start,
                             bytes: input_bytes.iter().map(|s| (*s, false)).collect(),
+                            src_words,
+                            dst_words: CopyMemoryWords::default(),
                         },
-                    );
+                    )?;
                 }
 
                 // write the result in the callee's memory
                 let rw_counter_start = state.block_ctx.rwc;
                 if call.is_success && !result.is_empty() {
-                    let (output_bytes, _prev_bytes) = state
-                        .gen_copy_steps_for_precompile_callee_memory(&mut exec_step, &result)?;
+                    let callee_memory = &mut state.call_ctx_mut()?.memory;
+                    let dst_words = CopyMemoryWords::new(callee_memory, 0, result.len() as u64);
+                    callee_memory.write_chunk(0.into(), &result);
 
                     state.push_copy(
                         &mut exec_step,
@@ -404,26 +417,24 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             src_id: NumberOrHash::Number(call.call_id),
                             src_type: CopyDataType::RlcAcc,
                             src_addr: 0,
-                            src_addr_end: output_bytes.len() as u64,
+                            src_addr_end: result.len() as u64,
                             dst_id: NumberOrHash::Number(call.call_id),
                             dst_type: CopyDataType::Memory,
                             dst_addr: 0,
                             log_id: None,
                             rw_counter_start,
-                            bytes: output_bytes.iter().map(|s| (*s, false)).collect(),
+                            bytes: result.iter().map(|s| (*s, false)).collect(),
+                            src_words: CopyMemoryWords::default(),
+                            dst_words,
                         },
-                    );
+                    )?;
                 }
 
                 // insert another copy event (output) for this step.
                 let rw_counter_start = state.block_ctx.rwc;
                 if call.is_success && length > 0 {
-                    let return_bytes = state.gen_copy_steps_for_precompile_returndata(
-                        &mut exec_step,
-                        call.return_data_offset,
-                        length,
-                        &result,
-                    )?;
+                    let src_words =
+                        CopyMemoryWords::source(&state.call_ctx()?.memory, 0, length as u64);
                     state.push_copy(
                         &mut exec_step,
                         CopyEvent {
@@ -436,9 +447,11 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                             dst_addr: call.return_data_offset,
                             log_id: None,
                             rw_counter_start,
-                            bytes: return_bytes.iter().map(|s| (*s, false)).collect(),
+                            bytes: result[..length].iter().map(|s| (*s, false)).collect(),
+                            src_words,
+                            dst_words: return_dst_words,
                         },
-                    );
+                    )?;
                 }
 
                 if has_oog_err {
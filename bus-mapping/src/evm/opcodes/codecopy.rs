@@ -1,6 +1,6 @@
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecStep, NumberOrHash,
     },
     Error,
 };
@@ -18,6 +18,7 @@ impl Opcode for Codecopy {
     ) -> Result<Vec<ExecStep>, Error> {
         let geth_step = &geth_steps[0];
         let mut exec_steps = vec![gen_codecopy_step(state, geth_step)?];
+        let copy_event = gen_copy_event(state, geth_step)?;
 
         // reconstruction
         let dst_offset = geth_step.stack.nth_last(0)?;
@@ -32,8 +33,7 @@ impl Opcode for Codecopy {
 
         memory.copy_from(dst_offset, code_offset, length, &code);
 
-        let copy_event = gen_copy_event(state, geth_step, &mut exec_steps[0])?;
-        state.push_copy(&mut exec_steps[0], copy_event);
+        state.push_copy(&mut exec_steps[0], copy_event)?;
         Ok(exec_steps)
     }
 }
@@ -67,7 +67,6 @@ fn gen_codecopy_step(
 fn gen_copy_event(
     state: &mut CircuitInputStateRef,
     geth_step: &GethExecStep,
-    exec_step: &mut ExecStep,
 ) -> Result<CopyEvent, Error> {
     let rw_counter_start = state.block_ctx.rwc;
 
@@ -89,14 +88,7 @@ fn gen_copy_event(
         .unwrap_or(src_addr_end)
         .min(src_addr_end);
 
-    let copy_steps = state.gen_copy_steps_for_bytecode(
-        exec_step,
-        &bytecode,
-        src_addr,
-        dst_addr,
-        src_addr_end,
-        length,
-    )?;
+    let copy_steps = state.gen_copy_steps_for_bytecode(&bytecode, src_addr, src_addr_end, length);
 
     Ok(CopyEvent {
         src_type: CopyDataType::Bytecode,
@@ -109,6 +101,8 @@ fn gen_copy_event(
         log_id: None,
         rw_counter_start,
         bytes: copy_steps,
+        src_words: CopyMemoryWords::default(),
+        dst_words: CopyMemoryWords::new(&state.call_ctx()?.memory, dst_addr, length),
     })
 }
 
@@ -128,7 +122,7 @@ mod codecopy_tests {
     use crate::{
         circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
         mock::BlockData,
-        operation::{MemoryOp, StackOp, RW},
+        operation::{MemoryWordOp, StackOp, RW},
     };
 
     #[test]
@@ -188,24 +182,29 @@ mod codecopy_tests {
             ]
         );
 
-        // RW table memory writes.
+        // RW table memory word writes.
         assert_eq!(
-            (0..size)
+            (0..size / 32)
                 .map(|idx| &builder.block.container.memory[idx])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            (0..size)
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            (0..size / 32)
                 .map(|idx| {
+                    let mut word = [0; 32];
+                    for (i, byte) in word.iter_mut().enumerate() {
+                        *byte = code.get_byte(code_offset + 32 * idx + i).unwrap_or(0);
+                    }
                     (
                         RW::WRITE,
-                        MemoryOp::new(
+                        MemoryWordOp::new(
                             1,
-                            MemoryAddress::from(dst_offset + idx),
-                            code.get_byte(code_offset + idx).unwrap_or(0),
+                            MemoryAddress::from(dst_offset + 32 * idx),
+                            Word::from_big_endian(&word),
+                            Word::zero(),
                         ),
                     )
                 })
-                .collect::<Vec<(RW, MemoryOp)>>(),
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
         );
 
         let copy_events = builder.block.copy_events.clone();
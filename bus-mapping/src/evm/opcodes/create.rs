@@ -1,10 +1,10 @@
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecStep, NumberOrHash,
     },
     error::ExecError,
     evm::Opcode,
-    operation::{AccountField, AccountOp, CallContextField, MemoryOp, RW},
+    operation::{AccountField, AccountOp, CallContextField},
     state_db::CodeDB,
     Error,
 };
@@ -121,6 +121,9 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
 
             // add contract address to access list
             state.tx_access_list_write(&mut exec_step, address)?;
+            if !is_address_collision {
+                state.sdb.create_account_in_tx(address);
+            }
 
             // this could be good place for checking callee_exists = true, since above
             // operation happens in evm create() method before checking
@@ -302,13 +305,8 @@ fn handle_copy(
     let bytes = initialization.code_vec();
 
     let rw_counter_start = state.block_ctx.rwc;
-    for (i, (byte, _)) in bytes.iter().enumerate() {
-        state.push_op(
-            step,
-            RW::READ,
-            MemoryOp::new(call_id, (offset + i).into(), *byte),
-        )?;
-    }
+    let src_words =
+        CopyMemoryWords::source(&state.call_ctx()?.memory, offset as u64, length as u64);
 
     state.push_copy(
         step,
@@ -323,8 +321,10 @@ fn handle_copy(
             dst_addr: 0,
             log_id: None,
             bytes,
+            src_words,
+            dst_words: CopyMemoryWords::default(),
         },
-    );
+    )?;
 
     Ok((initialization_bytes, code_hash))
 }
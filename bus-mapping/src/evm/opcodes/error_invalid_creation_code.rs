@@ -27,8 +27,9 @@ impl Opcode for ErrorCreationCode {
         assert!(call.is_create() && !length.is_zero());
 
         // Read the first byte of init code and check it must be 0xef for this error.
-        let init_code_first_byte = state.call_ctx()?.memory.0[offset.as_usize()];
-        state.memory_read(&mut exec_step, offset.try_into()?)?;
+        let offset = offset.as_usize();
+        let init_code_first_byte = state.call_ctx()?.memory.0[offset];
+        state.memory_read_word(&mut exec_step, (offset - offset % 32).into())?;
         assert_eq!(init_code_first_byte, INVALID_INIT_CODE_FIRST_BYTE);
 
         state.handle_return(&mut [&mut exec_step], geth_steps, true)?;
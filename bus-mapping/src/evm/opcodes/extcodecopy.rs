@@ -1,7 +1,7 @@
 use super::Opcode;
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecStep, NumberOrHash,
     },
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
     Error,
@@ -18,6 +18,7 @@ impl Opcode for Extcodecopy {
     ) -> Result<Vec<ExecStep>, Error> {
         let geth_step = &geth_steps[0];
         let mut exec_steps = vec![gen_extcodecopy_step(state, geth_step)?];
+        let copy_event = gen_copy_event(state, geth_step)?;
 
         // reconstruction
         let address = geth_steps[0].stack.nth_last(0)?.to_address();
@@ -34,8 +35,7 @@ impl Opcode for Extcodecopy {
 
         memory.copy_from(dst_offset, code_offset, length, &code);
 
-        let copy_event = gen_copy_event(state, geth_step, &mut exec_steps[0])?;
-        state.push_copy(&mut exec_steps[0], copy_event);
+        state.push_copy(&mut exec_steps[0], copy_event)?;
         Ok(exec_steps)
     }
 }
@@ -111,7 +111,6 @@ fn gen_extcodecopy_step(
 fn gen_copy_event(
     state: &mut CircuitInputStateRef,
     geth_step: &GethExecStep,
-    exec_step: &mut ExecStep,
 ) -> Result<CopyEvent, Error> {
     let rw_counter_start = state.block_ctx.rwc;
 
@@ -145,14 +144,7 @@ fn gen_copy_event(
         .unwrap_or(src_addr_end)
         .min(src_addr_end);
 
-    let copy_steps = state.gen_copy_steps_for_bytecode(
-        exec_step,
-        &bytecode,
-        src_addr,
-        dst_addr,
-        src_addr_end,
-        length,
-    )?;
+    let copy_steps = state.gen_copy_steps_for_bytecode(&bytecode, src_addr, src_addr_end, length);
 
     Ok(CopyEvent {
         src_addr,
@@ -165,6 +157,8 @@ fn gen_copy_event(
         log_id: None,
         rw_counter_start,
         bytes: copy_steps,
+        src_words: CopyMemoryWords::default(),
+        dst_words: CopyMemoryWords::new(&state.call_ctx()?.memory, dst_addr, length),
     })
 }
 
@@ -174,7 +168,7 @@ mod extcodecopy_tests {
         circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
         mock::BlockData,
         operation::{
-            AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, StackOp,
+            AccountField, AccountOp, CallContextField, CallContextOp, MemoryWordOp, StackOp,
             TxAccessListAccountOp, RW,
         },
         state_db::CodeDB,
@@ -398,23 +392,36 @@ mod extcodecopy_tests {
 
         let expected_call_id = transaction.calls()[step.call_index].call_id;
 
+        // The words holding the copied bytes are written, with the rest of them untouched.
+        let word_addr = memory_offset / 32 * 32;
+        let n_words = (memory_offset + copy_size + 31) / 32 - memory_offset / 32;
         assert_eq!(
-            (0..copy_size)
+            (0..n_words)
                 .map(|idx| &builder.block.container.memory[idx])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            (0..copy_size)
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            (0..n_words)
                 .map(|idx| {
+                    let address = word_addr + 32 * idx;
+                    let mut word = [0; 32];
+                    for (i, byte) in word.iter_mut().enumerate() {
+                        if (memory_offset..memory_offset + copy_size).contains(&(address + i)) {
+                            *byte = bytecode_ext
+                                .get_byte(data_offset + address + i - memory_offset)
+                                .unwrap_or(0);
+                        }
+                    }
                     (
                         RW::WRITE,
-                        MemoryOp::new(
+                        MemoryWordOp::new(
                             expected_call_id,
-                            MemoryAddress::from(memory_offset + idx),
-                            bytecode_ext.get_byte(data_offset + idx).unwrap_or(0),
+                            MemoryAddress::from(address),
+                            Word::from_big_endian(&word),
+                            Word::zero(),
                         ),
                     )
                 })
-                .collect::<Vec<(RW, MemoryOp)>>(),
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
         );
 
         let copy_events = builder.block.copy_events.clone();
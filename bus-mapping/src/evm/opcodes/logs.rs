@@ -1,7 +1,8 @@
 use super::Opcode;
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecState, ExecStep,
+        NumberOrHash,
     },
     operation::{CallContextField, TxLogField},
     Error,
@@ -19,8 +20,8 @@ impl Opcode for Log {
         let geth_step = &geth_steps[0];
         let mut exec_step = gen_log_step(state, geth_step)?;
         if state.call()?.is_persistent {
-            let copy_event = gen_copy_event(state, geth_step, &mut exec_step)?;
-            state.push_copy(&mut exec_step, copy_event);
+            let copy_event = gen_copy_event(state, geth_step)?;
+            state.push_copy(&mut exec_step, copy_event)?;
             state.tx_ctx.log_id += 1;
         }
 
@@ -129,55 +130,25 @@ fn gen_log_step(
     Ok(exec_step)
 }
 
-fn gen_copy_steps(
-    state: &mut CircuitInputStateRef,
-    exec_step: &mut ExecStep,
-    src_addr: u64,
-    bytes_left: usize,
-) -> Result<Vec<(u8, bool)>, Error> {
-    // Get memory data
-    let mem = state
-        .call_ctx()?
-        .memory
-        .read_chunk(src_addr.into(), bytes_left.into());
-
-    let mut copy_steps = Vec::with_capacity(bytes_left);
-    for (idx, byte) in mem.iter().enumerate() {
-        let addr = src_addr + idx as u64;
-
-        // Read memory
-        state.memory_read(exec_step, (addr as usize).into())?;
-
-        copy_steps.push((*byte, false));
-
-        // Write log
-        state.tx_log_write(
-            exec_step,
-            state.tx_ctx.id(),
-            state.tx_ctx.log_id + 1,
-            TxLogField::Data,
-            idx,
-            Word::from(*byte),
-        )?;
-    }
-
-    Ok(copy_steps)
-}
-
 fn gen_copy_event(
     state: &mut CircuitInputStateRef,
     geth_step: &GethExecStep,
-    exec_step: &mut ExecStep,
 ) -> Result<CopyEvent, Error> {
     let rw_counter_start = state.block_ctx.rwc;
 
     assert!(state.call()?.is_persistent, "Error: Call is not persistent");
     let memory_start = geth_step.stack.nth_last(0)?.as_u64();
-    let msize = geth_step.stack.nth_last(1)?.as_usize();
+    let msize = geth_step.stack.nth_last(1)?.as_u64();
 
-    let (src_addr, src_addr_end) = (memory_start, memory_start + msize as u64);
+    let (src_addr, src_addr_end) = (memory_start, memory_start + msize);
 
-    let steps = gen_copy_steps(state, exec_step, src_addr, msize)?;
+    // Get memory data
+    let memory = &state.call_ctx()?.memory;
+    let steps = memory
+        .read_chunk(src_addr.into(), msize.into())
+        .into_iter()
+        .map(|byte| (byte, false))
+        .collect();
 
     Ok(CopyEvent {
         src_type: CopyDataType::Memory,
@@ -190,6 +161,8 @@ fn gen_copy_event(
         log_id: Some(state.tx_ctx.log_id as u64 + 1),
         rw_counter_start,
         bytes: steps,
+        src_words: CopyMemoryWords::source(memory, src_addr, msize),
+        dst_words: CopyMemoryWords::default(),
     })
 }
 
@@ -198,7 +171,9 @@ mod log_tests {
     use crate::{
         circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
         mock::BlockData,
-        operation::{CallContextField, CallContextOp, MemoryOp, StackOp, TxLogField, TxLogOp, RW},
+        operation::{
+            CallContextField, CallContextOp, MemoryWordOp, StackOp, TxLogField, TxLogOp, RW,
+        },
     };
     use eth_types::{
         bytecode,
@@ -394,37 +369,42 @@ mod log_tests {
             { log_topic_ops },
         );
 
-        // memory reads.
-        let mut log_data_ops = Vec::with_capacity(msize);
+        // memory word reads.
         assert_eq!(
-            // skip first 32 writes of MSTORE ops
-            (mstart + 64..(mstart + 64 + msize))
+            // skip the 2 memory word writes of each MSTORE op
+            (4..4 + msize / 32)
                 .map(|idx| &builder.block.container.memory[idx])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            {
-                let mut memory_ops = Vec::with_capacity(msize);
-                (mstart..msize).for_each(|idx| {
-                    memory_ops.push((
-                        RW::READ,
-                        MemoryOp::new(1, (mstart + idx).into(), memory_data[mstart + idx]),
-                    ));
-                    // tx log addition
-                    log_data_ops.push((
-                        RW::WRITE,
-                        TxLogOp::new(
-                            1,
-                            step.log_id + 1, // because it is in next CopyToLog step
-                            TxLogField::Data,
-                            idx - mstart,
-                            Word::from(memory_data[mstart + idx]),
-                        ),
-                    ));
-                });
-
-                memory_ops
-            },
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            memory_data[mstart..mstart + msize]
+                .chunks(32)
+                .enumerate()
+                .map(|(idx, word)| (
+                    RW::READ,
+                    MemoryWordOp::new_read(
+                        1,
+                        (mstart + 32 * idx).into(),
+                        Word::from_big_endian(word)
+                    )
+                ))
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
         );
+
+        // tx log data writes.
+        let log_data_ops = (mstart..msize)
+            .map(|idx| {
+                (
+                    RW::WRITE,
+                    TxLogOp::new(
+                        1,
+                        step.log_id + 1, // because it is in next CopyToLog step
+                        TxLogField::Data,
+                        idx - mstart,
+                        Word::from(memory_data[mstart + idx]),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
         assert_eq!(
             ((1 + topic_count)..msize + 1 + topic_count)
                 .map(|idx| &builder.block.container.tx_log[idx])
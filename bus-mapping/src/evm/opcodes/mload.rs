@@ -3,12 +3,12 @@ use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecStep},
     Error,
 };
-use eth_types::{evm_types::MemoryAddress, GethExecStep, ToBigEndian};
+use eth_types::{evm_types::MemoryAddress, GethExecStep};
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the [`OpcodeId::MLOAD`](crate::evm::OpcodeId::MLOAD)
 /// `OpcodeId`. This is responsible of generating all of the associated
-/// [`crate::operation::StackOp`]s and [`crate::operation::MemoryOp`]s and place
+/// [`crate::operation::StackOp`]s and [`crate::operation::MemoryWordOp`]s and place
 /// them inside the trace's [`crate::operation::OperationContainer`].
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Mload;
@@ -29,7 +29,7 @@ impl Opcode for Mload {
         state.stack_read(&mut exec_step, stack_position, stack_value_read)?;
 
         // Read the memory
-        let mut mem_read_addr: MemoryAddress = stack_value_read.try_into()?;
+        let mem_read_addr: MemoryAddress = stack_value_read.try_into()?;
         // Accesses to memory that hasn't been initialized are valid, and return
         // 0.
         let mem_read_value = geth_steps[1].stack.last()?;
@@ -38,13 +38,11 @@ impl Opcode for Mload {
         //
         state.stack_write(&mut exec_step, stack_position, mem_read_value)?;
 
-        // First mem read -> 32 MemoryOp generated.
+        // Read the two memory words that hold the 32 bytes at the offset.
         //
-        for _ in mem_read_value.to_be_bytes() {
-            state.memory_read(&mut exec_step, mem_read_addr)?;
-
-            // Update mem_read_addr to next byte's one
-            mem_read_addr += MemoryAddress::from(1);
+        let word_addr = mem_read_addr.map(|addr| addr - addr % 32);
+        for word in 0..2 {
+            state.memory_read_word(&mut exec_step, word_addr.map(|addr| addr + 32 * word))?;
         }
 
         // reconstruction
@@ -64,7 +62,7 @@ mod mload_tests {
     use crate::{
         circuit_input_builder::ExecState,
         mock::BlockData,
-        operation::{MemoryOp, StackOp, RW},
+        operation::{MemoryWordOp, StackOp, RW},
     };
     use eth_types::{
         bytecode,
@@ -124,17 +122,21 @@ mod mload_tests {
         );
 
         assert_eq!(
-            (2..34)
+            (2..4)
                 .map(|idx| &builder.block.container.memory
                     [step.bus_mapping_instance[idx].as_usize()])
                 .map(|operation| (operation.rw(), operation.op().clone()))
                 .collect_vec(),
-            Word::from(0x80)
-                .to_be_bytes()
-                .into_iter()
-                .enumerate()
-                .map(|(idx, byte)| (RW::READ, MemoryOp::new(1, MemoryAddress(idx + 0x40), byte)))
-                .collect_vec()
+            [
+                (
+                    RW::READ,
+                    MemoryWordOp::new_read(1, MemoryAddress(0x40), Word::from(0x80))
+                ),
+                (
+                    RW::READ,
+                    MemoryWordOp::new_read(1, MemoryAddress(0x60), Word::zero())
+                ),
+            ]
         )
     }
 }
@@ -3,7 +3,7 @@ use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecStep},
     Error,
 };
-use eth_types::{evm_types::MemoryAddress, GethExecStep, ToBigEndian, ToLittleEndian};
+use eth_types::{evm_types::MemoryAddress, GethExecStep, ToBigEndian, Word};
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the [`OpcodeId::MSTORE`](crate::evm::OpcodeId::MSTORE)
@@ -28,48 +28,29 @@ impl<const IS_MSTORE8: bool> Opcode for Mstore<IS_MSTORE8> {
         let value_pos = geth_step.stack.nth_last_filled(1);
         state.stack_read(&mut exec_step, value_pos, value)?;
 
-        // First mem write -> 32 MemoryOp generated.
+        // Memory writes -> one word for mstore8, the two words holding the
+        // 32 bytes at the offset for mstore.
         let offset_addr: MemoryAddress = offset.try_into()?;
-
-        match IS_MSTORE8 {
-            true => {
-                // stack write operation for mstore8
-                state.memory_write(
-                    &mut exec_step,
-                    offset_addr,
-                    *value.to_le_bytes().first().unwrap(),
-                )?;
-            }
-            false => {
-                // stack write each byte for mstore
-                let bytes = value.to_be_bytes();
-                for (i, byte) in bytes.iter().enumerate() {
-                    state.memory_write(&mut exec_step, offset_addr.map(|a| a + i), *byte)?;
-                }
-            }
-        }
-
-        // reconstruction
-        let offset = geth_step.stack.nth_last(0)?;
-        let value = geth_step.stack.nth_last(1)?;
-        let offset_addr: MemoryAddress = offset.try_into()?;
-
-        let call_ctx = state.call_ctx_mut()?;
-        let memory = &mut call_ctx.memory;
         let minimal_length = offset_addr.0 + if IS_MSTORE8 { 1 } else { 32 };
-        memory.extend_at_least(minimal_length);
-
-        let mem_starts = offset_addr.0;
+        state.call_ctx_mut()?.memory.extend_at_least(minimal_length);
 
+        let word_addr = offset_addr.map(|addr| addr - addr % 32);
+        let n_words = if IS_MSTORE8 { 1 } else { 2 };
+        let mut words = state
+            .call_ctx()?
+            .memory
+            .read_chunk(word_addr, MemoryAddress(32 * n_words));
+        let shift = offset_addr.0 % 32;
         match IS_MSTORE8 {
-            true => {
-                let val = *value.to_le_bytes().first().unwrap();
-                memory.0[mem_starts] = val;
-            }
-            false => {
-                let bytes = value.to_be_bytes();
-                memory[mem_starts..mem_starts + 32].copy_from_slice(&bytes);
-            }
+            true => words[shift] = value.low_u64() as u8,
+            false => words[shift..shift + 32].copy_from_slice(&value.to_be_bytes()),
+        }
+        for (i, word) in words.chunks(32).enumerate() {
+            state.memory_write_word(
+                &mut exec_step,
+                word_addr.map(|addr| addr + 32 * i),
+                Word::from_big_endian(word),
+            )?;
         }
 
         Ok(vec![exec_step])
@@ -82,13 +63,12 @@ mod mstore_tests {
     use crate::{
         circuit_input_builder::ExecState,
         mock::BlockData,
-        operation::{MemoryOp, StackOp, RW},
+        operation::{MemoryWordOp, StackOp, RW},
     };
     use eth_types::{
         bytecode,
-        evm_types::{MemoryAddress, OpcodeId, StackAddress},
+        evm_types::{OpcodeId, StackAddress},
         geth_types::GethData,
-        Word,
     };
     use itertools::Itertools;
     use mock::test_ctx::{helpers::*, TestContext};
@@ -143,20 +123,79 @@ mod mstore_tests {
         );
 
         assert_eq!(
-            (2..34)
+            (2..4)
+                .map(|idx| &builder.block.container.memory
+                    [step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op().clone()))
+                .collect_vec(),
+            [
+                (
+                    RW::WRITE,
+                    MemoryWordOp::new(1, MemoryAddress(0x100), Word::from(0x1234u64), Word::zero())
+                ),
+                (
+                    RW::WRITE,
+                    MemoryWordOp::new(1, MemoryAddress(0x120), Word::zero(), Word::zero())
+                ),
+            ]
+        )
+    }
+
+    #[test]
+    fn mstore_unaligned_opcode_impl() {
+        let code = bytecode! {
+            .setup_state()
+            PUSH2(0x1234)
+            PUSH2(0x101)
+            MSTORE
+            STOP
+        };
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let builder = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .filter(|step| step.exec_state == ExecState::Op(OpcodeId::MSTORE))
+            .nth(1)
+            .unwrap();
+
+        // The value is split over the two words, with its last byte at the
+        // start of the second one.
+        assert_eq!(
+            (2..4)
                 .map(|idx| &builder.block.container.memory
                     [step.bus_mapping_instance[idx].as_usize()])
                 .map(|operation| (operation.rw(), operation.op().clone()))
                 .collect_vec(),
-            Word::from(0x1234u64)
-                .to_be_bytes()
-                .into_iter()
-                .enumerate()
-                .map(|(idx, byte)| (
+            [
+                (
+                    RW::WRITE,
+                    MemoryWordOp::new(1, MemoryAddress(0x100), Word::from(0x12u64), Word::zero())
+                ),
+                (
                     RW::WRITE,
-                    MemoryOp::new(1, MemoryAddress(idx + 0x100), byte)
-                ))
-                .collect_vec()
+                    MemoryWordOp::new(
+                        1,
+                        MemoryAddress(0x120),
+                        Word::from(0x34u64) << 248,
+                        Word::zero()
+                    )
+                ),
+            ]
         )
     }
 
@@ -210,7 +249,15 @@ mod mstore_tests {
         let memory_op = &builder.block.container.memory[step.bus_mapping_instance[2].as_usize()];
         assert_eq!(
             (memory_op.rw(), memory_op.op()),
-            (RW::WRITE, &MemoryOp::new(1, MemoryAddress(0x100), 0x34))
+            (
+                RW::WRITE,
+                &MemoryWordOp::new(
+                    1,
+                    MemoryAddress(0x100),
+                    Word::from(0x34u64) << 248,
+                    Word::zero()
+                )
+            )
         )
     }
 }
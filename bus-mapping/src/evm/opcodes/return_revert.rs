@@ -1,8 +1,10 @@
 use super::Opcode;
 use crate::{
-    circuit_input_builder::{CircuitInputStateRef, CopyDataType, CopyEvent, NumberOrHash},
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, NumberOrHash,
+    },
     evm::opcodes::ExecStep,
-    operation::{AccountField, AccountOp, CallContextField, MemoryOp, RW},
+    operation::{AccountField, AccountOp, CallContextField},
     state_db::CodeDB,
     Error,
 };
@@ -48,7 +50,7 @@ impl Opcode for ReturnRevert {
         if call.is_create() && call.is_success && length > 0 {
             // Read the first byte of init code and check it must not be 0xef (EIP-3541).
             let init_code_first_byte = state.call_ctx()?.memory.0[offset];
-            state.memory_read(&mut exec_step, offset.into())?;
+            state.memory_read_word(&mut exec_step, (offset - offset % 32).into())?;
             assert_ne!(init_code_first_byte, INVALID_INIT_CODE_FIRST_BYTE);
 
             // Note: handle_return updates state.code_db. All we need to do here is push the
@@ -113,14 +115,7 @@ impl Opcode for ReturnRevert {
             let return_data_length = usize::try_from(call.return_data_length).unwrap();
             let copy_length = std::cmp::min(return_data_length, length);
             if copy_length > 0 {
-                // reconstruction
-                let callee_memory = state.call_ctx()?.memory.clone();
-                let caller_ctx = state.caller_ctx_mut()?;
                 let return_offset = call.return_data_offset.try_into().unwrap();
-
-                caller_ctx.memory.0[return_offset..return_offset + copy_length]
-                    .copy_from_slice(&callee_memory.0[offset..offset + copy_length]);
-
                 handle_copy(
                     state,
                     &mut exec_step,
@@ -135,6 +130,12 @@ impl Opcode for ReturnRevert {
                         length: return_data_length,
                     },
                 )?;
+
+                // reconstruction
+                let callee_memory = state.call_ctx()?.memory.clone();
+                let caller_ctx = state.caller_ctx_mut()?;
+                caller_ctx.memory.0[return_offset..return_offset + copy_length]
+                    .copy_from_slice(&callee_memory.0[offset..offset + copy_length]);
             }
         }
 
@@ -168,18 +169,16 @@ fn handle_copy(
         .collect();
 
     let rw_counter_start = state.block_ctx.rwc;
-    for (i, (byte, _is_code)) in bytes.iter().enumerate() {
-        state.push_op(
-            step,
-            RW::READ,
-            MemoryOp::new(source.id, (source.offset + i).into(), *byte),
-        )?;
-        state.push_op(
-            step,
-            RW::WRITE,
-            MemoryOp::new(destination.id, (destination.offset + i).into(), *byte),
-        )?;
-    }
+    let src_words = CopyMemoryWords::source(
+        &state.call_ctx()?.memory,
+        source.offset as u64,
+        copy_length as u64,
+    );
+    let dst_words = CopyMemoryWords::new(
+        &state.caller_ctx()?.memory,
+        destination.offset as u64,
+        copy_length as u64,
+    );
 
     state.push_copy(
         step,
@@ -194,8 +193,10 @@ fn handle_copy(
             dst_addr: destination.offset.try_into().unwrap(),
             log_id: None,
             bytes,
+            src_words,
+            dst_words,
         },
-    );
+    )?;
 
     Ok(())
 }
@@ -211,13 +212,11 @@ fn handle_create(
     let bytes = bytecode.code_vec();
     let dst_id = NumberOrHash::Hash(code_hash);
     let rw_counter_start = state.block_ctx.rwc;
-    for (i, (byte, _)) in bytes.iter().enumerate() {
-        state.push_op(
-            step,
-            RW::READ,
-            MemoryOp::new(source.id, (source.offset + i).into(), *byte),
-        )?;
-    }
+    let src_words = CopyMemoryWords::source(
+        &state.call_ctx()?.memory,
+        source.offset as u64,
+        source.length as u64,
+    );
 
     state.push_copy(
         step,
@@ -232,8 +231,10 @@ fn handle_create(
             dst_addr: 0,
             log_id: None,
             bytes,
+            src_words,
+            dst_words: CopyMemoryWords::default(),
         },
-    );
+    )?;
 
     Ok(code_hash)
 }
@@ -1,9 +1,9 @@
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecStep, NumberOrHash,
     },
     evm::Opcode,
-    operation::{CallContextField, MemoryOp, RW},
+    operation::CallContextField,
     Error,
 };
 use eth_types::GethExecStep;
@@ -18,6 +18,7 @@ impl Opcode for Returndatacopy {
     ) -> Result<Vec<ExecStep>, Error> {
         let geth_step = &geth_steps[0];
         let mut exec_steps = vec![gen_returndatacopy_step(state, geth_step)?];
+        let copy_event = gen_copy_event(state, geth_step)?;
 
         // reconstruction
         let geth_step = &geth_steps[0];
@@ -32,8 +33,7 @@ impl Opcode for Returndatacopy {
         let memory = &mut call_ctx.memory;
         memory.copy_from(dst_offset, src_offset, length, &return_data);
 
-        let copy_event = gen_copy_event(state, geth_step, &mut exec_steps[0])?;
-        state.push_copy(&mut exec_steps[0], copy_event);
+        state.push_copy(&mut exec_steps[0], copy_event)?;
         Ok(exec_steps)
     }
 }
@@ -89,40 +89,28 @@ fn gen_returndatacopy_step(
 }
 
 fn gen_copy_steps(
-    state: &mut CircuitInputStateRef,
-    exec_step: &mut ExecStep,
+    state: &CircuitInputStateRef,
     src_addr: u64,
-    dst_addr: u64,
     src_addr_end: u64,
     bytes_left: u64,
 ) -> Result<Vec<(u8, bool)>, Error> {
-    let mut copy_steps = Vec::with_capacity(bytes_left as usize);
     let src_addr_base = state.call()?.last_callee_return_data_offset;
-    for idx in 0..bytes_left {
-        let addr = src_addr + idx;
-        let value = if addr < src_addr_end {
-            state.call_ctx()?.return_data[(addr - src_addr_base) as usize]
-        } else {
-            unreachable!("return data copy out of bound")
-        };
-        // Read
-        state.push_op(
-            exec_step,
-            RW::READ,
-            MemoryOp::new(state.call()?.last_callee_id, addr.into(), value),
-        )?;
-
-        // Write
-        copy_steps.push((value, false));
-        state.memory_write(exec_step, (dst_addr + idx).into(), value)?;
-    }
-    Ok(copy_steps)
+    let return_data = &state.call_ctx()?.return_data;
+    (0..bytes_left)
+        .map(|idx| {
+            let addr = src_addr + idx;
+            if addr < src_addr_end {
+                Ok((return_data[(addr - src_addr_base) as usize], false))
+            } else {
+                unreachable!("return data copy out of bound")
+            }
+        })
+        .collect()
 }
 
 fn gen_copy_event(
     state: &mut CircuitInputStateRef,
     geth_step: &GethExecStep,
-    exec_step: &mut ExecStep,
 ) -> Result<CopyEvent, Error> {
     // Get low Uint64 of destination offset to generate copy steps. Since it
     // could be Uint64 overflow if length is zero.
@@ -138,7 +126,9 @@ fn gen_copy_event(
     );
 
     let rw_counter_start = state.block_ctx.rwc;
-    let copy_steps = gen_copy_steps(state, exec_step, src_addr, dst_addr, src_addr_end, length)?;
+    let copy_steps = gen_copy_steps(state, src_addr, src_addr_end, length)?;
+    let src_words =
+        CopyMemoryWords::source(&state.call_ctx()?.last_callee_memory, src_addr, length);
 
     let (src_type, dst_type, src_id, dst_id) = (
         CopyDataType::Memory,
@@ -158,6 +148,8 @@ fn gen_copy_event(
         log_id: None,
         rw_counter_start,
         bytes: copy_steps,
+        src_words,
+        dst_words: CopyMemoryWords::new(&state.call_ctx()?.memory, dst_addr, length),
     })
 }
 
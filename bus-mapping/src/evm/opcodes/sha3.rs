@@ -1,7 +1,7 @@
 use super::Opcode;
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        CircuitInputStateRef, CopyDataType, CopyEvent, CopyMemoryWords, ExecStep, NumberOrHash,
     },
     Error,
 };
@@ -54,12 +54,9 @@ impl Opcode for Sha3 {
 
         // Memory read operations
         let rw_counter_start = state.block_ctx.rwc;
-        let mut steps = Vec::with_capacity(size.as_usize());
-        for (i, byte) in memory.iter().enumerate() {
-            // Read step
-            state.memory_read(&mut exec_step, (offset.as_usize() + i).into())?;
-            steps.push((*byte, false));
-        }
+        let steps = memory.iter().map(|byte| (*byte, false)).collect();
+        let src_words =
+            CopyMemoryWords::source(&state.call_ctx()?.memory, offset.low_u64(), size.as_u64());
         state.block.sha3_inputs.push(memory);
 
         let call_id = state.call()?.call_id;
@@ -79,8 +76,10 @@ impl Opcode for Sha3 {
                 log_id: None,
                 rw_counter_start,
                 bytes: steps,
+                src_words,
+                dst_words: CopyMemoryWords::default(),
             },
-        );
+        )?;
 
         Ok(vec![exec_step])
     }
@@ -88,7 +87,7 @@ impl Opcode for Sha3 {
 
 #[cfg(test)]
 pub(crate) mod sha3_tests {
-    use eth_types::{evm_types::OpcodeId, geth_types::GethData, U256};
+    use eth_types::{evm_types::OpcodeId, geth_types::GethData, Word, U256};
     use ethers_core::utils::keccak256;
     use mock::{
         test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
@@ -98,7 +97,7 @@ pub(crate) mod sha3_tests {
     use crate::{
         circuit_input_builder::ExecState,
         mock::BlockData,
-        operation::{MemoryOp, StackOp, RW},
+        operation::{MemoryWordOp, StackOp, RW},
     };
 
     fn test_ok(mut gen: Sha3CodeGen) {
@@ -108,7 +107,8 @@ pub(crate) mod sha3_tests {
 
         // The memory that is hashed.
         let mut memory_view = memory
-            .into_iter()
+            .iter()
+            .copied()
             .skip(offset)
             .take(size)
             .collect::<Vec<u8>>();
@@ -158,25 +158,33 @@ pub(crate) mod sha3_tests {
             ]
         );
 
-        // Memory reads.
-        // Initial memory_len bytes are the memory writes from MSTORE instruction, so we
-        // skip them.
+        // Memory word reads.
+        // Initial memory words are written by 2 memory ops of each MSTORE instruction,
+        // so we skip them.
+        let word_addr = offset / 32 * 32;
+        let n_words = (offset + size + 31) / 32 - offset / 32;
         assert_eq!(
-            (memory_len..(memory_len + size))
+            (memory_len / 16..(memory_len / 16 + n_words))
                 .map(|idx| &builder.block.container.memory[idx])
                 .map(|op| (op.rw(), op.op().clone()))
-                .collect::<Vec<(RW, MemoryOp)>>(),
-            {
-                let mut memory_ops = Vec::with_capacity(size);
-                (0..size).for_each(|idx| {
-                    let value = memory_view[idx];
-                    memory_ops.push((
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
+            (0..n_words)
+                .map(|idx| {
+                    let address = word_addr + 32 * idx;
+                    let mut word = [0; 32];
+                    for (i, byte) in word.iter_mut().enumerate() {
+                        *byte = memory.get(address + i).copied().unwrap_or_default();
+                    }
+                    (
                         RW::READ,
-                        MemoryOp::new(call_id, (offset + idx).into(), value),
-                    ));
-                });
-                memory_ops
-            },
+                        MemoryWordOp::new_read(
+                            call_id,
+                            address.into(),
+                            Word::from_big_endian(&word),
+                        ),
+                    )
+                })
+                .collect::<Vec<(RW, MemoryWordOp)>>(),
         );
 
         let copy_events = builder.block.copy_events.clone();
@@ -1,5 +1,5 @@
 //! Collection of structs and functions used to:
-//! - Define the internals of a [`MemoryOp`], [`StackOp`] and [`StorageOp`].
+//! - Define the internals of a [`MemoryWordOp`], [`StackOp`] and [`StorageOp`].
 //! - Define the actual operation types and a wrapper over them (the [`Operation`] enum).
 //! - Define structures that interact with operations such as [`OperationContainer`].
 pub(crate) mod container;
@@ -150,40 +150,55 @@ pub trait Op: Clone + Eq + Ord {
     fn reverse(&self) -> Self;
 }
 
-/// Represents a [`READ`](RW::READ)/[`WRITE`](RW::WRITE) into the memory implied
-/// by an specific [`OpcodeId`](eth_types::evm_types::opcode_ids::OpcodeId) of
-/// the [`ExecStep`](crate::circuit_input_builder::ExecStep).
+/// Represents a [`READ`](RW::READ)/[`WRITE`](RW::WRITE) of a 32-byte aligned
+/// memory word implied by an specific
+/// [`OpcodeId`](eth_types::evm_types::opcode_ids::OpcodeId) of the
+/// [`ExecStep`](crate::circuit_input_builder::ExecStep).
 #[derive(Clone, PartialEq, Eq)]
-pub struct MemoryOp {
+pub struct MemoryWordOp {
     /// Call ID
     pub call_id: usize,
-    /// Memory Address
+    /// Memory Address of the first byte of the word, a multiple of 32
     pub address: MemoryAddress,
-    /// Value
-    pub value: u8,
+    /// Value of the word, read from memory in big-endian order
+    pub value: Word,
+    /// Value of the word before a write, equal to `value` for a read
+    pub value_prev: Word,
 }
 
-impl fmt::Debug for MemoryOp {
+impl fmt::Debug for MemoryWordOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("MemoryOp { ")?;
+        f.write_str("MemoryWordOp { ")?;
         f.write_fmt(format_args!(
-            "call_id: {:?}, addr: {:?}, value: 0x{:02x}",
-            self.call_id, self.address, self.value
+            "call_id: {:?}, addr: {:?}, value: {:?}, value_prev: {:?}",
+            self.call_id, self.address, self.value, self.value_prev
         ))?;
         f.write_str(" }")
     }
 }
 
-impl MemoryOp {
-    /// Create a new instance of a `MemoryOp` from it's components.
-    pub fn new(call_id: usize, address: MemoryAddress, value: u8) -> MemoryOp {
-        MemoryOp {
+impl MemoryWordOp {
+    /// Create a new instance of a `MemoryWordOp` from it's components.
+    pub fn new(
+        call_id: usize,
+        address: MemoryAddress,
+        value: Word,
+        value_prev: Word,
+    ) -> MemoryWordOp {
+        debug_assert_eq!(address.0 % 32, 0, "memory word address must be aligned");
+        MemoryWordOp {
             call_id,
             address,
             value,
+            value_prev,
         }
     }
 
+    /// Create a new instance of a `MemoryWordOp` that reads `value`.
+    pub fn new_read(call_id: usize, address: MemoryAddress, value: Word) -> MemoryWordOp {
+        Self::new(call_id, address, value, value)
+    }
+
     /// Returns the [`Target`] (operation type) of this operation.
     pub const fn target(&self) -> Target {
         Target::Memory
@@ -199,29 +214,34 @@ impl MemoryOp {
         &self.address
     }
 
-    /// Returns the bytes read or written by this operation.
-    pub fn value(&self) -> u8 {
-        self.value
+    /// Returns the word read or written by this operation.
+    pub const fn value(&self) -> &Word {
+        &self.value
+    }
+
+    /// Returns the word before this operation.
+    pub const fn value_prev(&self) -> &Word {
+        &self.value_prev
     }
 }
 
-impl Op for MemoryOp {
+impl Op for MemoryWordOp {
     fn into_enum(self) -> OpEnum {
         OpEnum::Memory(self)
     }
 
     fn reverse(&self) -> Self {
-        unreachable!("MemoryOp can't be reverted")
+        unreachable!("MemoryWordOp can't be reverted")
     }
 }
 
-impl PartialOrd for MemoryOp {
+impl PartialOrd for MemoryWordOp {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for MemoryOp {
+impl Ord for MemoryWordOp {
     fn cmp(&self, other: &Self) -> Ordering {
         (&self.call_id, &self.address).cmp(&(&other.call_id, &other.address))
     }
@@ -931,13 +951,13 @@ impl Op for TxReceiptOp {
 }
 
 /// Generic enum that wraps over all the operation types possible.
-/// In particular [`StackOp`], [`MemoryOp`] and [`StorageOp`].
+/// In particular [`StackOp`], [`MemoryWordOp`] and [`StorageOp`].
 #[derive(Debug, Clone)]
 pub enum OpEnum {
     /// Stack
     Stack(StackOp),
     /// Memory
-    Memory(MemoryOp),
+    Memory(MemoryWordOp),
     /// Storage
     Storage(StorageOp),
     /// TxAccessListAccount
@@ -1055,7 +1075,7 @@ impl<T: Op> Operation<T> {
     //     matches!(*self, Operation::Stack(_))
     // }
 
-    // /// Returns true if the Operation hold internally is a [`MemoryOp`].
+    // /// Returns true if the Operation hold internally is a [`MemoryWordOp`].
     // pub const fn is_memory(&self) -> bool {
     //     matches!(*self, Operation::Memory(_))
     // }
@@ -1075,8 +1095,8 @@ impl<T: Op> Operation<T> {
     // }
 
     // /// Transmutes the internal (unlabeled) repr of the operation contained
-    // /// inside of the enum into a [`MemoryOp`].
-    // pub fn into_memory_unchecked(self) -> MemoryOp {
+    // /// inside of the enum into a [`MemoryWordOp`].
+    // pub fn into_memory_unchecked(self) -> MemoryWordOp {
     //     match self {
     //         Operation::Memory(memory_op) => memory_op,
     //         _ => panic!("Broken Invariant"),
@@ -1103,7 +1123,7 @@ mod operation_tests {
 
         let stack_op_as_operation = Operation::new(RWCounter(1), RW::WRITE, stack_op.clone());
 
-        let memory_op = MemoryOp::new(1, MemoryAddress(0x40), 0x40);
+        let memory_op = MemoryWordOp::new_read(1, MemoryAddress(0x40), Word::from(0x40));
 
         let memory_op_as_operation = Operation::new(RWCounter(1), RW::WRITE, memory_op.clone());
 
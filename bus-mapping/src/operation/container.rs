@@ -1,5 +1,5 @@
 use super::{
-    AccountOp, CallContextOp, MemoryOp, Op, OpEnum, Operation, RWCounter, StackOp, StartOp,
+    AccountOp, CallContextOp, MemoryWordOp, Op, OpEnum, Operation, RWCounter, StackOp, StartOp,
     StorageOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogOp, TxReceiptOp,
     TxRefundOp, RW,
 };
@@ -22,8 +22,8 @@ use itertools::Itertools;
 /// order to construct the State proof.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OperationContainer {
-    /// Operations of MemoryOp
-    pub memory: Vec<Operation<MemoryOp>>,
+    /// Operations of MemoryWordOp
+    pub memory: Vec<Operation<MemoryWordOp>>,
     /// Operations of StackOp
     pub stack: Vec<Operation<StackOp>>,
     /// Operations of StorageOp
@@ -167,9 +167,9 @@ impl OperationContainer {
         }
     }
 
-    /// Returns a sorted vector of all of the [`MemoryOp`]s contained inside of
+    /// Returns a sorted vector of all of the [`MemoryWordOp`]s contained inside of
     /// the container.
-    pub fn sorted_memory(&self) -> Vec<Operation<MemoryOp>> {
+    pub fn sorted_memory(&self) -> Vec<Operation<MemoryWordOp>> {
         self.memory.iter().sorted().cloned().collect()
     }
 
@@ -208,7 +208,7 @@ mod container_test {
         let memory_operation = Operation::new(
             global_counter.inc_pre(),
             RW::WRITE,
-            MemoryOp::new(1, MemoryAddress::from(1), 1),
+            MemoryWordOp::new(1, MemoryAddress::from(32), Word::from(1), Word::zero()),
         );
         let storage_operation = Operation::new(
             global_counter.inc_pre(),
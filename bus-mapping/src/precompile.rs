@@ -1,46 +1,55 @@
 //! precompile helpers
 
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
+    evm_types::{GasCost, Hardfork, OpcodeId},
     Address, Bytecode, Word,
 };
 #[cfg(not(target_arch = "wasm32"))]
 use revm_precompile::{Precompile, PrecompileError, Precompiles};
 
-#[allow(unused_variables)]
-/// Check if address is a precompiled or not.
+#[cfg(any(target_arch = "wasm32", test))]
+mod wasm;
+
+/// Check if address is a precompiled contract supported by the circuits,
+/// which are the ones from 0x01 to 0x09. The precompiled contracts of a
+/// hardfork are given by [`Hardfork::is_precompiled`], and a call to the other
+/// ones fails with [`Error::UnsupportedPrecompile`](crate::Error).
 pub fn is_precompiled(address: &Address) -> bool {
-    #[cfg(target_arch = "wasm32")]
-    if address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19]) {
-        // TODO add support for precompiles in WASM
-        panic!("Precompile {address} is currently not supported in WASM");
-    } else {
-        return false;
-    }
+    address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+}
 
-    #[cfg(not(target_arch = "wasm32"))]
-    Precompiles::berlin()
-        .get(address.as_fixed_bytes())
-        .is_some()
+/// Returns the precompiled contract of `hardfork` at `address` which the
+/// circuits do not support, if any.
+pub(crate) fn unsupported_precompile(hardfork: Hardfork, address: &Address) -> Option<Address> {
+    (hardfork.is_precompiled(address) && !is_precompiled(address)).then_some(*address)
 }
 
-#[allow(unused_variables)]
 pub(crate) fn execute_precompiled(
     address: &Address,
     input: &[u8],
     gas: u64,
 ) -> (Vec<u8>, u64, bool) {
     #[cfg(target_arch = "wasm32")]
-    // TODO add support for precompiles in WASM
-    panic!("Running precompile {address} is currently not supported in WASM");
+    let (return_data, gas_cost, is_oog, is_ok) = {
+        assert!(
+            is_precompiled(address),
+            "calling non-exist precompiled contract address"
+        );
+        match wasm::run(PrecompileCalls::from(address.0[19]), input, gas) {
+            Ok((return_value, gas_cost)) => (return_value, gas_cost, false, true),
+            Err(wasm::PrecompileFailure::OutOfGas) => (vec![], gas, true, false),
+            Err(wasm::PrecompileFailure::InvalidInput) => (vec![], gas, false, false),
+        }
+    };
 
+    // The supported precompiles are the same from Berlin to Cancun.
     #[cfg(not(target_arch = "wasm32"))]
-    {
+    let (return_data, gas_cost, is_oog, is_ok) = {
         let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
         .get(address.as_fixed_bytes())  else {
             panic!("calling non-exist precompiled contract address")
         };
-        let (return_data, gas_cost, is_oog, is_ok) = match precompile_fn(input, gas) {
+        match precompile_fn(input, gas) {
             Ok((gas_cost, return_value)) => {
                 // Some Revm behavior for invalid inputs might be overridden.
                 (return_value, gas_cost, false, true)
@@ -52,10 +61,11 @@ pub(crate) fn execute_precompiled(
                     (vec![], gas, false, false)
                 }
             },
-        };
-        log::trace!("called precompile with is_ok {is_ok} is_oog {is_oog}, gas_cost {gas_cost}, return_data len {}", return_data.len());
-        (return_data, gas_cost, is_oog)
-    }
+        }
+    };
+
+    log::trace!("called precompile with is_ok {is_ok} is_oog {is_oog}, gas_cost {gas_cost}, return_data len {}", return_data.len());
+    (return_data, gas_cost, is_oog)
 }
 
 /// Addresses of the precompiled contracts.
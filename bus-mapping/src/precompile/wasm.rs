@@ -0,0 +1,560 @@
+//! Pure-Rust execution of the precompiled contracts, for targets where
+//! revm-precompile is not available.  It follows the Berlin rules implemented
+//! by revm-precompile and gives the same results.
+
+use super::PrecompileCalls;
+use eth_types::{
+    keccak256,
+    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, recover_pk},
+    Word,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::{
+        bn256::{Bn256, Fq, Fq2, G1Affine, G2Affine, G2Prepared, Gt, G1, G2},
+        group::{prime::PrimeCurveAffine, Curve, Group},
+        pairing::{MillerLoopResult, MultiMillerLoop},
+    },
+};
+use num_bigint::BigUint;
+use sha2::Digest;
+
+/// Reason of a precompile call failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PrecompileFailure {
+    /// Not enough gas to run the precompile.
+    OutOfGas,
+    /// Invalid input, which consumes all the gas.
+    InvalidInput,
+}
+
+/// Run `precompile` on `input`, returning its output and gas cost.
+pub(crate) fn run(
+    precompile: PrecompileCalls,
+    input: &[u8],
+    gas: u64,
+) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    match precompile {
+        PrecompileCalls::ECRecover => ec_recover(input, gas),
+        PrecompileCalls::Sha256 => {
+            let gas_cost = linear_cost(input.len(), 60, 12, gas)?;
+            Ok((sha2::Sha256::digest(input).to_vec(), gas_cost))
+        }
+        PrecompileCalls::Ripemd160 => {
+            let gas_cost = linear_cost(input.len(), 600, 120, gas)?;
+            let mut output = vec![0u8; 32];
+            output[12..].copy_from_slice(&ripemd::Ripemd160::digest(input));
+            Ok((output, gas_cost))
+        }
+        PrecompileCalls::Identity => {
+            let gas_cost = linear_cost(input.len(), 15, 3, gas)?;
+            Ok((input.to_vec(), gas_cost))
+        }
+        PrecompileCalls::Modexp => modexp(input, gas),
+        PrecompileCalls::Bn128Add => bn128_add(input, gas),
+        PrecompileCalls::Bn128Mul => bn128_mul(input, gas),
+        PrecompileCalls::Bn128Pairing => bn128_pairing(input, gas),
+        PrecompileCalls::Blake2F => blake2f(input, gas),
+    }
+}
+
+fn check_gas(gas_cost: u64, gas: u64) -> Result<u64, PrecompileFailure> {
+    if gas_cost > gas {
+        Err(PrecompileFailure::OutOfGas)
+    } else {
+        Ok(gas_cost)
+    }
+}
+
+/// Cost of `base` plus `word` per 32-byte word of input.
+fn linear_cost(len: usize, base: u64, word: u64, gas: u64) -> Result<u64, PrecompileFailure> {
+    check_gas((len as u64 + 31) / 32 * word + base, gas)
+}
+
+/// Return `N` bytes of `input` starting at `offset`, right padded with zeros.
+fn padded<const N: usize>(input: &[u8], offset: usize) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    if offset < input.len() {
+        let len = (input.len() - offset).min(N);
+        bytes[..len].copy_from_slice(&input[offset..offset + len]);
+    }
+    bytes
+}
+
+fn ec_recover(input: &[u8], gas: u64) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    let gas_cost = check_gas(3000, gas)?;
+    let input = padded::<128>(input, 0);
+    // v must be 27 or 28, otherwise the call succeeds with no output.
+    if input[32..63].iter().any(|byte| *byte != 0) || !matches!(input[63], 27 | 28) {
+        return Ok((vec![], gas_cost));
+    }
+    let msg_hash: [u8; 32] = input[..32].try_into().unwrap();
+    let r = Word::from_big_endian(&input[64..96]);
+    let s = Word::from_big_endian(&input[96..128]);
+    let output = match recover_pk(input[63] - 27, &r, &s, &msg_hash) {
+        Ok(pk) => {
+            let pk_be = pk_bytes_swap_endianness(&pk_bytes_le(&pk));
+            let mut output = keccak256(&pk_be).to_vec();
+            output[..12].fill(0);
+            output
+        }
+        Err(_) => vec![],
+    };
+    Ok((output, gas_cost))
+}
+
+/// Read a length of the modexp header, which must fit into 64 bits.
+fn modexp_len(input: &[u8], offset: usize) -> Result<u64, PrecompileFailure> {
+    let len = Word::from_big_endian(&padded::<32>(input, offset));
+    if len > Word::from(u64::MAX) {
+        return Err(PrecompileFailure::InvalidInput);
+    }
+    Ok(len.as_u64())
+}
+
+fn modexp(input: &[u8], gas: u64) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    const MIN_GAS: u64 = 200;
+    let base_len = modexp_len(input, 0)?;
+    let mod_len = modexp_len(input, 64)?;
+    if base_len == 0 && mod_len == 0 {
+        return Ok((vec![], MIN_GAS));
+    }
+    let exp_len = modexp_len(input, 32)?;
+
+    // The leading 32 bytes of the exponent determine the iteration count.
+    let data = input.get(96..).unwrap_or_default();
+    let exp_head = {
+        let head_len = exp_len.min(32) as usize;
+        let exp_start = base_len.min(data.len() as u64) as usize;
+        let bytes = padded::<32>(data, exp_start);
+        Word::from_big_endian(&bytes[..head_len])
+    };
+    let exp_head_bits = exp_head.bits() as u64;
+    let iteration_count = if exp_len <= 32 {
+        exp_head_bits.saturating_sub(1)
+    } else {
+        8u64.saturating_mul(exp_len - 32)
+            .saturating_add(exp_head_bits.max(1) - 1)
+    }
+    .max(1);
+    // EIP-2565 gas cost
+    let words = Word::from((base_len.max(mod_len) + 7) / 8);
+    let gas_cost = words * words * Word::from(iteration_count) / Word::from(3u64);
+    let gas_cost = if gas_cost > Word::from(u64::MAX) {
+        u64::MAX
+    } else {
+        gas_cost.as_u64()
+    }
+    .max(MIN_GAS);
+    let gas_cost = check_gas(gas_cost, gas)?;
+
+    let read = |offset: u64, len: u64| {
+        let mut bytes = vec![0u8; len as usize];
+        if offset < data.len() as u64 {
+            let available = (data.len() as u64 - offset).min(len) as usize;
+            let offset = offset as usize;
+            bytes[..available].copy_from_slice(&data[offset..offset + available]);
+        }
+        BigUint::from_bytes_be(&bytes)
+    };
+    let base = read(0, base_len);
+    let exponent = read(base_len, exp_len);
+    let modulus = read(base_len.saturating_add(exp_len), mod_len);
+
+    let mut output = vec![0u8; mod_len as usize];
+    if modulus != BigUint::from(0u64) {
+        let result = base.modpow(&exponent, &modulus).to_bytes_be();
+        if result != [0] {
+            output[mod_len as usize - result.len()..].copy_from_slice(&result);
+        }
+    }
+    Ok((output, gas_cost))
+}
+
+/// Read a big-endian base field element, which must be reduced.
+fn read_fq(bytes: &[u8]) -> Result<Fq, PrecompileFailure> {
+    let mut le: [u8; 32] = bytes.try_into().unwrap();
+    le.reverse();
+    Option::from(Fq::from_bytes(&le)).ok_or(PrecompileFailure::InvalidInput)
+}
+
+fn write_fq(output: &mut [u8], fq: &Fq) {
+    let mut be = fq.to_bytes();
+    be.reverse();
+    output.copy_from_slice(&be);
+}
+
+/// Read a G1 point of 64 bytes, where (0, 0) is the point at infinity.
+fn read_g1(bytes: &[u8]) -> Result<G1, PrecompileFailure> {
+    let x = read_fq(&bytes[..32])?;
+    let y = read_fq(&bytes[32..64])?;
+    if x.is_zero_vartime() && y.is_zero_vartime() {
+        return Ok(G1::identity());
+    }
+    if y.square() != x.square() * x + Fq::from(3) {
+        return Err(PrecompileFailure::InvalidInput);
+    }
+    Ok(G1Affine { x, y }.into())
+}
+
+fn write_g1(point: G1) -> Vec<u8> {
+    let mut output = vec![0u8; 64];
+    if !bool::from(point.is_identity()) {
+        let point = point.to_affine();
+        write_fq(&mut output[..32], &point.x);
+        write_fq(&mut output[32..], &point.y);
+    }
+    output
+}
+
+/// Read a G2 point of 128 bytes, with the imaginary part of each coordinate
+/// first, where zero is the point at infinity.
+fn read_g2(bytes: &[u8]) -> Result<G2Affine, PrecompileFailure> {
+    let x = Fq2 {
+        c0: read_fq(&bytes[32..64])?,
+        c1: read_fq(&bytes[..32])?,
+    };
+    let y = Fq2 {
+        c0: read_fq(&bytes[96..128])?,
+        c1: read_fq(&bytes[64..96])?,
+    };
+    if x.is_zero_vartime() && y.is_zero_vartime() {
+        return Ok(G2Affine::identity());
+    }
+    // The twist has b = 3 / (9 + u).
+    let b = Fq2 {
+        c0: Fq::from(3),
+        c1: Fq::ZERO,
+    } * Fq2 {
+        c0: Fq::from(9),
+        c1: Fq::ONE,
+    }
+    .invert()
+    .unwrap();
+    if y.square() != x.square() * x + b {
+        return Err(PrecompileFailure::InvalidInput);
+    }
+    // Points of the twist have to be in the subgroup of order r.
+    let point = G2Affine { x, y };
+    if !bool::from(mul(G2::from(point), &BN256_ORDER).is_identity()) {
+        return Err(PrecompileFailure::InvalidInput);
+    }
+    Ok(point)
+}
+
+/// Order of the bn256 groups, in big endian.
+const BN256_ORDER: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Multiply `point` by a big-endian scalar of any size.
+fn mul<G: Group>(point: G, scalar: &[u8]) -> G {
+    let mut acc = G::identity();
+    for byte in scalar {
+        for bit in (0..8).rev() {
+            acc = acc.double();
+            if (byte >> bit) & 1 == 1 {
+                acc += point;
+            }
+        }
+    }
+    acc
+}
+
+fn bn128_add(input: &[u8], gas: u64) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    let gas_cost = check_gas(150, gas)?;
+    let input = padded::<128>(input, 0);
+    let sum = read_g1(&input[..64])? + read_g1(&input[64..])?;
+    Ok((write_g1(sum), gas_cost))
+}
+
+fn bn128_mul(input: &[u8], gas: u64) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    let gas_cost = check_gas(6000, gas)?;
+    let input = padded::<96>(input, 0);
+    let product = mul(read_g1(&input[..64])?, &input[64..]);
+    Ok((write_g1(product), gas_cost))
+}
+
+fn bn128_pairing(input: &[u8], gas: u64) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    const PAIR_LEN: usize = 192;
+    let gas_cost = check_gas(45000 + 34000 * (input.len() / PAIR_LEN) as u64, gas)?;
+    if input.len() % PAIR_LEN != 0 {
+        return Err(PrecompileFailure::InvalidInput);
+    }
+
+    let mut pairs = Vec::with_capacity(input.len() / PAIR_LEN);
+    for pair in input.chunks(PAIR_LEN) {
+        let g1 = read_g1(&pair[..64])?.to_affine();
+        let g2 = read_g2(&pair[64..])?;
+        // Pairs with the point at infinity don't change the product.
+        if !bool::from(g1.is_identity()) && !bool::from(g2.is_identity()) {
+            pairs.push((g1, G2Prepared::from(g2)));
+        }
+    }
+    let terms: Vec<_> = pairs.iter().map(|(g1, g2)| (g1, g2)).collect();
+    let is_one = Bn256::multi_miller_loop(&terms).final_exponentiation() == Gt::identity();
+
+    let mut output = vec![0u8; 32];
+    output[31] = is_one as u8;
+    Ok((output, gas_cost))
+}
+
+const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const BLAKE2B_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+#[allow(clippy::too_many_arguments)]
+fn blake2b_g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// The BLAKE2b compression function F, see EIP-152.
+fn blake2f(input: &[u8], gas: u64) -> Result<(Vec<u8>, u64), PrecompileFailure> {
+    if input.len() != 213 {
+        return Err(PrecompileFailure::InvalidInput);
+    }
+    let is_final = match input[212] {
+        0 => false,
+        1 => true,
+        _ => return Err(PrecompileFailure::InvalidInput),
+    };
+    let rounds = u32::from_be_bytes(input[..4].try_into().unwrap());
+    let gas_cost = check_gas(rounds as u64, gas)?;
+
+    let word = |offset: usize| u64::from_le_bytes(input[offset..offset + 8].try_into().unwrap());
+    let mut h: [u64; 8] = std::array::from_fn(|i| word(4 + i * 8));
+    let m: [u64; 16] = std::array::from_fn(|i| word(68 + i * 8));
+    let t = [word(196), word(204)];
+
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(&h);
+    v[8..].copy_from_slice(&BLAKE2B_IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if is_final {
+        v[14] = !v[14];
+    }
+    for round in 0..rounds as usize {
+        let s = &BLAKE2B_SIGMA[round % 10];
+        blake2b_g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        blake2b_g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        blake2b_g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        blake2b_g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        blake2b_g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        blake2b_g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        blake2b_g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        blake2b_g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+
+    Ok((
+        h.iter().flat_map(|word| word.to_le_bytes()).collect(),
+        gas_cost,
+    ))
+}
+
+#[cfg(all(test, feature = "notwasm"))]
+mod tests {
+    use super::*;
+    use eth_types::Address;
+    use pretty_assertions::assert_eq;
+    use revm_precompile::{Precompile, PrecompileError, Precompiles};
+
+    const G1: &str = "\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000002";
+    const G1_NEG: &str = "\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+    const G2: &str = "\
+        198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+        1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+        090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+        12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+
+    /// Run the precompile on both implementations and check they agree.
+    fn check(precompile: PrecompileCalls, input: &str, gas: u64) {
+        let input = hex::decode(input).unwrap();
+        let Some(Precompile::Standard(precompile_fn)) =
+            Precompiles::berlin().get(Address::from(precompile).as_fixed_bytes())
+        else {
+            unreachable!()
+        };
+        let expected = match precompile_fn(&input, gas) {
+            Ok((gas_cost, output)) => Ok((output, gas_cost)),
+            Err(PrecompileError::OutOfGas) => Err(PrecompileFailure::OutOfGas),
+            Err(_) => Err(PrecompileFailure::InvalidInput),
+        };
+        assert_eq!(
+            run(precompile, &input, gas),
+            expected,
+            "{precompile:?} with input {}",
+            hex::encode(&input)
+        );
+    }
+
+    #[test]
+    fn ec_recover() {
+        let signature = "\
+            456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3\
+            000000000000000000000000000000000000000000000000000000000000001c\
+            9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608\
+            4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada";
+        check(PrecompileCalls::ECRecover, signature, 3000);
+        check(PrecompileCalls::ECRecover, signature, 2999);
+        // Invalid v
+        check(
+            PrecompileCalls::ECRecover,
+            &signature.replace("1c9242", "1d9242"),
+            3000,
+        );
+        // Missing s
+        check(PrecompileCalls::ECRecover, &signature[..192], 3000);
+        check(PrecompileCalls::ECRecover, "", 3000);
+    }
+
+    #[test]
+    fn hashes_and_identity() {
+        for precompile in [
+            PrecompileCalls::Sha256,
+            PrecompileCalls::Ripemd160,
+            PrecompileCalls::Identity,
+        ] {
+            for input in ["", "ff", &"01".repeat(33)] {
+                check(precompile, input, 10_000);
+                check(precompile, input, 20);
+            }
+        }
+    }
+
+    #[test]
+    fn modexp() {
+        let header = |base_len: u8, exp_len: u8, mod_len: u8| {
+            format!("{base_len:064x}{exp_len:064x}{mod_len:064x}")
+        };
+        for input in [
+            // 3 ** (2 ** 256 - 2 ** 32 - 978) % (2 ** 256 - 2 ** 32 - 977)
+            format!(
+                "{}03{}{}",
+                header(1, 32, 32),
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e",
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"
+            ),
+            // Zero modulus
+            format!("{}0203{}", header(1, 1, 2), "0000"),
+            // Truncated input and long exponent
+            format!("{}0203{}", header(2, 40, 3), "ff".repeat(8)),
+            header(0, 0, 0),
+            header(0, 1, 1),
+            "".to_string(),
+        ] {
+            check(PrecompileCalls::Modexp, &input, 100_000);
+            check(PrecompileCalls::Modexp, &input, 199);
+        }
+        // Lengths over 64 bits
+        check(
+            PrecompileCalls::Modexp,
+            &format!("{}{}", "ff".repeat(32), header(0, 0, 1)),
+            100_000,
+        );
+    }
+
+    #[test]
+    fn bn128_add_and_mul() {
+        let invalid_point = G1.replace("02", "03");
+        for input in [
+            format!("{G1}{G1}"),
+            format!("{G1}{G1_NEG}"),
+            G1.to_string(),
+            format!("{G1}{invalid_point}"),
+            "".to_string(),
+        ] {
+            check(PrecompileCalls::Bn128Add, &input, 150);
+            check(PrecompileCalls::Bn128Add, &input, 149);
+        }
+        for input in [
+            format!("{G1}{:064x}", 2),
+            format!("{G1}{}", "ff".repeat(32)),
+            format!(
+                "{G1}{}",
+                "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+            ),
+            format!("{invalid_point}{:064x}", 2),
+            G1.to_string(),
+        ] {
+            check(PrecompileCalls::Bn128Mul, &input, 6000);
+            check(PrecompileCalls::Bn128Mul, &input, 5999);
+        }
+    }
+
+    #[test]
+    fn bn128_pairing() {
+        let invalid_g2 = G2.replace("5b12c8", "5c12c8");
+        for input in [
+            format!("{G1}{G2}{G1_NEG}{G2}"),
+            format!("{G1}{G2}{G1}{G2}"),
+            format!("{G1}{}", "00".repeat(128)),
+            format!("{G1}{invalid_g2}"),
+            G1.to_string(),
+            "".to_string(),
+        ] {
+            check(PrecompileCalls::Bn128Pairing, &input, 200_000);
+            check(PrecompileCalls::Bn128Pairing, &input, 45_000);
+        }
+    }
+
+    #[test]
+    fn blake2f() {
+        // Test vector 5 of EIP-152
+        let h = "\
+            48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+            d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b";
+        let m = format!("616263{}", "00".repeat(125));
+        let t = format!("03{}", "00".repeat(15));
+        let input = &format!("0000000c{h}{m}{t}01");
+        check(PrecompileCalls::Blake2F, input, 12);
+        check(PrecompileCalls::Blake2F, input, 11);
+        check(
+            PrecompileCalls::Blake2F,
+            &input.replace("0000000c", "00000000"),
+            0,
+        );
+        // Final block flag must be 0 or 1
+        check(
+            PrecompileCalls::Blake2F,
+            &format!("{}02", &input[..424]),
+            12,
+        );
+        check(PrecompileCalls::Blake2F, &input[..424], 12);
+    }
+}
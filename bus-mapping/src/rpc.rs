@@ -3,8 +3,9 @@
 
 use crate::Error;
 use eth_types::{
-    Address, Block, Bytes, EIP1186ProofResponse, GethExecTrace, Hash, ResultGethExecTraces,
-    Transaction, Word, U64,
+    Address, Block, Bytes, EIP1186ProofResponse, GethCallTrace, GethExecTrace, GethPrestateTrace,
+    Hash, ResultGethCallTraces, ResultGethExecTraces, ResultGethPrestateTraces, Transaction,
+    TransactionReceipt, Word, U64,
 };
 pub use ethers_core::types::BlockNumber;
 use ethers_providers::JsonRpcClient;
@@ -132,6 +133,50 @@ impl<P: JsonRpcClient> GethClient<P> {
         Ok(resp.0.into_iter().map(|step| step.result).collect())
     }
 
+    /// Calls `debug_traceBlockByNumber` with the `prestateTracer` via JSON-RPC
+    /// returning a [`Vec<GethPrestateTrace>`] with the state touched by each
+    /// transaction of the block before it is executed.
+    pub async fn trace_block_prestate_by_number(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<Vec<GethPrestateTrace>, Error> {
+        let num = serialize(&block_num);
+        let cfg = serde_json::json!({ "tracer": "prestateTracer" });
+        let resp: ResultGethPrestateTraces = self
+            .0
+            .request("debug_traceBlockByNumber", [num, cfg])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
+        Ok(resp.0.into_iter().map(|trace| trace.result).collect())
+    }
+
+    /// Calls `debug_traceBlockByNumber` with the `callTracer` via JSON-RPC
+    /// returning a [`Vec<GethCallTrace>`] with the calls made by each
+    /// transaction of the block.
+    pub async fn trace_block_calls_by_number(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<Vec<GethCallTrace>, Error> {
+        let num = serialize(&block_num);
+        let cfg = serde_json::json!({ "tracer": "callTracer" });
+        let resp: ResultGethCallTraces = self
+            .0
+            .request("debug_traceBlockByNumber", [num, cfg])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
+        Ok(resp.0.into_iter().map(|trace| trace.result).collect())
+    }
+
+    /// Calls `eth_getTransactionReceipt` via JSON-RPC returning the receipt of
+    /// a mined transaction.
+    pub async fn get_transaction_receipt(&self, hash: Hash) -> Result<TransactionReceipt, Error> {
+        let hash = serialize(&hash);
+        self.0
+            .request("eth_getTransactionReceipt", [hash])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))
+    }
+
     /// Calls `eth_getCode` via JSON-RPC returning a contract code
     pub async fn get_code(
         &self,
@@ -143,6 +143,9 @@ pub struct StateDB {
     // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
     // `true`. These accounts will be reset once `commit_tx` is called.
     destructed_account: HashSet<Address>,
+    // Accounts created in the current transaction, which `SELFDESTRUCT` still deletes after
+    // EIP-6780.
+    created_account: HashSet<Address>,
     refund: u64,
 }
 
@@ -286,6 +289,16 @@ impl StateDB {
         self.destructed_account.insert(addr);
     }
 
+    /// Set account as created in the current transaction.
+    pub fn create_account_in_tx(&mut self, addr: Address) {
+        self.created_account.insert(addr);
+    }
+
+    /// Returns `true` if the account was created in the current transaction.
+    pub fn is_account_created_in_tx(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
     /// Retrieve refund.
     pub fn refund(&self) -> u64 {
         self.refund
@@ -311,6 +324,7 @@ impl StateDB {
             let (_, account) = self.get_account_mut(&addr);
             *account = ACCOUNT_ZERO.clone();
         }
+        self.created_account = HashSet::new();
         self.refund = 0;
     }
 }
@@ -8,7 +8,7 @@ mod tests {
     use ethers_signers::{LocalWallet, Signer};
     use halo2_proofs::{
         halo2curves::bn256::{Bn256, Fr, G1Affine},
-        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit},
         poly::{
             commitment::ParamsProver,
             kzg::{
@@ -25,7 +25,10 @@ mod tests {
     use rand::SeedableRng;
     use rand_chacha::ChaChaRng;
     use std::{collections::HashMap, env::var};
-    use zkevm_circuits::super_circuit::SuperCircuit;
+    use zkevm_circuits::{
+        key_store::{KeyDescriptor, KeyStore},
+        super_circuit::SuperCircuit,
+    };
 
     #[cfg_attr(not(feature = "benches"), ignore)]
     #[test]
@@ -97,13 +100,33 @@ mod tests {
         // Bench setup generation
         let setup_message = format!("{} {} with degree = {}", BENCHMARK_ID, setup_prfx, degree);
         let start1 = start_timer!(|| setup_message);
-        let general_params = ParamsKZG::<Bn256>::setup(degree, &mut rng);
+        // Reuse params and keys from previous runs when `KEY_STORE_DIR` is set
+        let key_store = var("KEY_STORE_DIR")
+            .ok()
+            .map(|dir| KeyStore::new(dir).expect("cannot open key store"));
+        let general_params = match &key_store {
+            Some(store) => store
+                .params_or_insecure_setup(degree, &mut rng)
+                .expect("cannot load or generate params"),
+            None => ParamsKZG::<Bn256>::setup(degree, &mut rng),
+        };
         let verifier_params: ParamsVerifierKZG<Bn256> = general_params.verifier_params().clone();
         end_timer!(start1);
 
         // Initialize the proving key
-        let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk should not fail");
-        let pk = keygen_pk(&general_params, vk, &circuit).expect("keygen_pk should not fail");
+        let pk = match &key_store {
+            Some(store) => {
+                let descriptor =
+                    KeyDescriptor::new("super", degree, &(circuits_params, circuit.params()));
+                store
+                    .proving_key(&descriptor, &general_params, &circuit)
+                    .expect("cannot load or generate the proving key")
+            }
+            None => {
+                let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk should not fail");
+                keygen_pk(&general_params, vk, &circuit).expect("keygen_pk should not fail")
+            }
+        };
         // Create a proof
         let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
 
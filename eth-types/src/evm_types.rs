@@ -4,11 +4,13 @@
 // use std::fmt;
 
 pub mod gas_utils;
+pub mod hardfork;
 pub mod memory;
 pub mod opcode_ids;
 pub mod stack;
 pub mod storage;
 
+pub use hardfork::Hardfork;
 pub use memory::{Memory, MemoryAddress};
 pub use opcode_ids::OpcodeId;
 pub use stack::{Stack, StackAddress};
@@ -28,38 +30,8 @@ pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
 /// <https://github.com/ethereum/go-ethereum/blob/e6b6a8b738069ad0579f6798ee59fde93ed13b43/core/vm/gas_table.go#L38>
 pub const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
 
-#[cfg(feature = "shanghai")]
-mod gas_create {
-    // For EIP-3860, there are 2 special gas cost constraints in geth
-    // [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
-    // (similar for CREATE).
-    // 1. size <= 49152 (MaxInitCodeSize)
-    // 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not
-    //    overflow for Uint64.
-    // No need to constrain the second condition, since the maximum gas cost
-    // cannot overflow for Uint64 (36028809887100925 calculated by
-    // `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
-    // satisfied.
-
-    /// Maximum init code size to permit in a creation transaction and create instructions.
-    pub const MAX_INIT_CODE_SIZE: u64 = 2 * super::MAX_CODE_SIZE;
-    /// Once per word of the init code when creating a contract.
-    pub const INIT_CODE_WORD_GAS: u64 = 2;
-    /// Gas per code word for CREATE.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
-    /// Gas per code word for CREATE2.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS + super::GasCost::COPY_SHA3.0;
-}
-#[cfg(not(feature = "shanghai"))]
-mod gas_create {
-    /// Maximum init code size (0x1FFFFFFFE0) if not EIP-3860.
-    pub use super::MAX_EXPANDED_MEMORY_ADDRESS as MAX_INIT_CODE_SIZE;
-    /// Gas per code word for CREATE if not EIP-3860.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = 0;
-    /// Gas per code word for CREATE2 if not EIP-3860.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = super::GasCost::COPY_SHA3;
-}
-pub use gas_create::*;
+/// Maximum contract code size to permit in a deployment (EIP-170).
+pub const MAX_CODE_SIZE: u64 = 24576;
 
 /// Defines the gas consumption.
 pub struct GasCost;
@@ -155,6 +127,3 @@ impl GasCost {
     /// Base gas cost for precompile call: BLAKE2F
     pub const PRECOMPILE_BLAKE2F: u64 = 0;
 }
-
-/// This constant is used to iterate through precompile contract addresses 0x01 to 0x09
-pub const PRECOMPILE_COUNT: u64 = 9;
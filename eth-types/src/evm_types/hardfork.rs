@@ -0,0 +1,166 @@
+//! Hardfork dependent EVM rules
+use super::{OpcodeId, INIT_CODE_WORD_GAS, MAX_CODE_SIZE, MAX_EXPANDED_MEMORY_ADDRESS};
+use crate::Address;
+use serde::{Deserialize, Serialize};
+
+/// Ethereum hardforks supported by the circuits, in activation order.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+pub enum Hardfork {
+    /// London, with the EIP-1559 fee market and the EIP-3529 refund rules.
+    London,
+    /// Paris (the Merge), where `DIFFICULTY` returns `PREVRANDAO`.
+    Paris,
+    /// Shanghai, adding `PUSH0` (EIP-3855), the warm coinbase (EIP-3651) and
+    /// init code metering (EIP-3860).
+    #[default]
+    Shanghai,
+    /// Cancun, adding the transient storage (EIP-1153), `MCOPY` (EIP-5656), the
+    /// blob opcodes and point evaluation precompile (EIP-4844, EIP-7516) and
+    /// restricting `SELFDESTRUCT` (EIP-6780).
+    Cancun,
+}
+
+impl Hardfork {
+    /// Returns `true` if `opcode` is defined in this hardfork.
+    pub fn is_opcode_enabled(&self, opcode: OpcodeId) -> bool {
+        match opcode {
+            OpcodeId::INVALID(_) => false,
+            OpcodeId::PUSH0 => *self >= Self::Shanghai,
+            OpcodeId::TLOAD
+            | OpcodeId::TSTORE
+            | OpcodeId::MCOPY
+            | OpcodeId::BLOBHASH
+            | OpcodeId::BLOBBASEFEE => *self >= Self::Cancun,
+            _ => true,
+        }
+    }
+
+    /// Returns the opcodes defined in this hardfork.
+    pub fn valid_opcodes(&self) -> Vec<OpcodeId> {
+        (u8::MIN..=u8::MAX)
+            .map(OpcodeId::from)
+            .filter(|opcode| self.is_opcode_enabled(*opcode))
+            .collect()
+    }
+
+    /// Returns the opcodes which halt with an invalid opcode error in this
+    /// hardfork.
+    pub fn invalid_opcodes(&self) -> Vec<OpcodeId> {
+        (u8::MIN..=u8::MAX)
+            .map(OpcodeId::from)
+            .filter(|opcode| !self.is_opcode_enabled(*opcode))
+            .collect()
+    }
+
+    /// Number of precompiled contracts, which are at the addresses from 0x01
+    /// up to this count. Cancun adds the point evaluation precompile at 0x0a.
+    pub fn precompile_count(&self) -> u64 {
+        if *self >= Self::Cancun {
+            10
+        } else {
+            9
+        }
+    }
+
+    /// Returns `true` if `address` is a precompiled contract in this hardfork.
+    pub fn is_precompiled(&self, address: &Address) -> bool {
+        address.0[0..19] == [0u8; 19]
+            && (1..=self.precompile_count()).contains(&(address.0[19] as u64))
+    }
+
+    /// Returns `true` if `SELFDESTRUCT` only deletes an account created in the
+    /// same transaction, and otherwise only sends its balance (EIP-6780).
+    pub fn is_selfdestruct_restricted(&self) -> bool {
+        *self >= Self::Cancun
+    }
+
+    /// Returns `true` if the coinbase is added to the access list at the
+    /// beginning of a transaction (EIP-3651).
+    pub fn is_coinbase_warm(&self) -> bool {
+        *self >= Self::Shanghai
+    }
+
+    /// Gas charged per word of init code when creating a contract (EIP-3860).
+    pub fn init_code_word_gas(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            INIT_CODE_WORD_GAS
+        } else {
+            0
+        }
+    }
+
+    /// Maximum init code size permitted in a creation transaction and in
+    /// `CREATE`/`CREATE2` (EIP-3860). Before Shanghai the size is only bounded
+    /// by the memory expansion limit.
+    ///
+    /// Within the limit, the gas cost of a create cannot overflow a `u64`
+    /// (36028809887100925 with a memory size of 0x1FFFFFFFE0 and an init code
+    /// of 49152 bytes), so geth's second overflow check needs no constraint.
+    pub fn max_init_code_size(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            2 * MAX_CODE_SIZE
+        } else {
+            MAX_EXPANDED_MEMORY_ADDRESS
+        }
+    }
+}
+
+#[cfg(test)]
+mod hardfork_tests {
+    use super::*;
+
+    #[test]
+    fn opcodes_by_hardfork() {
+        let cancun_opcodes = [
+            OpcodeId::TLOAD,
+            OpcodeId::TSTORE,
+            OpcodeId::MCOPY,
+            OpcodeId::BLOBHASH,
+            OpcodeId::BLOBBASEFEE,
+        ];
+        for (hardfork, push0, cancun) in [
+            (Hardfork::London, false, false),
+            (Hardfork::Paris, false, false),
+            (Hardfork::Shanghai, true, false),
+            (Hardfork::Cancun, true, true),
+        ] {
+            assert_eq!(hardfork.is_opcode_enabled(OpcodeId::PUSH0), push0);
+            for opcode in cancun_opcodes {
+                assert_eq!(hardfork.is_opcode_enabled(opcode), cancun);
+            }
+            assert_eq!(
+                hardfork.valid_opcodes().len() + hardfork.invalid_opcodes().len(),
+                256
+            );
+        }
+        for (byte, opcode) in [0x5c, 0x5d, 0x5e, 0x49, 0x4a]
+            .into_iter()
+            .zip(cancun_opcodes)
+        {
+            assert_eq!(OpcodeId::from(byte), opcode);
+        }
+        assert!(Hardfork::Cancun
+            .invalid_opcodes()
+            .iter()
+            .all(|opcode| matches!(opcode, OpcodeId::INVALID(_))));
+    }
+
+    #[test]
+    fn precompiles_by_hardfork() {
+        let point_evaluation = Address::from_low_u64_be(0x0a);
+        assert!(!Hardfork::Shanghai.is_precompiled(&point_evaluation));
+        assert!(Hardfork::Cancun.is_precompiled(&point_evaluation));
+        assert!(Hardfork::London.is_precompiled(&Address::from_low_u64_be(0x09)));
+        assert!(!Hardfork::Cancun.is_precompiled(&Address::zero()));
+    }
+
+    #[test]
+    fn init_code_limits_by_hardfork() {
+        assert_eq!(Hardfork::Paris.max_init_code_size(), 0x1FFFFFFFE0);
+        assert_eq!(Hardfork::Paris.init_code_word_gas(), 0);
+        assert_eq!(Hardfork::Shanghai.max_init_code_size(), 49152);
+        assert_eq!(Hardfork::Cancun.init_code_word_gas(), 2);
+    }
+}
@@ -5,7 +5,7 @@ use core::{
     str::FromStr,
 };
 use itertools::Itertools;
-use serde::{Serialize, Serializer};
+use serde::{ser::SerializeSeq, Serialize, Serializer};
 use std::{cmp, fmt};
 
 /// Represents a `MemoryAddress` of the EVM.
@@ -254,8 +254,14 @@ impl Serialize for Memory {
     where
         S: Serializer,
     {
-        let encoded = hex::encode(&self.0);
-        serializer.serialize_str(encoded.as_str())
+        // Same format as geth: chunks of 32 bytes, in hex.
+        let mut ser = serializer.serialize_seq(Some((self.0.len() + 31) / 32))?;
+        for chunk in self.0.chunks(32) {
+            let mut word = [0u8; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            ser.serialize_element(&hex::encode(word))?;
+        }
+        ser.end()
     }
 }
 
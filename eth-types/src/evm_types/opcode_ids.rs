@@ -8,7 +8,7 @@ use std::{fmt, matches, str::FromStr};
 use strum_macros::EnumIter;
 
 /// Opcode enum. One-to-one corresponding to an `u8` value.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Hash, EnumIter)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, EnumIter)]
 pub enum OpcodeId {
     /// `STOP`
     STOP,
@@ -93,6 +93,12 @@ pub enum OpcodeId {
     MSIZE,
     /// `JUMPDEST`
     JUMPDEST,
+    /// `TLOAD`
+    TLOAD,
+    /// `TSTORE`
+    TSTORE,
+    /// `MCOPY`
+    MCOPY,
 
     // PUSHn
     /// `PUSH0`
@@ -281,6 +287,10 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
+    /// `BLOBBASEFEE`
+    BLOBBASEFEE,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
@@ -409,6 +419,9 @@ impl OpcodeId {
             OpcodeId::PC => 0x58u8,
             OpcodeId::MSIZE => 0x59u8,
             OpcodeId::JUMPDEST => 0x5bu8,
+            OpcodeId::TLOAD => 0x5cu8,
+            OpcodeId::TSTORE => 0x5du8,
+            OpcodeId::MCOPY => 0x5eu8,
             OpcodeId::PUSH0 => 0x5fu8,
             OpcodeId::PUSH1 => 0x60u8,
             OpcodeId::PUSH2 => 0x61u8,
@@ -498,6 +511,8 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
+            OpcodeId::BLOBBASEFEE => 0x4au8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
             OpcodeId::GAS => 0x5au8,
@@ -576,6 +591,8 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
+            OpcodeId::BLOBBASEFEE => GasCost::QUICK,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
@@ -588,6 +605,9 @@ impl OpcodeId {
             OpcodeId::MSIZE => GasCost::QUICK,
             OpcodeId::GAS => GasCost::QUICK,
             OpcodeId::JUMPDEST => GasCost::ONE,
+            OpcodeId::TLOAD => GasCost::WARM_ACCESS,
+            OpcodeId::TSTORE => GasCost::WARM_ACCESS,
+            OpcodeId::MCOPY => GasCost::FASTEST,
             OpcodeId::PUSH0 => GasCost::QUICK,
             OpcodeId::PUSH1 => GasCost::FASTEST,
             OpcodeId::PUSH2 => GasCost::FASTEST,
@@ -731,6 +751,8 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
+            OpcodeId::BLOBBASEFEE => (1, 1024),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
@@ -743,6 +765,9 @@ impl OpcodeId {
             OpcodeId::MSIZE => (1, 1024),
             OpcodeId::GAS => (1, 1024),
             OpcodeId::JUMPDEST => (0, 1024),
+            OpcodeId::TLOAD => (0, 1023),
+            OpcodeId::TSTORE => (0, 1022),
+            OpcodeId::MCOPY => (0, 1021),
             OpcodeId::PUSH0 => (1, 1024),
             OpcodeId::PUSH1 => (1, 1024),
             OpcodeId::PUSH2 => (1, 1024),
@@ -845,6 +870,7 @@ impl OpcodeId {
                 | OpcodeId::RETURNDATACOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
+                | OpcodeId::MCOPY
         )
     }
 
@@ -948,6 +974,9 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
+            0x5cu8 => OpcodeId::TLOAD,
+            0x5du8 => OpcodeId::TSTORE,
+            0x5eu8 => OpcodeId::MCOPY,
             0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
             0x61u8 => OpcodeId::PUSH2,
@@ -1037,6 +1066,8 @@ impl From<u8> for OpcodeId {
             0x46u8 => OpcodeId::CHAINID,
             0x47u8 => OpcodeId::SELFBALANCE,
             0x48u8 => OpcodeId::BASEFEE,
+            0x49u8 => OpcodeId::BLOBHASH,
+            0x4au8 => OpcodeId::BLOBBASEFEE,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
             0x5au8 => OpcodeId::GAS,
@@ -1102,6 +1133,7 @@ impl FromStr for OpcodeId {
             "PC" => OpcodeId::PC,
             "MSIZE" => OpcodeId::MSIZE,
             "JUMPDEST" => OpcodeId::JUMPDEST,
+            "MCOPY" => OpcodeId::MCOPY,
             "PUSH0" => OpcodeId::PUSH0,
             "PUSH1" => OpcodeId::PUSH1,
             "PUSH2" => OpcodeId::PUSH2,
@@ -1206,6 +1238,10 @@ impl FromStr for OpcodeId {
             "SELFDESTRUCT" => OpcodeId::SELFDESTRUCT,
             "CHAINID" => OpcodeId::CHAINID,
             "BASEFEE" => OpcodeId::BASEFEE,
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            "BLOBBASEFEE" => OpcodeId::BLOBBASEFEE,
+            // The draft EIP-1153 opcodes of the pinned geth (v1.11.5), see
+            // `OpcodeId::TLOAD` and `OpcodeId::TSTORE` for the final ones
             "TLOAD" => OpcodeId::INVALID(0xb3),
             "TSTORE" => OpcodeId::INVALID(0xb4),
             _ => {
@@ -1213,6 +1249,8 @@ impl FromStr for OpcodeId {
                 lazy_static! {
                     static ref RE: Regex = Regex::new("opcode 0x([[:xdigit:]]{1,2}) not defined")
                         .expect("invalid regex");
+                    static ref BYTE_RE: Regex =
+                        Regex::new("^0x([[:xdigit:]]{1,2})$").expect("invalid regex");
                 }
                 if let Some(cap) = RE.captures(s) {
                     if let Some(byte_hex) = cap.get(1).map(|m| m.as_str()) {
@@ -1221,6 +1259,14 @@ impl FromStr for OpcodeId {
                         ));
                     }
                 }
+                // Parse an opcode serialized as its byte, see `Serialize`
+                if let Some(cap) = BYTE_RE.captures(s) {
+                    if let Some(byte_hex) = cap.get(1).map(|m| m.as_str()) {
+                        return Ok(OpcodeId::from(
+                            u8::from_str_radix(byte_hex, 16).expect("invalid hex byte from regex"),
+                        ));
+                    }
+                }
                 return Err(Error::OpcodeParsing(s.to_string()));
             }
         })
@@ -1237,6 +1283,25 @@ impl<'de> Deserialize<'de> for OpcodeId {
     }
 }
 
+impl Serialize for OpcodeId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            // Same format as geth, so that it can be parsed back by `from_str`
+            OpcodeId::INVALID(b) => {
+                serializer.serialize_str(&format!("opcode 0x{:x} not defined", b))
+            }
+            // The names the pinned geth gives to other bytes, see `from_str`
+            OpcodeId::TLOAD | OpcodeId::TSTORE => {
+                serializer.serialize_str(&format!("0x{:x}", self.as_u8()))
+            }
+            _ => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
 impl fmt::Display for OpcodeId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -1267,6 +1332,31 @@ mod opcode_ids_tests {
         assert_eq!(OpcodeId::CALLCODE.postfix(), None);
     }
 
+    #[test]
+    fn from_str_geth() {
+        assert_eq!(
+            OpcodeId::from_str("TLOAD").unwrap(),
+            OpcodeId::INVALID(0xb3)
+        );
+        assert_eq!(
+            OpcodeId::from_str("TSTORE").unwrap(),
+            OpcodeId::INVALID(0xb4)
+        );
+        assert_eq!(
+            OpcodeId::from_str("opcode 0x5c not defined").unwrap(),
+            OpcodeId::INVALID(0x5c)
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        for byte in u8::MIN..=u8::MAX {
+            let opcode = OpcodeId::from(byte);
+            let json = serde_json::to_string(&opcode).unwrap();
+            assert_eq!(serde_json::from_str::<OpcodeId>(&json).unwrap(), opcode);
+        }
+    }
+
     #[test]
     fn data_len() {
         assert_eq!(OpcodeId::PUSH0.data_len(), 0);
@@ -1,14 +1,17 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
-    evm_types::{self, GasCost},
+    evm_types::{GasCost, Hardfork},
     keccak256,
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
-    AccessList, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash, ToBigEndian,
-    ToLittleEndian, ToWord, Word, U64,
+    AccessList, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash, Signature,
+    ToBigEndian, ToLittleEndian, ToWord, Word, U64,
 };
 use ethers_core::{
-    types::{transaction::response, NameOrAddress, TransactionRequest},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip2930::Eip2930TransactionRequest, response},
+        Eip1559TransactionRequest, NameOrAddress, TransactionRequest,
+    },
     utils::get_contract_address,
 };
 use ethers_signers::{LocalWallet, Signer};
@@ -156,6 +159,8 @@ pub struct Withdrawal {
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
+    /// EIP-2718 transaction type (0 for legacy, 1 for EIP-2930, 2 for EIP-1559)
+    pub transaction_type: U64,
     /// Sender address
     pub from: Address,
     /// Recipient address (None for contract creation)
@@ -193,6 +198,7 @@ pub struct Transaction {
 impl From<&Transaction> for crate::Transaction {
     fn from(tx: &Transaction) -> crate::Transaction {
         crate::Transaction {
+            transaction_type: Some(tx.transaction_type),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.to_word(),
@@ -214,6 +220,7 @@ impl From<&Transaction> for crate::Transaction {
 impl From<&crate::Transaction> for Transaction {
     fn from(tx: &crate::Transaction) -> Transaction {
         Transaction {
+            transaction_type: tx.transaction_type.unwrap_or_default(),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.as_u64().into(),
@@ -259,14 +266,17 @@ impl Transaction {
             secp256k1::Fq::from_repr(sig_s_le),
             Error::Signature(libsecp256k1::Error::InvalidSignature),
         )?;
-        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s])
-        let req: TransactionRequest = self.into();
-        let msg = req.chain_id(chain_id).rlp();
-        let msg_hash: [u8; 32] = keccak256(&msg);
-        let v = self
-            .v
-            .checked_sub(35 + chain_id * 2)
-            .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
+        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s]) for legacy txs,
+        // and tx_type || rlp([chain_id, nonce, ...]) for typed (EIP-2718) txs.
+        let msg_hash: [u8; 32] = self.typed_tx(chain_id).sighash().to_fixed_bytes();
+        // Typed txs carry the y-parity directly, legacy ones the EIP-155 `v`.
+        let v = if self.v > 1 {
+            self.v
+                .checked_sub(35 + chain_id * 2)
+                .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8
+        } else {
+            self.v as u8
+        };
         let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
         // msg_hash = msg_hash % q
         let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
@@ -283,6 +293,46 @@ impl Transaction {
         })
     }
 
+    /// Return the transaction as the ethers request matching its EIP-2718 type.
+    pub fn typed_tx(&self, chain_id: u64) -> TypedTransaction {
+        let req: TransactionRequest = self.into();
+        let req = req.chain_id(chain_id);
+        match self.transaction_type.as_u64() {
+            1 => Eip2930TransactionRequest::new(req, self.access_list.clone().unwrap_or_default())
+                .into(),
+            2 => Eip1559TransactionRequest {
+                from: req.from,
+                to: req.to,
+                gas: req.gas,
+                value: req.value,
+                data: req.data,
+                nonce: req.nonce,
+                access_list: self.access_list.clone().unwrap_or_default(),
+                max_priority_fee_per_gas: Some(self.gas_tip_cap),
+                max_fee_per_gas: Some(self.gas_fee_cap),
+                chain_id: req.chain_id,
+            }
+            .into(),
+            _ => req.into(),
+        }
+    }
+
+    /// Return the signed transaction encoding as it is stored in the block's
+    /// transactions trie: the plain RLP list for legacy txs and
+    /// `tx_type || rlp(...)` for typed ones (EIP-2718).
+    pub fn rlp_signed(&self, chain_id: u64) -> Bytes {
+        self.typed_tx(chain_id).rlp_signed(&Signature {
+            r: self.r,
+            s: self.s,
+            v: self.v,
+        })
+    }
+
+    /// Return the transaction hash, i.e. the keccak of [`Self::rlp_signed`].
+    pub fn hash(&self, chain_id: u64) -> Hash {
+        Hash::from(keccak256(self.rlp_signed(chain_id)))
+    }
+
     /// Compute call data gas cost from call data
     pub fn call_data_gas_cost(&self) -> u64 {
         self.call_data
@@ -290,12 +340,12 @@ impl Transaction {
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
     }
 
-    /// Compute the intrinsic gas cost
-    pub fn intrinsic_gas_cost(&self) -> u64 {
+    /// Compute the intrinsic gas cost under the rules of `hardfork`
+    pub fn intrinsic_gas_cost(&self, hardfork: Hardfork) -> u64 {
         let is_create = self.is_create() as u64;
         // Calculate gas cost of init code for EIP-3860.
         let init_code_gas_cost =
-            ((self.call_data.len() as u64 + 31) / 32) * evm_types::INIT_CODE_WORD_GAS;
+            ((self.call_data.len() as u64 + 31) / 32) * hardfork.init_code_word_gas();
         is_create * (GasCost::CREATION_TX + init_code_gas_cost)
             + (1 - is_create) * GasCost::TX
             + self.call_data_gas_cost()
@@ -369,9 +419,8 @@ impl GethData {
             let wallet = wallets.get(&tx.from).unwrap();
             assert_eq!(Word::from(wallet.chain_id()), self.chain_id);
             let geth_tx: Transaction = (&*tx).into();
-            let req: TransactionRequest = (&geth_tx).into();
             let sig = wallet
-                .sign_transaction_sync(&req.chain_id(self.chain_id.as_u64()).into())
+                .sign_transaction_sync(&geth_tx.typed_tx(self.chain_id.as_u64()))
                 .unwrap();
             tx.v = U64::from(sig.v);
             tx.r = sig.r;
@@ -379,3 +428,67 @@ impl GethData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::ordered_trie_root;
+    use ethers_core::types::transaction::eip2930::AccessListItem;
+
+    const CHAIN_ID: u64 = 1;
+
+    fn tx(transaction_type: u64) -> Transaction {
+        Transaction {
+            transaction_type: transaction_type.into(),
+            from: Address::repeat_byte(0x11),
+            to: Some(Address::repeat_byte(0x22)),
+            nonce: 7.into(),
+            gas_limit: 50_000.into(),
+            value: Word::from(1_000_000_000u64),
+            gas_price: Word::from(30_000_000_000u64),
+            gas_fee_cap: Word::from(40_000_000_000u64),
+            gas_tip_cap: Word::from(2_000_000_000u64),
+            call_data: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+            access_list: Some(AccessList(vec![AccessListItem {
+                address: Address::repeat_byte(0x33),
+                storage_keys: vec![Hash::repeat_byte(0x44)],
+            }])),
+            v: if transaction_type == 0 {
+                CHAIN_ID * 2 + 35
+            } else {
+                1
+            },
+            r: Word::from(0x1234),
+            s: Word::from(0x5678),
+        }
+    }
+
+    #[test]
+    fn rlp_signed_typed_txs() {
+        for transaction_type in 0..=2 {
+            let tx = tx(transaction_type);
+            let rlp = tx.rlp_signed(CHAIN_ID);
+
+            // Typed txs are prefixed by their type; legacy ones are a bare list.
+            if transaction_type == 0 {
+                assert!(rlp[0] >= 0xc0, "legacy tx must be an RLP list");
+            } else {
+                assert_eq!(rlp[0] as u64, transaction_type);
+            }
+
+            // And it must match the encoding of the ethers response type, which
+            // is what the trie is built from on the node side.
+            let mut response: crate::Transaction = (&tx).into();
+            response.chain_id = Some(CHAIN_ID.into());
+            assert_eq!(rlp, response.rlp());
+            assert_eq!(tx.hash(CHAIN_ID), Hash::from(keccak256(response.rlp())));
+        }
+    }
+
+    #[test]
+    fn transactions_root_depends_on_tx_type() {
+        let legacy_only = ordered_trie_root([tx(0).rlp_signed(CHAIN_ID)]);
+        let typed = ordered_trie_root([tx(2).rlp_signed(CHAIN_ID)]);
+        assert_ne!(legacy_only, typed);
+    }
+}
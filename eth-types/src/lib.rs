@@ -20,6 +20,7 @@ pub mod evm_types;
 pub mod geth_types;
 pub mod keccak;
 pub mod sign_types;
+pub mod trie;
 pub use keccak::{keccak256, Keccak};
 
 pub use bytecode::Bytecode;
@@ -38,7 +39,7 @@ pub use ethers_core::{
     abi::ethereum_types::{BigEndianHash, U512},
     types::{
         transaction::{eip2930::AccessList, response::Transaction},
-        Address, Block, Bytes, Signature, H160, H256, H64, U256, U64,
+        Address, Block, Bytes, Log, Signature, TransactionReceipt, H160, H256, H64, U256, U64,
     },
 };
 
@@ -333,7 +334,7 @@ impl<F: Field> ToScalar<F> for usize {
 }
 
 /// Struct used to define the storage proof
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct StorageProof {
     /// Storage key
     pub key: U256,
@@ -344,7 +345,7 @@ pub struct StorageProof {
 }
 
 /// Struct used to define the result of `eth_getProof` call
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EIP1186ProofResponse {
     /// Account address
@@ -393,6 +394,7 @@ pub struct GethExecStep {
     pub pc: u64,
     pub op: OpcodeId,
     pub gas: u64,
+    #[serde(rename = "gasCost")]
     pub gas_cost: u64,
     pub refund: u64,
     pub depth: u16,
@@ -509,6 +511,86 @@ pub struct GethExecTrace {
     pub struct_logs: Vec<GethExecStep>,
 }
 
+/// State of an account before a transaction, as reported by geth's
+/// `prestateTracer`.  Only the accounts and storage slots the transaction
+/// touches are reported.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GethPrestateAccount {
+    /// Balance
+    #[serde(default)]
+    pub balance: Word,
+    /// Nonce
+    #[serde(default)]
+    pub nonce: u64,
+    /// EVM Code
+    #[serde(default)]
+    pub code: Bytes,
+    /// Storage slots touched by the transaction
+    #[serde(default)]
+    pub storage: HashMap<H256, H256>,
+}
+
+/// Output of geth's `prestateTracer` for one transaction.
+pub type GethPrestateTrace = HashMap<Address, GethPrestateAccount>;
+
+/// Call frame of a transaction, as reported by geth's `callTracer`, with the
+/// frames of the calls it makes.  Only the fields needed to follow the calls of
+/// a trace are deserialized.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GethCallTrace {
+    /// Kind of the call, e.g. `CALL` or `CREATE2`
+    #[serde(rename = "type")]
+    pub call_type: String,
+    /// Error of the call, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Frames of the calls made by this call, in order
+    #[serde(default)]
+    pub calls: Vec<GethCallTrace>,
+}
+
+impl GethCallTrace {
+    /// Return whether each call made in this frame and its descendants
+    /// succeeded, in the order the calls are made.
+    pub fn calls_is_success(&self) -> Vec<bool> {
+        let mut calls_is_success = Vec::new();
+        let mut frames = self.calls.iter().rev().collect::<Vec<_>>();
+        while let Some(frame) = frames.pop() {
+            calls_is_success.push(frame.error.is_none());
+            frames.extend(frame.calls.iter().rev());
+        }
+        calls_is_success
+    }
+}
+
+/// Helper type built to deal with the weird `result` field added between
+/// `GethCallTrace`s in `debug_traceBlockByNumber` Geth JSON-RPC calls.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[doc(hidden)]
+pub struct ResultGethCallTraces(pub Vec<ResultGethCallTrace>);
+
+/// Helper type built to deal with the weird `result` field added between
+/// `GethCallTrace`s in `debug_traceBlockByNumber` Geth JSON-RPC calls.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[doc(hidden)]
+pub struct ResultGethCallTrace {
+    pub result: GethCallTrace,
+}
+
+/// Helper type built to deal with the weird `result` field added between
+/// `GethPrestateTrace`s in `debug_traceBlockByNumber` Geth JSON-RPC calls.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[doc(hidden)]
+pub struct ResultGethPrestateTraces(pub Vec<ResultGethPrestateTrace>);
+
+/// Helper type built to deal with the weird `result` field added between
+/// `GethPrestateTrace`s in `debug_traceBlockByNumber` Geth JSON-RPC calls.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[doc(hidden)]
+pub struct ResultGethPrestateTrace {
+    pub result: GethPrestateTrace,
+}
+
 #[macro_export]
 /// Create an [`Address`] from a hex string.  Panics on invalid input.
 macro_rules! address {
@@ -682,6 +764,46 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn serialize_geth_exec_trace_roundtrip() {
+        let trace = GethExecTrace {
+            gas: 21000,
+            failed: true,
+            invalid: false,
+            return_value: "".to_owned(),
+            struct_logs: vec![
+                GethExecStep {
+                    pc: 7,
+                    op: OpcodeId::SSTORE,
+                    gas: 22705,
+                    refund: 4800,
+                    gas_cost: 20000,
+                    depth: 1,
+                    error: None,
+                    stack: Stack(vec![word!("0x0"), word!("0x3635c9adc5dea00000")]),
+                    storage: Storage(word_map!("0x0" => "0x6f")),
+                    memory: Memory::from(vec![word!("0x0"), word!("0x80")]),
+                },
+                GethExecStep {
+                    pc: 8,
+                    op: OpcodeId::INVALID(0x0c),
+                    gas: 2705,
+                    refund: 4800,
+                    gas_cost: 0,
+                    depth: 1,
+                    error: Some("invalid opcode: opcode 0xc not defined".to_owned()),
+                    stack: Stack::new(),
+                    storage: Storage(word_map!()),
+                    memory: Memory::new(),
+                },
+            ],
+        };
+        let json = serde_json::to_string(&trace).expect("json-serialize GethExecTrace");
+        let decoded: GethExecTrace =
+            serde_json::from_str(&json).expect("json-deserialize GethExecTrace");
+        assert_eq!(decoded, trace);
+    }
 }
 
 #[cfg(test)]
@@ -783,4 +905,49 @@ mod eth_types_test {
         assert_eq!(req.to, None);
         Ok(())
     }
+
+    #[test]
+    fn deserialize_geth_prestate_traces() {
+        let traces: ResultGethPrestateTraces = serde_json::from_str(
+            r#"
+[
+  {
+    "txHash": "0x2b1ae3b8e3ec5b5d1c8b1c6b9cfa7bc7d9d4b6e4ad4a1b8c1c9f62c3b9f0a2e1",
+    "result": {
+      "0x00000000000000000000000000000000000000fe": {
+        "balance": "0xde0b6b3a7640000",
+        "nonce": 3
+      },
+      "0x00000000000000000000000000000000000000ff": {
+        "balance": "0x0",
+        "code": "0x600160005500",
+        "storage": {
+          "0x0000000000000000000000000000000000000000000000000000000000000000": "0x000000000000000000000000000000000000000000000000000000000000002a"
+        }
+      }
+    }
+  }
+]
+        "#,
+        )
+        .expect("json-deserialize ResultGethPrestateTraces");
+        let trace = &traces.0[0].result;
+        assert_eq!(
+            trace[&address!("0x00000000000000000000000000000000000000fe")],
+            GethPrestateAccount {
+                balance: Word::from(10).pow(18.into()),
+                nonce: 3,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            trace[&address!("0x00000000000000000000000000000000000000ff")],
+            GethPrestateAccount {
+                balance: Word::zero(),
+                nonce: 0,
+                code: Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]),
+                storage: HashMap::from([(H256::zero(), H256::from_low_u64_be(0x2a))]),
+            }
+        );
+    }
 }
@@ -0,0 +1,362 @@
+//! Minimal Merkle Patricia Trie root computation for ordered lists, as used
+//! by the block header `transactionsRoot`, `receiptsRoot` and
+//! `withdrawalsRoot`.
+
+use crate::{keccak256, H256};
+use ethers_core::utils::rlp::RlpStream;
+
+/// Root of an empty trie: `keccak256(rlp(""))`.
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Kind of a node listed by [`ordered_trie_nodes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrieNodeKind {
+    /// Leaf holding the value at position `index`.
+    Leaf {
+        /// Position of the value
+        index: usize,
+    },
+    /// Extension over the next `len` nibbles of the key of the value at
+    /// position `index` (any value below the extension will do).
+    Extension {
+        /// Position of a value below the extension
+        index: usize,
+        /// Number of nibbles of the extension
+        len: usize,
+        /// Hash of the child node
+        child: H256,
+    },
+    /// Branch with the hashes of its children.
+    Branch {
+        /// Hash of the child node at each nibble
+        children: [Option<H256>; 16],
+    },
+}
+
+/// A node of the trie built by [`ordered_trie_root`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrieNode {
+    /// Nibble path from the root to the node
+    pub path: Vec<u8>,
+    /// RLP encoding of the node
+    pub encoded: Vec<u8>,
+    /// Node kind
+    pub kind: TrieNodeKind,
+}
+
+/// Key of the i-th value of an ordered trie: `rlp(i)`.
+pub fn ordered_trie_key(index: usize) -> Vec<u8> {
+    let mut key = RlpStream::new();
+    key.append(&index);
+    key.out().to_vec()
+}
+
+/// Compute the root of the trie built from `values`, where the i-th value is
+/// keyed by `rlp(i)`.
+pub fn ordered_trie_root<I, V>(values: I) -> H256
+where
+    I: IntoIterator<Item = V>,
+    V: AsRef<[u8]>,
+{
+    trie_root(
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (ordered_trie_key(index), value)),
+    )
+}
+
+/// List the nodes of the trie built by [`ordered_trie_root`] in pre-order,
+/// root first. Children are always referenced by hash, so the listing only
+/// matches the real trie when every node encodes to at least 32 bytes.
+pub fn ordered_trie_nodes<I, V>(values: I) -> Vec<TrieNode>
+where
+    I: IntoIterator<Item = V>,
+    V: AsRef<[u8]>,
+{
+    let values = values
+        .into_iter()
+        .map(|value| value.as_ref().to_vec())
+        .collect::<Vec<_>>();
+    let mut keys = (0..values.len())
+        .map(|index| (bytes_to_nibbles(&ordered_trie_key(index)), index))
+        .collect::<Vec<_>>();
+    keys.sort();
+    let entries = keys
+        .iter()
+        .map(|(key, index)| (key.as_slice(), values[*index].as_slice(), *index))
+        .collect::<Vec<_>>();
+
+    let mut nodes = vec![];
+    if !entries.is_empty() {
+        collect_nodes(&entries, vec![], &mut nodes);
+    }
+    nodes
+}
+
+/// Compute the root of the trie holding the given `(key, value)` pairs.
+/// Keys must be unique.
+pub fn trie_root<I, K, V>(entries: I) -> H256
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    let mut entries = entries
+        .into_iter()
+        .map(|(key, value)| (bytes_to_nibbles(key.as_ref()), value.as_ref().to_vec()))
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return EMPTY_TRIE_ROOT;
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let entries = entries
+        .iter()
+        .map(|(key, value)| (key.as_slice(), value.as_slice()))
+        .collect::<Vec<_>>();
+    H256(keccak256(&encode_node(&entries)))
+}
+
+/// Split bytes into nibbles, high nibble first.
+pub fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Hex-prefix encoding of a nibble path (Yellow Paper, Appendix C).
+pub fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        out.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        out.push(flag << 4);
+        nibbles
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+/// Append the reference to a child node: nodes whose encoding is shorter than
+/// 32 bytes are embedded, the rest are referenced by their hash.
+fn append_node_ref(stream: &mut RlpStream, encoded: &[u8]) {
+    if encoded.len() < 32 {
+        stream.append_raw(encoded, 1);
+    } else {
+        stream.append(&keccak256(encoded).to_vec());
+    }
+}
+
+/// RLP encode the node holding `entries`, which must be sorted by key and
+/// non-empty.
+fn encode_node(entries: &[(&[u8], &[u8])]) -> Vec<u8> {
+    if let [(key, value)] = entries {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(key, true));
+        stream.append(&value.to_vec());
+        return stream.out().to_vec();
+    }
+
+    // Entries are sorted, so the common prefix of all of them is the common
+    // prefix of the first and the last one.
+    let (first, last) = (entries[0].0, entries[entries.len() - 1].0);
+    let prefix_len = first
+        .iter()
+        .zip(last.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix_len > 0 {
+        let children = entries
+            .iter()
+            .map(|(key, value)| (&key[prefix_len..], *value))
+            .collect::<Vec<_>>();
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(&first[..prefix_len], false));
+        append_node_ref(&mut stream, &encode_node(&children));
+        return stream.out().to_vec();
+    }
+
+    let mut stream = RlpStream::new_list(17);
+    let mut branch_value: &[u8] = &[];
+    let mut rest = entries;
+    if let Some(((key, value), tail)) = rest.split_first() {
+        if key.is_empty() {
+            branch_value = value;
+            rest = tail;
+        }
+    }
+    for nibble in 0..16u8 {
+        let end = rest
+            .iter()
+            .position(|(key, _)| key[0] != nibble)
+            .unwrap_or(rest.len());
+        let (children, tail) = rest.split_at(end);
+        if children.is_empty() {
+            stream.append_empty_data();
+        } else {
+            let children = children
+                .iter()
+                .map(|(key, value)| (&key[1..], *value))
+                .collect::<Vec<_>>();
+            append_node_ref(&mut stream, &encode_node(&children));
+        }
+        rest = tail;
+    }
+    stream.append(&branch_value.to_vec());
+    stream.out().to_vec()
+}
+
+type IndexedEntry<'a> = (&'a [u8], &'a [u8], usize);
+
+fn node_hash(entries: &[IndexedEntry]) -> H256 {
+    let entries = entries
+        .iter()
+        .map(|(key, value, _)| (*key, *value))
+        .collect::<Vec<_>>();
+    H256(keccak256(&encode_node(&entries)))
+}
+
+/// Push the node holding `entries` (sorted, non-empty and with prefix-free
+/// keys) followed by its descendants.
+fn collect_nodes(entries: &[IndexedEntry], path: Vec<u8>, nodes: &mut Vec<TrieNode>) {
+    let encoded = encode_node(
+        &entries
+            .iter()
+            .map(|(key, value, _)| (*key, *value))
+            .collect::<Vec<_>>(),
+    );
+    if let [(_, _, index)] = entries {
+        nodes.push(TrieNode {
+            path,
+            encoded,
+            kind: TrieNodeKind::Leaf { index: *index },
+        });
+        return;
+    }
+
+    let (first, last) = (entries[0].0, entries[entries.len() - 1].0);
+    let prefix_len = first
+        .iter()
+        .zip(last.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix_len > 0 {
+        let children = entries
+            .iter()
+            .map(|(key, value, index)| (&key[prefix_len..], *value, *index))
+            .collect::<Vec<_>>();
+        let child_path = [path.as_slice(), &first[..prefix_len]].concat();
+        nodes.push(TrieNode {
+            path,
+            encoded,
+            kind: TrieNodeKind::Extension {
+                index: entries[0].2,
+                len: prefix_len,
+                child: node_hash(&children),
+            },
+        });
+        collect_nodes(&children, child_path, nodes);
+        return;
+    }
+
+    let mut children = [None; 16];
+    let mut groups = vec![];
+    let mut rest = entries;
+    for nibble in 0..16u8 {
+        let end = rest
+            .iter()
+            .position(|(key, _, _)| key[0] != nibble)
+            .unwrap_or(rest.len());
+        let (group, tail) = rest.split_at(end);
+        if !group.is_empty() {
+            let group = group
+                .iter()
+                .map(|(key, value, index)| (&key[1..], *value, *index))
+                .collect::<Vec<_>>();
+            children[nibble as usize] = Some(node_hash(&group));
+            groups.push((nibble, group));
+        }
+        rest = tail;
+    }
+    nodes.push(TrieNode {
+        path: path.clone(),
+        encoded,
+        kind: TrieNodeKind::Branch { children },
+    });
+    for (nibble, group) in groups {
+        collect_nodes(&group, [path.as_slice(), &[nibble]].concat(), nodes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn empty_trie_root() {
+        assert_eq!(ordered_trie_root(Vec::<Vec<u8>>::new()), EMPTY_TRIE_ROOT);
+        assert_eq!(
+            H256(keccak256(&[0x80])),
+            EMPTY_TRIE_ROOT,
+            "empty root must be the hash of the empty string"
+        );
+    }
+
+    #[test]
+    fn single_value_trie_root() {
+        // The root of a single leaf is the hash of [hp(key, leaf), value].
+        let mut leaf = RlpStream::new_list(2);
+        leaf.append(&vec![0x20u8, 0x80]);
+        leaf.append(&vec![0xabu8; 40]);
+        assert_eq!(
+            ordered_trie_root([vec![0xabu8; 40]]),
+            H256(keccak256(&leaf.out()))
+        );
+    }
+
+    #[test]
+    fn reference_trie_root() {
+        // Example from the Ethereum wiki's Patricia Tree page.
+        let entries = [
+            ("do", "verb"),
+            ("dog", "puppy"),
+            ("doge", "coin"),
+            ("horse", "stallion"),
+        ];
+        assert_eq!(
+            trie_root(entries),
+            H256::from_str("0x5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn ordered_trie_nodes_match_root() {
+        for n in [1, 2, 17, 130] {
+            let values = (0..n).map(|i| vec![i as u8; 40]).collect::<Vec<_>>();
+            let nodes = ordered_trie_nodes(&values);
+            assert_eq!(
+                H256(keccak256(&nodes[0].encoded)),
+                ordered_trie_root(&values)
+            );
+            assert!(nodes[0].path.is_empty());
+            assert_eq!(
+                nodes
+                    .iter()
+                    .filter(|node| matches!(node.kind, TrieNodeKind::Leaf { .. }))
+                    .count(),
+                n
+            );
+            for node in &nodes {
+                if let TrieNodeKind::Leaf { index } = node.kind {
+                    // The path plus the hex-prefix encoded rest is the full key.
+                    assert!(bytes_to_nibbles(&ordered_trie_key(index)).starts_with(&node.path));
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,19 @@
 //! This module generates traces by connecting to an external tracer
 
 use eth_types::{
+    evm_types::Hardfork,
     geth_types::{Account, BlockConstants, Transaction, Withdrawal},
     Address, Error, GethExecTrace, Word,
 };
 use serde::Serialize;
 use std::collections::HashMap;
 
+#[cfg(feature = "revm-tracer")]
+pub mod revm_tracer;
+
+#[cfg(not(any(feature = "geth-tracer", feature = "revm-tracer")))]
+compile_error!("enable at least one of the `geth-tracer` and `revm-tracer` features");
+
 /// Configuration structure for `geth_utils::trace`
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct TraceConfig {
@@ -25,6 +32,8 @@ pub struct TraceConfig {
     pub withdrawals: Vec<Withdrawal>,
     /// logger
     pub logger_config: LoggerConfig,
+    /// hardfork whose rules the transactions are executed with
+    pub hardfork: Hardfork,
 }
 
 /// Configuration structure for `logger.Config`
@@ -63,15 +72,11 @@ impl LoggerConfig {
 
 /// Creates a trace for the specified config
 pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
-    // Get the trace
-    let trace_string = geth_utils::trace(&serde_json::to_string(&config).unwrap()).map_err(
-        |error| match error {
-            geth_utils::Error::TracingError(error) => Error::TracingError(error),
-        },
-    )?;
+    #[cfg(feature = "geth-tracer")]
+    let trace = trace_geth(config)?;
+    #[cfg(not(feature = "geth-tracer"))]
+    let trace = revm_tracer::trace(config)?;
 
-    let trace: Vec<GethExecTrace> =
-        serde_json::from_str(&trace_string).map_err(Error::SerdeError)?;
     // Don't throw only for specific invalid transactions we support.
     for trace in trace.iter() {
         let error = &trace.return_value;
@@ -85,3 +90,17 @@ pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
     }
     Ok(trace)
 }
+
+/// Creates a trace for the specified config with the go-ethereum tracer of
+/// `geth_utils`. Cancun is not supported, since the pinned go-ethereum only
+/// has draft Cancun rules.
+#[cfg(feature = "geth-tracer")]
+pub fn trace_geth(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    let trace_string = geth_utils::trace(&serde_json::to_string(&config).unwrap()).map_err(
+        |error| match error {
+            geth_utils::Error::TracingError(error) => Error::TracingError(error),
+        },
+    )?;
+
+    serde_json::from_str(&trace_string).map_err(Error::SerdeError)
+}
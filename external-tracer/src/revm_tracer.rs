@@ -0,0 +1,903 @@
+//! In-process tracer built on revm, emitting the same struct logs as the go-ethereum
+//! `StructLogger` driven by geth-utils.
+
+use crate::{LoggerConfig, TraceConfig};
+use eth_types::{
+    evm_types::{
+        gas_utils::memory_expansion_gas_cost, GasCost, Hardfork, Memory, OpcodeId, Stack, Storage,
+        MAX_EXPANDED_MEMORY_ADDRESS,
+    },
+    Error, GethExecStep, GethExecTrace, ToBigEndian, Word,
+};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{
+        keccak256, AccountInfo, Address, Bytecode, Bytes, EVMError, ExecutionResult,
+        InvalidTransaction, SpecId, TransactTo, TxEnv, B256, U256,
+    },
+    Database, EVMData, Inspector, EVM,
+};
+use std::collections::HashMap;
+
+/// Creates a trace for the specified config by executing its transactions with revm.
+pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    let block = &config.block_constants;
+    let txs_gas_limit: u64 = config
+        .transactions
+        .iter()
+        .map(|tx| tx.gas_limit.as_u64())
+        .sum();
+    if Word::from(txs_gas_limit) > block.gas_limit {
+        return Err(Error::TracingError(format!(
+            "txs total gas: {} Exceeds block gas limit: {}",
+            txs_gas_limit, block.gas_limit
+        )));
+    }
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    for (address, account) in config.accounts.iter() {
+        let code = Bytecode::new_raw(Bytes::from(account.code.to_vec()));
+        let info = AccountInfo::new(
+            to_u256(&account.balance),
+            account.nonce.as_u64(),
+            keccak256(&account.code),
+            code,
+        );
+        db.insert_account_info(to_address(address), info);
+        for (key, value) in account.storage.iter() {
+            db.insert_account_storage(to_address(address), to_u256(key), to_u256(value))
+                .expect("empty database is infallible");
+        }
+    }
+    // The latest history hash belongs to the parent of the traced block.
+    let number = block.number.as_u64();
+    for (distance, hash) in config.history_hashes.iter().rev().enumerate() {
+        if let Some(number) = number.checked_sub(distance as u64 + 1) {
+            db.block_hashes
+                .insert(U256::from(number), B256::from(hash.to_be_bytes()));
+        }
+    }
+
+    let mut evm = EVM::new();
+    evm.database(db);
+    evm.env.cfg.chain_id = config.chain_id.as_u64();
+    evm.env.cfg.spec_id = match config.hardfork {
+        Hardfork::London => SpecId::LONDON,
+        Hardfork::Paris => SpecId::MERGE,
+        Hardfork::Shanghai => SpecId::SHANGHAI,
+        Hardfork::Cancun => SpecId::CANCUN,
+    };
+    evm.env.block.number = U256::from(number);
+    evm.env.block.coinbase = to_address(&block.coinbase);
+    evm.env.block.timestamp = to_u256(&block.timestamp);
+    evm.env.block.gas_limit = to_u256(&block.gas_limit);
+    evm.env.block.basefee = to_u256(&block.base_fee);
+    evm.env.block.difficulty = to_u256(&block.difficulty);
+    evm.env.block.prevrandao = Some(B256::from(block.difficulty.to_be_bytes()));
+
+    let mut traces = Vec::with_capacity(config.transactions.len());
+    for tx in config.transactions.iter() {
+        evm.env.tx = TxEnv {
+            caller: to_address(&tx.from),
+            gas_limit: tx.gas_limit.as_u64(),
+            // Transactions are traced with legacy pricing, as in geth-utils.
+            gas_price: to_u256(&tx.gas_price),
+            transact_to: match tx.to {
+                Some(to) => TransactTo::Call(to_address(&to)),
+                None => TransactTo::create(),
+            },
+            value: to_u256(&tx.value),
+            data: Bytes::from(tx.call_data.to_vec()),
+            nonce: Some(tx.nonce.as_u64()),
+            access_list: tx
+                .access_list
+                .iter()
+                .flat_map(|access_list| access_list.0.iter())
+                .map(|item| {
+                    let keys = item
+                        .storage_keys
+                        .iter()
+                        .map(|key| U256::from_be_bytes(key.to_fixed_bytes()))
+                        .collect();
+                    (to_address(&item.address), keys)
+                })
+                .collect(),
+            ..Default::default()
+        };
+        // geth only skips the base fee checks for transactions that pay no fee.
+        evm.env.cfg.disable_base_fee = tx.gas_price.is_zero();
+
+        let mut struct_logs = Vec::new();
+        let result = evm.inspect_commit(StructLogger::new(
+            &config.logger_config,
+            config.hardfork,
+            &mut struct_logs,
+        ));
+        let trace = match result {
+            Ok(result) => {
+                let (gas, failed, output) = match result {
+                    ExecutionResult::Success {
+                        gas_used, output, ..
+                    } => (gas_used, false, output.into_data()),
+                    ExecutionResult::Revert { gas_used, output } => (gas_used, true, output),
+                    ExecutionResult::Halt { gas_used, .. } => (gas_used, true, Bytes::new()),
+                };
+                GethExecTrace {
+                    gas,
+                    failed,
+                    invalid: false,
+                    return_value: output.iter().map(|byte| format!("{:02x}", byte)).collect(),
+                    struct_logs,
+                }
+            }
+            Err(EVMError::Transaction(error)) => GethExecTrace {
+                gas: 0,
+                failed: true,
+                invalid: true,
+                return_value: invalid_tx_error(tx, error),
+                struct_logs: vec![],
+            },
+            Err(error) => return Err(Error::TracingError(format!("{:?}", error))),
+        };
+        traces.push(trace);
+    }
+    Ok(traces)
+}
+
+/// Formats an invalid transaction error the way geth reports it.
+fn invalid_tx_error(tx: &eth_types::geth_types::Transaction, error: InvalidTransaction) -> String {
+    match error {
+        InvalidTransaction::NonceTooLow { tx: nonce, state } => format!(
+            "nonce too low: address {:?}, tx: {} state: {}",
+            tx.from, nonce, state
+        ),
+        InvalidTransaction::NonceTooHigh { tx: nonce, state } => format!(
+            "nonce too high: address {:?}, tx: {} state: {}",
+            tx.from, nonce, state
+        ),
+        InvalidTransaction::CallGasCostMoreThanGasLimit => {
+            format!("intrinsic gas too low: have {}", tx.gas_limit)
+        }
+        InvalidTransaction::LackOfFundForMaxFee { fee, balance } => format!(
+            "insufficient funds for gas * price + value: address {:?} have {} want {}",
+            tx.from, balance, fee
+        ),
+        error => format!("{:?}", error),
+    }
+}
+
+fn to_address(address: &eth_types::Address) -> Address {
+    Address::from_slice(address.as_bytes())
+}
+
+fn to_u256(word: &Word) -> U256 {
+    U256::from_be_bytes(word.to_be_bytes())
+}
+
+fn to_word(value: &U256) -> Word {
+    Word::from_big_endian(&value.to_be_bytes::<32>())
+}
+
+/// Step whose `step_end` has not been reached yet. Steps nest when an opcode
+/// opens a sub-context.
+struct PendingStep {
+    /// Index of the step in the struct logs.
+    index: usize,
+    /// Gas left before the step.
+    gas: u64,
+    /// Global refund counter before the step.
+    refund: u64,
+    /// Stack length before the step.
+    stack_len: usize,
+    /// Gas forwarded to a created contract, which geth leaves out of the cost.
+    forwarded_gas: u64,
+    /// Gas handed back by a finished sub-context.
+    returned_gas: u64,
+    /// Cost geth logs for the step if it runs out of gas: the constant gas,
+    /// plus the dynamic gas if the constant gas can be paid. `None` if the
+    /// memory size of the step overflows, which geth reports as its own error.
+    out_of_gas_cost: Option<u64>,
+}
+
+/// Port of geth's `StructLogger` as a revm [`Inspector`].
+struct StructLogger<'a> {
+    config: &'a LoggerConfig,
+    hardfork: Hardfork,
+    struct_logs: &'a mut Vec<GethExecStep>,
+    pending_steps: Vec<PendingStep>,
+    /// Storage accessed so far in each contract, as geth accumulates it.
+    storage: HashMap<Address, HashMap<Word, Word>>,
+}
+
+impl<'a> StructLogger<'a> {
+    fn new(
+        config: &'a LoggerConfig,
+        hardfork: Hardfork,
+        struct_logs: &'a mut Vec<GethExecStep>,
+    ) -> Self {
+        Self {
+            config,
+            hardfork,
+            struct_logs,
+            pending_steps: Vec::new(),
+            storage: HashMap::new(),
+        }
+    }
+
+    /// Records `key => value` for `address` and returns the accumulated storage.
+    fn record_storage(&mut self, address: Address, key: Word, value: Word) -> Storage {
+        let storage = self.storage.entry(address).or_default();
+        storage.insert(key, value);
+        Storage::new(storage.clone())
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StructLogger<'_> {
+    fn step(&mut self, interp: &mut Interpreter, data: &mut EVMData<'_, DB>) {
+        let op = OpcodeId::from(interp.current_opcode());
+        let stack = interp.stack.data();
+        // revm keeps refunds per context; the parent's share was captured by the
+        // step that opened this context.
+        let parent_refund = self.pending_steps.last().map_or(0, |parent| parent.refund);
+        let refund = (parent_refund as i64 + interp.gas.refunded()).max(0) as u64;
+
+        let storage = if op == OpcodeId::SSTORE && stack.len() >= 2 && !self.config.disable_storage
+        {
+            let key = to_word(&stack[stack.len() - 1]);
+            let value = to_word(&stack[stack.len() - 2]);
+            self.record_storage(interp.contract.address, key, value)
+        } else {
+            Storage::empty()
+        };
+
+        // The dynamic gas depends on the state before the step, such as the
+        // access list, so it is computed whether or not the step fails.
+        let constant_gas = op.constant_gas_cost();
+        let out_of_gas_cost = match interp.gas.remaining().checked_sub(constant_gas) {
+            Some(gas) => dynamic_gas(op, self.hardfork, interp, data, gas)
+                .map(|dynamic_gas| constant_gas.wrapping_add(dynamic_gas)),
+            None => Some(constant_gas),
+        };
+
+        self.pending_steps.push(PendingStep {
+            index: self.struct_logs.len(),
+            gas: interp.gas.remaining(),
+            refund,
+            stack_len: stack.len(),
+            forwarded_gas: 0,
+            returned_gas: 0,
+            out_of_gas_cost,
+        });
+        self.struct_logs.push(GethExecStep {
+            pc: interp.program_counter() as u64,
+            op,
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            refund,
+            depth: data.journaled_state.depth() as u16,
+            error: None,
+            stack: if self.config.disable_stack {
+                Stack::new()
+            } else {
+                Stack(stack.iter().map(to_word).collect())
+            },
+            memory: if self.config.enable_memory {
+                Memory(interp.shared_memory.context_memory().to_vec())
+            } else {
+                Memory(vec![])
+            },
+            storage,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _data: &mut EVMData<'_, DB>) {
+        let Some(pending) = self.pending_steps.pop() else {
+            return;
+        };
+        let op = self.struct_logs[pending.index].op;
+
+        // geth only reports the errors raised before the opcode executes. It
+        // logs the constant gas as the cost of a stack error, and the constant
+        // plus the dynamic gas it computed, even if unpaid, as the cost of an
+        // out of gas error, see `dynamic_gas`.
+        let (min_stack_ptr, max_stack_ptr) = stack_ptr_bounds(op);
+        let out_of_gas = matches!(
+            interp.instruction_result,
+            InstructionResult::OutOfGas
+                | InstructionResult::MemoryOOG
+                | InstructionResult::MemoryLimitOOG
+                | InstructionResult::PrecompileOOG
+                | InstructionResult::InvalidOperandOOG
+        );
+        let error = match interp.instruction_result {
+            _ if out_of_gas => Some(match pending.out_of_gas_cost {
+                Some(_) => "out of gas".to_string(),
+                None => "gas uint64 overflow".to_string(),
+            }),
+            InstructionResult::StackUnderflow => Some(format!(
+                "stack underflow ({} <=> {})",
+                pending.stack_len,
+                1024 - max_stack_ptr
+            )),
+            InstructionResult::StackOverflow => Some(format!(
+                "stack limit reached {} ({})",
+                pending.stack_len,
+                1024 - min_stack_ptr
+            )),
+            _ => None,
+        };
+
+        if op == OpcodeId::SLOAD && error.is_none() && !self.config.disable_storage {
+            let key = self.struct_logs[pending.index].stack.0.last().copied();
+            let value = interp.stack.data().last().map(to_word);
+            if let (Some(key), Some(value)) = (key, value) {
+                self.struct_logs[pending.index].storage =
+                    self.record_storage(interp.contract.address, key, value);
+            }
+        }
+
+        let step = &mut self.struct_logs[pending.index];
+        step.gas_cost = match error {
+            Some(_) if out_of_gas => pending
+                .out_of_gas_cost
+                .unwrap_or_else(|| op.constant_gas_cost()),
+            Some(_) => op.constant_gas_cost(),
+            None => (pending.gas + pending.returned_gas)
+                .saturating_sub(interp.gas.remaining() + pending.forwarded_gas),
+        };
+        step.error = error;
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(pending) = self.pending_steps.last_mut() {
+            pending.returned_gas += remaining_gas.remaining();
+        }
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let Some(pending) = self.pending_steps.last_mut() {
+            pending.forwarded_gas = inputs.gas_limit;
+        }
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let Some(pending) = self.pending_steps.last_mut() {
+            pending.returned_gas += remaining_gas.remaining();
+        }
+        (ret, address, remaining_gas, out)
+    }
+}
+
+/// Returns the dynamic gas of `op` as geth computes it before executing the
+/// step, given the gas left after the constant gas, even if it exceeds that
+/// gas. Returns `None` if the memory size of the step overflows 64 bits, which
+/// geth checks first. geth charges no dynamic gas when it fails to compute it,
+/// for example beyond the maximum memory size.
+fn dynamic_gas<DB: Database>(
+    op: OpcodeId,
+    hardfork: Hardfork,
+    interp: &Interpreter,
+    data: &mut EVMData<'_, DB>,
+    gas: u64,
+) -> Option<u64> {
+    let stack = interp.stack.data();
+    // The `n`th item from the top of the stack, zero if missing, in which case
+    // the step fails with a stack underflow instead.
+    let back = |n: usize| {
+        stack
+            .len()
+            .checked_sub(n + 1)
+            .map_or(Word::zero(), |index| to_word(&stack[index]))
+    };
+    let back_address = |n: usize| Address::from_slice(&back(n).to_be_bytes()[12..]);
+
+    let memory_size = match op {
+        OpcodeId::MLOAD | OpcodeId::MSTORE => memory_size(back(0), Word::from(32))?,
+        OpcodeId::MSTORE8 => memory_size(back(0), Word::one())?,
+        OpcodeId::SHA3
+        | OpcodeId::RETURN
+        | OpcodeId::REVERT
+        | OpcodeId::LOG0
+        | OpcodeId::LOG1
+        | OpcodeId::LOG2
+        | OpcodeId::LOG3
+        | OpcodeId::LOG4 => memory_size(back(0), back(1))?,
+        OpcodeId::CALLDATACOPY | OpcodeId::CODECOPY | OpcodeId::RETURNDATACOPY => {
+            memory_size(back(0), back(2))?
+        }
+        OpcodeId::EXTCODECOPY => memory_size(back(1), back(3))?,
+        OpcodeId::MCOPY => memory_size(back(0).max(back(1)), back(2))?,
+        OpcodeId::CREATE | OpcodeId::CREATE2 => memory_size(back(1), back(2))?,
+        OpcodeId::CALL | OpcodeId::CALLCODE => {
+            memory_size(back(5), back(6))?.max(memory_size(back(3), back(4))?)
+        }
+        OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+            memory_size(back(4), back(5))?.max(memory_size(back(2), back(3))?)
+        }
+        _ => 0,
+    };
+    // geth rounds the memory size up to words
+    let memory_size = memory_size.checked_add(31)? / 32 * 32;
+    let memory_len = interp.shared_memory.context_memory().len() as u64;
+    let memory_gas = || {
+        if memory_size > MAX_EXPANDED_MEMORY_ADDRESS {
+            None
+        } else if memory_size > memory_len {
+            Some(memory_expansion_gas_cost(memory_len / 32, memory_size / 32))
+        } else {
+            Some(0)
+        }
+    };
+    let copy_gas = |length: Word, word_gas: u64| words(length)?.checked_mul(word_gas);
+
+    let dynamic_gas = match op {
+        OpcodeId::MLOAD
+        | OpcodeId::MSTORE
+        | OpcodeId::MSTORE8
+        | OpcodeId::RETURN
+        | OpcodeId::REVERT => memory_gas(),
+        OpcodeId::SHA3 => memory_gas()?.checked_add(copy_gas(back(1), GasCost::COPY_SHA3)?),
+        OpcodeId::CALLDATACOPY
+        | OpcodeId::CODECOPY
+        | OpcodeId::RETURNDATACOPY
+        | OpcodeId::MCOPY => memory_gas()?.checked_add(copy_gas(back(2), GasCost::COPY)?),
+        OpcodeId::EXTCODECOPY => {
+            let cold_gas = if is_cold(data, hardfork, back_address(0)) {
+                GasCost::COLD_ACCOUNT_ACCESS - GasCost::WARM_ACCESS
+            } else {
+                0
+            };
+            memory_gas()?
+                .checked_add(copy_gas(back(3), GasCost::COPY)?)?
+                .checked_add(cold_gas)
+        }
+        OpcodeId::LOG0 | OpcodeId::LOG1 | OpcodeId::LOG2 | OpcodeId::LOG3 | OpcodeId::LOG4 => {
+            let topics = (op.as_u8() - OpcodeId::LOG0.as_u8()) as u64;
+            let size = to_u64(back(1))?;
+            memory_gas()?
+                .checked_add(GasCost::LOG * (topics + 1))?
+                .checked_add(size.checked_mul(8)?)
+        }
+        OpcodeId::EXP => {
+            let exponent_bytes = (back(1).bits() as u64 + 7) / 8;
+            Some(exponent_bytes * GasCost::EXP_BYTE_TIMES)
+        }
+        OpcodeId::CREATE | OpcodeId::CREATE2 => {
+            let size = to_u64(back(2)).filter(|size| *size <= hardfork.max_init_code_size())?;
+            let word_gas = hardfork.init_code_word_gas()
+                + if op == OpcodeId::CREATE2 {
+                    GasCost::COPY_SHA3
+                } else {
+                    0
+                };
+            memory_gas()?.checked_add((size + 31) / 32 * word_gas)
+        }
+        OpcodeId::SLOAD => {
+            let address = interp.contract.address;
+            let key = stack.last().copied().unwrap_or_default();
+            Some(if storage_slot(data, address, key).0 {
+                GasCost::WARM_ACCESS
+            } else {
+                GasCost::COLD_SLOAD
+            })
+        }
+        OpcodeId::SSTORE => {
+            // geth fails to compute the dynamic gas within the call stipend
+            if gas <= GasCost::SSTORE_SENTRY {
+                return Some(0);
+            }
+            let address = interp.contract.address;
+            let key = stack.last().copied().unwrap_or_default();
+            let (warm, original, current) = storage_slot(data, address, key);
+            let value = stack
+                .len()
+                .checked_sub(2)
+                .map_or(U256::ZERO, |index| stack[index]);
+            let cold_gas = if warm { 0 } else { GasCost::COLD_SLOAD };
+            Some(
+                cold_gas
+                    + if current == value || original != current {
+                        GasCost::WARM_ACCESS
+                    } else if original == U256::ZERO {
+                        GasCost::SSTORE_SET
+                    } else {
+                        GasCost::SSTORE_RESET
+                    },
+            )
+        }
+        OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
+            Some(if is_cold(data, hardfork, back_address(0)) {
+                GasCost::COLD_ACCOUNT_ACCESS - GasCost::WARM_ACCESS
+            } else {
+                0
+            })
+        }
+        OpcodeId::SELFDESTRUCT => {
+            let beneficiary = back_address(0);
+            let cold_gas = if is_cold(data, hardfork, beneficiary) {
+                GasCost::COLD_ACCOUNT_ACCESS
+            } else {
+                0
+            };
+            let has_balance = data
+                .journaled_state
+                .state
+                .get(&interp.contract.address)
+                .map_or(false, |account| account.info.balance != U256::ZERO);
+            let new_account_gas = if has_balance && is_empty(data, beneficiary) {
+                GasCost::NEW_ACCOUNT
+            } else {
+                0
+            };
+            Some(cold_gas + new_account_gas)
+        }
+        OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+            // The cold access gas is charged first, and no dynamic gas if it
+            // can't be paid.
+            let callee = back_address(1);
+            let cold_gas = if is_cold(data, hardfork, callee) {
+                GasCost::COLD_ACCOUNT_ACCESS - GasCost::WARM_ACCESS
+            } else {
+                0
+            };
+            let Some(gas) = gas.checked_sub(cold_gas) else {
+                return Some(0);
+            };
+            let transfers_value =
+                matches!(op, OpcodeId::CALL | OpcodeId::CALLCODE) && !back(2).is_zero();
+            let new_account_gas =
+                if op == OpcodeId::CALL && transfers_value && is_empty(data, callee) {
+                    GasCost::NEW_ACCOUNT
+                } else {
+                    0
+                };
+            let value_gas = if transfers_value {
+                GasCost::CALL_WITH_VALUE
+            } else {
+                0
+            };
+            let base_gas = memory_gas()
+                .and_then(|memory_gas| memory_gas.checked_add(new_account_gas + value_gas));
+            base_gas.and_then(|base_gas| {
+                // geth caps the requested gas to all but one 64th of the gas
+                // left after the base gas, which wraps around if it exceeds it.
+                let available_gas = gas.wrapping_sub(base_gas);
+                let capped_gas = available_gas - available_gas / 64;
+                let call_gas =
+                    to_u64(back(0)).map_or(capped_gas, |requested| requested.min(capped_gas));
+                base_gas
+                    .checked_add(call_gas)
+                    .map(|gas| gas.wrapping_add(cold_gas))
+            })
+        }
+        _ => Some(0),
+    };
+    Some(dynamic_gas.unwrap_or(0))
+}
+
+/// Returns the memory size needed to access `length` bytes at `offset`, or
+/// `None` if it overflows 64 bits, as geth's `calcMemSize64`.
+fn memory_size(offset: Word, length: Word) -> Option<u64> {
+    let length = to_u64(length)?;
+    if length == 0 {
+        return Some(0);
+    }
+    to_u64(offset)?.checked_add(length)
+}
+
+/// Returns the number of words of `length` bytes, or `None` if `length`
+/// overflows 64 bits.
+fn words(length: Word) -> Option<u64> {
+    let length = to_u64(length)?;
+    Some(length / 32 + u64::from(length % 32 != 0))
+}
+
+fn to_u64(word: Word) -> Option<u64> {
+    (word.bits() <= 64).then(|| word.low_u64())
+}
+
+/// Returns whether the storage slot `key` of `address` is warm, and its
+/// original and current values.
+fn storage_slot<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    address: Address,
+    key: U256,
+) -> (bool, U256, U256) {
+    match data
+        .journaled_state
+        .state
+        .get(&address)
+        .and_then(|account| account.storage.get(&key))
+    {
+        Some(slot) => (true, slot.original_value(), slot.present_value()),
+        None => {
+            let value = data.db.storage(address, key).unwrap_or_default();
+            (false, value, value)
+        }
+    }
+}
+
+/// Returns whether `address` is not in the access list, which holds the
+/// accounts loaded so far and the precompiles.
+fn is_cold<DB: Database>(data: &EVMData<'_, DB>, hardfork: Hardfork, address: Address) -> bool {
+    !data.journaled_state.state.contains_key(&address)
+        && !hardfork.is_precompiled(&eth_types::Address::from_slice(address.as_slice()))
+}
+
+/// Returns whether `address` has no nonce, balance nor code, as geth's `Empty`.
+fn is_empty<DB: Database>(data: &mut EVMData<'_, DB>, address: Address) -> bool {
+    match data.journaled_state.state.get(&address) {
+        Some(account) => account.info.is_empty(),
+        None => data
+            .db
+            .basic(address)
+            .ok()
+            .flatten()
+            .map_or(true, |info| info.is_empty()),
+    }
+}
+
+/// Returns the `(min, max)` stack pointers `op` runs with, recovered from
+/// [`OpcodeId::invalid_stack_ptrs`].
+fn stack_ptr_bounds(op: OpcodeId) -> (u32, u32) {
+    let (overflow, underflow): (Vec<u32>, Vec<u32>) = op
+        .invalid_stack_ptrs()
+        .into_iter()
+        .partition(|ptr| *ptr < 512);
+    (
+        overflow.len() as u32,
+        underflow.first().map_or(1024, |ptr| ptr - 1),
+    )
+}
+
+#[cfg(all(test, feature = "geth-tracer"))]
+mod tests {
+    use crate::{trace_geth, LoggerConfig, TraceConfig};
+    use eth_types::{
+        address, bytecode,
+        geth_types::{Account, BlockConstants, Transaction},
+        Bytecode, Word,
+    };
+    use std::collections::HashMap;
+
+    fn config(code: Bytecode, callee: Bytecode, txs: Vec<Transaction>) -> TraceConfig {
+        let sender = address!("0x00000000000000000000000000000000000000fe");
+        let contract = address!("0x00000000000000000000000000000000000000ff");
+        let callee_address = address!("0x00000000000000000000000000000000000000aa");
+        let accounts = [
+            Account {
+                address: sender,
+                balance: Word::from(10u64).pow(20.into()),
+                ..Default::default()
+            },
+            Account {
+                address: contract,
+                code: code.into(),
+                storage: HashMap::from([(Word::from(1), Word::from(7))]),
+                ..Default::default()
+            },
+            Account {
+                address: callee_address,
+                code: callee.into(),
+                ..Default::default()
+            },
+        ];
+        TraceConfig {
+            chain_id: Word::from(1338),
+            history_hashes: vec![Word::from(0xbeef), Word::from(0xcafe)],
+            block_constants: BlockConstants {
+                number: 2.into(),
+                gas_limit: Word::from(10_000_000),
+                base_fee: Word::from(1),
+                ..Default::default()
+            },
+            accounts: accounts
+                .into_iter()
+                .map(|account| (account.address, account))
+                .collect(),
+            transactions: txs
+                .into_iter()
+                .map(|tx| Transaction {
+                    from: sender,
+                    to: Some(contract),
+                    gas_price: Word::from(2),
+                    ..tx
+                })
+                .collect(),
+            logger_config: LoggerConfig::enable_memory(),
+            ..Default::default()
+        }
+    }
+
+    fn tx(nonce: u64, gas_limit: u64) -> Transaction {
+        Transaction {
+            nonce: nonce.into(),
+            gas_limit: gas_limit.into(),
+            ..Default::default()
+        }
+    }
+
+    fn assert_same_traces(config: &TraceConfig) {
+        let expected = trace_geth(config).expect("geth trace");
+        let traces = super::trace(config).expect("revm trace");
+        assert_eq!(traces, expected);
+    }
+
+    #[test]
+    fn memory_and_return_data() {
+        let code = bytecode! {
+            PUSH32(Word::MAX)
+            PUSH1(0x21)
+            MSTORE
+            PUSH1(0x40)
+            PUSH1(0x10)
+            SHA3
+            PUSH1(0x20)
+            PUSH1(0x30)
+            RETURN
+        };
+        assert_same_traces(&config(code, Bytecode::default(), vec![tx(0, 100_000)]));
+    }
+
+    #[test]
+    fn storage_and_refund() {
+        let code = bytecode! {
+            PUSH1(1)
+            SLOAD
+            PUSH1(0)
+            PUSH1(1)
+            SSTORE
+            PUSH1(3)
+            PUSH1(2)
+            SSTORE
+            PUSH1(2)
+            SLOAD
+            STOP
+        };
+        assert_same_traces(&config(code, Bytecode::default(), vec![tx(0, 100_000)]));
+    }
+
+    #[test]
+    fn nested_call_and_create() {
+        let callee = bytecode! {
+            PUSH1(5)
+            PUSH1(4)
+            SSTORE
+            NUMBER
+            PUSH1(1)
+            SWAP1
+            SUB
+            BLOCKHASH
+            PUSH1(0)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0)
+            REVERT
+        };
+        let code = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0xaa)
+            PUSH3(0x10000)
+            CALL
+            // Create a contract out of the reverted data.
+            PUSH1(0x20)
+            PUSH1(0)
+            PUSH1(0)
+            CREATE
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0xaa)
+            GAS
+            STATICCALL
+            STOP
+        };
+        assert_same_traces(&config(code, callee, vec![tx(0, 300_000), tx(1, 100_000)]));
+    }
+
+    #[test]
+    fn traced_errors() {
+        let stack_underflow = bytecode! {
+            PUSH1(1)
+            ADD
+        };
+        assert_same_traces(&config(
+            stack_underflow,
+            Bytecode::default(),
+            vec![tx(0, 50_000)],
+        ));
+
+        let out_of_gas = bytecode! {
+            PUSH1(1)
+            PUSH1(0)
+            SSTORE
+        };
+        assert_same_traces(&config(
+            out_of_gas,
+            Bytecode::default(),
+            vec![tx(0, 21_010)],
+        ));
+
+        // geth logs the constant plus the dynamic gas of a step running out
+        // of gas: the memory expansion, the hashed words and the cold access.
+        let memory_out_of_gas = bytecode! {
+            PUSH3(0x100000)
+            MLOAD
+        };
+        let sha3_out_of_gas = bytecode! {
+            PUSH3(0x10000)
+            PUSH1(0)
+            SHA3
+        };
+        let sload_out_of_gas = bytecode! {
+            PUSH1(5)
+            SLOAD
+        };
+        for (code, gas_limit) in [
+            (memory_out_of_gas, 30_000),
+            (sha3_out_of_gas, 30_000),
+            (sload_out_of_gas, 21_100),
+        ] {
+            assert_same_traces(&config(code, Bytecode::default(), vec![tx(0, gas_limit)]));
+        }
+
+        // A memory size overflowing 64 bits is its own error.
+        let memory_overflow = bytecode! {
+            PUSH32(Word::MAX)
+            MLOAD
+        };
+        assert_same_traces(&config(
+            memory_overflow,
+            Bytecode::default(),
+            vec![tx(0, 50_000)],
+        ));
+    }
+
+    #[test]
+    fn invalid_tx() {
+        let config = config(
+            Bytecode::default(),
+            Bytecode::default(),
+            vec![tx(3, 21_000)],
+        );
+        let expected = trace_geth(&config).expect("geth trace");
+        let traces = super::trace(&config).expect("revm trace");
+        assert!(traces[0].invalid);
+        assert!(traces[0].return_value.starts_with("nonce too high"));
+        assert_eq!(traces[0].gas, expected[0].gas);
+        assert_eq!(traces[0].failed, expected[0].failed);
+    }
+}
@@ -123,6 +123,7 @@ type TraceConfig struct {
 	Accounts      map[common.Address]Account `json:"accounts"`
 	Transactions  []Transaction              `json:"transactions"`
 	LoggerConfig  *logger.Config             `json:"logger_config"`
+	Hardfork      string                     `json:"hardfork"`
 }
 
 func newUint64(val uint64) *uint64 { return &val }
@@ -156,6 +157,26 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		TerminalTotalDifficultyPassed: true,
 	}
 
+	// Execute the transactions with the rules of the hardfork, Shanghai by default
+	isMerge := true
+	switch config.Hardfork {
+	case "London":
+		chainConfig.ShanghaiTime = nil
+		chainConfig.TerminalTotalDifficulty = nil
+		chainConfig.TerminalTotalDifficultyPassed = false
+		isMerge = false
+	case "Paris":
+		chainConfig.ShanghaiTime = nil
+	case "", "Shanghai":
+	case "Cancun":
+		// The Cancun rules of go-ethereum v1.11.5 have the draft EIP-1153
+		// TLOAD/TSTORE at 0xb3/0xb4 and neither MCOPY nor BLOBBASEFEE, so they
+		// don't match the final ones.
+		return nil, fmt.Errorf("unsupported hardfork with go-ethereum v1.11.5: %s", config.Hardfork)
+	default:
+		return nil, fmt.Errorf("unsupported hardfork: %s", config.Hardfork)
+	}
+
 	var txsGasLimit uint64
 	blockGasLimit := toBigInt(config.Block.GasLimit).Uint64()
 	messages := make([]core.Message, len(config.Transactions))
@@ -193,7 +214,12 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 
 	// For opcode PREVRANDAO
 	// Difficulty is one of MixHash or Difficulty.
-	randao := common.BigToHash(toBigInt(config.Block.Difficulty))
+	// A nil Random selects the pre-merge rules.
+	var random *common.Hash
+	if isMerge {
+		randao := common.BigToHash(toBigInt(config.Block.Difficulty))
+		random = &randao
+	}
 
 	blockCtx := vm.BlockContext{
 		CanTransfer: core.CanTransfer,
@@ -210,7 +236,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		BlockNumber: toBigInt(config.Block.Number),
 		Time:        toBigInt(config.Block.Timestamp).Uint64(),
 		Difficulty:  toBigInt(config.Block.Difficulty),
-		Random:      &randao,
+		Random:      random,
 		BaseFee:     toBigInt(config.Block.BaseFee),
 		GasLimit:    blockGasLimit,
 	}
@@ -34,6 +34,7 @@ use zkevm_circuits::{
     evm_circuit::TestEvmCircuit,
     exp_circuit::TestExpCircuit,
     keccak_circuit::TestKeccakCircuit,
+    key_store::{KeyDescriptor, KeyStore},
     pi_circuit::TestPiCircuit,
     root_circuit::{
         compile, Config, EvmTranscript, NativeLoader, PoseidonTranscript, RootCircuit, Shplonk,
@@ -103,6 +104,10 @@ lazy_static! {
 
 lazy_static! {
     static ref GEN_PARAMS: Mutex<HashMap<u32, ParamsKZG<Bn256>>> = Mutex::new(HashMap::new());
+    /// Optional on-disk store for params and keys, enabled by setting `KEY_STORE_DIR`.
+    static ref KEY_STORE: Option<KeyStore> = std::env::var("KEY_STORE_DIR")
+        .ok()
+        .map(|dir| KeyStore::new(dir).expect("cannot open key store"));
 }
 
 lazy_static! {
@@ -287,10 +292,21 @@ impl<C: SubCircuit<Fr> + Circuit<Fr>> IntegrationTest<C> {
                 let circuit = C::new_from_block(&block);
                 let general_params = get_general_params(self.degree);
 
-                let verifying_key =
-                    keygen_vk(&general_params, &circuit).expect("keygen_vk should not fail");
-                let key = keygen_pk(&general_params, verifying_key, &circuit)
-                    .expect("keygen_pk should not fail");
+                let key = match KEY_STORE.as_ref() {
+                    Some(store) => {
+                        let descriptor =
+                            KeyDescriptor::new(self.name, self.degree, &CIRCUITS_PARAMS);
+                        store
+                            .proving_key(&descriptor, &general_params, &circuit)
+                            .expect("cannot load or generate the proving key")
+                    }
+                    None => {
+                        let verifying_key = keygen_vk(&general_params, &circuit)
+                            .expect("keygen_vk should not fail");
+                        keygen_pk(&general_params, verifying_key, &circuit)
+                            .expect("keygen_pk should not fail")
+                    }
+                };
                 self.key = Some(key.clone());
                 key
             }
@@ -324,10 +340,26 @@ impl<C: SubCircuit<Fr> + Circuit<Fr>> IntegrationTest<C> {
                 .unwrap();
 
                 let general_params = get_general_params(self.root_degree);
-                let verifying_key =
-                    keygen_vk(&general_params, &circuit).expect("keygen_vk should not fail");
-                let key = keygen_pk(&general_params, verifying_key, &circuit)
-                    .expect("keygen_pk should not fail");
+                let key = match KEY_STORE.as_ref() {
+                    Some(store) => {
+                        // The root circuit embeds the verifying key of the inner circuit, so
+                        // its degree is part of the configuration too.
+                        let descriptor = KeyDescriptor::new(
+                            format!("{}_root", self.name),
+                            self.root_degree,
+                            &(self.degree, CIRCUITS_PARAMS),
+                        );
+                        store
+                            .proving_key(&descriptor, &general_params, &circuit)
+                            .expect("cannot load or generate the root proving key")
+                    }
+                    None => {
+                        let verifying_key = keygen_vk(&general_params, &circuit)
+                            .expect("keygen_vk should not fail");
+                        keygen_pk(&general_params, verifying_key, &circuit)
+                            .expect("keygen_pk should not fail")
+                    }
+                };
                 self.root_key = Some(key.clone());
                 key
             }
@@ -500,7 +532,12 @@ fn get_general_params(degree: u32) -> ParamsKZG<Bn256> {
     match map.get(&degree) {
         Some(params) => params.clone(),
         None => {
-            let params = ParamsKZG::<Bn256>::setup(degree, RNG.clone());
+            let params = match KEY_STORE.as_ref() {
+                Some(store) => store
+                    .params_or_insecure_setup(degree, RNG.clone())
+                    .expect("cannot load or generate params"),
+                None => ParamsKZG::<Bn256>::setup(degree, RNG.clone()),
+            };
             map.insert(degree, params.clone());
             params
         }
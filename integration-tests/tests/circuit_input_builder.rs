@@ -1,7 +1,7 @@
 #![cfg(feature = "circuit_input_builder")]
 
 use bus_mapping::circuit_input_builder::{
-    build_state_code_db, get_state_accesses, BuilderClient, FixedCParams,
+    build_state_code_db, get_state_accesses, BlockWitnessBundle, BuilderClient, FixedCParams,
 };
 use integration_tests::{get_client, log_init, GenDataOutput};
 use lazy_static::lazy_static;
@@ -59,6 +59,19 @@ async fn test_circuit_input_builder_block(block_num: u64) {
         .unwrap();
 
     trace!("CircuitInputBuilder: {:#?}", builder);
+
+    // The same inputs can be generated offline from a witness bundle
+    let bundle = cli.gen_witness_bundle(block_num).await.unwrap();
+    let mut encoded = Vec::new();
+    bundle.write(&mut encoded).unwrap();
+    let bundle = BlockWitnessBundle::read(&encoded[..]).unwrap();
+    let replayed = cli.gen_inputs_from_bundle(&bundle).unwrap();
+    assert_eq!(replayed.block.container, builder.block.container);
+
+    // Re-executing the block from its prestateTracer output gives the same
+    // inputs, with a post-state matching the chain
+    let (from_prestate, _) = cli.gen_inputs_from_prestate(block_num).await.unwrap();
+    assert_eq!(from_prestate.block.container, builder.block.container);
 }
 
 macro_rules! declare_tests {
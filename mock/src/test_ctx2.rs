@@ -2,6 +2,7 @@
 
 use crate::{withdrawal::MockWithdrawal, MockAccount, MockBlock, MockTransaction};
 use eth_types::{
+    evm_types::Hardfork,
     geth_types::{Account, BlockConstants, GethData, Withdrawal},
     Block, Error, GethExecTrace, Transaction, Word,
 };
@@ -221,6 +222,7 @@ pub fn gen_geth_traces(
             .collect(),
         withdrawals,
         logger_config,
+        hardfork: Hardfork::default(),
     };
     let traces = trace(&trace_config)?;
     Ok(traces)
@@ -155,6 +155,18 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
     let tx: TypedTransaction = tx.into();
 
     let sig = wallet.sign_transaction_sync(&tx).unwrap();
+    let (transaction_type, gas_fee_cap, gas_tip_cap, access_list) = match &tx {
+        TypedTransaction::Legacy(_) => (0u64, U256::zero(), U256::zero(), None),
+        TypedTransaction::Eip2930(tx) => {
+            (1, U256::zero(), U256::zero(), Some(tx.access_list.clone()))
+        }
+        TypedTransaction::Eip1559(tx) => (
+            2,
+            tx.max_fee_per_gas.unwrap_or_default(),
+            tx.max_priority_fee_per_gas.unwrap_or_default(),
+            Some(tx.access_list.clone()),
+        ),
+    };
 
     (
         st.id,
@@ -171,16 +183,17 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
             },
 
             transactions: vec![geth_types::Transaction {
+                transaction_type: U64::from(transaction_type),
                 from: st.from,
                 to: st.to,
                 nonce: U64::from(st.nonce),
                 value: st.value,
                 gas_limit: U64::from(st.gas_limit),
                 gas_price: st.gas_price,
-                gas_fee_cap: U256::zero(),
-                gas_tip_cap: U256::zero(),
+                gas_fee_cap,
+                gas_tip_cap,
                 call_data: st.data,
-                access_list: None,
+                access_list,
                 v: sig.v,
                 r: sig.r,
                 s: sig.s,
@@ -0,0 +1,239 @@
+//! Prove a block from a `BlockWitnessBundle` saved to disk.
+//!
+//! ```text
+//! zkevm-prover <bundle.json> <output dir> [options]
+//!
+//! Options:
+//!   --mock                    Run the MockProver instead of creating a proof
+//!   --circuits-params <file>  JSON encoded `FixedCParams`. Defaults to the
+//!                             smallest parameters fitting the block
+//!   --degree <k>              Circuit degree. Defaults to the minimum needed
+//!   --keys <dir>              Directory of the params and keys store, holding
+//!                             the params of a trusted setup
+//!   --insecure-setup          Run an insecure setup, for testing only, when
+//!                             there are no trusted params
+//!   --randomness <n>          Randomness used by the SuperCircuit (default 0x100)
+//!   --hardfork <name>         Hardfork of the block: London, Paris, Shanghai or Cancun
+//!                             (default Shanghai)
+//!   --no-eip1559              Disable the EIP-1559 fee market
+//!   --difficulty              Allow a non-zero block difficulty
+//!   --invalid-tx              Allow invalid transactions in the block
+//!   --free-first-tx           Don't charge the first transaction for gas
+//! ```
+//!
+//! A real run writes `proof.bin` and `instances.json` to the output directory.
+
+use bus_mapping::circuit_input_builder::{BlockWitnessBundle, FeatureConfig, FixedCParams};
+use eth_types::Word;
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        ff::PrimeField,
+    },
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit},
+    poly::{
+        commitment::ParamsProver,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use rand::rngs::OsRng;
+use std::{
+    env,
+    fs::{self, File},
+    io::BufReader,
+    path::PathBuf,
+};
+use zkevm_circuits::{
+    key_store::{KeyDescriptor, KeyStore},
+    super_circuit::SuperCircuit,
+};
+
+/// Default randomness, matching the one used in the integration tests.
+const DEFAULT_RANDOMNESS: u64 = 0x100;
+
+struct Args {
+    bundle: PathBuf,
+    output: PathBuf,
+    mock: bool,
+    circuits_params: Option<FixedCParams>,
+    degree: Option<u32>,
+    keys: Option<PathBuf>,
+    insecure_setup: bool,
+    randomness: u64,
+    feature_config: FeatureConfig,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut args = env::args().skip(1);
+        let usage = "usage: zkevm-prover <bundle.json> <output dir> [--mock] \
+            [--circuits-params <file>] [--degree <k>] [--keys <dir>] [--insecure-setup] \
+            [--randomness <n>] [--hardfork <name>] [--no-eip1559] [--difficulty] \
+            [--invalid-tx] [--free-first-tx]";
+        let bundle = args.next().expect(usage).into();
+        let output = args.next().expect(usage).into();
+        let mut parsed = Self {
+            bundle,
+            output,
+            mock: false,
+            circuits_params: None,
+            degree: None,
+            keys: None,
+            insecure_setup: false,
+            randomness: DEFAULT_RANDOMNESS,
+            feature_config: FeatureConfig::default(),
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().expect(usage);
+            match arg.as_str() {
+                "--mock" => parsed.mock = true,
+                "--circuits-params" => {
+                    let file = File::open(value()).expect("cannot open circuits params file");
+                    parsed.circuits_params = Some(
+                        serde_json::from_reader(BufReader::new(file))
+                            .expect("cannot parse circuits params"),
+                    );
+                }
+                "--degree" => parsed.degree = Some(value().parse().expect("invalid degree")),
+                "--keys" => parsed.keys = Some(value().into()),
+                "--insecure-setup" => parsed.insecure_setup = true,
+                "--randomness" => {
+                    let value = value();
+                    parsed.randomness = match value.strip_prefix("0x") {
+                        Some(hex) => u64::from_str_radix(hex, 16),
+                        None => value.parse(),
+                    }
+                    .expect("invalid randomness")
+                }
+                "--hardfork" => {
+                    parsed.feature_config.hardfork =
+                        serde_json::from_value(serde_json::Value::String(value()))
+                            .expect("invalid hardfork")
+                }
+                "--no-eip1559" => parsed.feature_config.enable_eip1559 = false,
+                "--difficulty" => parsed.feature_config.zero_difficulty = false,
+                "--invalid-tx" => parsed.feature_config.invalid_tx = true,
+                "--free-first-tx" => parsed.feature_config.free_first_tx = true,
+                _ => panic!("unknown argument {}\n{}", arg, usage),
+            }
+        }
+        parsed
+    }
+}
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    let bundle = BlockWitnessBundle::load(&args.bundle).expect("cannot load bundle");
+    let builder = match args.circuits_params {
+        Some(circuits_params) => bundle.gen_inputs(circuits_params, args.feature_config),
+        None => bundle.gen_inputs_dynamic(args.feature_config),
+    }
+    .expect("cannot build circuit inputs");
+    let circuits_params = builder.circuits_params;
+    log::info!("circuits params: {:?}", circuits_params);
+    log::info!("feature config: {:?}", args.feature_config);
+
+    let (min_degree, circuit, instance) =
+        SuperCircuit::<Fr>::build_from_circuit_input_builder(&builder, args.randomness.into())
+            .expect("cannot build the super circuit");
+    let degree = match args.degree {
+        Some(degree) => {
+            assert!(
+                degree >= min_degree,
+                "degree {} is too small, the block needs at least {}",
+                degree,
+                min_degree
+            );
+            degree
+        }
+        None => min_degree,
+    };
+    log::info!("degree: {}", degree);
+
+    if args.mock {
+        let prover = MockProver::<Fr>::run(degree, &circuit, instance).unwrap();
+        prover.assert_satisfied_par();
+        println!("MockProver verification succeeded");
+        return;
+    }
+
+    let (params, pk) = match &args.keys {
+        Some(dir) => {
+            let store = KeyStore::new(dir).expect("cannot open key store");
+            let params = if args.insecure_setup {
+                store.params_or_insecure_setup(degree, OsRng)
+            } else {
+                store.params(degree)
+            }
+            .expect("cannot load or generate params");
+            let descriptor =
+                KeyDescriptor::new("super", degree, &(circuits_params, circuit.params()));
+            let pk = store
+                .proving_key(&descriptor, &params, &circuit)
+                .expect("cannot load or generate the proving key");
+            (params, pk)
+        }
+        None => {
+            assert!(
+                args.insecure_setup,
+                "no trusted params: pass --keys <dir> holding params_k{}.bin, or --insecure-setup",
+                degree
+            );
+            let params = ParamsKZG::<Bn256>::setup(degree, OsRng);
+            let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+            let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+            (params, pk)
+        }
+    };
+
+    let instance_refs: Vec<&[Fr]> = instance.iter().map(|v| &v[..]).collect();
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &params,
+        &pk,
+        &[circuit],
+        &[&instance_refs],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("proof generation failed");
+    let proof = transcript.finalize();
+
+    let mut verifier_transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        params.verifier_params(),
+        pk.get_vk(),
+        SingleStrategy::new(&params),
+        &[&instance_refs],
+        &mut verifier_transcript,
+    )
+    .expect("proof verification failed");
+
+    fs::create_dir_all(&args.output).expect("cannot create output dir");
+    fs::write(args.output.join("proof.bin"), &proof).expect("cannot write proof");
+    let instance_words: Vec<Vec<Word>> = instance
+        .iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|value| Word::from_little_endian(value.to_repr().as_ref()))
+                .collect()
+        })
+        .collect();
+    fs::write(
+        args.output.join("instances.json"),
+        serde_json::to_string_pretty(&instance_words).unwrap(),
+    )
+    .expect("cannot write instances");
+    println!("proof written to {}", args.output.display());
+}
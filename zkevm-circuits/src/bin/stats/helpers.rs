@@ -1,7 +1,7 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashMap, iter};
 
 use bus_mapping::{
-    circuit_input_builder::{self, ExecState},
+    circuit_input_builder::{self, CopyDataType, ExecState, ExecStep, FeatureConfig},
     mock::BlockData,
 };
 use cli_table::{
@@ -9,9 +9,18 @@ use cli_table::{
     print_stdout, Table, WithTitle,
 };
 use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Address, Bytecode, ToWord};
+use halo2_proofs::{halo2curves::bn256::Fr, plonk::ConstraintSystem};
 use mock::{eth, test_ctx::TestContext, MOCK_ACCOUNTS};
+use serde::Serialize;
 use strum::IntoEnumIterator;
-use zkevm_circuits::evm_circuit::step::ExecutionState;
+use zkevm_circuits::{
+    evm_circuit::{
+        param::MAX_STEP_HEIGHT,
+        step::{step_state_width, ExecutionState},
+        EvmCircuit, EvmCircuitParams,
+    },
+    witness::Block,
+};
 
 /// Generate the prefix bytecode to trigger a big amount of rw operations
 pub(crate) fn bytecode_prefix_op_big_rws(opcode: OpcodeId) -> Bytecode {
@@ -147,7 +156,7 @@ pub(crate) fn print_circuit_stats_by_states(
         if !fn_filter(state) {
             continue;
         }
-        for responsible_op in state.responsible_opcodes() {
+        for responsible_op in state.responsible_opcodes(FeatureConfig::default().hardfork) {
             let opcode = responsible_op.opcode();
             let mut code = bytecode! {
                 PUSH2(0x00)
@@ -240,3 +249,380 @@ pub(crate) fn print_circuit_stats_by_states(
     print_stdout(rows.with_title().separator(Separator::builder().build()))
         .expect("the table renders");
 }
+
+/// Cost of the steps of an execution state in a block
+#[derive(Debug, Default, Serialize, Table)]
+pub(crate) struct StateCost {
+    #[table(title = "Execution State")]
+    pub(crate) state: String,
+    #[table(title = "Steps", justify = "Justify::Right")]
+    pub(crate) steps: usize,
+    #[table(title = "Rows", justify = "Justify::Right")]
+    pub(crate) rows: usize,
+    #[table(title = "RW Entries", justify = "Justify::Right")]
+    pub(crate) rws: usize,
+    #[table(title = "Copy Rows", justify = "Justify::Right")]
+    pub(crate) copy_rows: usize,
+    #[table(title = "Keccak Permutations", justify = "Justify::Right")]
+    pub(crate) keccak_permutations: usize,
+}
+
+/// Computes the cost of each execution state of the block, sorted by
+/// decreasing number of rows:
+/// - steps: number of steps in the state
+/// - rows: number of EVM circuit rows used by the steps
+/// - rws: number of RW table entries of the steps, including the copied ones
+/// - copy_rows: number of copy table rows of the copy events done by the steps
+/// - keccak_permutations: number of keccak_f of the inputs hashed by the steps, before the
+///   deduplication of the Keccak circuit inputs: the SHA3 input, the CREATE init code and contract
+///   address, and the contract address of a creation transaction
+///
+/// The costs not done by a step, like the hashing of the bytecodes and
+/// transactions, are not reported. The rows are given by the height of each
+/// execution state with the layout of the EVM circuit.
+pub(crate) fn block_cost_by_states<F: eth_types::Field>(
+    block: &Block<F>,
+    step_heights: &HashMap<ExecutionState, usize>,
+) -> Vec<StateCost> {
+    // Keccak rate in bytes
+    const RATE: usize = 136;
+
+    let steps = block
+        .txs
+        .iter()
+        .flat_map(|tx| tx.steps())
+        // The rws of the copy events are pushed to the step doing the copy
+        .map(|step| (step, step.rw_indices_len()))
+        .collect::<Vec<_>>();
+
+    // Number of keccak_f to hash an input of `len` bytes
+    let permutations = |len: usize| len / RATE + 1;
+
+    let is_create_op = |step: &ExecStep| {
+        matches!(
+            step.exec_state,
+            ExecState::Op(OpcodeId::CREATE | OpcodeId::CREATE2)
+        )
+    };
+    // The contract address of a creation is the hash of a short input, and the
+    // other inputs are copied: the SHA3 input to the keccak table and the CREATE
+    // init code to the bytecode table.
+    let mut keccak_permutations = block
+        .txs
+        .iter()
+        .flat_map(|tx| {
+            tx.steps().iter().map(|step| {
+                let is_creation = if step.exec_state == ExecState::BeginTx {
+                    tx.is_create()
+                } else {
+                    is_create_op(step) && step.error.is_none()
+                };
+                is_creation as usize
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Copy events are attributed to the step whose rw counters contain the start
+    // of the event.
+    let mut copy_rows = vec![0; steps.len()];
+    for event in block.copy_events.iter() {
+        let start = event.rw_counter_start.0;
+        let index = steps
+            .iter()
+            .position(|(step, rws)| step.rwc.0 <= start && start < step.rwc.0 + rws)
+            .or_else(|| steps.iter().rposition(|(step, _)| step.rwc.0 <= start));
+        if let Some(index) = index {
+            copy_rows[index] += event.steps().len() * 2;
+            if event.dst_type == CopyDataType::RlcAcc
+                || (event.dst_type == CopyDataType::Bytecode && is_create_op(steps[index].0))
+            {
+                keccak_permutations[index] += permutations(event.bytes.len());
+            }
+        }
+    }
+
+    let mut costs: HashMap<ExecutionState, StateCost> = HashMap::new();
+    for (((step, rws), copy_rows), keccak_permutations) in
+        steps.iter().zip(copy_rows).zip(keccak_permutations)
+    {
+        let state = ExecutionState::from(*step);
+        let cost = costs.entry(state).or_insert_with(|| StateCost {
+            state: format!("{:?}", state),
+            ..Default::default()
+        });
+        cost.steps += 1;
+        cost.rows += step_heights.get(&state).copied().unwrap_or_default();
+        cost.rws += rws;
+        cost.copy_rows += copy_rows;
+        cost.keccak_permutations += keccak_permutations;
+    }
+
+    let mut costs = costs.into_values().collect::<Vec<_>>();
+    costs.sort_by(|a, b| b.rows.cmp(&a.rows).then_with(|| a.state.cmp(&b.state)));
+    costs
+}
+
+/// Layout of the columns of a step and its cost for a workload
+#[derive(Debug, Serialize, Table)]
+pub(crate) struct StepLayout {
+    #[table(title = "Order")]
+    pub(crate) order: &'static str,
+    #[table(title = "Step Width", justify = "Justify::Right")]
+    pub(crate) step_width: usize,
+    #[table(title = "Phase1 Columns", justify = "Justify::Right")]
+    pub(crate) phase1_columns: usize,
+    #[table(title = "Max Step Height", justify = "Justify::Right")]
+    pub(crate) max_step_height: usize,
+    #[table(title = "Rows", justify = "Justify::Right")]
+    pub(crate) rows: usize,
+    #[table(title = "Area", justify = "Justify::Right")]
+    pub(crate) area: usize,
+}
+
+/// Searches the step layouts minimising the area, the step width times the
+/// rows of the workload, which is given as the number of steps per execution
+/// state. Returns the layout of `params` followed by the best `n` layouts
+/// found.
+///
+/// The heights are estimated from the cells used by each execution state, as
+/// given by the instrumentation of the EVM circuit:
+/// - row-major: the lookup, phase2, copy, u8 and u16 columns are kept, and the phase1 columns range
+///   from the step state width up to twice the current number of phase1 columns. The height of a
+///   step is the largest height of its cell types, with the phase1 cells substituted to copy
+///   columns.
+/// - column-major: the columns of each cell type are filled up to a max step height ranging from 2
+///   to twice the current one, and the width of each cell type is the smallest fitting all the
+///   execution states.
+pub(crate) fn search_step_layouts(
+    params: &EvmCircuitParams,
+    workload: &HashMap<ExecutionState, usize>,
+    n: usize,
+) -> Vec<StepLayout> {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let circuit = EvmCircuit::configure_with_params(&mut meta, params.clone());
+    let report = circuit.0.execution.instrument().clone().analyze();
+
+    let layout = &params.layout;
+    let fixed_columns = layout.lookup_columns()
+        + layout.phase2_columns
+        + layout.copy_columns
+        + layout.u8_lookups
+        + layout.u16_lookups;
+    // Cells used by each state: (phase1, copy, [(other cell type cells, columns)])
+    let states = report
+        .iter()
+        .map(|state| {
+            let others = [
+                (state.storage_2.used_cells, layout.phase2_columns),
+                (state.u8_lookup.used_cells, layout.u8_lookups),
+                (state.u16_lookup.used_cells, layout.u16_lookups),
+            ]
+            .into_iter()
+            .chain(
+                [
+                    &state.fixed_table,
+                    &state.tx_table,
+                    &state.rw_table,
+                    &state.bytecode_table,
+                    &state.block_table,
+                    &state.copy_table,
+                    &state.keccak_table,
+                    &state.exp_table,
+                ]
+                .into_iter()
+                .zip(&layout.lookups)
+                .map(|(row, (_, count))| (row.used_cells, *count)),
+            )
+            .collect::<Vec<_>>();
+            (
+                *workload.get(&state.state).unwrap_or(&0),
+                state.storage_1.used_cells,
+                state.storage_perm.used_cells,
+                others,
+            )
+        })
+        .collect::<Vec<_>>();
+    let ceil = |cells: usize, columns: usize| (cells + columns - 1) / columns;
+
+    let row_major = |phase1_columns: usize| {
+        let heights = states
+            .iter()
+            .map(|(count, phase1, copy, others)| {
+                let height = others
+                    .iter()
+                    .filter(|(cells, _)| *cells > 0)
+                    .map(|(cells, columns)| ceil(*cells, *columns))
+                    .chain([
+                        ceil(*copy, layout.copy_columns),
+                        ceil(phase1 + copy, phase1_columns + layout.copy_columns),
+                        1,
+                    ])
+                    .max()
+                    .unwrap();
+                (*count, height)
+            })
+            .collect::<Vec<_>>();
+        let step_width = fixed_columns + phase1_columns;
+        let rows = heights.iter().map(|(count, height)| count * height).sum();
+        StepLayout {
+            order: "row-major",
+            step_width,
+            phase1_columns,
+            max_step_height: heights.iter().map(|(_, height)| *height).max().unwrap_or(1),
+            rows,
+            area: step_width * rows,
+        }
+    };
+
+    let column_major = |max_step_height: usize| {
+        let cell_types =
+            |(_, phase1, copy, others): &(usize, usize, usize, Vec<(usize, usize)>)| {
+                [*phase1, *copy]
+                    .into_iter()
+                    .chain(others.iter().map(|(cells, _)| *cells))
+                    .collect::<Vec<_>>()
+            };
+        let num_cell_types = 2 + 3 + layout.lookups.len();
+        let widths = (0..num_cell_types)
+            .map(|idx| {
+                states
+                    .iter()
+                    .map(|state| ceil(cell_types(state)[idx], max_step_height))
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let rows = states
+            .iter()
+            .map(|state| {
+                let height = cell_types(state)
+                    .into_iter()
+                    .map(|cells| cells.min(max_step_height))
+                    .chain([1])
+                    .max()
+                    .unwrap();
+                state.0 * height
+            })
+            .sum();
+        let phase1_columns = widths[0].max(step_state_width());
+        let step_width = phase1_columns + widths[1..].iter().sum::<usize>();
+        StepLayout {
+            order: "column-major",
+            step_width,
+            phase1_columns,
+            max_step_height,
+            rows,
+            area: step_width * rows,
+        }
+    };
+
+    let mut layouts = (step_state_width()..=2 * layout.phase1_columns())
+        .map(row_major)
+        .chain((2..=2 * MAX_STEP_HEIGHT).map(column_major))
+        .collect::<Vec<_>>();
+    layouts.sort_by_key(|layout| layout.area);
+    layouts.truncate(n);
+
+    let mut current = row_major(layout.phase1_columns());
+    current.order = "current";
+    iter::once(current).chain(layouts).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkevm_circuits::{
+        copy_circuit::CopyCircuit, keccak_circuit::KeccakCircuit, state_circuit::StateCircuit,
+        util::SubCircuit, witness::block_convert,
+    };
+
+    #[test]
+    fn block_cost_by_states_matches_circuits() {
+        // Loop long enough for the steps to outgrow the fixed tables of the EVM
+        // circuit, then copy the calldata and hash 200 bytes of memory.
+        let code = bytecode! {
+            PUSH2(1000)
+            JUMPDEST
+            PUSH1(1)
+            SWAP1
+            SUB
+            DUP1
+            PUSH1(3)
+            JUMPI
+            POP
+            CALLDATASIZE
+            PUSH1(0)
+            PUSH1(0x20)
+            CALLDATACOPY
+            PUSH1(200)
+            PUSH1(0)
+            SHA3
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(eth(10))
+                    .code(code);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .input(vec![7; 40].into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let costs = block_cost_by_states(&block, &EvmCircuitParams::default().step_heights());
+        let total = |cost: fn(&StateCost) -> usize| costs.iter().map(cost).sum::<usize>();
+
+        // The EVM circuit adds the EndBlock and an unused row
+        assert_eq!(
+            EvmCircuit::<Fr>::min_num_rows_block(&block).0,
+            total(|cost| cost.rows) + 2
+        );
+        // The state circuit adds the rws of the EndBlock and a Start row
+        assert_eq!(
+            StateCircuit::<Fr>::min_num_rows_block(&block).0,
+            total(|cost| cost.rws) + block.end_block_last.rw_indices_len() + 1
+        );
+        // The copy circuit adds the disabled rows
+        assert_eq!(
+            CopyCircuit::<Fr>::min_num_rows_block(&block).0,
+            total(|cost| cost.copy_rows) + 2
+        );
+        // The keccak circuit also hashes the bytecodes, the transactions and the
+        // public inputs, the steps hash the SHA3 inputs
+        let keccak_rows = |keccak_inputs: Vec<Vec<u8>>| {
+            KeccakCircuit::<Fr>::min_num_rows_block(&Block {
+                keccak_inputs,
+                ..block.clone()
+            })
+            .0
+        };
+        let not_by_steps = keccak_rows(
+            block
+                .keccak_inputs
+                .iter()
+                .filter(|input| !block.sha3_inputs.contains(input))
+                .cloned()
+                .collect(),
+        );
+        assert_eq!(total(|cost| cost.keccak_permutations), 2);
+        assert_eq!(
+            keccak_rows(block.keccak_inputs.clone()),
+            not_by_steps + total(|cost| cost.keccak_permutations) * keccak_rows(vec![vec![0]])
+        );
+    }
+}
@@ -1,22 +1,30 @@
-use bus_mapping::circuit_input_builder::FeatureConfig;
-use cli_table::{print_stdout, Cell, Style, Table};
+use bus_mapping::circuit_input_builder::{BlockWitnessBundle, FeatureConfig};
+use cli_table::{print_stdout, Cell, Style, Table, WithTitle};
 use eth_types::{bytecode, evm_types::OpcodeId, ToWord};
 use halo2_proofs::{
     halo2curves::bn256::Fr,
     plonk::{Circuit, ConstraintSystem},
 };
 mod helpers;
-use helpers::{bytecode_prefix_op_big_rws, print_circuit_stats_by_states};
+use helpers::{
+    block_cost_by_states, bytecode_prefix_op_big_rws, print_circuit_stats_by_states,
+    search_step_layouts,
+};
 use itertools::Itertools;
 use mock::MOCK_ACCOUNTS;
-use std::env;
-use zkevm_circuits::evm_circuit::{
-    param::{
-        LOOKUP_CONFIG, N_COPY_COLUMNS, N_PHASE1_COLUMNS, N_PHASE2_COLUMNS, N_U16_LOOKUPS,
-        N_U8_LOOKUPS,
+use std::{collections::HashMap, env};
+use strum::IntoEnumIterator;
+use zkevm_circuits::{
+    evm_circuit::{
+        coverage::ConstraintCoverage,
+        param::{
+            LOOKUP_CONFIG, N_COPY_COLUMNS, N_PHASE1_COLUMNS, N_PHASE2_COLUMNS, N_U16_LOOKUPS,
+            N_U8_LOOKUPS,
+        },
+        step::ExecutionState,
+        EvmCircuit, EvmCircuitParams,
     },
-    step::ExecutionState,
-    EvmCircuit,
+    witness::{block_convert, WitnessDump},
 };
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -26,6 +34,20 @@ fn main() {
         "state" => state_states_stats(),
         "copy" => copy_states_stats(),
         "exec" => get_exec_steps_occupancy(),
+        "block" => block_states_stats(
+            args.get(2).expect("Missing trace bundle path"),
+            args.get(3).map(String::as_str).unwrap_or("table"),
+        ),
+        "layout" => step_layouts_stats(args.get(2).map(String::as_str)),
+        "coverage" => constraint_coverage_stats(args.get(2).expect("Missing coverage file path")),
+        "witness" => witness_dump(
+            args.get(2).expect("Missing trace bundle path"),
+            args.get(3).expect("Missing witness dump path"),
+        ),
+        "witness-diff" => witness_diff(
+            args.get(2).expect("Missing witness dump path"),
+            args.get(3).expect("Missing witness dump path"),
+        ),
         &_ => unreachable!("Unsupported arg"),
     }
 }
@@ -104,17 +126,153 @@ fn copy_states_stats() {
             block
                 .copy_events
                 .iter()
-                .map(|c| c.bytes.len() * 2)
+                .map(|c| c.steps().len() * 2)
                 .sum::<usize>()
         },
     );
 }
 
+/// Prints the cost per execution state of the block of a trace bundle, as
+/// a table, JSON or CSV.
+fn block_states_stats(path: &str, format: &str) {
+    let bundle = BlockWitnessBundle::load(path).expect("Could not load the trace bundle");
+    let builder = bundle
+        .gen_inputs_dynamic(FeatureConfig::default())
+        .expect("Could not generate the circuit inputs");
+    let block = block_convert::<Fr>(&builder).expect("Could not convert the block");
+    let step_heights = EvmCircuitParams {
+        feature_config: block.feature_config,
+        ..Default::default()
+    }
+    .step_heights();
+    let costs = block_cost_by_states(&block, &step_heights);
+
+    match format {
+        "table" => print_stdout(costs.with_title()).expect("the table renders"),
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&costs).expect("the costs serialize")
+        ),
+        "csv" => {
+            println!("state,steps,rows,rws,copy_rows,keccak_permutations");
+            for cost in costs {
+                println!(
+                    "{},{},{},{},{},{}",
+                    cost.state,
+                    cost.steps,
+                    cost.rows,
+                    cost.rws,
+                    cost.copy_rows,
+                    cost.keccak_permutations
+                );
+            }
+        }
+        _ => unreachable!("Unsupported format"),
+    }
+}
+
+/// Prints the step layouts of the EVM circuit with the smallest area for the
+/// steps of the block of a trace bundle, or for one step of each execution
+/// state without a bundle.
+fn step_layouts_stats(path: Option<&str>) {
+    let workload: HashMap<ExecutionState, usize> = match path {
+        Some(path) => {
+            let bundle = BlockWitnessBundle::load(path).expect("Could not load the trace bundle");
+            let builder = bundle
+                .gen_inputs_dynamic(FeatureConfig::default())
+                .expect("Could not generate the circuit inputs");
+            let block = block_convert::<Fr>(&builder).expect("Could not convert the block");
+            block
+                .txs
+                .iter()
+                .flat_map(|tx| tx.steps())
+                .map(ExecutionState::from)
+                .counts()
+        }
+        None => ExecutionState::iter().map(|state| (state, 1)).collect(),
+    };
+
+    print_stdout(search_step_layouts(&EvmCircuitParams::default(), &workload, 10).with_title())
+        .expect("the table renders");
+}
+
+/// Prints the constraint coverage of the execution gadgets collected by the
+/// tests with `CircuitTestBuilder::constraint_coverage`, the gadgets with the
+/// most constraints never hit first, followed by the constraints never hit.
+fn constraint_coverage_stats(path: &str) {
+    let coverage = ConstraintCoverage::load(path).expect("Could not load the coverage file");
+
+    let summary = coverage
+        .summary()
+        .into_iter()
+        .sorted_by_key(|state| {
+            let uncovered =
+                state.gates + state.lookups - state.covered_gates - state.covered_lookups;
+            std::cmp::Reverse(uncovered)
+        })
+        .map(|state| {
+            vec![
+                state.execution_state,
+                format!("{}/{}", state.covered_gates, state.gates),
+                format!("{}/{}", state.covered_lookups, state.lookups),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let table = summary.table().title(vec![
+        "state".cell().bold(true),
+        "covered gates".cell().bold(true),
+        "covered lookups".cell().bold(true),
+    ]);
+    print_stdout(table).expect("the table renders");
+
+    let uncovered = coverage
+        .uncovered()
+        .map(|key| {
+            vec![
+                key.execution_state.clone(),
+                format!("{:?}", key.kind),
+                key.name.clone(),
+                key.location.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let table = uncovered.table().title(vec![
+        "state".cell().bold(true),
+        "kind".cell().bold(true),
+        "name".cell().bold(true),
+        "location".cell().bold(true),
+    ]);
+    print_stdout(table).expect("the table renders");
+}
+
+/// Writes the witness of the block of a trace bundle as a JSON dump.
+fn witness_dump(bundle_path: &str, dump_path: &str) {
+    let bundle = BlockWitnessBundle::load(bundle_path).expect("Could not load the trace bundle");
+    let builder = bundle
+        .gen_inputs_dynamic(FeatureConfig::default())
+        .expect("Could not generate the circuit inputs");
+    let block = block_convert::<Fr>(&builder).expect("Could not convert the block");
+    WitnessDump::new(&block)
+        .save(dump_path)
+        .expect("Could not save the witness dump");
+}
+
+/// Prints the fields in which two witness dumps differ.
+fn witness_diff(left_path: &str, right_path: &str) {
+    let left = WitnessDump::load(left_path).expect("Could not load the witness dump");
+    let right = WitnessDump::load(right_path).expect("Could not load the witness dump");
+    let diffs = left.diff(&right);
+    for diff in &diffs {
+        println!("{}", diff);
+    }
+    println!("{} differences", diffs.len());
+}
+
 /// This function prints to stdout a table with the top X ExecutionState
 /// cell consumers of each EVM Cell type.
 fn get_exec_steps_occupancy() {
     let mut meta = ConstraintSystem::<Fr>::default();
-    let circuit = EvmCircuit::configure_with_params(&mut meta, FeatureConfig::default());
+    let circuit = EvmCircuit::configure_with_params(&mut meta, EvmCircuitParams::default());
 
     let report = circuit.0.execution.instrument().clone().analyze();
     macro_rules! gen_report {
@@ -14,9 +14,9 @@ use crate::{
     evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
     table::{
         BytecodeFieldTag, BytecodeTable, CopyTable, LookupTable, RwTable, TxContextFieldTag,
-        TxTable,
+        TxTable, UXTable,
     },
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{word::WordLoHi, Challenges, SubCircuit, SubCircuitConfig},
     witness,
     witness::{RwMap, Transaction},
 };
@@ -28,6 +28,7 @@ use bus_mapping::{
 use eth_types::Field;
 use gadgets::{
     binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
     less_than::{LtChip, LtConfig, LtInstruction},
     util::{and, not, or, Expr},
 };
@@ -57,13 +58,38 @@ pub struct CopyCircuitConfig<F> {
     pub is_last: Column<Advice>,
     /// The value copied in this copy step.
     pub value: Column<Advice>,
-    /// Random linear combination accumulator value.
+    /// The memory value before this copy step, equal to `value` unless the
+    /// byte is copied to memory.
+    pub value_prev: Column<Advice>,
+    /// Random linear combination accumulator value of the copied bytes, kept
+    /// separately by the read and the write rows.
     pub value_acc_rlc: Column<Advice>,
     /// Whether the row is padding.
     pub is_pad: Column<Advice>,
     /// In case of a bytecode tag, this denotes whether or not the copied byte
     /// is an opcode or push data byte.
     pub is_code: Column<Advice>,
+    /// Whether the row is not a copied byte, but completes a memory word or
+    /// pads the shorter side of the copy event.
+    pub mask: Column<Advice>,
+    /// Whether the row is a masked row before the first copied byte.
+    pub front_mask: Column<Advice>,
+    /// Whether the row is a byte of a memory word.
+    pub is_word: Column<Advice>,
+    /// The index of the byte in its memory word.
+    pub word_index: Column<Advice>,
+    /// Whether the byte is in the low 16 bytes of its memory word.
+    pub is_word_lo: Column<Advice>,
+    /// The 32-byte aligned address of the memory word.
+    pub word_addr: Column<Advice>,
+    /// The memory word accumulated up to this byte.
+    pub value_word: WordLoHi<Column<Advice>>,
+    /// The memory word before the copy accumulated up to this byte.
+    pub value_word_prev: WordLoHi<Column<Advice>>,
+    /// Whether the byte is the last one of its memory word.
+    pub is_word_end: IsZeroConfig<F>,
+    /// Whether the byte is the last one of the high half of its memory word.
+    pub is_word_hi_end: IsZeroConfig<F>,
     /// Whether the row is enabled or not.
     pub q_enable: Column<Fixed>,
     /// The Copy Table contains the columns that are exposed via the lookup
@@ -80,6 +106,8 @@ pub struct CopyCircuitConfig<F> {
     pub rw_table: RwTable,
     /// BytecodeTable
     pub bytecode_table: BytecodeTable,
+    /// u8 table
+    pub u8_table: UXTable<8>,
 }
 
 /// Circuit configuration arguments
@@ -94,6 +122,8 @@ pub struct CopyCircuitConfigArgs<F: Field> {
     pub copy_table: CopyTable,
     /// q_enable
     pub q_enable: Column<Fixed>,
+    /// u8 table
+    pub u8_table: UXTable<8>,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -111,15 +141,25 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             bytecode_table,
             copy_table,
             q_enable,
+            u8_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
         let q_step = meta.complex_selector();
         let is_last = meta.advice_column();
         let value = meta.advice_column();
+        let value_prev = meta.advice_column();
         let value_acc_rlc = meta.advice_column_in(SecondPhase);
         let is_code = meta.advice_column();
         let is_pad = meta.advice_column();
+        let mask = meta.advice_column();
+        let front_mask = meta.advice_column();
+        let is_word = meta.advice_column();
+        let word_index = meta.advice_column();
+        let is_word_lo = meta.advice_column();
+        let word_addr = meta.advice_column();
+        let value_word = WordLoHi::new([meta.advice_column(), meta.advice_column()]);
+        let value_word_prev = WordLoHi::new([meta.advice_column(), meta.advice_column()]);
         let is_first = copy_table.is_first;
         let id = copy_table.id;
         let addr = copy_table.addr;
@@ -135,6 +175,57 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         rw_table.annotate_columns(meta);
         bytecode_table.annotate_columns(meta);
         copy_table.annotate_columns(meta);
+        u8_table.annotate_columns(meta);
+
+        let is_word_end = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_enable, Rotation::cur()),
+            |meta| meta.query_advice(word_index, Rotation::cur()) - 31.expr(),
+            meta.advice_column(),
+        );
+        let is_word_hi_end = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_enable, Rotation::cur()),
+            |meta| meta.query_advice(word_index, Rotation::cur()) - 15.expr(),
+            meta.advice_column(),
+        );
+
+        // A memory word starts with the high half accumulating its first byte.
+        let constrain_word_start =
+            |cb: &mut BaseConstraintBuilder<F>, meta: &mut VirtualCells<F>, rotation: Rotation| {
+                cb.condition(meta.query_advice(is_word, rotation), |cb| {
+                    cb.require_zero(
+                        "word_index == 0 for a new word",
+                        meta.query_advice(word_index, rotation),
+                    );
+                    cb.require_zero(
+                        "is_word_lo == 0 for a new word",
+                        meta.query_advice(is_word_lo, rotation),
+                    );
+                    for (word, byte) in [(value_word, value), (value_word_prev, value_prev)] {
+                        cb.require_equal_word(
+                            "memory word starts with its first byte",
+                            word.map(|limb| meta.query_advice(limb, rotation)),
+                            WordLoHi::new([0.expr(), meta.query_advice(byte, rotation)]),
+                        );
+                    }
+                });
+            };
+        // Each side of a copy event ends with all the bytes copied and the last memory word
+        // complete.
+        let constrain_side_end = |cb: &mut BaseConstraintBuilder<F>,
+                                  meta: &mut VirtualCells<F>,
+                                  is_word_end: Expression<F>| {
+            cb.require_equal(
+                "bytes_left == 1 - mask for the last row of a side",
+                meta.query_advice(bytes_left, Rotation::cur()),
+                not::expr(meta.query_advice(mask, Rotation::cur())),
+            );
+            cb.require_zero(
+                "the last row of a side ends its memory word",
+                meta.query_advice(is_word, Rotation::cur()) * not::expr(is_word_end),
+            );
+        };
 
         let addr_lt_addr_end = LtChip::configure(
             meta,
@@ -171,6 +262,60 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
 
             constrain_must_terminate(&mut cb, meta, q_enable, &tag);
 
+            let is_mask = meta.query_advice(mask, Rotation::cur());
+            let is_word_row = meta.query_advice(is_word, Rotation::cur());
+            cb.require_boolean("mask is boolean", is_mask.clone());
+            cb.require_boolean(
+                "front_mask is boolean",
+                meta.query_advice(front_mask, Rotation::cur()),
+            );
+            cb.require_boolean("is_word is boolean", is_word_row.clone());
+            cb.require_boolean(
+                "is_word_lo is boolean",
+                meta.query_advice(is_word_lo, Rotation::cur()),
+            );
+            cb.require_zero(
+                "mask == 1 when front_mask == 1",
+                meta.query_advice(front_mask, Rotation::cur()) * not::expr(is_mask.clone()),
+            );
+            cb.require_zero(
+                "mask == 0 when is_pad == 1",
+                meta.query_advice(is_pad, Rotation::cur()) * is_mask.clone(),
+            );
+
+            let is_memory = tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta);
+            cb.require_zero(
+                "is_word == 0 when tag != Memory",
+                is_word_row.clone() * not::expr(is_memory.clone()),
+            );
+            cb.require_zero(
+                "is_word == 1 for a copied memory byte",
+                and::expr([
+                    is_memory.clone(),
+                    not::expr(meta.query_advice(is_pad, Rotation::cur())),
+                    not::expr(is_mask.clone()),
+                    not::expr(is_word_row.clone()),
+                ]),
+            );
+            cb.condition(is_word_row.clone() * not::expr(is_mask.clone()), |cb| {
+                cb.require_equal(
+                    "addr == word_addr + word_index for a copied memory byte",
+                    meta.query_advice(addr, Rotation::cur()),
+                    meta.query_advice(word_addr, Rotation::cur())
+                        + meta.query_advice(word_index, Rotation::cur()),
+                );
+            });
+            cb.condition(
+                is_word_row.clone() * or::expr([meta.query_selector(q_step), is_mask.clone()]),
+                |cb| {
+                    cb.require_equal(
+                        "value == value_prev unless a copied byte is written to memory",
+                        meta.query_advice(value, Rotation::cur()),
+                        meta.query_advice(value_prev, Rotation::cur()),
+                    );
+                },
+            );
+
             let not_last_two_rows = 1.expr()
                 - meta.query_advice(is_last, Rotation::cur())
                 - meta.query_advice(is_last, Rotation::next());
@@ -191,8 +336,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                         tag.value(Rotation(2))(meta),
                     );
                     cb.require_equal(
-                        "rows[0].addr + 1 == rows[2].addr",
-                        meta.query_advice(addr, Rotation::cur()) + 1.expr(),
+                        "rows[0].addr + 1 - rows[0].mask == rows[2].addr",
+                        meta.query_advice(addr, Rotation::cur()) + not::expr(is_mask.clone()),
                         meta.query_advice(addr, Rotation(2)),
                     );
                     cb.require_equal(
@@ -200,16 +345,101 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                         meta.query_advice(src_addr_end, Rotation::cur()),
                         meta.query_advice(src_addr_end, Rotation(2)),
                     );
+                    cb.require_equal(
+                        "rows[0].bytes_left == rows[2].bytes_left + 1 - rows[0].mask",
+                        meta.query_advice(bytes_left, Rotation::cur()),
+                        meta.query_advice(bytes_left, Rotation(2)) + not::expr(is_mask.clone()),
+                    );
+                    cb.require_zero(
+                        "front_mask only precedes the copied bytes",
+                        meta.query_advice(front_mask, Rotation(2))
+                            * not::expr(meta.query_advice(front_mask, Rotation::cur())),
+                    );
+
+                    let value_acc_rlc_next = meta.query_advice(value_acc_rlc, Rotation(2));
+                    let value_acc_rlc = meta.query_advice(value_acc_rlc, Rotation::cur());
+                    cb.condition(meta.query_advice(mask, Rotation(2)), |cb| {
+                        cb.require_equal(
+                            "value_acc_rlc(2) == value_acc_rlc(0) for a masked row",
+                            value_acc_rlc_next.clone(),
+                            value_acc_rlc.clone(),
+                        );
+                    });
+                    cb.condition(not::expr(meta.query_advice(mask, Rotation(2))), |cb| {
+                        cb.require_equal(
+                            "value_acc_rlc(2) == value_acc_rlc(0) * r + value(2)",
+                            value_acc_rlc_next,
+                            value_acc_rlc * challenges.keccak_input()
+                                + meta.query_advice(value, Rotation(2)),
+                        );
+                    });
+
+                    // The bytes of a memory word are contiguous, and the memory words of a
+                    // side are contiguous too.
+                    let is_word_next = meta.query_advice(is_word, Rotation(2));
+                    let is_word_end = is_word_end.expr();
+                    cb.require_zero(
+                        "the memory words come before the other rows",
+                        is_word_next.clone() * not::expr(is_word_row.clone()),
+                    );
+                    cb.condition(is_word_row.clone() * not::expr(is_word_end.clone()), |cb| {
+                        cb.require_equal(
+                            "the memory word continues on rows[2]",
+                            is_word_next.clone(),
+                            1.expr(),
+                        );
+                        cb.require_equal(
+                            "rows[0].word_index + 1 == rows[2].word_index",
+                            meta.query_advice(word_index, Rotation::cur()) + 1.expr(),
+                            meta.query_advice(word_index, Rotation(2)),
+                        );
+                        cb.require_equal(
+                            "rows[0].word_addr == rows[2].word_addr",
+                            meta.query_advice(word_addr, Rotation::cur()),
+                            meta.query_advice(word_addr, Rotation(2)),
+                        );
+                        cb.require_equal(
+                            "is_word_lo switches on after the high half of the word",
+                            meta.query_advice(is_word_lo, Rotation::cur()) + is_word_hi_end.expr(),
+                            meta.query_advice(is_word_lo, Rotation(2)),
+                        );
+                        let is_word_lo_next = meta.query_advice(is_word_lo, Rotation(2));
+                        for (word, byte) in [(value_word, value), (value_word_prev, value_prev)] {
+                            let byte = meta.query_advice(byte, Rotation(2));
+                            let (lo, hi) = word
+                                .map(|limb| meta.query_advice(limb, Rotation::cur()))
+                                .to_lo_hi();
+                            let (lo_next, hi_next) = word
+                                .map(|limb| meta.query_advice(limb, Rotation(2)))
+                                .to_lo_hi();
+                            cb.require_equal(
+                                "memory word hi accumulates the high half of the word",
+                                hi_next,
+                                hi.clone()
+                                    + not::expr(is_word_lo_next.clone())
+                                        * (hi * 255.expr() + byte.clone()),
+                            );
+                            cb.require_equal(
+                                "memory word lo accumulates the low half of the word",
+                                lo_next,
+                                lo.clone() + is_word_lo_next.clone() * (lo * 255.expr() + byte),
+                            );
+                        }
+                    });
+                    cb.condition(is_word_next * is_word_end, |cb| {
+                        cb.require_equal(
+                            "rows[0].word_addr + 32 == rows[2].word_addr for a new word",
+                            meta.query_advice(word_addr, Rotation::cur()) + 32.expr(),
+                            meta.query_advice(word_addr, Rotation(2)),
+                        );
+                        constrain_word_start(cb, meta, Rotation(2));
+                    });
                 },
             );
 
-            let rw_diff = and::expr([
-                or::expr([
-                    tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
-                    tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta),
-                ]),
-                not::expr(meta.query_advice(is_pad, Rotation::cur())),
-            ]);
+            let rw_diff = meta.query_advice(is_word, Rotation::cur()) * is_word_end.expr()
+                + tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta)
+                    * not::expr(is_mask.clone());
             cb.condition(
                 not::expr(meta.query_advice(is_last, Rotation::cur())),
                 |cb| {
@@ -236,6 +466,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     meta.query_advice(rwc_inc_left, Rotation::cur()),
                     rw_diff,
                 );
+                constrain_side_end(cb, meta, is_word_end.expr());
             });
 
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
@@ -270,58 +501,30 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         meta.create_gate("verify step (q_step == 1)", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
-            cb.require_zero(
-                "bytes_left == 1 for last step",
-                and::expr([
-                    meta.query_advice(is_last, Rotation::next()),
-                    1.expr() - meta.query_advice(bytes_left, Rotation::cur()),
-                ]),
-            );
-            cb.condition(
-                not::expr(or::expr([
-                    meta.query_advice(is_last, Rotation::next()),
-                    tag.value_equals(CopyDataType::Padding, Rotation::cur())(meta),
-                ])),
-                |cb| {
+            cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "rows[0].bytes_left == rows[1].bytes_left at every first copy event",
+                    meta.query_advice(bytes_left, Rotation::cur()),
+                    meta.query_advice(bytes_left, Rotation::next()),
+                );
+                for rotation in [Rotation::cur(), Rotation::next()] {
                     cb.require_equal(
-                        "bytes_left == bytes_left_next + 1 for non-last step",
-                        meta.query_advice(bytes_left, Rotation::cur()),
-                        meta.query_advice(bytes_left, Rotation(2)) + 1.expr(),
+                        "value_acc_rlc == value unless masked at every first copy event",
+                        meta.query_advice(value_acc_rlc, rotation),
+                        meta.query_advice(value, rotation)
+                            * not::expr(meta.query_advice(mask, rotation)),
                     );
-                },
-            );
-            cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
+                    constrain_word_start(cb, meta, rotation);
+                }
+            });
+            cb.condition(meta.query_advice(is_last, Rotation::next()), |cb| {
                 cb.require_equal(
-                    "value == value_acc_rlc at every first copy event",
-                    meta.query_advice(value, Rotation::cur()),
+                    "read and write value_acc_rlc are equal on the last step",
                     meta.query_advice(value_acc_rlc, Rotation::cur()),
+                    meta.query_advice(value_acc_rlc, Rotation::next()),
                 );
+                constrain_side_end(cb, meta, is_word_end.expr());
             });
-            cb.require_equal(
-                "write value == read value",
-                meta.query_advice(value, Rotation::cur()),
-                meta.query_advice(value, Rotation::next()),
-            );
-            cb.require_equal(
-                "value_acc_rlc is same for read-write rows",
-                meta.query_advice(value_acc_rlc, Rotation::cur()),
-                meta.query_advice(value_acc_rlc, Rotation::next()),
-            );
-            cb.condition(
-                and::expr([
-                    not::expr(meta.query_advice(is_last, Rotation::next())),
-                    not::expr(meta.query_advice(is_pad, Rotation::cur())),
-                ]),
-                |cb| {
-                    cb.require_equal(
-                        "value_acc_rlc(2) == value_acc_rlc(0) * r + value(2)",
-                        meta.query_advice(value_acc_rlc, Rotation(2)),
-                        meta.query_advice(value_acc_rlc, Rotation::cur())
-                            * challenges.keccak_input()
-                            + meta.query_advice(value, Rotation(2)),
-                    );
-                },
-            );
             cb.require_zero(
                 "value == 0 when is_pad == 1 for read",
                 and::expr([
@@ -329,11 +532,13 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     meta.query_advice(value, Rotation::cur()),
                 ]),
             );
-            cb.require_equal(
-                "is_pad == 1 - (src_addr < src_addr_end) for read row",
-                1.expr() - addr_lt_addr_end.is_lt(meta, None),
-                meta.query_advice(is_pad, Rotation::cur()),
-            );
+            cb.condition(not::expr(meta.query_advice(mask, Rotation::cur())), |cb| {
+                cb.require_equal(
+                    "is_pad == 1 - (src_addr < src_addr_end) for read row",
+                    1.expr() - addr_lt_addr_end.is_lt(meta, None),
+                    meta.query_advice(is_pad, Rotation::cur()),
+                );
+            });
             cb.require_zero(
                 "is_pad == 0 for write row",
                 meta.query_advice(is_pad, Rotation::next()),
@@ -344,21 +549,24 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
 
         meta.lookup_any("Memory lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
-                * tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta)
-                * not::expr(meta.query_advice(is_pad, Rotation::cur()));
+                * meta.query_advice(is_word, Rotation::cur())
+                * is_word_end.expr();
+            let value_word = value_word.map(|limb| meta.query_advice(limb, Rotation::cur()));
+            let value_word_prev =
+                value_word_prev.map(|limb| meta.query_advice(limb, Rotation::cur()));
             vec![
                 meta.query_advice(rw_counter, Rotation::cur()),
                 not::expr(meta.query_selector(q_step)),
                 Target::Memory.expr(),
                 meta.query_advice(id.lo(), Rotation::cur()), // call_id
-                meta.query_advice(addr, Rotation::cur()),    // memory address
+                meta.query_advice(word_addr, Rotation::cur()), // memory word address
                 0.expr(),                                    // field tag
                 0.expr(),                                    // storage_key_lo
                 0.expr(),                                    // storage_key_hi
-                meta.query_advice(value, Rotation::cur()),   // value_lo
-                0.expr(),                                    // value_hi
-                0.expr(),                                    // value_prev_lo
-                0.expr(),                                    // value_prev_hi
+                value_word.lo(),                             // value_lo
+                value_word.hi(),                             // value_hi
+                value_word_prev.lo(),                        // value_prev_lo
+                value_word_prev.hi(),                        // value_prev_hi
                 0.expr(),                                    // init_val_lo
                 0.expr(),                                    // init_val_hi
             ]
@@ -368,9 +576,24 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             .collect()
         });
 
+        for (name, column) in [
+            ("Memory value is a byte", value),
+            ("Memory value_prev is a byte", value_prev),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let cond = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_word, Rotation::cur());
+                vec![(
+                    cond * meta.query_advice(column, Rotation::cur()),
+                    u8_table.table_exprs(meta)[0].clone(),
+                )]
+            });
+        }
+
         meta.lookup_any("TxLog lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
-                * tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta);
+                * tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta)
+                * not::expr(meta.query_advice(mask, Rotation::cur()));
             vec![
                 meta.query_advice(rw_counter, Rotation::cur()),
                 1.expr(),
@@ -396,7 +619,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         meta.lookup_any("Bytecode lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
                 * tag.value_equals(CopyDataType::Bytecode, Rotation::cur())(meta)
-                * not::expr(meta.query_advice(is_pad, Rotation::cur()));
+                * not::expr(meta.query_advice(is_pad, Rotation::cur()))
+                * not::expr(meta.query_advice(mask, Rotation::cur()));
             vec![
                 meta.query_advice(id.lo(), Rotation::cur()),
                 meta.query_advice(id.hi(), Rotation::cur()),
@@ -414,7 +638,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         meta.lookup_any("Tx calldata lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
                 * tag.value_equals(CopyDataType::TxCalldata, Rotation::cur())(meta)
-                * not::expr(meta.query_advice(is_pad, Rotation::cur()));
+                * not::expr(meta.query_advice(is_pad, Rotation::cur()))
+                * not::expr(meta.query_advice(mask, Rotation::cur()));
             vec![
                 meta.query_advice(id.lo(), Rotation::cur()), /* For transaction ID we use lo
                                                               * limb only */
@@ -465,15 +690,27 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             q_step,
             is_last,
             value,
+            value_prev,
             value_acc_rlc,
             is_pad,
             is_code,
+            mask,
+            front_mask,
+            is_word,
+            word_index,
+            is_word_lo,
+            word_addr,
+            value_word,
+            value_word_prev,
+            is_word_end,
+            is_word_hi_end,
             q_enable,
             addr_lt_addr_end,
             copy_table,
             tx_table,
             rw_table,
             bytecode_table,
+            u8_table,
         }
     }
 }
@@ -512,9 +749,14 @@ impl<F: Field> CopyCircuitConfig<F> {
         challenges: Challenges<Value<F>>,
         copy_event: &CopyEvent,
     ) -> Result<(), Error> {
-        for (step_idx, (tag, table_row, circuit_row)) in
+        let steps = copy_event
+            .steps()
+            .into_iter()
+            .flat_map(|(read_step, write_step)| [read_step, write_step]);
+        for (step_idx, ((tag, table_row, circuit_row), copy_step)) in
             CopyTable::assignments(copy_event, challenges)
                 .iter()
+                .zip_eq(steps)
                 .enumerate()
         {
             let is_read = step_idx % 2 == 0;
@@ -525,8 +767,8 @@ impl<F: Field> CopyCircuitConfig<F> {
                     .iter()
                     .zip_eq(table_row)
             {
-                // Leave sr_addr_end and bytes_left unassigned when !is_read
-                if !is_read && (label == "src_addr_end" || label == "bytes_left") {
+                // Leave sr_addr_end unassigned when !is_read
+                if !is_read && label == "src_addr_end" {
                 } else {
                     region.assign_advice(
                         || format!("{} at row: {}", label, offset),
@@ -549,17 +791,8 @@ impl<F: Field> CopyCircuitConfig<F> {
                 || Value::known(F::ONE),
             )?;
 
-            // is_last, value, is_pad, is_code
-            for (column, &(value, label)) in [
-                self.is_last,
-                self.value,
-                self.value_acc_rlc,
-                self.is_pad,
-                self.is_code,
-            ]
-            .iter()
-            .zip_eq(circuit_row)
-            {
+            // is_last, value, is_pad, is_code, mask and the memory word columns
+            for (column, &(value, label)) in self.circuit_columns().iter().zip_eq(circuit_row) {
                 region.assign_advice(
                     || format!("{} at row: {}", label, *offset),
                     *column,
@@ -571,14 +804,16 @@ impl<F: Field> CopyCircuitConfig<F> {
             // tag
             tag_chip.assign(region, *offset, tag)?;
 
+            // is_word_end, is_word_hi_end
+            let word_index = copy_step.word.map_or(0, |(_, index)| index) as u64;
+            self.assign_word_index(region, *offset, word_index)?;
+
             // lt chip
             if is_read {
                 lt_chip.assign(
                     region,
                     *offset,
-                    Value::known(F::from(
-                        copy_event.src_addr + u64::try_from(step_idx).unwrap() / 2u64,
-                    )),
+                    Value::known(F::from(copy_step.addr)),
                     Value::known(F::from(copy_event.src_addr_end)),
                 )?;
             }
@@ -589,6 +824,44 @@ impl<F: Field> CopyCircuitConfig<F> {
         Ok(())
     }
 
+    // The columns of the copy circuit rows generated by `CopyTable::assignments`.
+    fn circuit_columns(&self) -> [Column<Advice>; 16] {
+        [
+            self.is_last,
+            self.value,
+            self.value_prev,
+            self.value_acc_rlc,
+            self.is_pad,
+            self.is_code,
+            self.mask,
+            self.front_mask,
+            self.is_word,
+            self.word_index,
+            self.is_word_lo,
+            self.word_addr,
+            self.value_word.lo(),
+            self.value_word.hi(),
+            self.value_word_prev.lo(),
+            self.value_word_prev.hi(),
+        ]
+    }
+
+    fn assign_word_index(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        word_index: u64,
+    ) -> Result<(), Error> {
+        for (config, word_end) in [(&self.is_word_end, 31), (&self.is_word_hi_end, 15)] {
+            IsZeroChip::construct(config.clone()).assign(
+                region,
+                offset,
+                Value::known(F::from(word_index) - F::from(word_end)),
+            )?;
+        }
+        Ok(())
+    }
+
     /// Assign vec of copy events
     pub fn assign_copy_events(
         &self,
@@ -597,7 +870,10 @@ impl<F: Field> CopyCircuitConfig<F> {
         max_copy_rows: usize,
         challenges: Challenges<Value<F>>,
     ) -> Result<(), Error> {
-        let copy_rows_needed = copy_events.iter().map(|c| c.bytes.len() * 2).sum::<usize>();
+        let copy_rows_needed = copy_events
+            .iter()
+            .map(|c| c.steps().len() * 2)
+            .sum::<usize>();
 
         assert!(
             copy_rows_needed + DISABLED_ROWS + UNUSED_ROWS <= max_copy_rows,
@@ -615,8 +891,19 @@ impl<F: Field> CopyCircuitConfig<F> {
             |mut region| {
                 region.name_column(|| "is_last", self.is_last);
                 region.name_column(|| "value", self.value);
+                region.name_column(|| "value_prev", self.value_prev);
                 region.name_column(|| "is_code", self.is_code);
                 region.name_column(|| "is_pad", self.is_pad);
+                region.name_column(|| "mask", self.mask);
+                region.name_column(|| "front_mask", self.front_mask);
+                region.name_column(|| "is_word", self.is_word);
+                region.name_column(|| "word_index", self.word_index);
+                region.name_column(|| "is_word_lo", self.is_word_lo);
+                region.name_column(|| "word_addr", self.word_addr);
+                self.is_word_end
+                    .annotate_columns_in_region(&mut region, "is_word_end");
+                self.is_word_hi_end
+                    .annotate_columns_in_region(&mut region, "is_word_hi_end");
 
                 let mut offset = 0;
                 for copy_event in copy_events.iter() {
@@ -671,13 +958,15 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::ZERO),
         )?;
-        // is_last
-        region.assign_advice(
-            || format!("assign is_last {}", *offset),
-            self.is_last,
-            *offset,
-            || Value::known(F::ZERO),
-        )?;
+        // is_last, value, is_pad, is_code, mask and the memory word columns
+        for column in self.circuit_columns() {
+            region.assign_advice(
+                || format!("assign copy circuit column {}", *offset),
+                column,
+                *offset,
+                || Value::known(F::ZERO),
+            )?;
+        }
         // id
         region.assign_advice(
             || format!("assign id lo {}", *offset),
@@ -712,20 +1001,6 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::ZERO),
         )?;
-        // value
-        region.assign_advice(
-            || format!("assign value {}", *offset),
-            self.value,
-            *offset,
-            || Value::known(F::ZERO),
-        )?;
-        // value_acc_rlc
-        region.assign_advice(
-            || format!("assign value_acc_rlc {}", *offset),
-            self.value_acc_rlc,
-            *offset,
-            || Value::known(F::ZERO),
-        )?;
         // rlc_acc
         region.assign_advice(
             || format!("assign rlc_acc {}", *offset),
@@ -733,20 +1008,6 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::ZERO),
         )?;
-        // is_code
-        region.assign_advice(
-            || format!("assign is_code {}", *offset),
-            self.is_code,
-            *offset,
-            || Value::known(F::ZERO),
-        )?;
-        // is_pad
-        region.assign_advice(
-            || format!("assign is_pad {}", *offset),
-            self.is_pad,
-            *offset,
-            || Value::known(F::ZERO),
-        )?;
         // rw_counter
         region.assign_advice(
             || format!("assign rw_counter {}", *offset),
@@ -763,6 +1024,8 @@ impl<F: Field> CopyCircuitConfig<F> {
         )?;
         // tag
         tag_chip.assign(region, *offset, &CopyDataType::Padding)?;
+        // is_word_end, is_word_hi_end
+        self.assign_word_index(region, *offset, 0)?;
         // Assign LT gadget
         lt_chip.assign(region, *offset, Value::known(F::ZERO), Value::known(F::ONE))?;
 
@@ -868,7 +1131,7 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
             block
                 .copy_events
                 .iter()
-                .map(|c| c.bytes.len() * 2)
+                .map(|c| c.steps().len() * 2)
                 .sum::<usize>()
                 + 2,
             block.circuits_params.max_copy_rows,
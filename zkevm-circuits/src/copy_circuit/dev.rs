@@ -2,7 +2,7 @@ pub use super::CopyCircuit;
 
 use crate::{
     copy_circuit::{CopyCircuitConfig, CopyCircuitConfigArgs},
-    table::{BytecodeTable, CopyTable, RwTable, TxTable},
+    table::{BytecodeTable, CopyTable, RwTable, TxTable, UXTable},
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
 use eth_types::Field;
@@ -26,6 +26,7 @@ impl<F: Field> Circuit<F> for CopyCircuit<F> {
         let bytecode_table = BytecodeTable::construct(meta);
         let q_enable = meta.fixed_column();
         let copy_table = CopyTable::construct(meta, q_enable);
+        let u8_table = UXTable::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenge_exprs = challenges.exprs(meta);
 
@@ -38,6 +39,7 @@ impl<F: Field> Circuit<F> for CopyCircuit<F> {
                     bytecode_table,
                     copy_table,
                     q_enable,
+                    u8_table,
                     challenges: challenge_exprs,
                 },
             ),
@@ -69,6 +71,7 @@ impl<F: Field> Circuit<F> for CopyCircuit<F> {
             .0
             .bytecode_table
             .load(&mut layouter, self.external_data.bytecodes.clone())?;
+        config.0.u8_table.load(&mut layouter)?;
         self.synthesize_sub(&config.0, &challenge_values, &mut layouter)
     }
 }
@@ -88,6 +88,38 @@ fn gen_calldatacopy_data() -> CircuitInputBuilder<FixedCParams> {
         .unwrap()
 }
 
+fn gen_unaligned_calldatacopy_data() -> CircuitInputBuilder<FixedCParams> {
+    let length = 0x45usize;
+    let code = bytecode! {
+        PUSH32(Word::MAX)
+        PUSH1(0x20)
+        MSTORE
+        PUSH32(Word::from(length))
+        PUSH32(Word::from(0x03))
+        PUSH32(Word::from(0x11))
+        CALLDATACOPY
+        STOP
+    };
+    let calldata = rand_bytes(0x40);
+    let test_ctx = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(code),
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[1].address)
+                .to(accs[0].address)
+                .input(calldata.into());
+        },
+        |block, _txs| block.number(0xcafeu64),
+    )
+    .unwrap();
+    let block: GethData = test_ctx.into();
+    let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap()
+}
+
 fn gen_codecopy_data() -> CircuitInputBuilder<FixedCParams> {
     let code = bytecode! {
         PUSH32(Word::from(0x20))
@@ -172,6 +204,24 @@ fn gen_tx_log_data() -> CircuitInputBuilder<FixedCParams> {
     builder
 }
 
+fn gen_unaligned_tx_log_data() -> CircuitInputBuilder<FixedCParams> {
+    let code = bytecode! {
+        PUSH32(Word::MAX)   // value
+        PUSH32(0)           // offset
+        MSTORE
+        PUSH1(32)           // length
+        PUSH1(3)            // offset
+        LOG0
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap()
+}
+
 #[test]
 fn copy_circuit_valid_calldatacopy() {
     let builder = gen_calldatacopy_data();
@@ -179,6 +229,13 @@ fn copy_circuit_valid_calldatacopy() {
     assert_eq!(test_copy_circuit_from_block(14, block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_unaligned_calldatacopy() {
+    let builder = gen_unaligned_calldatacopy_data();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_valid_codecopy() {
     let builder = gen_codecopy_data();
@@ -287,6 +344,36 @@ fn copy_circuit_invalid_tx_log() {
     );
 }
 
+#[test]
+fn copy_circuit_invalid_src_words() {
+    let mut builder = gen_unaligned_tx_log_data();
+
+    // modify the first byte of the source word before the copied bytes
+    builder.block.copy_events[0].src_words.front[0] ^= 1;
+
+    let block = block_convert::<Fr>(&builder).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(10, block),
+        vec!["Memory lookup"],
+    );
+}
+
+#[test]
+fn copy_circuit_invalid_dst_words() {
+    let mut builder = gen_unaligned_calldatacopy_data();
+
+    // modify the first byte of the destination word before the copied bytes
+    builder.block.copy_events[0].dst_words.front[0] ^= 1;
+
+    let block = block_convert::<Fr>(&builder).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(10, block),
+        vec!["Memory lookup"],
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     let builder = gen_tx_log_data();
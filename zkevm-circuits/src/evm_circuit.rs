@@ -5,6 +5,7 @@ use halo2_proofs::{
     plonk::*,
 };
 
+pub mod coverage;
 mod execution;
 pub mod param;
 pub mod step;
@@ -19,17 +20,23 @@ pub use self::EvmCircuit as TestEvmCircuit;
 
 pub use crate::witness;
 use crate::{
-    evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
+    evm_circuit::{
+        param::{
+            step_height_map, EvmColumnLayout, EvmLayoutError, MAX_STEP_HEIGHT, STEP_STATE_HEIGHT,
+        },
+        step::{step_state_width, ExecutionState},
+    },
     table::{
         BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
         UXTable,
     },
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{cell_manager::CellPlacementOrder, Challenges, SubCircuit, SubCircuitConfig},
 };
 use bus_mapping::{circuit_input_builder::FeatureConfig, evm::OpcodeId};
-use eth_types::Field;
+use eth_types::{evm_types::Hardfork, Field};
 use execution::ExecutionConfig;
 use itertools::Itertools;
+use std::collections::HashMap;
 use strum::IntoEnumIterator;
 use table::FixedTableTag;
 use witness::Block;
@@ -44,12 +51,13 @@ pub struct EvmCircuitConfig<F> {
     pub execution: Box<ExecutionConfig<F>>,
     // External tables
     tx_table: TxTable,
-    rw_table: RwTable,
+    pub(crate) rw_table: RwTable,
     bytecode_table: BytecodeTable,
     block_table: BlockTable,
-    copy_table: CopyTable,
-    keccak_table: KeccakTable,
+    pub(crate) copy_table: CopyTable,
+    pub(crate) keccak_table: KeccakTable,
     exp_table: ExpTable,
+    hardfork: Hardfork,
 }
 
 /// Circuit configuration arguments
@@ -76,6 +84,12 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub u16_table: UXTable<16>,
     /// Feature config
     pub feature_config: FeatureConfig,
+    /// Column layout
+    pub layout: EvmColumnLayout,
+    /// Order of the cells of the execution states
+    pub cell_placement: CellPlacementOrder,
+    /// Collect the constraint coverage of the execution gadgets
+    pub constraint_coverage: bool,
 }
 
 impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
@@ -96,9 +110,39 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             u8_table,
             u16_table,
             feature_config,
+            layout,
+            cell_placement,
+            constraint_coverage,
         }: Self::ConfigArgs,
     ) -> Self {
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
+        // In column-major order the columns of a cell type are filled one after
+        // the other, so the ones left empty by every execution state are found
+        // by configuring a copy of the constraint system, and dropped.
+        let layout = match cell_placement {
+            CellPlacementOrder::RowMajor => layout,
+            CellPlacementOrder::ColumnMajor => {
+                let execution = ExecutionConfig::configure(
+                    &mut meta.clone(),
+                    challenges.clone(),
+                    &fixed_table,
+                    &u8_table,
+                    &u16_table,
+                    &tx_table,
+                    &rw_table,
+                    &bytecode_table,
+                    &block_table,
+                    &copy_table,
+                    &keccak_table,
+                    &exp_table,
+                    feature_config,
+                    &layout,
+                    cell_placement,
+                    false,
+                );
+                layout.narrowed(execution.instrument().columns_used())
+            }
+        };
         let execution = Box::new(ExecutionConfig::configure(
             meta,
             challenges,
@@ -113,6 +157,9 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &keccak_table,
             &exp_table,
             feature_config,
+            &layout,
+            cell_placement,
+            constraint_coverage,
         ));
 
         u8_table.annotate_columns(meta);
@@ -142,6 +189,7 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            hardfork: feature_config.hardfork,
         }
     }
 }
@@ -157,7 +205,11 @@ impl<F: Field> EvmCircuitConfig<F> {
             || "fixed table",
             |mut region| {
                 for (offset, row) in std::iter::once([F::ZERO; 4])
-                    .chain(fixed_table_tags.iter().flat_map(|tag| tag.build()))
+                    .chain(
+                        fixed_table_tags
+                            .iter()
+                            .flat_map(|tag| tag.build(self.hardfork)),
+                    )
                     .enumerate()
                 {
                     for (column, value) in self.fixed_table.iter().zip_eq(row) {
@@ -171,12 +223,98 @@ impl<F: Field> EvmCircuitConfig<F> {
     }
 }
 
+/// Parameters of the EVM circuit configuration
+#[derive(Clone, Debug, Default)]
+pub struct EvmCircuitParams {
+    /// Feature config
+    pub feature_config: FeatureConfig,
+    /// Column layout
+    pub layout: EvmColumnLayout,
+    /// Order of the cells of the execution states after the state of the step:
+    /// row-major (default) gives the lowest step heights, and column-major
+    /// uses the fewest columns, the layout being narrowed to the columns
+    /// holding cells.
+    pub cell_placement: CellPlacementOrder,
+    /// Tag the constraints and lookups of the execution gadgets to collect
+    /// their coverage, see [`coverage`].
+    pub constraint_coverage: bool,
+}
+
+impl EvmCircuitParams {
+    /// Check that the EVM circuit can be configured with the column layout:
+    /// every cell type has columns and every execution state fits in the max
+    /// step height of the layout.
+    pub fn validate(&self) -> Result<(), EvmLayoutError> {
+        // A step height no execution state reaches
+        const UNBOUNDED_STEP_HEIGHT: usize = 1 << 16;
+
+        self.layout.check_columns(step_state_width())?;
+
+        // Configure with an unbounded step height to find out the height of
+        // each execution state. The column-major order fills the columns up to
+        // the max step height, so the cells of a type out of columns overflow
+        // it instead.
+        let layout = match self.cell_placement {
+            CellPlacementOrder::RowMajor => EvmColumnLayout {
+                max_step_height: UNBOUNDED_STEP_HEIGHT,
+                ..self.layout.clone()
+            },
+            CellPlacementOrder::ColumnMajor => EvmColumnLayout {
+                overflow_columns: true,
+                ..self.layout.clone()
+            },
+        };
+        let mut meta = ConstraintSystem::<halo2_proofs::halo2curves::bn256::Fr>::default();
+        let config = EvmCircuit::configure_with_params(
+            &mut meta,
+            Self {
+                feature_config: self.feature_config,
+                layout,
+                cell_placement: self.cell_placement,
+                constraint_coverage: false,
+            },
+        );
+        let too_high = config
+            .0
+            .execution
+            .height_map
+            .into_iter()
+            .filter(|(_, height)| *height > self.layout.max_step_height)
+            .sorted_by_key(|(state, _)| *state as usize)
+            .collect::<Vec<_>>();
+        if too_high.is_empty() {
+            Ok(())
+        } else {
+            Err(EvmLayoutError::StepTooHigh(too_high))
+        }
+    }
+
+    /// Return the height of each execution state with the feature config,
+    /// column layout and cell placement order, which must be valid.
+    pub fn step_heights(&self) -> HashMap<ExecutionState, usize> {
+        // The heights of the default layout are cached by feature config
+        if self.layout == EvmColumnLayout::default()
+            && self.cell_placement == CellPlacementOrder::RowMajor
+        {
+            return step_height_map(self.feature_config);
+        }
+        let mut meta = ConstraintSystem::<halo2_proofs::halo2curves::bn256::Fr>::default();
+        EvmCircuit::configure_with_params(&mut meta, self.clone())
+            .0
+            .execution
+            .height_map
+    }
+}
+
 /// Tx Circuit for verifying transaction signatures
 #[derive(Clone, Default, Debug)]
 pub struct EvmCircuit<F: Field> {
     /// Block
     pub block: Option<Block<F>>,
     fixed_table_tags: Vec<FixedTableTag>,
+    layout: EvmColumnLayout,
+    cell_placement: CellPlacementOrder,
+    constraint_coverage: bool,
 }
 
 impl<F: Field> EvmCircuit<F> {
@@ -185,8 +323,52 @@ impl<F: Field> EvmCircuit<F> {
         Self {
             block: Some(block),
             fixed_table_tags: FixedTableTag::iter().collect(),
+            layout: EvmColumnLayout::default(),
+            cell_placement: CellPlacementOrder::default(),
+            constraint_coverage: false,
+        }
+    }
+
+    /// Configure the circuit with the column layout instead of the default
+    /// one. The layout is expected to be valid, see
+    /// [`EvmCircuitParams::validate`].
+    pub fn with_layout(self, layout: EvmColumnLayout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Return the column layout of the circuit
+    pub fn layout(&self) -> &EvmColumnLayout {
+        &self.layout
+    }
+
+    /// Configure the circuit with the cell placement order instead of the
+    /// default row-major one.
+    pub fn with_cell_placement(self, cell_placement: CellPlacementOrder) -> Self {
+        Self {
+            cell_placement,
+            ..self
+        }
+    }
+
+    /// Return the cell placement order of the circuit
+    pub fn cell_placement(&self) -> CellPlacementOrder {
+        self.cell_placement
+    }
+
+    /// Collect the constraint coverage of the execution gadgets while the
+    /// circuit is synthesized, see [`coverage`].
+    pub fn with_constraint_coverage(self) -> Self {
+        Self {
+            constraint_coverage: true,
+            ..self
         }
     }
+
+    /// Return the height of each execution state with the layout of the
+    /// circuit
+    pub fn step_heights(&self) -> HashMap<ExecutionState, usize> {
+        self.params().step_heights()
+    }
     #[cfg(any(test, feature = "test-circuits"))]
     /// Construct the EvmCircuit with only subset of Fixed table tags required by tests to save
     /// testing time
@@ -195,12 +377,19 @@ impl<F: Field> EvmCircuit<F> {
         Self {
             block: Some(block),
             fixed_table_tags,
+            layout: EvmColumnLayout::default(),
+            cell_placement: CellPlacementOrder::default(),
+            constraint_coverage: false,
         }
     }
     #[cfg(any(test, feature = "test-circuits"))]
-    /// Calculate which rows are "actually" used in the circuit
-    pub(crate) fn get_active_rows(block: &Block<F>) -> (Vec<usize>, Vec<usize>) {
-        let max_offset = Self::get_num_rows_required(block);
+    /// Calculate which rows are "actually" used in the circuit, given the
+    /// height of each execution state
+    pub(crate) fn get_active_rows(
+        block: &Block<F>,
+        step_heights: &HashMap<ExecutionState, usize>,
+    ) -> (Vec<usize>, Vec<usize>) {
+        let max_offset = Self::get_num_rows_required(block, step_heights);
         // some gates are enabled on all rows
         let gates_row_ids = (0..max_offset).collect();
         // lookups are enabled at "q_step" rows and byte lookup rows
@@ -208,22 +397,33 @@ impl<F: Field> EvmCircuit<F> {
         (gates_row_ids, lookup_row_ids)
     }
     /// Get the minimum number of rows required to process the block
-    /// If unspecified, then compute it
-    pub(crate) fn get_num_rows_required(block: &Block<F>) -> usize {
+    /// If unspecified, then compute it from the height of each execution state
+    pub(crate) fn get_num_rows_required(
+        block: &Block<F>,
+        step_heights: &HashMap<ExecutionState, usize>,
+    ) -> usize {
         let evm_rows = block.circuits_params.max_evm_rows;
         if evm_rows == 0 {
-            Self::get_min_num_rows_required(block)
+            Self::get_min_num_rows_required(block, step_heights)
         } else {
             // It must have at least one unused row.
             block.circuits_params.max_evm_rows + 1
         }
     }
-    /// Compute the minimum number of rows required to process the block
-    fn get_min_num_rows_required(block: &Block<F>) -> usize {
+    /// Compute the minimum number of rows required to process the block, given
+    /// the height of each execution state
+    pub(crate) fn get_min_num_rows_required(
+        block: &Block<F>,
+        step_heights: &HashMap<ExecutionState, usize>,
+    ) -> usize {
         let mut num_rows = 0;
         for transaction in &block.txs {
             for step in transaction.steps() {
-                num_rows += step.execution_state().get_step_height();
+                let execution_state = step.execution_state();
+                num_rows += step_heights
+                    .get(&execution_state)
+                    .copied()
+                    .unwrap_or_else(|| panic!("Execution state unknown: {:?}", execution_state));
             }
         }
 
@@ -245,12 +445,14 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
         Self::new(block.clone())
     }
 
-    /// Return the minimum number of rows required to prove the block
+    /// Return the minimum number of rows required to prove the block with the
+    /// default column layout
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let num_rows_required_for_execution_steps: usize = Self::get_num_rows_required(block);
+        let num_rows_required_for_execution_steps: usize =
+            Self::get_num_rows_required(block, &step_height_map(block.feature_config));
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(block)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| tag.build::<F>(block.feature_config.hardfork).count())
             .sum();
         (
             std::cmp::max(
@@ -315,7 +517,7 @@ pub(crate) mod cached {
         static ref CACHE: Cache = {
             let mut meta = ConstraintSystem::<Fr>::default();
             // Cached EVM circuit is configured with Mainnet FeatureConfig
-            let config = EvmCircuit::<Fr>::configure_with_params(&mut meta, FeatureConfig::default());
+            let config = EvmCircuit::<Fr>::configure_with_params(&mut meta, EvmCircuitParams::default());
             Cache { cs: meta, config }
         };
     }
@@ -357,11 +559,11 @@ pub(crate) mod cached {
     }
 }
 
-// Always exported because of `EXECUTION_STATE_HEIGHT_MAP`
+// Always exported because of `step_height_map`
 impl<F: Field> Circuit<F> for EvmCircuit<F> {
     type Config = (EvmCircuitConfig<F>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = FeatureConfig;
+    type Params = EvmCircuitParams;
 
     fn without_witnesses(&self) -> Self {
         Self::default()
@@ -369,10 +571,16 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
 
     /// Try to get the [`FeatureConfig`] from the block or fallback to default
     fn params(&self) -> Self::Params {
-        self.block
-            .as_ref()
-            .map(|block| block.feature_config)
-            .unwrap_or_default()
+        EvmCircuitParams {
+            feature_config: self
+                .block
+                .as_ref()
+                .map(|block| block.feature_config)
+                .unwrap_or_default(),
+            layout: self.layout.clone(),
+            cell_placement: self.cell_placement,
+            constraint_coverage: self.constraint_coverage,
+        }
     }
 
     fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
@@ -403,7 +611,10 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     exp_table,
                     u8_table,
                     u16_table,
-                    feature_config: params,
+                    feature_config: params.feature_config,
+                    layout: params.layout,
+                    cell_placement: params.cell_placement,
+                    constraint_coverage: params.constraint_coverage,
                 },
             ),
             challenges,
@@ -456,9 +667,13 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
 #[cfg(test)]
 mod evm_circuit_stats {
     use crate::{
-        evm_circuit::EvmCircuit,
+        evm_circuit::{
+            param::{EvmColumnLayout, EvmLayoutError},
+            step::{ExecutionState, HasExecutionState},
+            EvmCircuit, EvmCircuitParams,
+        },
         test_util::CircuitTestBuilder,
-        util::{unusable_rows, SubCircuit},
+        util::{cell_manager::CellPlacementOrder, log2_ceil, unusable_rows, SubCircuit},
         witness::block_convert,
     };
     use bus_mapping::{
@@ -467,7 +682,12 @@ mod evm_circuit_stats {
     };
 
     use eth_types::{bytecode, geth_types::GethData};
-    use halo2_proofs::{self, dev::MockProver, halo2curves::bn256::Fr};
+    use halo2_proofs::{
+        self,
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem},
+    };
 
     use mock::test_ctx::{
         helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
@@ -485,14 +705,174 @@ mod evm_circuit_stats {
 
         assert_eq!(
             computed,
-            unusable_rows::<Fr, EvmCircuit::<Fr>>(mainnet_config),
+            unusable_rows::<Fr, EvmCircuit::<Fr>>(EvmCircuitParams {
+                feature_config: mainnet_config,
+                ..Default::default()
+            }),
         );
         assert_eq!(
             computed,
-            unusable_rows::<Fr, EvmCircuit::<Fr>>(invalid_tx_config),
+            unusable_rows::<Fr, EvmCircuit::<Fr>>(EvmCircuitParams {
+                feature_config: invalid_tx_config,
+                ..Default::default()
+            }),
         )
     }
 
+    #[test]
+    fn evm_circuit_layout_validation() {
+        assert_eq!(EvmCircuitParams::default().validate(), Ok(()));
+
+        let params = |layout| EvmCircuitParams {
+            layout,
+            ..Default::default()
+        };
+        assert_eq!(
+            params(EvmColumnLayout {
+                copy_columns: 0,
+                ..Default::default()
+            })
+            .validate(),
+            Err(EvmLayoutError::MissingColumns(vec!["copy".to_string()]))
+        );
+        assert!(matches!(
+            params(EvmColumnLayout {
+                step_width: 40,
+                ..Default::default()
+            })
+            .validate(),
+            Err(EvmLayoutError::NotEnoughPhase1Columns { .. })
+        ));
+        match params(EvmColumnLayout {
+            u8_lookups: 2,
+            ..Default::default()
+        })
+        .validate()
+        {
+            Err(EvmLayoutError::StepTooHigh(states)) => {
+                assert!(states
+                    .iter()
+                    .any(|(state, _)| *state == ExecutionState::MULMOD));
+            }
+            result => panic!("unexpected validation result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn evm_circuit_custom_layout() {
+        // Trade six more u8 lookup columns for shorter steps
+        let layout = EvmColumnLayout {
+            step_width: EvmColumnLayout::default().step_width + 6,
+            u8_lookups: EvmColumnLayout::default().u8_lookups + 6,
+            ..Default::default()
+        };
+        let params = EvmCircuitParams {
+            layout: layout.clone(),
+            ..Default::default()
+        };
+        assert_eq!(params.validate(), Ok(()));
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let k = block.get_test_degree();
+        let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block).with_layout(layout);
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn evm_circuit_column_major_layout() {
+        let params = EvmCircuitParams {
+            cell_placement: CellPlacementOrder::ColumnMajor,
+            ..Default::default()
+        };
+        assert_eq!(params.validate(), Ok(()));
+
+        // A cell type out of columns is reported instead of panicking
+        let params_out_of_columns = EvmCircuitParams {
+            layout: EvmColumnLayout {
+                u8_lookups: 2,
+                ..Default::default()
+            },
+            ..params.clone()
+        };
+        match params_out_of_columns.validate() {
+            Err(EvmLayoutError::StepTooHigh(states)) => {
+                assert!(states
+                    .iter()
+                    .any(|(state, _)| *state == ExecutionState::MULMOD));
+            }
+            result => panic!("unexpected validation result {:?}", result),
+        }
+
+        // The columns left empty by the column-major placement are dropped
+        let num_advice_columns = |params| {
+            let mut meta = ConstraintSystem::<Fr>::default();
+            EvmCircuit::configure_with_params(&mut meta, params);
+            meta.num_advice_columns()
+        };
+        assert!(num_advice_columns(params) < num_advice_columns(EvmCircuitParams::default()));
+    }
+
+    #[test]
+    fn evm_circuit_narrowed_layout_rows() {
+        // The column-major placement narrows the layout, with taller steps
+        let params = EvmCircuitParams {
+            cell_placement: CellPlacementOrder::ColumnMajor,
+            ..Default::default()
+        };
+        let step_heights = params.step_heights();
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let rows = EvmCircuit::<Fr>::get_min_num_rows_required(&block, &step_heights);
+        let step_rows: usize = block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.steps())
+            .map(|step| step_heights[&step.execution_state()])
+            .sum();
+        assert_eq!(rows, step_rows + 2);
+        assert!(
+            rows > EvmCircuit::<Fr>::get_min_num_rows_required(
+                &block,
+                &EvmCircuitParams::default().step_heights()
+            )
+        );
+
+        let k = block
+            .get_test_degree()
+            .max(log2_ceil(rows + EvmCircuit::<Fr>::unusable_rows()));
+        let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block)
+            .with_cell_placement(CellPlacementOrder::ColumnMajor);
+        assert_eq!(circuit.step_heights(), step_heights);
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
     #[test]
     pub fn empty_evm_circuit_no_padding() {
         CircuitTestBuilder::new_from_test_ctx(
@@ -0,0 +1,414 @@
+//! Constraint coverage of the execution gadgets.
+//!
+//! When an EVM circuit is configured with
+//! [`EvmCircuitParams::constraint_coverage`](crate::evm_circuit::EvmCircuitParams), the
+//! `EVMConstraintBuilder` tags every constraint and lookup with its execution state and the
+//! source line that added it. While the steps are assigned, a constraint is hit when its selector
+//! and conditions are enabled and it queries at least one non-zero advice cell, which filters out
+//! the constraints that are trivially satisfied by an all-zero witness.
+//!
+//! The hits are collected per process and merged into a file by [`flush_constraint_coverage`],
+//! which the `CircuitTestBuilder` calls after the EVM circuit tests that set
+//! `constraint_coverage`, so the file accumulates the coverage of a whole test run:
+//!
+//! ```ignore
+//! CircuitTestBuilder::new_from_test_ctx(ctx)
+//!     .constraint_coverage("/tmp/coverage.json")
+//!     .run();
+//! ```
+//!
+//! ```text
+//! cargo run --bin stats --features stats -- coverage /tmp/coverage.json
+//! ```
+
+use crate::evm_circuit::{
+    step::ExecutionState,
+    util::{constraint_builder::ConstraintLocation, CachedRegion},
+};
+use eth_types::Field;
+use halo2_proofs::plonk::Expression;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, panic::Location, path::Path, sync::Mutex};
+use thiserror::Error;
+
+lazy_static::lazy_static! {
+    static ref COVERAGE: Mutex<ConstraintCoverage> = Mutex::new(ConstraintCoverage::default());
+}
+
+/// Kind of a coverage point
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ConstraintKind {
+    /// Polynomial constraint of a gate
+    Gate,
+    /// Lookup into a table
+    Lookup,
+}
+
+/// Identifies a constraint or lookup of an execution gadget
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CoverageKey {
+    /// Execution state of the gadget
+    pub execution_state: String,
+    /// Gate or lookup
+    pub kind: ConstraintKind,
+    /// Source line that added the constraint, as `file:line`
+    pub location: String,
+    /// Name of the constraint
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CoverageEntry {
+    #[serde(flatten)]
+    key: CoverageKey,
+    hits: usize,
+}
+
+/// Errors when reading or writing a coverage file
+#[derive(Debug, Error)]
+pub enum CoverageError {
+    /// The coverage file can't be read or written
+    #[error("coverage file: {0}")]
+    Io(#[from] io::Error),
+    /// The coverage file isn't valid
+    #[error("invalid coverage file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Number of steps that hit each constraint and lookup of the execution gadgets
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConstraintCoverage {
+    hits: BTreeMap<CoverageKey, usize>,
+}
+
+/// Coverage summary of an execution gadget
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateCoverage {
+    /// Execution state of the gadget
+    pub execution_state: String,
+    /// Number of constraints hit at least once
+    pub covered_gates: usize,
+    /// Number of constraints
+    pub gates: usize,
+    /// Number of lookups hit at least once
+    pub covered_lookups: usize,
+    /// Number of lookups
+    pub lookups: usize,
+}
+
+impl ConstraintCoverage {
+    /// Reads a coverage file, an absent file is an empty coverage.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CoverageError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let entries: Vec<CoverageEntry> = serde_json::from_str(&json)?;
+        Ok(Self {
+            hits: entries
+                .into_iter()
+                .map(|entry| (entry.key, entry.hits))
+                .collect(),
+        })
+    }
+
+    /// Writes the coverage to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CoverageError> {
+        let entries = self
+            .hits
+            .iter()
+            .map(|(key, hits)| CoverageEntry {
+                key: key.clone(),
+                hits: *hits,
+            })
+            .collect::<Vec<_>>();
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    /// Adds the hits of another coverage.
+    pub fn merge(&mut self, other: &Self) {
+        for (key, hits) in &other.hits {
+            *self.hits.entry(key.clone()).or_insert(0) += hits;
+        }
+    }
+
+    /// Returns the number of hits of every constraint and lookup.
+    pub fn hits(&self) -> impl Iterator<Item = (&CoverageKey, usize)> {
+        self.hits.iter().map(|(key, hits)| (key, *hits))
+    }
+
+    /// Returns the constraints and lookups that were never hit.
+    pub fn uncovered(&self) -> impl Iterator<Item = &CoverageKey> {
+        self.hits()
+            .filter(|(_, hits)| *hits == 0)
+            .map(|(key, _)| key)
+    }
+
+    /// Returns the coverage summary of each execution gadget.
+    pub fn summary(&self) -> Vec<StateCoverage> {
+        let mut summary: BTreeMap<&str, StateCoverage> = BTreeMap::new();
+        for (key, hits) in self.hits() {
+            let state = summary
+                .entry(key.execution_state.as_str())
+                .or_insert_with(|| StateCoverage {
+                    execution_state: key.execution_state.clone(),
+                    covered_gates: 0,
+                    gates: 0,
+                    covered_lookups: 0,
+                    lookups: 0,
+                });
+            let (covered, total) = match key.kind {
+                ConstraintKind::Gate => (&mut state.covered_gates, &mut state.gates),
+                ConstraintKind::Lookup => (&mut state.covered_lookups, &mut state.lookups),
+            };
+            *covered += (hits > 0) as usize;
+            *total += 1;
+        }
+        summary.into_values().collect()
+    }
+
+    fn record(&mut self, key: CoverageKey, hit: bool) {
+        *self.hits.entry(key).or_insert(0) += hit as usize;
+    }
+}
+
+/// Merges the coverage collected since the last flush into the file at `path`.
+pub fn flush_constraint_coverage(path: impl AsRef<Path>) -> Result<(), CoverageError> {
+    let path = path.as_ref();
+    // Hold the lock while the file is rewritten so that parallel tests don't lose hits
+    let mut coverage = COVERAGE.lock().unwrap();
+    let mut merged = ConstraintCoverage::load(path)?;
+    merged.merge(&coverage);
+    merged.save(path)?;
+    *coverage = ConstraintCoverage::default();
+    Ok(())
+}
+
+/// A constraint or lookup added by an execution gadget
+#[derive(Clone, Debug)]
+pub(crate) struct CoveragePoint<F> {
+    pub(crate) kind: ConstraintKind,
+    pub(crate) name: String,
+    pub(crate) location: &'static Location<'static>,
+    pub(crate) constraint_location: ConstraintLocation,
+    /// Product of the conditions the constraint is added under
+    pub(crate) condition: Option<Expression<F>>,
+    /// Constraint or lookup input expressions, without the condition
+    pub(crate) exprs: Vec<Expression<F>>,
+}
+
+impl<F: Field> CoveragePoint<F> {
+    fn key(&self, execution_state: ExecutionState) -> CoverageKey {
+        CoverageKey {
+            execution_state: format!("{:?}", execution_state),
+            kind: self.kind,
+            location: format!("{}:{}", self.location.file(), self.location.line()),
+            name: self.name.clone(),
+        }
+    }
+
+    /// Returns true when the point is enabled in the step assigned at `offset` and queries at
+    /// least one non-zero advice cell.
+    fn is_hit(
+        &self,
+        region: &CachedRegion<'_, '_, F>,
+        offset: usize,
+        is_first: bool,
+        is_last: bool,
+    ) -> bool {
+        let enabled = match self.constraint_location {
+            ConstraintLocation::Step => true,
+            ConstraintLocation::StepFirst => is_first,
+            ConstraintLocation::NotStepLast => !is_last,
+        };
+        let condition = match &self.condition {
+            Some(condition) => evaluate(condition, region, offset).map(|v| !v.is_zero_vartime()),
+            None => Some(true),
+        };
+        enabled
+            && condition == Some(true)
+            && self
+                .exprs
+                .iter()
+                .any(|expr| queries_non_zero_advice(expr, region, offset))
+    }
+}
+
+/// Adds the points of an execution gadget to the coverage, so that the ones that are never hit
+/// are reported.
+pub(crate) fn register_coverage_points<F: Field>(
+    execution_state: ExecutionState,
+    points: &[CoveragePoint<F>],
+) {
+    let mut coverage = COVERAGE.lock().unwrap();
+    for point in points {
+        coverage.record(point.key(execution_state), false);
+    }
+}
+
+/// Records the points hit by the step assigned at `offset`.
+pub(crate) fn record_step_coverage<F: Field>(
+    execution_state: ExecutionState,
+    points: &[CoveragePoint<F>],
+    region: &CachedRegion<'_, '_, F>,
+    offset: usize,
+    is_first: bool,
+    is_last: bool,
+) {
+    let hits = points
+        .iter()
+        .map(|point| {
+            (
+                point.key(execution_state),
+                point.is_hit(region, offset, is_first, is_last),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut coverage = COVERAGE.lock().unwrap();
+    for (key, hit) in hits {
+        coverage.record(key, hit);
+    }
+}
+
+/// Evaluates an expression on the cached step witness, `None` when it queries a cell outside of
+/// the cached region or a value that isn't known.
+fn evaluate<F: Field>(
+    expr: &Expression<F>,
+    region: &CachedRegion<'_, '_, F>,
+    offset: usize,
+) -> Option<F> {
+    expr.evaluate(
+        &|scalar| Some(scalar),
+        &|_| None,
+        &|_| None,
+        &|query| region.try_get_advice(offset, query.column_index(), query.rotation()),
+        &|_| None,
+        &|challenge| {
+            let mut value = None;
+            region.challenges().indexed()[challenge.index()].map(|v| value = Some(v));
+            value
+        },
+        &|a| a.map(|a| -a),
+        &|a, b| Some(a? + b?),
+        &|a, b| Some(a? * b?),
+        &|a, scalar| a.map(|a| a * scalar),
+    )
+}
+
+fn queries_non_zero_advice<F: Field>(
+    expr: &Expression<F>,
+    region: &CachedRegion<'_, '_, F>,
+    offset: usize,
+) -> bool {
+    expr.evaluate(
+        &|_| false,
+        &|_| false,
+        &|_| false,
+        &|query| {
+            region
+                .try_get_advice(offset, query.column_index(), query.rotation())
+                .map_or(false, |v| !v.is_zero_vartime())
+        },
+        &|_| false,
+        &|_| false,
+        &|a| a,
+        &|a, b| a || b,
+        &|a, b| a || b,
+        &|a, _| a,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::bytecode;
+    use mock::TestContext;
+
+    fn key(execution_state: &str, kind: ConstraintKind, line: usize) -> CoverageKey {
+        CoverageKey {
+            execution_state: execution_state.to_string(),
+            kind,
+            location: format!("src/evm_circuit/execution/add_sub.rs:{}", line),
+            name: "constraint".to_string(),
+        }
+    }
+
+    #[test]
+    fn constraint_coverage_merge_and_summary() {
+        let mut coverage = ConstraintCoverage::default();
+        coverage.record(key("ADD_SUB", ConstraintKind::Gate, 1), true);
+        coverage.record(key("ADD_SUB", ConstraintKind::Gate, 2), false);
+        coverage.record(key("ADD_SUB", ConstraintKind::Lookup, 3), false);
+
+        let mut other = ConstraintCoverage::default();
+        other.record(key("ADD_SUB", ConstraintKind::Lookup, 3), true);
+        other.record(key("MULMOD", ConstraintKind::Gate, 4), false);
+        coverage.merge(&other);
+
+        assert_eq!(
+            coverage.summary(),
+            vec![
+                StateCoverage {
+                    execution_state: "ADD_SUB".to_string(),
+                    covered_gates: 1,
+                    gates: 2,
+                    covered_lookups: 1,
+                    lookups: 1,
+                },
+                StateCoverage {
+                    execution_state: "MULMOD".to_string(),
+                    covered_gates: 0,
+                    gates: 1,
+                    covered_lookups: 0,
+                    lookups: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            coverage.uncovered().cloned().collect::<Vec<_>>(),
+            vec![
+                key("ADD_SUB", ConstraintKind::Gate, 2),
+                key("MULMOD", ConstraintKind::Gate, 4)
+            ]
+        );
+
+        let path = std::env::temp_dir().join("evm_constraint_coverage_test.json");
+        coverage.save(&path).unwrap();
+        assert_eq!(ConstraintCoverage::load(&path).unwrap(), coverage);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn constraint_coverage_of_circuit_test() {
+        let path = std::env::temp_dir().join("evm_constraint_coverage_builder_test.json");
+        let _ = fs::remove_file(&path);
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        })
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .constraint_coverage(&path)
+            .run();
+
+        let coverage = ConstraintCoverage::load(&path).unwrap();
+        fs::remove_file(path).unwrap();
+        let add_sub = coverage
+            .summary()
+            .into_iter()
+            .find(|state| state.execution_state == "ADD_SUB")
+            .expect("the ADD_SUB gadget is registered");
+        assert!(add_sub.covered_gates > 0);
+        // The gadgets without steps are registered but never hit
+        assert!(coverage
+            .summary()
+            .iter()
+            .any(|state| state.execution_state == "MULMOD" && state.covered_gates == 0));
+    }
+}
@@ -1,16 +1,13 @@
 use super::{
-    param::{
-        BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS,
-        FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, N_COPY_COLUMNS, N_PHASE1_COLUMNS, N_U16_LOOKUPS,
-        N_U8_LOOKUPS, RW_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
-    },
+    param::EvmColumnLayout,
     step::HasExecutionState,
     util::{instrumentation::Instrument, CachedRegion, StoredExpression},
 };
 use crate::{
     evm_circuit::{
-        param::{EVM_LOOKUP_COLS, MAX_STEP_HEIGHT, N_PHASE2_COLUMNS, STEP_WIDTH},
-        step::{ExecutionState, Step},
+        coverage::{record_step_coverage, register_coverage_points, CoveragePoint},
+        param::MAX_STEP_HEIGHT,
+        step::{step_state_width, ExecutionState, Step},
         table::Table,
         util::{
             constraint_builder::{
@@ -22,7 +19,7 @@ use crate::{
     },
     table::LookupTable,
     util::{
-        cell_manager::{CMFixedWidthStrategy, CellManager, CellType},
+        cell_manager::{CMFixedWidthStrategy, CellManager, CellPlacementOrder, CellType},
         Challenges, Expr,
     },
 };
@@ -241,11 +238,15 @@ pub struct ExecutionConfig<F> {
     q_step_first: Selector,
     // Selector enabled in the row where the last execution step starts.
     q_step_last: Selector,
-    advices: [Column<Advice>; STEP_WIDTH],
+    advices: Vec<Column<Advice>>,
+    layout: EvmColumnLayout,
     step: Step<F>,
     pub(crate) height_map: HashMap<ExecutionState, usize>,
     stored_expressions_map: HashMap<ExecutionState, Vec<StoredExpression<F>>>,
     debug_expressions_map: HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
+    // Constraints and lookups of each execution state, tagged when the coverage is collected
+    coverage_points_map: HashMap<ExecutionState, Vec<CoveragePoint<F>>>,
+    constraint_coverage: bool,
     instrument: Instrument,
     // internal state gadgets
     begin_tx_gadget: Box<BeginTxGadget<F>>,
@@ -358,7 +359,13 @@ impl<F: Field> ExecutionConfig<F> {
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
         feature_config: FeatureConfig,
+        layout: &EvmColumnLayout,
+        cell_placement: CellPlacementOrder,
+        constraint_coverage: bool,
     ) -> Self {
+        layout
+            .check_columns(step_state_width())
+            .unwrap_or_else(|err| panic!("Invalid EVM circuit column layout: {}", err));
         let mut instrument = Instrument::default();
         let q_usable = meta.complex_selector();
         let q_step = meta.advice_column();
@@ -369,23 +376,20 @@ impl<F: Field> ExecutionConfig<F> {
         let q_step_first = meta.complex_selector();
         let q_step_last = meta.complex_selector();
 
-        let advices = [(); STEP_WIDTH]
-            .iter()
-            .enumerate()
-            .map(|(n, _)| {
-                if n < EVM_LOOKUP_COLS {
+        let lookup_columns = layout.lookup_columns();
+        let advices = (0..layout.step_width)
+            .map(|n| {
+                if n < lookup_columns {
                     meta.advice_column_in(ThirdPhase)
-                } else if n < EVM_LOOKUP_COLS + N_PHASE2_COLUMNS {
+                } else if n < lookup_columns + layout.phase2_columns {
                     meta.advice_column_in(SecondPhase)
                 } else {
                     meta.advice_column_in(FirstPhase)
                 }
             })
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
+            .collect::<Vec<_>>();
 
-        let step_curr = Step::new(meta, advices, 0);
+        let step_curr = Step::new(meta, &advices, 0, layout).with_cell_placement(cell_placement);
         let mut height_map = HashMap::new();
 
         meta.create_gate("Constrain execution state", |meta| {
@@ -486,6 +490,7 @@ impl<F: Field> ExecutionConfig<F> {
 
         let mut stored_expressions_map = HashMap::new();
         let mut debug_expressions_map = HashMap::new();
+        let mut coverage_points_map = HashMap::new();
 
         macro_rules! configure_gadget {
             () => {
@@ -497,7 +502,8 @@ impl<F: Field> ExecutionConfig<F> {
                 (|| {
                     Box::new(Self::configure_gadget(
                         meta,
-                        advices,
+                        &advices,
+                        layout,
                         q_usable,
                         q_step,
                         num_rows_until_next_step,
@@ -508,6 +514,8 @@ impl<F: Field> ExecutionConfig<F> {
                         &mut height_map,
                         &mut stored_expressions_map,
                         &mut debug_expressions_map,
+                        &mut coverage_points_map,
+                        constraint_coverage,
                         &mut instrument,
                         feature_config.clone(),
                     ))
@@ -525,7 +533,8 @@ impl<F: Field> ExecutionConfig<F> {
             num_rows_inv,
             q_step_first,
             q_step_last,
-            advices,
+            advices: advices.clone(),
+            layout: layout.clone(),
             // internal states
             begin_tx_gadget: configure_gadget!(),
             end_block_gadget: configure_gadget!(),
@@ -620,6 +629,8 @@ impl<F: Field> ExecutionConfig<F> {
             height_map,
             stored_expressions_map,
             debug_expressions_map,
+            coverage_points_map,
+            constraint_coverage,
             instrument,
         };
 
@@ -648,7 +659,8 @@ impl<F: Field> ExecutionConfig<F> {
     #[allow(clippy::too_many_arguments)]
     fn configure_gadget<G: ExecutionGadget<F>>(
         meta: &mut ConstraintSystem<F>,
-        advices: [Column<Advice>; STEP_WIDTH],
+        advices: &[Column<Advice>],
+        layout: &EvmColumnLayout,
         q_usable: Selector,
         q_step: Column<Advice>,
         num_rows_until_next_step: Column<Advice>,
@@ -659,13 +671,15 @@ impl<F: Field> ExecutionConfig<F> {
         height_map: &mut HashMap<ExecutionState, usize>,
         stored_expressions_map: &mut HashMap<ExecutionState, Vec<StoredExpression<F>>>,
         debug_expressions_map: &mut HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
+        coverage_points_map: &mut HashMap<ExecutionState, Vec<CoveragePoint<F>>>,
+        constraint_coverage: bool,
         instrument: &mut Instrument,
         feature_config: FeatureConfig,
     ) -> G {
         // Configure the gadget with the max height first so we can find out the actual
         // height
         let height = {
-            let dummy_step_next = Step::new(meta, advices, MAX_STEP_HEIGHT);
+            let dummy_step_next = Step::new(meta, advices, layout.max_step_height, layout);
             let mut cb = EVMConstraintBuilder::new(
                 meta,
                 step_curr.clone(),
@@ -680,7 +694,7 @@ impl<F: Field> ExecutionConfig<F> {
         };
 
         // Now actually configure the gadget with the correct minimal height
-        let step_next = &Step::new(meta, advices, height);
+        let step_next = &Step::new(meta, advices, height, layout);
         let mut cb = EVMConstraintBuilder::new(
             meta,
             step_curr.clone(),
@@ -689,6 +703,7 @@ impl<F: Field> ExecutionConfig<F> {
             G::EXECUTION_STATE,
             feature_config,
         );
+        cb.collect_coverage = constraint_coverage;
 
         let gadget = G::configure(&mut cb);
 
@@ -703,6 +718,7 @@ impl<F: Field> ExecutionConfig<F> {
             height_map,
             stored_expressions_map,
             debug_expressions_map,
+            coverage_points_map,
             instrument,
             G::NAME,
             G::EXECUTION_STATE,
@@ -725,12 +741,16 @@ impl<F: Field> ExecutionConfig<F> {
         height_map: &mut HashMap<ExecutionState, usize>,
         stored_expressions_map: &mut HashMap<ExecutionState, Vec<StoredExpression<F>>>,
         debug_expressions_map: &mut HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
+        coverage_points_map: &mut HashMap<ExecutionState, Vec<CoveragePoint<F>>>,
         instrument: &mut Instrument,
         name: &'static str,
         execution_state: ExecutionState,
         height: usize,
         mut cb: EVMConstraintBuilder<F>,
     ) {
+        // The step height constraint below isn't part of the gadget
+        let coverage_points = std::mem::take(&mut cb.coverage_points);
+
         // Enforce the step height for this opcode
         let num_rows_until_next_step_next = cb
             .query_expression(|meta| meta.query_advice(num_rows_until_next_step, Rotation::next()));
@@ -760,6 +780,10 @@ impl<F: Field> ExecutionConfig<F> {
         );
         stored_expressions_map.insert(execution_state, stored_expressions);
         debug_expressions_map.insert(execution_state, debug_expressions);
+        if !coverage_points.is_empty() {
+            register_coverage_points(execution_state, &coverage_points);
+        }
+        coverage_points_map.insert(execution_state, coverage_points);
 
         // Enforce the logic for this opcode
         let sel_step: &dyn Fn(&mut VirtualCells<F>) -> Expression<F> =
@@ -999,7 +1023,7 @@ impl<F: Field> ExecutionConfig<F> {
                     if next.is_none() {
                         break;
                     }
-                    let height = step.execution_state().get_step_height();
+                    let height = self.height_map[&step.execution_state()];
 
                     // Assign the step witness
                     self.assign_exec_step(
@@ -1031,7 +1055,7 @@ impl<F: Field> ExecutionConfig<F> {
                         );
                         return Err(Error::Synthesis);
                     }
-                    let height = ExecutionState::EndBlock.get_step_height();
+                    let height = self.height_map[&ExecutionState::EndBlock];
                     debug_assert_eq!(height, 1);
                     let last_row = evm_rows - 1;
                     log::trace!(
@@ -1059,7 +1083,7 @@ impl<F: Field> ExecutionConfig<F> {
                 }
 
                 // part3: assign the last EndBlock at offset `evm_rows - 1`
-                let height = ExecutionState::EndBlock.get_step_height();
+                let height = self.height_map[&ExecutionState::EndBlock];
                 debug_assert_eq!(height, 1);
                 log::trace!("assign last EndBlock at offset {}", offset);
                 self.assign_exec_step(
@@ -1101,31 +1125,25 @@ impl<F: Field> ExecutionConfig<F> {
     }
 
     fn annotate_circuit(&self, region: &mut Region<F>) {
-        let groups = [
-            ("EVM_lookup_fixed", FIXED_TABLE_LOOKUPS),
-            ("EVM_lookup_tx", TX_TABLE_LOOKUPS),
-            ("EVM_lookup_rw", RW_TABLE_LOOKUPS),
-            ("EVM_lookup_bytecode", BYTECODE_TABLE_LOOKUPS),
-            ("EVM_lookup_block", BLOCK_TABLE_LOOKUPS),
-            ("EVM_lookup_copy", COPY_TABLE_LOOKUPS),
-            ("EVM_lookup_keccak", KECCAK_TABLE_LOOKUPS),
-            ("EVM_lookup_exp", EXP_TABLE_LOOKUPS),
-            ("EVM_adv_phase2", N_PHASE2_COLUMNS),
-            ("EVM_copy", N_COPY_COLUMNS),
-            ("EVM_lookup_u8", N_U8_LOOKUPS),
-            ("EVM_lookup_u16", N_U16_LOOKUPS),
-            ("EVM_adv_phase1", N_PHASE1_COLUMNS),
-        ];
-        let mut group_index = 0;
-        let mut index = 0;
-        for col in self.advices {
-            let (name, length) = groups[group_index];
-            region.name_column(|| format!("{}_{}", name, index), col);
-            index += 1;
-            if index >= length {
-                index = 0;
-                group_index += 1;
-            }
+        let groups = self
+            .layout
+            .lookups
+            .iter()
+            .map(|(table, count)| {
+                let name = format!("EVM_lookup_{}", format!("{:?}", table).to_lowercase());
+                (name, *count)
+            })
+            .chain([
+                ("EVM_adv_phase2".to_string(), self.layout.phase2_columns),
+                ("EVM_copy".to_string(), self.layout.copy_columns),
+                ("EVM_lookup_u8".to_string(), self.layout.u8_lookups),
+                ("EVM_lookup_u16".to_string(), self.layout.u16_lookups),
+                ("EVM_adv_phase1".to_string(), self.layout.phase1_columns()),
+            ]);
+        let names = groups
+            .flat_map(|(name, length)| (0..length).map(move |index| format!("{}_{}", name, index)));
+        for (col, name) in self.advices.iter().zip(names) {
+            region.name_column(|| name.clone(), *col);
         }
 
         region.name_column(|| "EVM_q_step", self.q_step);
@@ -1430,6 +1448,9 @@ impl<F: Field> ExecutionConfig<F> {
             // does.
             region.challenges().lookup_input().assert_if_known(|_| {
                 self.print_debug_expressions(region, offset, step);
+                if self.constraint_coverage {
+                    self.record_constraint_coverage(region, offset, block, step);
+                }
                 true
             });
 
@@ -1496,6 +1517,24 @@ impl<F: Field> ExecutionConfig<F> {
         }
     }
 
+    fn record_constraint_coverage(
+        &self,
+        region: &CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        step: &ExecStep,
+    ) {
+        record_step_coverage(
+            step.execution_state(),
+            &self.coverage_points_map[&step.execution_state()],
+            region,
+            offset,
+            offset == 0,
+            // Only the EndBlock step at the last row enables `q_step_last`
+            std::ptr::eq(step, &block.end_block_last),
+        );
+    }
+
     fn check_rw_lookup(
         assigned_stored_expressions: &[(String, F)],
         step: &ExecStep,
@@ -1607,12 +1646,13 @@ impl<F: Field> ExecutionConfig<F> {
             };
 
             // If assigned_rw_value is a `copy lookup` event, the following
-            // `step.copy_rw_counter_delta` rw lookups must be memory operations.
+            // `step.copy_rw_counter_delta` rw lookups must be memory word or tx log
+            // operations.
             if assigned_rw_value.0.starts_with("copy lookup") {
                 for i in 0..step.copy_rw_counter_delta as usize {
                     let index = idx + i;
                     let rw = block.get_rws(step, index);
-                    if rw.tag() != Target::Memory {
+                    if rw.tag() != Target::Memory && rw.tag() != Target::TxLog {
                         log::error!(
                                 "incorrect rw memory witness from copy lookup.\n lookup name: \"{}\"\n {}th rw of step {:?}, rw: {:?}",
                                 assigned_rw_value.0,
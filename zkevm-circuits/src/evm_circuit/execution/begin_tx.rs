@@ -27,7 +27,7 @@ use crate::{
     },
 };
 use bus_mapping::state_db::CodeDB;
-use eth_types::{evm_types::PRECOMPILE_COUNT, keccak256, Field, OpsIdentity, ToWord, U256};
+use eth_types::{keccak256, Field, OpsIdentity, ToWord, U256};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -48,12 +48,12 @@ pub(crate) struct BeginTxGadget<F> {
     create: ContractCreateGadget<F, false>,
     callee_not_exists: IsZeroWordGadget<F, WordLoHiCell<F>>,
     is_caller_callee_equal: Cell<F>,
-    // EIP-3651 (Warm COINBASE)
-    coinbase: WordLoHiCell<F>,
+    // EIP-3651 (Warm COINBASE), only from Shanghai
+    coinbase: Option<WordLoHiCell<F>>,
     // Caller, callee and a list addresses are added to the access list before
     // coinbase, and may be duplicate.
     // <https://github.com/ethereum/go-ethereum/blob/604e215d1bb070dff98fb76aa965064c74e3633f/core/state/statedb.go#LL1119C9-L1119C9>
-    is_coinbase_warm: Cell<F>,
+    is_coinbase_warm: Option<Cell<F>>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
@@ -108,7 +108,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         ); // rwc_delta += 1
 
         // Add precompile contract address to access list
-        for addr in 1..=PRECOMPILE_COUNT {
+        let precompile_count = cb.feature_config.hardfork.precompile_count();
+        for addr in 1..=precompile_count {
             cb.account_access_list_write_unchecked(
                 tx_id.expr(),
                 WordLoHi::new([addr.expr(), 0.expr()]),
@@ -116,7 +117,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 0.expr(),
                 None,
             );
-        } // rwc_delta += PRECOMPILE_COUNT
+        } // rwc_delta += precompile_count
 
         // Prepare access list of caller and callee
         cb.account_access_list_write_unchecked(
@@ -138,20 +139,27 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         ); // rwc_delta += 1
 
         // Query coinbase address.
-        let coinbase = cb.query_word_unchecked();
-        let is_coinbase_warm = cb.query_bool();
-        cb.block_lookup(
-            BlockContextFieldTag::Coinbase.expr(),
-            None,
-            coinbase.to_word(),
-        );
-        cb.account_access_list_write_unchecked(
-            tx_id.expr(),
-            coinbase.to_word(),
-            1.expr(),
-            is_coinbase_warm.expr(),
-            None,
-        ); // rwc_delta += 1
+        let warm_coinbase = cb.feature_config.hardfork.is_coinbase_warm();
+        let (coinbase, is_coinbase_warm) = if warm_coinbase {
+            let coinbase = cb.query_word_unchecked();
+            let is_coinbase_warm = cb.query_bool();
+            cb.block_lookup(
+                BlockContextFieldTag::Coinbase.expr(),
+                None,
+                coinbase.to_word(),
+            );
+            cb.account_access_list_write_unchecked(
+                tx_id.expr(),
+                coinbase.to_word(),
+                1.expr(),
+                is_coinbase_warm.expr(),
+                None,
+            ); // rwc_delta += 1
+            (Some(coinbase), Some(is_coinbase_warm))
+        } else {
+            (None, None)
+        };
+        let coinbase_rw_delta = warm_coinbase.expr();
 
         // Read code_hash of callee
         let code_hash = cb.query_word_unchecked();
@@ -268,10 +276,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsPersistent
                 //   - Write CallContext IsSuccess
                 //   - Write Account (Caller) Nonce
-                //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                //   - Write TxAccessListAccount (Precompile) x precompile_count
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
-                //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                //   - Write TxAccessListAccount (Coinbase) for EIP-3651 (Shanghai onwards)
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
                 //   - Write CallContext Depth
@@ -288,7 +296,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
                 rw_counter: Delta(
-                    23.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                    22.expr()
+                        + coinbase_rw_delta.clone()
+                        + transfer_with_gas_fee.rw_delta()
+                        + precompile_count.expr(),
                 ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
@@ -328,14 +339,17 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsPersistent
                     //   - Write CallContext IsSuccess
                     //   - Write Account Nonce
-                    //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                    //   - Write TxAccessListAccount (Precompile) x precompile_count
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
-                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651 (Shanghai onwards)
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     rw_counter: Delta(
-                        9.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        8.expr()
+                            + coinbase_rw_delta.clone()
+                            + transfer_with_gas_fee.rw_delta()
+                            + precompile_count.expr(),
                     ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
@@ -391,10 +405,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsPersistent
                     //   - Write CallContext IsSuccess
                     //   - Write Account Nonce
-                    //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
+                    //   - Write TxAccessListAccount (Precompile) x precompile_count
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
-                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651 (Shanghai onwards)
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     //   - Write CallContext Depth
@@ -411,7 +425,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
                     rw_counter: Delta(
-                        22.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        21.expr()
+                            + coinbase_rw_delta
+                            + transfer_with_gas_fee.rw_delta()
+                            + precompile_count.expr(),
                     ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
@@ -459,9 +476,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let mut rws = StepRws::new(block, step);
         rws.offset_add(7);
 
-        rws.offset_add(PRECOMPILE_COUNT as usize);
+        rws.offset_add(block.feature_config.hardfork.precompile_count() as usize);
 
-        let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
+        let is_coinbase_warm = self
+            .is_coinbase_warm
+            .as_ref()
+            .map(|_| rws.next().tx_access_list_value_pair().1);
         let mut callee_code_hash = zero;
         if !is_precompiled(&tx.to_or_contract_addr()) {
             callee_code_hash = rws.next().account_codehash_pair().1;
@@ -545,13 +565,16 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         )?;
 
-        self.coinbase
-            .assign_h160(region, offset, block.context.coinbase)?;
-        self.is_coinbase_warm.assign(
-            region,
-            offset,
-            Value::known(F::from(is_coinbase_warm as u64)),
-        )?;
+        if let (Some(coinbase), Some(is_coinbase_warm_cell), Some(is_coinbase_warm)) =
+            (&self.coinbase, &self.is_coinbase_warm, is_coinbase_warm)
+        {
+            coinbase.assign_h160(region, offset, block.context.coinbase)?;
+            is_coinbase_warm_cell.assign(
+                region,
+                offset,
+                Value::known(F::from(is_coinbase_warm as u64)),
+            )?;
+        }
 
         Ok(())
     }
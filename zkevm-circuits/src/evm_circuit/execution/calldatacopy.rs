@@ -24,11 +24,9 @@ use crate::{
     },
 };
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use eth_types::{evm_types::GasCost, Field};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
-use std::cmp::min;
-
 #[derive(Clone, Debug)]
 pub(crate) struct CallDataCopyGadget<F> {
     same_context: SameContextGadget<F>,
@@ -218,31 +216,12 @@ impl<F: Field> ExecutionGadget<F> for CallDataCopyGadget<F> {
         self.data_offset
             .assign(region, offset, data_offset, F::from(call_data_length))?;
 
-        // rw_counter increase from copy lookup is `length` memory writes + a variable
-        // number of memory reads.
-        let copy_rwc_inc = length
-            + if call.is_root {
-                // no memory reads when reading from tx call data.
-                0
-            } else {
-                // memory reads when reading from memory of caller is capped by call_data_length
-                // - data_offset.
-                min(
-                    length.as_u64(),
-                    u64::try_from(data_offset)
-                        .ok()
-                        .and_then(|offset| call_data_length.checked_sub(offset))
-                        .unwrap_or_default(),
-                )
-            };
+        // rw_counter increase from copy lookup is the memory words written + the memory
+        // words read from the caller, which are none when reading from tx call data.
         self.copy_rwc_inc.assign(
             region,
             offset,
-            Value::known(
-                copy_rwc_inc
-                    .to_scalar()
-                    .expect("unexpected U256 -> Scalar conversion failure"),
-            ),
+            Value::known(F::from(step.copy_rw_counter_delta)),
         )?;
 
         // Memory expansion
@@ -1,5 +1,5 @@
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
+use eth_types::{Field, ToBigEndian, Word};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -16,7 +16,7 @@ use crate::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
             },
-            memory_gadget::BufferReaderGadget,
+            memory_gadget::{BufferReaderGadget, MemoryWordsGadget},
             not, select, CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
@@ -53,6 +53,8 @@ pub(crate) struct CallDataLoadGadget<F> {
     /// Gadget to read from tx calldata, which we validate against the word
     /// pushed to stack.
     buffer_reader: BufferReaderGadget<F, N_BYTES_WORD, N_BYTES_MEMORY_ADDRESS>,
+    /// The two caller memory words holding the call data of an internal call.
+    memory_words: MemoryWordsGadget<F, 2>,
 }
 
 impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
@@ -127,6 +129,26 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
 
         let buffer_reader = BufferReaderGadget::construct(cb, src_addr.expr(), src_addr_end);
 
+        // For an internal call, the call data comes from the caller memory words.
+        let memory_words = MemoryWordsGadget::construct_read(cb, src_addr.expr());
+        cb.condition(
+            and::expr([
+                data_offset.not_overflow(),
+                not::expr(cb.curr.state.is_root.expr()),
+            ]),
+            |cb| {
+                for idx in 0..2 {
+                    cb.memory_lookup(
+                        0.expr(),
+                        memory_words.word_address(idx),
+                        memory_words.word(idx),
+                        memory_words.word_prev(idx),
+                        Some(src_id.expr()),
+                    );
+                }
+            },
+        );
+
         let mut calldata_word: Vec<_> = (0..N_BYTES_WORD)
             .map(|idx| {
                 // For a root call, the call data comes from tx's data field.
@@ -153,11 +175,10 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
                         not::expr(cb.curr.state.is_root.expr()),
                     ]),
                     |cb| {
-                        cb.memory_lookup(
-                            0.expr(),
-                            src_addr.expr() + idx.expr(),
+                        cb.require_equal(
+                            "call data byte is read from the caller memory words",
                             buffer_reader.byte(idx),
-                            Some(src_id.expr()),
+                            memory_words.byte(idx),
                         );
                     },
                 );
@@ -197,6 +218,7 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
             call_data_offset,
             data_offset,
             buffer_reader,
+            memory_words,
         }
     }
 
@@ -252,6 +274,22 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
             .unwrap_or(src_addr_end)
             .min(src_addr_end);
 
+        let mut words = [Word::zero(); 2];
+        if offset_not_overflow && !call.is_root {
+            for (idx, word) in words.iter_mut().enumerate() {
+                *word = block
+                    .get_rws(step, OFFSET_RW_MEMORY_INDICES + idx)
+                    .memory_word_pair()
+                    .0;
+            }
+        }
+        self.memory_words
+            .assign(region, offset, src_addr, words, words)?;
+        let memory_bytes = words
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+
         let mut calldata_bytes = vec![0u8; N_BYTES_WORD];
         if offset_not_overflow {
             for (i, byte) in calldata_bytes.iter_mut().enumerate() {
@@ -261,11 +299,9 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
                         *byte = tx.call_data[src_addr as usize + i];
                     }
                 } else {
-                    // Fetch from memory.
+                    // Fetch from the caller memory words.
                     if src_addr + (i as u64) < call.call_data_offset + call.call_data_length {
-                        *byte = block
-                            .get_rws(step, OFFSET_RW_MEMORY_INDICES + i)
-                            .memory_value();
+                        *byte = memory_bytes[(src_addr % 32) as usize + i];
                     }
                 }
             }
@@ -464,7 +464,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                             precompile_return_data_copy_size.min(),
                             0.expr(),
                             precompile_return_rws.expr(), // writes
-                        ); // rwc_delta += `return_data_copy_size.min()` for precompile
+                        ); // rwc_delta += memory words read and written for precompile
                         precompile_return_bytes_rlc
                     },
                 );
@@ -481,7 +481,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     + call_gadget.has_value.clone() * GAS_STIPEND_CALL_WITH_VALUE.expr();
 
                 let precompile_output_word_size_div: ConstantDivisionGadget<F, N_BYTES_U64> =
-                    ConstantDivisionGadget::construct(cb, precompile_output_rws.expr(), 32);
+                    ConstantDivisionGadget::construct(cb, precompile_return_length.expr(), 32);
                 let precompile_output_word_size_div_remainder_zero =
                     IsZeroGadget::construct(cb, precompile_output_word_size_div.remainder());
                 let precompile_output_word_size = precompile_output_word_size_div.quotient()
@@ -1025,17 +1025,22 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 cd_length.as_usize()
             };
 
-            let input_bytes = (0..input_len)
-                .map(|_| rws.next().memory_value())
-                .collect::<Vec<_>>();
-            let output_bytes = (0..precompile_return_length.as_u64())
-                .map(|_| rws.next().memory_value())
-                .collect::<Vec<_>>();
+            // Each copy moves whole memory words, so the rw operations of a copy are
+            // counted from its copy event rather than from its length.
+            let mut copy = |has_bytes: bool| {
+                if !has_bytes {
+                    return (Vec::new(), 0);
+                }
+                let copy_event = block.get_copy_event(step, rws.offset());
+                let copy_rws = copy_event.rw_counter_delta();
+                rws.offset_add(copy_rws as usize);
+                let bytes = copy_event.bytes.iter().map(|(byte, _)| *byte).collect();
+                (bytes, copy_rws)
+            };
+            let (input_bytes, input_rws) = copy(input_len > 0);
+            let (output_bytes, output_rws) = copy(!precompile_return_length.is_zero());
             let return_length = min(precompile_return_length, rd_length);
-            let return_bytes = (0..(return_length.as_u64() * 2))
-                .map(|_| rws.next().memory_value())
-                .step_by(2)
-                .collect::<Vec<_>>();
+            let (return_bytes, return_rws) = copy(!return_length.is_zero());
 
             let input_bytes_rlc = region
                 .challenges()
@@ -1050,10 +1055,6 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 .keccak_input()
                 .map(|randomness| rlc::value(return_bytes.iter().rev(), randomness));
 
-            let input_rws = input_bytes.len() as u64;
-            let output_rws = output_bytes.len() as u64;
-            let return_rws = (return_bytes.len() * 2) as u64;
-
             (
                 input_len as u64,
                 input_bytes_rlc,
@@ -1093,9 +1094,11 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         self.precompile_return_rws
             .assign(region, offset, Value::known(F::from(return_rws)))?;
 
-        let (_, remainder) =
-            self.precompile_output_word_size_div
-                .assign(region, offset, output_rws.into())?;
+        let (_, remainder) = self.precompile_output_word_size_div.assign(
+            region,
+            offset,
+            precompile_return_length.as_u128(),
+        )?;
         self.precompile_output_word_size_div_remainder_zero.assign(
             region,
             offset,
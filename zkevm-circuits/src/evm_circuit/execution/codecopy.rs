@@ -1,5 +1,5 @@
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use eth_types::{evm_types::GasCost, Field};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 use crate::{
@@ -186,14 +186,11 @@ impl<F: Field> ExecutionGadget<F> for CodeCopyGadget<F> {
         )?;
         self.memory_copier_gas
             .assign(region, offset, size.as_u64(), memory_expansion_cost)?;
-        // rw_counter increase from copy table lookup is number of bytes copied.
+        // rw_counter increase from copy table lookup is number of memory words written.
         self.copy_rwc_inc.assign(
             region,
             offset,
-            Value::known(
-                size.to_scalar()
-                    .expect("unexpected U256 -> Scalar conversion failure"),
-            ),
+            Value::known(F::from(step.copy_rw_counter_delta)),
         )?;
 
         Ok(())
@@ -32,10 +32,7 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::CopyDataType, evm::OpcodeId, operation::Target, state_db::CodeDB,
 };
-use eth_types::{
-    evm_types::{GasCost, INIT_CODE_WORD_GAS},
-    Field, OpsIdentity, ToBigEndian, ToScalar, ToWord, U256,
-};
+use eth_types::{evm_types::GasCost, Field, OpsIdentity, ToBigEndian, ToScalar, ToWord, U256};
 use ethers_core::utils::keccak256;
 use gadgets::util::{and, select};
 use halo2_proofs::{
@@ -69,6 +66,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     init_code: MemoryAddressGadget<F>,
     init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
     init_code_rlc: Cell<F>,
+    copy_rw_increase: Cell<F>,
     keccak_output: Word32Cell<F>,
 
     is_depth_in_range: LtGadget<F, N_BYTES_U64>,
@@ -161,11 +159,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             init_code.length() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
         );
+        let init_code_word_gas = cb.feature_config.hardfork.init_code_word_gas();
         let keccak_gas_cost = init_code_word_size.quotient()
             * select::expr(
                 is_create2.expr(),
-                (INIT_CODE_WORD_GAS + GasCost::COPY_SHA3).expr(),
-                INIT_CODE_WORD_GAS.expr(),
+                (init_code_word_gas + GasCost::COPY_SHA3).expr(),
+                init_code_word_gas.expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
@@ -174,6 +173,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
         let was_warm = cb.query_bool();
         let init_code_rlc = cb.query_cell_phase2();
+        // The copy lookup fixes the rw counter increase to the number of memory words read.
+        let copy_rw_increase = cb.query_cell();
         let prev_code_hash = cb.query_word_unchecked();
         let callee_nonce = cb.query_cell();
         let (prev_code_hash_is_zero, not_address_collision) =
@@ -274,10 +275,10 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     CopyDataType::Bytecode.expr(),
                     init_code.offset(),
                     init_code.address(),
-                    0.expr(),             // dst_addr
-                    init_code.length(),   // length
-                    init_code_rlc.expr(), // rlc_acc
-                    init_code.length(),   // rwc_inc
+                    0.expr(),                // dst_addr
+                    init_code.length(),      // length
+                    init_code_rlc.expr(),    // rlc_acc
+                    copy_rw_increase.expr(), // rwc_inc
                 );
             });
         });
@@ -491,6 +492,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             transfer,
             init_code,
             init_code_rlc,
+            copy_rw_increase,
             memory_expansion,
             gas_left,
             init_code_word_size,
@@ -592,11 +594,16 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             U256::from(is_address_collision as u8),
         )?;
 
-        let copy_rw_increase = init_code_length.as_usize();
-        let values: Vec<u8> = if is_precheck_ok {
-            (0..copy_rw_increase)
-                .map(|_| rws.next().memory_value())
-                .collect()
+        let copy_rw_increase = step.copy_rw_counter_delta as usize;
+        self.copy_rw_increase.assign(
+            region,
+            offset,
+            Value::known(F::from(copy_rw_increase as u64)),
+        )?;
+        let values: Vec<u8> = if is_precheck_ok && copy_rw_increase > 0 {
+            let values = block.get_copy_bytes(step, rws.offset());
+            rws.offset_add(copy_rw_increase);
+            values
         } else {
             Vec::new()
         };
@@ -618,11 +625,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             offset,
             (31u64 + init_code_length.as_u64()).into(),
         )?;
+        let init_code_word_gas = block.feature_config.hardfork.init_code_word_gas();
         let initcode_gas_cost = u64::try_from(init_code_word_size).unwrap()
             * if is_create2 {
-                INIT_CODE_WORD_GAS + GasCost::COPY_SHA3
+                init_code_word_gas + GasCost::COPY_SHA3
             } else {
-                INIT_CODE_WORD_GAS
+                init_code_word_gas
             };
         let gas_left =
             step.gas_left - GasCost::CREATE - memory_expansion_gas_cost - initcode_gas_cost;
@@ -6,14 +6,14 @@ use crate::{
             common_gadget::CommonErrorGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::IsEqualGadget,
-            memory_gadget::{CommonMemoryAddressGadget, MemoryAddressGadget},
+            memory_gadget::{CommonMemoryAddressGadget, MemoryAddressGadget, MemoryWordsGadget},
             CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     util::{word::WordExpr, Expr},
 };
-use eth_types::{evm_types::INVALID_INIT_CODE_FIRST_BYTE, Field};
+use eth_types::{evm_types::INVALID_INIT_CODE_FIRST_BYTE, Field, ToBigEndian};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget for the invalid creation code error
@@ -21,6 +21,7 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 pub(crate) struct ErrorInvalidCreationCodeGadget<F> {
     opcode: Cell<F>,
     first_byte: Cell<F>,
+    first_word: MemoryWordsGadget<F, 1>,
     is_first_byte_invalid: IsEqualGadget<F>,
     memory_address: MemoryAddressGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
@@ -43,7 +44,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidCreationCodeGadget<F> {
         cb.require_true("is_create is true", cb.curr.state.is_create.expr());
 
         let memory_address = MemoryAddressGadget::construct(cb, offset, length);
-        cb.memory_lookup(0.expr(), memory_address.offset(), first_byte.expr(), None);
+        let first_word = MemoryWordsGadget::construct_read(cb, memory_address.offset());
+        cb.memory_lookup(
+            0.expr(),
+            first_word.word_address(0),
+            first_word.word(0),
+            first_word.word_prev(0),
+            None,
+        );
+        cb.require_equal(
+            "first byte is read from its memory word",
+            first_byte.expr(),
+            first_word.byte(0),
+        );
 
         let is_first_byte_invalid =
             IsEqualGadget::construct(cb, first_byte.expr(), INVALID_INIT_CODE_FIRST_BYTE.expr());
@@ -58,6 +71,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidCreationCodeGadget<F> {
         Self {
             opcode,
             first_byte,
+            first_word,
             is_first_byte_invalid,
             memory_address,
             common_error_gadget,
@@ -81,7 +95,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidCreationCodeGadget<F> {
         self.memory_address
             .assign(region, offset, memory_offset, length)?;
 
-        let first_byte = block.rws[step.rw_index(2)].memory_value().into();
+        let (first_word, _) = block.rws[step.rw_index(2)].memory_word_pair();
+        self.first_word.assign(
+            region,
+            offset,
+            memory_offset.low_u64(),
+            [first_word],
+            [first_word],
+        )?;
+        let first_byte = first_word.to_be_bytes()[(memory_offset.low_u64() % 32) as usize].into();
         self.first_byte
             .assign(region, offset, Value::known(F::from(first_byte)))?;
         self.is_first_byte_invalid.assign(
@@ -18,9 +18,7 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 use eth_types::{
-    evm_types::{
-        GasCost, OpcodeId, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE,
-    },
+    evm_types::{GasCost, OpcodeId},
     Field, U256,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -35,7 +33,7 @@ pub(crate) struct ErrorOOGCreateGadget<F> {
     memory_address: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     // Init code size is overflow when it is greater than 49152
-    // (maximum init code size) if Shanghai, otherwise when it is greater than
+    // (maximum init code size) from Shanghai, otherwise when it is greater than
     // 0x1FFFFFFFE0 (maximum value of offset + size).
     // Uint64 overflow is checked in `memory_address` (offset + length).
     init_code_size_overflow: LtGadget<F, { N_BYTES_MEMORY_ADDRESS }>,
@@ -68,8 +66,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         cb.stack_pop(memory_address.length_word());
         cb.condition(is_create2.expr().0, |cb| cb.stack_pop(salt.to_word()));
 
-        let init_code_size_overflow =
-            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+        let hardfork = cb.feature_config.hardfork;
+        let init_code_size_overflow = LtGadget::construct(
+            cb,
+            hardfork.max_init_code_size().expr(),
+            memory_address.length(),
+        );
 
         let minimum_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
@@ -77,8 +79,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         let code_store_gas_cost = minimum_word_size.expr()
             * select::expr(
                 is_create2.expr().0,
-                CREATE2_GAS_PER_CODE_WORD.expr(),
-                CREATE_GAS_PER_CODE_WORD.expr(),
+                (hardfork.init_code_word_gas() + GasCost::COPY_SHA3).expr(),
+                hardfork.init_code_word_gas().expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + code_store_gas_cost;
         let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
@@ -165,18 +167,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
             .assign(region, offset, step.memory_word_size(), [memory_address])?
             .1;
 
+        let hardfork = block.feature_config.hardfork;
         self.init_code_size_overflow.assign(
             region,
             offset,
-            F::from(MAX_INIT_CODE_SIZE),
+            F::from(hardfork.max_init_code_size()),
             F::from(init_code_size),
         )?;
 
         let code_store_gas_cost = minimum_word_size
             * if is_create2 {
-                CREATE2_GAS_PER_CODE_WORD
+                hardfork.init_code_word_gas() + GasCost::COPY_SHA3
             } else {
-                CREATE_GAS_PER_CODE_WORD
+                hardfork.init_code_word_gas()
             };
         self.insufficient_gas.assign(
             region,
@@ -202,7 +205,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 mod tests {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, word, Bytecode, ToWord};
+    use eth_types::{bytecode, evm_types::Hardfork, word, Bytecode, ToWord};
     use mock::{
         eth,
         test_ctx::{helpers::account_0_code_account_1_no_code, LoggerConfig},
@@ -300,15 +303,14 @@ mod tests {
     #[test]
     fn test_oog_create_max_init_code_size() {
         for is_create2 in [true, false] {
-            // For Shanghai, MAX_INIT_CODE_SIZE is 49152, it is constrained by
-            // `init_code_size_overflow`.
-            // For not Shanghai, MAX_INIT_CODE_SIZE is 0x1FFFFFFFE0, it is
-            // constrained by `memory_address.overflow()`
-            // (and `init_code_size_overflow`).
+            // From Shanghai, the maximum init code size is 49152, it is
+            // constrained by `init_code_size_overflow`.
+            // Before Shanghai, it is 0x1FFFFFFFE0, it is constrained by
+            // `memory_address.overflow()` (and `init_code_size_overflow`).
             let case = TestCase::new(
                 is_create2,
                 U256::zero(),
-                (MAX_INIT_CODE_SIZE + 1).into(),
+                (Hardfork::default().max_init_code_size() + 1).into(),
                 MOCK_BLOCK_GAS_LIMIT,
             );
 
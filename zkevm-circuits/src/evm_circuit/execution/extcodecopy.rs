@@ -21,7 +21,7 @@ use crate::{
     util::word::{Word32Cell, WordExpr, WordLoHi},
 };
 use bus_mapping::circuit_input_builder::CopyDataType;
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use eth_types::{evm_types::GasCost, Field};
 use gadgets::util::Expr;
 use halo2_proofs::{
     circuit::Value,
@@ -222,14 +222,11 @@ impl<F: Field> ExecutionGadget<F> for ExtcodecopyGadget<F> {
         self.code_offset
             .assign(region, offset, code_offset, F::from(code_size))?;
 
+        // rw_counter increase from copy table lookup is number of memory words written.
         self.copy_rwc_inc.assign(
             region,
             offset,
-            Value::known(
-                memory_length
-                    .to_scalar()
-                    .expect("unexpected U256 -> Scalar conversion failure"),
-            ),
+            Value::known(F::from(step.copy_rw_counter_delta)),
         )?;
 
         let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
@@ -120,7 +120,8 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             region,
             offset,
             tx.gas().scalar(),
-            tx.intrinsic_gas_cost().scalar(),
+            tx.intrinsic_gas_cost(block.feature_config.hardfork)
+                .scalar(),
         )?;
         self.balance.assign_u256(region, offset, balance)?;
         self.insufficient_balance.assign(
@@ -27,7 +27,7 @@ use array_init::array_init;
 use bus_mapping::circuit_input_builder::CopyDataType;
 use eth_types::{
     evm_types::{GasCost, OpcodeId},
-    Field, ToScalar, U256,
+    Field,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -244,16 +244,12 @@ impl<F: Field> ExecutionGadget<F> for LogGadget<F> {
             .assign(region, offset, Value::known(F::from(is_persistent)))?;
         self.tx_id
             .assign(region, offset, Value::known(F::from(tx.id)))?;
-        // rw_counter increase from copy table lookup is `msize` memory reads + `msize`
+        // rw_counter increase from copy table lookup is the memory words read + `msize`
         // log writes when `is_persistent` is true.
         self.copy_rwc_inc.assign(
             region,
             offset,
-            Value::known(
-                ((msize + msize) * U256::from(is_persistent))
-                    .to_scalar()
-                    .expect("unexpected U256 -> Scalar conversion failure"),
-            ),
+            Value::known(F::from(step.copy_rw_counter_delta)),
         )?;
 
         Ok(())
@@ -10,7 +10,7 @@ use crate::{
                 Transition::{Delta, To},
             },
             math_gadget::IsEqualGadget,
-            memory_gadget::MemoryExpansionGadget,
+            memory_gadget::{MemoryExpansionGadget, MemoryWordsGadget},
             not, CachedRegion, MemoryAddress,
         },
         witness::{Block, Call, ExecStep, Transaction},
@@ -20,7 +20,7 @@ use crate::{
         Expr,
     },
 };
-use eth_types::{evm_types::OpcodeId, Field};
+use eth_types::{evm_types::OpcodeId, Field, Word};
 use halo2_proofs::plonk::Error;
 
 #[derive(Clone, Debug)]
@@ -28,6 +28,7 @@ pub(crate) struct MemoryGadget<F> {
     same_context: SameContextGadget<F>,
     address: MemoryAddress<F>,
     value: Word32Cell<F>,
+    memory_words: MemoryWordsGadget<F, 2>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     is_mload: IsEqualGadget<F>,
     is_mstore8: IsEqualGadget<F>,
@@ -72,30 +73,55 @@ impl<F: Field> ExecutionGadget<F> for MemoryGadget<F> {
             value.to_word(),
         );
 
+        // The value bytes in memory order, the stack value being little-endian.
+        let value_bytes = (0..32)
+            .map(|idx| value.limbs[31 - idx].expr())
+            .collect::<Vec<_>>();
+        let memory_words = MemoryWordsGadget::construct_update(cb, address.expr());
+        cb.condition(is_mload.expr(), |cb| {
+            for (idx, byte) in value_bytes.iter().enumerate() {
+                cb.require_equal(
+                    "MLOAD value is read from the memory words",
+                    byte.clone(),
+                    memory_words.byte(idx),
+                );
+            }
+        });
         cb.condition(is_mstore8.expr(), |cb| {
-            cb.memory_lookup(1.expr(), address.expr(), value.limbs[0].expr(), None);
+            memory_words.require_update(cb, &[value.limbs[0].expr()]);
+        });
+        cb.condition(is_store.clone() * is_not_mstore8.clone(), |cb| {
+            memory_words.require_update(cb, &value_bytes);
         });
 
+        // MSTORE8 accesses the word holding the byte, MLOAD and MSTORE the two words holding the
+        // 32 bytes.
+        cb.memory_lookup(
+            is_store.clone(),
+            memory_words.word_address(0),
+            memory_words.word(0),
+            memory_words.word_prev(0),
+            None,
+        );
         cb.condition(is_not_mstore8, |cb| {
-            for idx in 0..32 {
-                cb.memory_lookup(
-                    is_store.clone(),
-                    address.expr() + idx.expr(),
-                    value.limbs[31 - idx].expr(),
-                    None,
-                );
-            }
+            cb.memory_lookup(
+                is_store.clone(),
+                memory_words.word_address(1),
+                memory_words.word(1),
+                memory_words.word_prev(1),
+                None,
+            );
         });
 
         // State transition
-        // - `rw_counter` needs to be increased by 34 when is_not_mstore8, otherwise to be increased
-        //   by 31
+        // - `rw_counter` needs to be increased by 4 when is_not_mstore8, otherwise to be increased
+        //   by 3
         // - `program_counter` needs to be increased by 1
         // - `stack_pointer` needs to be increased by 2 when is_store, otherwise to be same
         // - `memory_size` needs to be set to `next_memory_size`
         let gas_cost = OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost();
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(34.expr() - is_mstore8.expr() * 31.expr()),
+            rw_counter: Delta(4.expr() - is_mstore8.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(is_store * 2.expr()),
             gas_left: Delta(-gas_cost),
@@ -108,6 +134,7 @@ impl<F: Field> ExecutionGadget<F> for MemoryGadget<F> {
             same_context,
             address,
             value,
+            memory_words,
             memory_expansion,
             is_mload,
             is_mstore8,
@@ -147,6 +174,21 @@ impl<F: Field> ExecutionGadget<F> for MemoryGadget<F> {
             F::from(OpcodeId::MSTORE8.as_u64()),
         )?;
 
+        // Memory words
+        let n_words = if is_mstore8 == F::ONE { 1 } else { 2 };
+        let mut words = [Word::zero(); 2];
+        let mut words_prev = [Word::zero(); 2];
+        for (idx, (word, word_prev)) in words
+            .iter_mut()
+            .zip(words_prev.iter_mut())
+            .enumerate()
+            .take(n_words)
+        {
+            (*word, *word_prev) = block.get_rws(step, 2 + idx).memory_word_pair();
+        }
+        self.memory_words
+            .assign(region, offset, address.as_u64(), words, words_prev)?;
+
         // Memory expansion
         self.memory_expansion.assign(
             region,
@@ -225,6 +267,29 @@ mod test {
         );
     }
 
+    #[test]
+    fn memory_gadget_overlapping_words() {
+        // The accesses share memory words, so each update has to keep the bytes around it.
+        let bytecode = bytecode! {
+            PUSH32(Word::from_big_endian(&(1..33).collect::<Vec<_>>()))
+            PUSH1(0x10)
+            MSTORE
+            PUSH1(0xff)
+            PUSH1(0x21)
+            MSTORE8
+            PUSH1(0x08)
+            MLOAD
+            PUSH1(0x2f)
+            MLOAD
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .run();
+    }
+
     #[test]
     fn memory_gadget_rand() {
         let calc_gas_cost = |opcode, memory_address: Word| {
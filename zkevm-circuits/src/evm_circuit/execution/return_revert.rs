@@ -12,6 +12,7 @@ use crate::{
             math_gadget::{IsEqualGadget, IsZeroGadget, MinMaxGadget},
             memory_gadget::{
                 CommonMemoryAddressGadget, MemoryAddressGadget, MemoryExpansionGadget,
+                MemoryWordsGadget,
             },
             not, CachedRegion, Cell,
         },
@@ -26,7 +27,7 @@ use crate::{
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
 use eth_types::{
     evm_types::{GasCost, INVALID_INIT_CODE_FIRST_BYTE},
-    Field, ToScalar, U256,
+    Field, ToBigEndian, ToScalar, U256,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -41,6 +42,7 @@ pub(crate) struct ReturnRevertGadget<F> {
     restore_context: RestoreContextGadget<F>,
 
     // Used to check first byte of create init code must not be 0xef (EIP-3541).
+    init_code_first_word: MemoryWordsGadget<F, 1>,
     init_code_first_byte: Cell<F>,
     is_init_code_first_byte_invalid: IsEqualGadget<F>,
 
@@ -98,10 +100,11 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
 
         // Case A in the specs.
         cb.condition(is_create.clone() * is_success.expr(), |cb| {
+            // The copy lookup fixes the rw counter increase to the number of memory words read.
             cb.require_equal(
-                "increase rw counter once for each memory to bytecode byte copied",
-                copy_rw_increase.expr(),
-                range.length(),
+                "rw counter increase is 0 if and only if no byte is copied",
+                copy_rw_increase_is_zero.expr(),
+                not::expr(range.has_length()),
             );
         });
 
@@ -116,12 +119,25 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
             reversion_info,
             code_hash,
             deployed_code_rlc,
+            init_code_first_word,
             init_code_first_byte,
             is_init_code_first_byte_invalid,
         ) = cb.condition(is_contract_deployment.clone(), |cb| {
             // Read the first byte and check it must not be 0xef (EIP-3541).
+            let init_code_first_word = MemoryWordsGadget::construct_read(cb, range.offset());
+            cb.memory_lookup(
+                0.expr(),
+                init_code_first_word.word_address(0),
+                init_code_first_word.word(0),
+                init_code_first_word.word_prev(0),
+                None,
+            );
             let init_code_first_byte = cb.query_byte();
-            cb.memory_lookup(0.expr(), range.offset(), init_code_first_byte.expr(), None);
+            cb.require_equal(
+                "init code first byte is read from its memory word",
+                init_code_first_byte.expr(),
+                init_code_first_word.byte(0),
+            );
             let is_init_code_first_byte_invalid = IsEqualGadget::construct(
                 cb,
                 init_code_first_byte.expr(),
@@ -168,6 +184,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                 reversion_info,
                 code_hash,
                 deployed_code_rlc,
+                init_code_first_word,
                 init_code_first_byte,
                 is_init_code_first_byte_invalid,
             )
@@ -220,11 +237,14 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                 .map(|field_tag| cb.call_context(None, field_tag));
                 let copy_length =
                     MinMaxGadget::construct(cb, return_data_length.expr(), range.length());
-                cb.require_equal(
-                    "increase rw counter twice for each memory to memory byte copied",
-                    copy_length.min() + copy_length.min(),
-                    copy_rw_increase.expr(),
-                );
+                // The copy lookup fixes the rw counter increase to the number of memory words
+                // read and written.
+                cb.condition(copy_rw_increase_is_zero.expr(), |cb| {
+                    cb.require_zero(
+                        "no byte is copied if the rw counter increase is 0",
+                        copy_length.min(),
+                    );
+                });
                 (return_data_offset, return_data_length, copy_length)
             },
         );
@@ -262,6 +282,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
             range,
             deployed_code_rlc,
             is_success,
+            init_code_first_word,
             init_code_first_byte,
             is_init_code_first_byte_invalid,
             copy_length,
@@ -321,9 +342,11 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
         }
 
         if call.is_create() && call.is_success {
-            let values: Vec<_> = (4..4 + length.as_usize())
-                .map(|index| block.get_rws(step, index).memory_value())
-                .collect();
+            let values = if length.is_zero() {
+                vec![]
+            } else {
+                block.get_copy_bytes(step, 4)
+            };
             self.deployed_code_rlc.assign(
                 region,
                 offset,
@@ -335,13 +358,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                 .assign_u256(region, offset, U256::from_little_endian(&code_hash))?;
         }
 
-        let copy_rw_increase = if call.is_create() && call.is_success {
-            length.as_u64()
-        } else if !call.is_root {
-            2 * std::cmp::min(call.return_data_length, length.as_u64())
-        } else {
-            0
-        };
+        let copy_rw_increase = step.copy_rw_counter_delta;
         self.copy_rw_increase
             .assign(region, offset, Value::known(F::from(copy_rw_increase)))?;
         self.copy_rw_increase_is_zero
@@ -349,8 +366,20 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
 
         let is_contract_deployment = call.is_create() && call.is_success && !length.is_zero();
 
+        let init_code_first_word = if is_contract_deployment {
+            block.get_rws(step, 3).memory_word_pair().0
+        } else {
+            U256::zero()
+        };
+        self.init_code_first_word.assign(
+            region,
+            offset,
+            memory_offset.low_u64(),
+            [init_code_first_word],
+            [init_code_first_word],
+        )?;
         let init_code_first_byte = if is_contract_deployment {
-            block.get_rws(step, 3).memory_value()
+            init_code_first_word.to_be_bytes()[(memory_offset.low_u64() % 32) as usize]
         } else {
             0
         }
@@ -369,7 +398,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
 
         if !call.is_root {
             let rw_counter_offset = 3 + if is_contract_deployment {
-                6 + length.as_u64()
+                6 + copy_rw_increase
             } else {
                 0
             };
@@ -234,16 +234,11 @@ impl<F: Field> ExecutionGadget<F> for ReturnDataCopyGadget<F> {
         self.memory_copier_gas
             .assign(region, offset, size.as_u64(), memory_expansion_cost)?;
 
-        // rw_counter always increases by `size` reads and `size` writes
-        let copy_rwc_inc = size + size;
+        // rw_counter increases by the memory words read and written by the copy
         self.copy_rwc_inc.assign(
             region,
             offset,
-            Value::known(
-                copy_rwc_inc
-                    .to_scalar()
-                    .expect("unexpected U256 -> Scalar conversion failure"),
-            ),
+            Value::known(F::from(step.copy_rw_counter_delta)),
         )?;
 
         self.in_bound_check.assign(
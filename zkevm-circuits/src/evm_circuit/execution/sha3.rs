@@ -1,5 +1,5 @@
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use eth_types::{evm_types::GasCost, Field};
 use gadgets::util::{not, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -133,15 +133,14 @@ impl<F: Field> ExecutionGadget<F> for Sha3Gadget<F> {
         self.copy_rwc_inc.assign(
             region,
             offset,
-            Value::known(
-                size.to_scalar()
-                    .expect("unexpected U256 -> Scalar conversion failure"),
-            ),
+            Value::known(F::from(step.copy_rw_counter_delta)),
         )?;
 
-        let values: Vec<u8> = (3..3 + (size.low_u64() as usize))
-            .map(|i| block.get_rws(step, i).memory_value())
-            .collect();
+        let values: Vec<u8> = if size.is_zero() {
+            Vec::new()
+        } else {
+            block.get_copy_bytes(step, 3)
+        };
 
         let rlc_acc = region
             .challenges()
@@ -1,15 +1,20 @@
 //! Constants and parameters for the EVM circuit
 use super::table::Table;
-use crate::evm_circuit::{step::ExecutionState, EvmCircuit};
+use crate::{
+    evm_circuit::{step::ExecutionState, EvmCircuit, EvmCircuitParams},
+    util::cell_manager::CellType,
+};
 use bus_mapping::circuit_input_builder::FeatureConfig;
 use halo2_proofs::{
     halo2curves::bn256::Fr,
     plonk::{Circuit, ConstraintSystem},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
+use strum::IntoEnumIterator;
+use thiserror::Error;
 
 // Step dimension
-pub(crate) const STEP_WIDTH: usize = 128;
+pub(crate) const STEP_WIDTH: usize = 126;
 /// Step height
 pub const MAX_STEP_HEIGHT: usize = 19;
 /// The height of the state of a step, used by gates that connect two
@@ -28,11 +33,13 @@ pub const N_PHASE1_COLUMNS: usize =
 pub const N_COPY_COLUMNS: usize = 2;
 
 /// Number of columns reserved for u8 lookup
-pub const N_U8_LOOKUPS: usize = 24;
+pub const N_U8_LOOKUPS: usize = 18;
 
-// TODO shift #column/2 from u8 to u16
-/// Number of columns reserved for u16 lookup
-pub const N_U16_LOOKUPS: usize = 0;
+/// Number of columns reserved for u16 lookup, used by the range checks that
+/// pack two bytes per cell. Together with the u8 columns they fit the bytes
+/// and range checks of MULMOD, the tallest state, in `MAX_STEP_HEIGHT`, with
+/// two columns less than the 24 u8 columns they replace.
+pub const N_U16_LOOKUPS: usize = 4;
 
 /// Amount of lookup columns in the EVM circuit dedicated to lookups.
 pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
@@ -80,6 +87,162 @@ pub const KECCAK_TABLE_LOOKUPS: usize = 1;
 /// Exp Table lookups done in EVMCircuit
 pub const EXP_TABLE_LOOKUPS: usize = 1;
 
+/// Number of advice columns of each cell type in a step of the EVM circuit.
+/// The phase1 columns are the ones left of `step_width`. The default layout is
+/// given by the constants of this module.
+///
+/// Fewer lookup columns give taller steps for the execution states using them,
+/// and so more rows for the same block, until a state doesn't fit in
+/// `MAX_STEP_HEIGHT`. See [`EvmCircuitParams::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmColumnLayout {
+    /// Number of advice columns of a step
+    pub step_width: usize,
+    /// Number of lookup columns for each table
+    pub lookups: Vec<(Table, usize)>,
+    /// Number of Advice Phase2 columns
+    pub phase2_columns: usize,
+    /// Number of copy columns
+    pub copy_columns: usize,
+    /// Number of columns reserved for u8 lookup
+    pub u8_lookups: usize,
+    /// Number of columns reserved for u16 lookup
+    pub u16_lookups: usize,
+    // Max height of the cell manager of a step, only lifted to find out the
+    // heights of the execution states when validating a layout.
+    pub(crate) max_step_height: usize,
+    // Let the cells of a type out of columns overflow the max step height in
+    // its last column with the column-major placement, only set to find out the
+    // heights of the execution states when validating a layout.
+    pub(crate) overflow_columns: bool,
+}
+
+impl Default for EvmColumnLayout {
+    fn default() -> Self {
+        Self {
+            step_width: STEP_WIDTH,
+            lookups: LOOKUP_CONFIG.to_vec(),
+            phase2_columns: N_PHASE2_COLUMNS,
+            copy_columns: N_COPY_COLUMNS,
+            u8_lookups: N_U8_LOOKUPS,
+            u16_lookups: N_U16_LOOKUPS,
+            max_step_height: MAX_STEP_HEIGHT,
+            overflow_columns: false,
+        }
+    }
+}
+
+impl EvmColumnLayout {
+    /// Number of columns dedicated to the table lookups
+    pub fn lookup_columns(&self) -> usize {
+        self.lookups.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Number of Advice Phase1 columns, or 0 when the other columns don't fit
+    /// in the step width.
+    pub fn phase1_columns(&self) -> usize {
+        self.step_width.saturating_sub(
+            self.lookup_columns()
+                + self.phase2_columns
+                + self.copy_columns
+                + self.u8_lookups
+                + self.u16_lookups,
+        )
+    }
+
+    /// Layout keeping only the columns holding cells, given as the number of
+    /// columns used per cell type. Every cell type keeps at least one column.
+    pub(crate) fn narrowed(&self, columns_used: &HashMap<CellType, usize>) -> Self {
+        let used = |cell_type: CellType, columns: usize| {
+            columns_used
+                .get(&cell_type)
+                .copied()
+                .unwrap_or_default()
+                .clamp(1, columns.max(1))
+        };
+        let lookups = self
+            .lookups
+            .iter()
+            .map(|(table, count)| (*table, used(CellType::Lookup(*table), *count)))
+            .collect::<Vec<_>>();
+        let phase2_columns = used(CellType::StoragePhase2, self.phase2_columns);
+        let copy_columns = used(CellType::StoragePermutation, self.copy_columns);
+        let u8_lookups = used(CellType::Lookup(Table::U8), self.u8_lookups);
+        let u16_lookups = used(CellType::Lookup(Table::U16), self.u16_lookups);
+        let phase1_columns = used(CellType::StoragePhase1, self.phase1_columns());
+        Self {
+            step_width: lookups.iter().map(|(_, count)| count).sum::<usize>()
+                + phase2_columns
+                + copy_columns
+                + u8_lookups
+                + u16_lookups
+                + phase1_columns,
+            lookups,
+            phase2_columns,
+            copy_columns,
+            u8_lookups,
+            u16_lookups,
+            max_step_height: self.max_step_height,
+            overflow_columns: self.overflow_columns,
+        }
+    }
+
+    /// Check that every cell type has columns, and that the phase1 columns
+    /// can hold the state of a step, which must fit in its first row.
+    pub(crate) fn check_columns(&self, step_state_width: usize) -> Result<(), EvmLayoutError> {
+        let missing = Table::iter()
+            .filter(|table| !matches!(table, Table::U8 | Table::U16))
+            .filter(|table| {
+                self.lookups
+                    .iter()
+                    .all(|(lookup, count)| lookup != table || *count == 0)
+            })
+            .map(|table| format!("{:?} lookup", table))
+            .chain(
+                [
+                    ("phase2", self.phase2_columns),
+                    ("copy", self.copy_columns),
+                    ("u8 lookup", self.u8_lookups),
+                    ("u16 lookup", self.u16_lookups),
+                ]
+                .into_iter()
+                .filter(|(_, count)| *count == 0)
+                .map(|(name, _)| name.to_string()),
+            )
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(EvmLayoutError::MissingColumns(missing));
+        }
+        if self.phase1_columns() < step_state_width {
+            return Err(EvmLayoutError::NotEnoughPhase1Columns {
+                columns: self.phase1_columns(),
+                required: step_state_width,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reasons an [`EvmColumnLayout`] can't configure the EVM circuit
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EvmLayoutError {
+    /// Cell types without columns
+    #[error("MissingColumns({0:?})")]
+    MissingColumns(Vec<String>),
+    /// The phase1 columns can't hold the state of a step
+    #[error("NotEnoughPhase1Columns(columns: {columns}, required: {required})")]
+    NotEnoughPhase1Columns {
+        /// Number of phase1 columns of the layout
+        columns: usize,
+        /// Number of phase1 cells of the state of a step
+        required: usize,
+    },
+    /// Execution states whose height exceeds the max step height of the layout,
+    /// with their height
+    #[error("StepTooHigh({0:?})")]
+    StepTooHigh(Vec<(ExecutionState, usize)>),
+}
+
 /// Maximum number of bytes that an integer can fit in field without wrapping
 /// around.
 pub(crate) const MAX_N_BYTES_INTEGER: usize = 31;
@@ -139,7 +302,8 @@ pub(crate) const N_BYTES_BLOCK: usize = N_BYTES_COINBASE
 
 pub(crate) const N_BYTES_EXTRA_VALUE: usize = N_BYTES_WORD // block hash
     + N_BYTES_WORD // state root
-    + N_BYTES_WORD; // prev state root
+    + N_BYTES_WORD // prev state root
+    + N_BYTES_WORD; // transactions root
 
 // Number of bytes that will be used for tx values
 pub(crate) const N_BYTES_TX_NONCE: usize = N_BYTES_U64;
@@ -173,19 +337,47 @@ lazy_static::lazy_static! {
         invalid_tx: true,
         ..Default::default()
     };
-    // Step slot height in evm circuit
+    // Step slot height in evm circuit with the default hardfork, see `step_height_map` for the
+    // heights with another feature config
     // We enable the invalid_tx feature to get invalid tx's ExecutionState height
     // We garentee the heights of other ExecutionStates remains unchanged in the following test
-    pub(crate) static ref EXECUTION_STATE_HEIGHT_MAP : HashMap<ExecutionState, usize> = get_step_height_map(*INVALID_TX_CONFIG);
+    pub(crate) static ref EXECUTION_STATE_HEIGHT_MAP : HashMap<ExecutionState, usize> = step_height_map(*INVALID_TX_CONFIG);
+    // Step slot heights in evm circuit with the default column layout by feature config
+    static ref STEP_HEIGHT_MAPS: Mutex<HashMap<FeatureConfig, HashMap<ExecutionState, usize>>> =
+        Mutex::default();
+}
+
+/// Returns the height of each execution state with the default column layout
+/// and `feature_config`. The heights depend on the hardfork, e.g. `BeginTx`
+/// warms up the coinbase from Shanghai and one more precompile from Cancun, so
+/// they are computed once per feature config.
+pub(crate) fn step_height_map(feature_config: FeatureConfig) -> HashMap<ExecutionState, usize> {
+    if let Some(map) = STEP_HEIGHT_MAPS.lock().unwrap().get(&feature_config) {
+        return map.clone();
+    }
+    let map = get_step_height_map(feature_config);
+    STEP_HEIGHT_MAPS
+        .lock()
+        .unwrap()
+        .insert(feature_config, map.clone());
+    map
 }
+
 fn get_step_height_map(feature_config: FeatureConfig) -> HashMap<ExecutionState, usize> {
     let mut meta = ConstraintSystem::<Fr>::default();
-    let circuit = EvmCircuit::configure_with_params(&mut meta, feature_config);
+    let circuit = EvmCircuit::configure_with_params(
+        &mut meta,
+        EvmCircuitParams {
+            feature_config,
+            ..Default::default()
+        },
+    );
     circuit.0.execution.height_map
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use eth_types::evm_types::Hardfork;
 
     #[test]
     fn test_get_step_height_map() {
@@ -200,4 +392,32 @@ mod tests {
         // We show that the invalid tx feature affects none of the other execution state heights
         assert_eq!(map_invalid_tx, map_mainnet);
     }
+
+    #[test]
+    fn test_step_height_map_hardfork() {
+        for hardfork in [
+            Hardfork::London,
+            Hardfork::Paris,
+            Hardfork::Shanghai,
+            Hardfork::Cancun,
+        ] {
+            let feature_config = FeatureConfig {
+                hardfork,
+                ..Default::default()
+            };
+            // The heights are cached by feature config
+            assert_eq!(
+                step_height_map(feature_config),
+                get_step_height_map(feature_config)
+            );
+            assert_eq!(
+                EvmCircuitParams {
+                    feature_config,
+                    ..Default::default()
+                }
+                .step_heights(),
+                get_step_height_map(feature_config)
+            );
+        }
+    }
 }
@@ -4,16 +4,16 @@
 //! reaches.
 
 use super::{
-    param::MAX_STEP_HEIGHT,
+    param::EvmColumnLayout,
     util::{evm_cm_distribute_advice, CachedRegion, Cell, CellType},
 };
 use crate::{
     evm_circuit::{
-        param::{EXECUTION_STATE_HEIGHT_MAP, STEP_WIDTH},
+        param::EXECUTION_STATE_HEIGHT_MAP,
         witness::{Block, Call, ExecStep},
     },
     util::{
-        cell_manager::{CMFixedWidthStrategy, CellManager},
+        cell_manager::{CMFixedWidthStrategy, CellManager, CellPlacementOrder},
         word::{WordLoHi, WordLoHiCell},
         Expr,
     },
@@ -24,9 +24,10 @@ use bus_mapping::{
     evm::OpcodeId,
     precompile::PrecompileCalls,
 };
-use eth_types::{evm_unimplemented, Field, ToWord};
+use eth_types::{evm_types::Hardfork, evm_unimplemented, Field, ToWord};
 use halo2_proofs::{
     circuit::Value,
+    halo2curves::bn256::Fr,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
 use std::{fmt::Display, iter};
@@ -430,10 +431,11 @@ impl ExecutionState {
             || self.halts_in_exception()
     }
 
-    /// Get the opocdes that are related to the execution state
-    pub fn responsible_opcodes(&self) -> Vec<ResponsibleOp> {
+    /// Get the opocdes that are related to the execution state in `hardfork`
+    pub fn responsible_opcodes(&self, hardfork: Hardfork) -> Vec<ResponsibleOp> {
         if matches!(self, Self::ErrorStack) {
-            return OpcodeId::valid_opcodes()
+            return hardfork
+                .valid_opcodes()
                 .into_iter()
                 .flat_map(|op| {
                     op.invalid_stack_ptrs()
@@ -588,7 +590,7 @@ impl ExecutionState {
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::CREATE2 => vec![OpcodeId::CREATE2],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
+            Self::ErrorInvalidOpcode => hardfork.invalid_opcodes(),
             _ => vec![],
         }
         .into_iter()
@@ -596,7 +598,9 @@ impl ExecutionState {
         .collect()
     }
 
-    /// Get the state hight
+    /// Get the state hight with the default feature config, see
+    /// [`EvmCircuitParams::step_heights`](crate::evm_circuit::EvmCircuitParams::step_heights)
+    /// for the heights with another hardfork
     pub fn get_step_height_option(&self) -> Option<usize> {
         EXECUTION_STATE_HEIGHT_MAP.get(self).copied()
     }
@@ -775,13 +779,17 @@ pub(crate) struct Step<F> {
 impl<F: Field> Step<F> {
     pub(crate) fn new(
         meta: &mut ConstraintSystem<F>,
-        advices: [Column<Advice>; STEP_WIDTH],
+        advices: &[Column<Advice>],
         offset: usize,
+        layout: &EvmColumnLayout,
     ) -> Self {
-        let cell_manager_strategy =
-            CMFixedWidthStrategy::new(evm_cm_distribute_advice::<F>(meta, &advices), offset)
+        let mut cell_manager_strategy =
+            CMFixedWidthStrategy::new(evm_cm_distribute_advice::<F>(meta, advices, layout), offset)
                 .with_perm_substitution()
-                .with_max_height(MAX_STEP_HEIGHT);
+                .with_max_height(layout.max_step_height);
+        if layout.overflow_columns {
+            cell_manager_strategy = cell_manager_strategy.with_column_overflow();
+        }
 
         let mut cell_manager = CellManager::new(cell_manager_strategy);
         let state = {
@@ -813,6 +821,12 @@ impl<F: Field> Step<F> {
         }
     }
 
+    /// Sets the order of the cells placed after the state of the step.
+    pub(crate) fn with_cell_placement(mut self, order: CellPlacementOrder) -> Self {
+        self.cell_manager.get_strategy().set_order(order);
+        self
+    }
+
     pub(crate) fn execution_state_selector(
         &self,
         execution_states: impl IntoIterator<Item = ExecutionState>,
@@ -877,3 +891,19 @@ impl<F: Field> Step<F> {
         Ok(())
     }
 }
+
+/// Returns the number of StoragePhase1 cells of the state of a step, which must
+/// fit in its first row.
+pub fn step_state_width() -> usize {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let layout = EvmColumnLayout::default();
+    let advices = (0..layout.step_width)
+        .map(|_| meta.advice_column())
+        .collect::<Vec<_>>();
+    let step = Step::new(&mut meta, &advices, 0, &layout);
+    step.cell_manager
+        .get_stats()
+        .get(&CellType::StoragePhase1)
+        .map(|(_, _, cells)| *cells)
+        .unwrap_or_default()
+}
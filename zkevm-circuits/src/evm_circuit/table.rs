@@ -6,7 +6,7 @@ use crate::{
     util::word::WordLoHi,
 };
 use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
-use eth_types::Field;
+use eth_types::{evm_types::Hardfork, Field};
 use gadgets::util::Expr;
 use halo2_proofs::plonk::Expression;
 use strum::IntoEnumIterator;
@@ -54,7 +54,7 @@ impl_expr!(FixedTableTag);
 
 impl FixedTableTag {
     /// build up the fixed table row values
-    pub(crate) fn build<F: Field>(&self) -> Box<dyn Iterator<Item = [F; 4]>> {
+    pub(crate) fn build<F: Field>(&self, hardfork: Hardfork) -> Box<dyn Iterator<Item = [F; 4]>> {
         let tag = F::from(*self as u64);
         match self {
             Self::Zero => Box::new((0..1).map(move |_| [tag, F::ZERO, F::ZERO, F::ZERO])),
@@ -101,8 +101,10 @@ impl FixedTableTag {
             })),
             Self::ResponsibleOpcode => {
                 Box::new(ExecutionState::iter().flat_map(move |execution_state| {
-                    execution_state.responsible_opcodes().into_iter().map(
-                        move |responsible_opcode| {
+                    execution_state
+                        .responsible_opcodes(hardfork)
+                        .into_iter()
+                        .map(move |responsible_opcode| {
                             let (op, aux) = match responsible_opcode {
                                 ResponsibleOp::Op(op) => (op, F::ZERO),
                                 ResponsibleOp::InvalidStackPtr(op, stack_ptr) => {
@@ -115,8 +117,7 @@ impl FixedTableTag {
                                 F::from(op.as_u64()),
                                 aux,
                             ]
-                        },
-                    )
+                        })
                 }))
             }
             Self::Pow2 => Box::new((0..256).map(move |value| {
@@ -4,9 +4,7 @@ pub use crate::util::{
 };
 use crate::{
     evm_circuit::{
-        param::{
-            LOOKUP_CONFIG, N_BYTES_MEMORY_ADDRESS, N_COPY_COLUMNS, N_PHASE2_COLUMNS, N_U8_LOOKUPS,
-        },
+        param::{EvmColumnLayout, N_BYTES_MEMORY_ADDRESS},
         table::Table,
     },
     util::{cell_manager::CMFixedWidthStrategyDistribution, int_decomposition::IntDecomposition},
@@ -31,7 +29,7 @@ pub(crate) mod tx;
 
 pub use gadgets::util::{and, not, or, select, sum};
 
-use super::param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_U64, N_U16_LOOKUPS};
+use super::param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_U64};
 
 #[deprecated(note = "Removing this would require to edit almost all gadget")]
 pub(crate) use crate::util::cell_manager::{Cell, CellType};
@@ -135,6 +133,22 @@ impl<'r, 'b, F: Field> CachedRegion<'r, 'b, F> {
             [(((row_index - self.height_start) as i32) + rotation.0) as usize]
     }
 
+    /// Returns the cached value of an advice cell, `None` when the cell is outside of the cached
+    /// columns and rows.
+    pub(crate) fn try_get_advice(
+        &self,
+        row_index: usize,
+        column_index: usize,
+        rotation: Rotation,
+    ) -> Option<F> {
+        let column = column_index.checked_sub(self.width_start)?;
+        let row = (row_index as i32 - self.height_start as i32) + rotation.0;
+        self.advice
+            .get(column)?
+            .get(usize::try_from(row).ok()?)
+            .copied()
+    }
+
     pub fn challenges(&self) -> &Challenges<Value<F>> {
         self.challenges
     }
@@ -230,12 +244,13 @@ impl<F: Field> StoredExpression<F> {
 pub(crate) fn evm_cm_distribute_advice<F: Field>(
     meta: &mut ConstraintSystem<F>,
     advices: &[Column<Advice>],
+    layout: &EvmColumnLayout,
 ) -> CMFixedWidthStrategyDistribution {
     let mut dist = CMFixedWidthStrategyDistribution::default();
 
     let mut column_idx = 0;
     // Mark columns used for lookups in Phase3
-    for &(table, count) in LOOKUP_CONFIG {
+    for &(table, count) in layout.lookups.iter() {
         for _ in 0usize..count {
             dist.add(CellType::Lookup(table), advices[column_idx]);
             column_idx += 1;
@@ -243,29 +258,27 @@ pub(crate) fn evm_cm_distribute_advice<F: Field>(
     }
 
     // Mark columns used for Phase2 constraints
-    for _ in 0..N_PHASE2_COLUMNS {
+    for _ in 0..layout.phase2_columns {
         dist.add(CellType::StoragePhase2, advices[column_idx]);
         column_idx += 1;
     }
 
     // Mark columns used for copy constraints
-    for _ in 0..N_COPY_COLUMNS {
+    for _ in 0..layout.copy_columns {
         meta.enable_equality(advices[column_idx]);
         dist.add(CellType::StoragePermutation, advices[column_idx]);
         column_idx += 1;
     }
 
     // Mark columns used for byte lookup
-    #[allow(clippy::reversed_empty_ranges)]
-    for _ in 0..N_U8_LOOKUPS {
+    for _ in 0..layout.u8_lookups {
         dist.add(CellType::Lookup(Table::U8), advices[column_idx]);
         assert_eq!(advices[column_idx].column_type().phase(), 0);
         column_idx += 1;
     }
 
-    // Mark columns used for byte lookup
-    #[allow(clippy::reversed_empty_ranges)]
-    for _ in 0..N_U16_LOOKUPS {
+    // Mark columns used for u16 lookup
+    for _ in 0..layout.u16_lookups {
         dist.add(CellType::Lookup(Table::U16), advices[column_idx]);
         assert_eq!(advices[column_idx].column_type().phase(), 0);
         column_idx += 1;
@@ -362,6 +375,37 @@ pub(crate) mod from_bytes {
     }
 }
 
+/// Decodes a field element from its little endian limbs of two bytes, range
+/// checked with the u16 table, where the last limb is a single byte when the
+/// number of bytes is odd.
+pub(crate) mod from_u16_limbs {
+    use crate::{evm_circuit::param::MAX_N_BYTES_INTEGER, util::Expr};
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field, E: Expr<F>>(limbs: &[E]) -> Expression<F> {
+        debug_assert!(
+            limbs.len() * 2 <= MAX_N_BYTES_INTEGER + 1,
+            "Too many limbs to compose an integer in field"
+        );
+        let mut value = 0.expr();
+        let mut multiplier = F::ONE;
+        for limb in limbs.iter() {
+            value = value + limb.expr() * multiplier;
+            multiplier *= F::from(1 << 16);
+        }
+        value
+    }
+
+    /// Returns the values of the limbs of the little endian bytes.
+    pub(crate) fn values<F: Field>(bytes: &[u8]) -> Vec<F> {
+        bytes
+            .chunks(2)
+            .map(|limb| F::from(limb[0] as u64 + limb.get(1).map_or(0, |byte| *byte as u64) * 256))
+            .collect()
+    }
+}
+
 /// Returns the random linear combination of the inputs.
 /// Encoding is done as follows: v_0 * R^0 + v_1 * R^1 + ...
 pub(crate) mod rlc {
@@ -469,6 +513,10 @@ impl<'a> StepRws<'a> {
     pub(crate) fn offset_add(&mut self, inc: usize) {
         self.offset += inc
     }
+    /// Return the current step rw operation offset.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
     /// Return the next rw operation from the step.
     pub(crate) fn next(&mut self) -> Rw {
         let rw = self.rws[self.step.rw_index(self.offset)];
@@ -481,7 +529,9 @@ impl<'a> StepRws<'a> {
 mod tests {
     use halo2_proofs::halo2curves::bn256::Fr;
 
-    use crate::evm_circuit::param::STEP_WIDTH;
+    use crate::evm_circuit::param::{
+        LOOKUP_CONFIG, N_COPY_COLUMNS, N_PHASE2_COLUMNS, N_U16_LOOKUPS, N_U8_LOOKUPS, STEP_WIDTH,
+    };
 
     use super::*;
 
@@ -490,7 +540,7 @@ mod tests {
         let mut cs = ConstraintSystem::<Fr>::default();
         let advices = vec![cs.advice_column(); STEP_WIDTH];
 
-        let cm = evm_cm_distribute_advice(&mut cs, &advices);
+        let cm = evm_cm_distribute_advice(&mut cs, &advices, &EvmColumnLayout::default());
 
         let lookup_config_size = LOOKUP_CONFIG
             .iter()
@@ -532,4 +582,19 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_from_u16_limbs_values() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05];
+        let limbs = from_u16_limbs::values::<Fr>(&bytes);
+
+        assert_eq!(
+            limbs,
+            vec![Fr::from(0x0201), Fr::from(0x0403), Fr::from(0x05)]
+        );
+        assert_eq!(
+            limbs[0] + limbs[1] * Fr::from(1 << 16) + limbs[2] * Fr::from(1 << 32),
+            from_bytes::value(&bytes)
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
     evm_circuit::{
+        coverage::{ConstraintKind, CoveragePoint},
         param::STACK_CAPACITY,
         step::{ExecutionState, Step},
         table::{FixedTableTag, Lookup, RwValues, Table},
@@ -28,6 +29,7 @@ use halo2_proofs::{
         VirtualCells,
     },
 };
+use std::panic::Location;
 
 use super::{
     rlc, AccountAddress, CachedRegion, CellType, MemoryAddress, StoredExpression, U64Cell,
@@ -160,14 +162,17 @@ impl<F: Field> ReversionInfo<F> {
 pub(crate) trait ConstrainBuilderCommon<F: Field> {
     fn add_constraint(&mut self, name: &'static str, constraint: Expression<F>);
 
+    #[track_caller]
     fn require_zero(&mut self, name: &'static str, constraint: Expression<F>) {
         self.add_constraint(name, constraint);
     }
 
+    #[track_caller]
     fn require_zero_word(&mut self, name: &'static str, word: WordLoHi<Expression<F>>) {
         self.require_equal_word(name, word, WordLoHi::zero());
     }
 
+    #[track_caller]
     fn require_equal_word(
         &mut self,
         name: &'static str,
@@ -180,18 +185,22 @@ pub(crate) trait ConstrainBuilderCommon<F: Field> {
         self.add_constraint(name, lhs_hi - rhs_hi);
     }
 
+    #[track_caller]
     fn require_equal(&mut self, name: &'static str, lhs: Expression<F>, rhs: Expression<F>) {
         self.add_constraint(name, lhs - rhs);
     }
 
+    #[track_caller]
     fn require_boolean(&mut self, name: &'static str, value: Expression<F>) {
         self.add_constraint(name, value.clone() * (1.expr() - value));
     }
 
+    #[track_caller]
     fn require_true(&mut self, name: &'static str, value: Expression<F>) {
         self.require_equal(name, value, 1.expr());
     }
 
+    #[track_caller]
     fn require_in_set(
         &mut self,
         name: &'static str,
@@ -205,6 +214,7 @@ pub(crate) trait ConstrainBuilderCommon<F: Field> {
         );
     }
 
+    #[track_caller]
     fn add_constraints(&mut self, constraints: Vec<(&'static str, Expression<F>)>) {
         for (name, constraint) in constraints {
             self.add_constraint(name, constraint);
@@ -280,8 +290,8 @@ impl<F: Field> BaseConstraintBuilder<F> {
 }
 
 /// Internal type to select the location where the constraints are enabled
-#[derive(Debug, PartialEq)]
-enum ConstraintLocation {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ConstraintLocation {
     Step,
     StepFirst,
     NotStepLast,
@@ -313,12 +323,21 @@ pub(crate) struct EVMConstraintBuilder<'a, F: Field> {
     constraints_location: ConstraintLocation,
     stored_expressions: Vec<StoredExpression<F>>,
     pub(crate) debug_expressions: Vec<(String, Expression<F>)>,
+    pub(crate) coverage_points: Vec<CoveragePoint<F>>,
+    // Tag the constraints and lookups with their source line for the constraint coverage
+    pub(crate) collect_coverage: bool,
     meta: &'a mut ConstraintSystem<F>,
     pub(crate) feature_config: FeatureConfig,
 }
 
 impl<'a, F: Field> ConstrainBuilderCommon<F> for EVMConstraintBuilder<'a, F> {
+    #[track_caller]
     fn add_constraint(&mut self, name: &'static str, constraint: Expression<F>) {
+        self.add_coverage_point(
+            ConstraintKind::Gate,
+            name,
+            std::slice::from_ref(&constraint),
+        );
         let constraint = self.split_expression(
             name,
             constraint * self.condition_expr(),
@@ -361,6 +380,8 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             stored_expressions: Vec::new(),
             meta,
             debug_expressions: Vec::new(),
+            coverage_points: Vec::new(),
+            collect_coverage: false,
             feature_config,
         }
     }
@@ -432,6 +453,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         cell
     }
 
+    #[track_caller]
     pub(crate) fn query_bool(&mut self) -> Cell<F> {
         let cell = self.query_cell();
         self.require_boolean("Constrain cell to be a bool", cell.expr());
@@ -481,6 +503,20 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.query_cells(CellType::Lookup(Table::U8), count)
     }
 
+    pub(crate) fn query_u16_dyn(&mut self, count: usize) -> Vec<Cell<F>> {
+        self.query_cells(CellType::Lookup(Table::U16), count)
+    }
+
+    // query_u16_limbs packs two bytes per u16 cell to range check an integer of `n_bytes`, with a
+    // byte cell for the last byte when `n_bytes` is odd. See `from_u16_limbs`.
+    pub(crate) fn query_u16_limbs(&mut self, n_bytes: usize) -> Vec<Cell<F>> {
+        let mut limbs = self.query_u16_dyn(n_bytes / 2);
+        if n_bytes % 2 == 1 {
+            limbs.push(self.query_byte());
+        }
+        limbs
+    }
+
     pub(crate) fn query_cell(&mut self) -> Cell<F> {
         self.query_cell_with_type(CellType::StoragePhase1)
     }
@@ -515,6 +551,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         Word32::new(EMPTY_CODE_HASH_LE.map(|byte| byte.expr())).to_word()
     }
 
+    #[track_caller]
     pub(crate) fn require_next_state(&mut self, execution_state: ExecutionState) {
         let next_state = self.next.execution_state_selector([execution_state]);
         self.add_constraint(
@@ -523,6 +560,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn require_step_state_transition(
         &mut self,
         step_state_transition: StepStateTransition<F>,
@@ -583,6 +621,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // Fixed
 
+    #[track_caller]
     pub(crate) fn range_lookup(&mut self, value: Expression<F>, range: u64) {
         let (name, tag) = match range {
             5 => ("Range5", FixedTableTag::Range5),
@@ -605,6 +644,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // precompiled contract information
+    #[track_caller]
     pub(crate) fn precompile_info_lookup(
         &mut self,
         execution_state: Expression<F>,
@@ -621,6 +661,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // constant gas
+    #[track_caller]
     pub(crate) fn constant_gas_lookup(&mut self, opcode: Expression<F>, gas: Expression<F>) {
         self.add_lookup(
             "constant gas",
@@ -633,6 +674,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // Opcode
 
+    #[track_caller]
     pub(crate) fn opcode_lookup(&mut self, opcode: Expression<F>, is_code: Expression<F>) {
         self.opcode_lookup_at(
             self.curr.state.program_counter.expr() + self.program_counter_offset.expr(),
@@ -642,6 +684,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.program_counter_offset += 1;
     }
 
+    #[track_caller]
     pub(crate) fn opcode_lookup_at(
         &mut self,
         index: Expression<F>,
@@ -662,6 +705,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn bytecode_lookup(
         &mut self,
         code_hash: WordLoHi<Expression<F>>,
@@ -681,6 +725,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         )
     }
 
+    #[track_caller]
     pub(crate) fn bytecode_length(
         &mut self,
         code_hash: WordLoHi<Expression<F>>,
@@ -700,6 +745,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // Tx context
 
+    #[track_caller]
     pub(crate) fn tx_context(
         &mut self,
         id: Expression<F>,
@@ -716,6 +762,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
         cell
     }
+    #[track_caller]
     pub(crate) fn tx_context_as_word32(
         &mut self,
         id: Expression<F>,
@@ -727,6 +774,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         word
     }
 
+    #[track_caller]
     pub(crate) fn tx_context_as_word(
         &mut self,
         id: Expression<F>,
@@ -738,6 +786,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         word
     }
 
+    #[track_caller]
     pub(crate) fn tx_context_lookup(
         &mut self,
         id: Expression<F>,
@@ -757,6 +806,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // block
+    #[track_caller]
     pub(crate) fn block_lookup(
         &mut self,
         tag: Expression<F>,
@@ -777,6 +827,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     /// Add a Lookup::Rw without increasing the rw_counter_offset, which is
     /// useful for state reversion or dummy lookup.
+    #[track_caller]
     fn rw_lookup_with_counter(
         &mut self,
         name: &str,
@@ -799,6 +850,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     /// Add a Lookup::Rw and increase the rw_counter_offset, useful in normal
     /// cases.
+    #[track_caller]
     fn rw_lookup(
         &mut self,
         name: &'static str,
@@ -828,6 +880,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         };
     }
 
+    #[track_caller]
     fn reversible_write(
         &mut self,
         name: &'static str,
@@ -859,6 +912,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Access list
+    #[track_caller]
     pub(crate) fn account_access_list_write_unchecked(
         &mut self,
         tx_id: Expression<F>,
@@ -883,6 +937,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn account_access_list_read(
         &mut self,
         tx_id: Expression<F>,
@@ -904,6 +959,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             ),
         );
     }
+    #[track_caller]
     pub(crate) fn account_storage_access_list_write(
         &mut self,
         tx_id: Expression<F>,
@@ -929,6 +985,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn account_storage_access_list_read(
         &mut self,
         tx_id: Expression<F>,
@@ -954,6 +1011,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // Tx Refund
 
+    #[track_caller]
     pub(crate) fn tx_refund_read(&mut self, tx_id: Expression<F>, value: WordLoHi<Expression<F>>) {
         self.rw_lookup(
             "TxRefund read",
@@ -971,6 +1029,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn tx_refund_write(
         &mut self,
         tx_id: Expression<F>,
@@ -995,6 +1054,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Account
+    #[track_caller]
     pub(crate) fn account_read(
         &mut self,
         account_address: WordLoHi<Expression<F>>,
@@ -1017,6 +1077,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn account_write(
         &mut self,
         account_address: WordLoHi<Expression<F>>,
@@ -1042,6 +1103,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Account Storage
+    #[track_caller]
     pub(crate) fn account_storage_read(
         &mut self,
         account_address: WordLoHi<Expression<F>>,
@@ -1067,6 +1129,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     #[allow(clippy::too_many_arguments)]
+    #[track_caller]
     pub(crate) fn account_storage_write(
         &mut self,
         account_address: WordLoHi<Expression<F>>,
@@ -1094,6 +1157,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Call context
+    #[track_caller]
     pub(crate) fn call_context(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1112,6 +1176,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         cell
     }
 
+    #[track_caller]
     pub(crate) fn call_context_read_as_word(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1122,6 +1187,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         word
     }
 
+    #[track_caller]
     pub(crate) fn call_context_lookup_read(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1146,6 +1212,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // same as call_context_lookup_write with bypassing external rwc
     // Note: will not bumping internal rwc
+    #[track_caller]
     pub(crate) fn call_context_lookup_write_with_counter(
         &mut self,
         rw_counter: Expression<F>,
@@ -1170,6 +1237,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     pub(crate) fn call_context_lookup_write(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1192,6 +1260,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    #[track_caller]
     fn reversion_info(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1231,6 +1300,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         }
     }
 
+    #[track_caller]
     pub(crate) fn reversion_info_read(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1238,6 +1308,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.reversion_info(call_id, false)
     }
 
+    #[track_caller]
     pub(crate) fn reversion_info_write_unchecked(
         &mut self,
         call_id: Option<Expression<F>>,
@@ -1246,16 +1317,19 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Stack
+    #[track_caller]
     pub(crate) fn stack_pop(&mut self, value: WordLoHi<Expression<F>>) {
         self.stack_lookup(false.expr(), self.stack_pointer_offset.clone(), value);
         self.stack_pointer_offset = self.stack_pointer_offset.clone() + self.condition_expr();
     }
 
+    #[track_caller]
     pub(crate) fn stack_push(&mut self, value: WordLoHi<Expression<F>>) {
         self.stack_pointer_offset = self.stack_pointer_offset.clone() - self.condition_expr();
         self.stack_lookup(true.expr(), self.stack_pointer_offset.expr(), value);
     }
 
+    #[track_caller]
     pub(crate) fn stack_lookup(
         &mut self,
         is_write: Expression<F>,
@@ -1280,11 +1354,13 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // Memory
 
+    #[track_caller]
     pub(crate) fn memory_lookup(
         &mut self,
         is_write: Expression<F>,
-        memory_address: Expression<F>,
-        byte: Expression<F>,
+        word_address: Expression<F>,
+        value: WordLoHi<Expression<F>>,
+        value_prev: WordLoHi<Expression<F>>,
         call_id: Option<Expression<F>>,
     ) {
         self.rw_lookup(
@@ -1293,17 +1369,17 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             Target::Memory,
             RwValues::new(
                 call_id.unwrap_or_else(|| self.curr.state.call_id.expr()),
-                memory_address,
+                word_address,
                 0.expr(),
                 WordLoHi::zero(),
-                // TODO assure range check since write=true also possible
-                WordLoHi::from_lo_unchecked(byte),
-                WordLoHi::zero(),
+                value,
+                value_prev,
                 WordLoHi::zero(),
             ),
         );
     }
 
+    #[track_caller]
     pub(crate) fn tx_log_lookup(
         &mut self,
         tx_id: Expression<F>,
@@ -1329,6 +1405,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Tx Receipt
+    #[track_caller]
     pub(crate) fn tx_receipt_lookup(
         &mut self,
         is_write: Expression<F>,
@@ -1355,6 +1432,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
 
     // RwTable Padding (Start tag)
 
+    #[track_caller]
     pub(crate) fn rw_table_start_lookup(&mut self, counter: Expression<F>) {
         self.rw_lookup_with_counter(
             "Start lookup",
@@ -1376,6 +1454,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     // Copy Table
 
     #[allow(clippy::too_many_arguments)]
+    #[track_caller]
     pub(crate) fn copy_table_lookup(
         &mut self,
         src_id: WordLoHi<Expression<F>>,
@@ -1412,6 +1491,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     // Exponentiation Table
 
     #[allow(clippy::too_many_arguments)]
+    #[track_caller]
     pub(crate) fn exp_table_lookup(
         &mut self,
         identifier: Expression<F>,
@@ -1433,6 +1513,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     // Keccak Table
+    #[track_caller]
     pub(crate) fn keccak_table_lookup(
         &mut self,
         input_rlc: Expression<F>,
@@ -1573,12 +1654,14 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         }
     }
 
+    #[track_caller]
     pub(crate) fn add_lookup(&mut self, name: &str, lookup: Lookup<F>) {
         debug_assert_eq!(
             self.constraints_location,
             ConstraintLocation::Step,
             "lookup do not support conditional on constraint location other than `ConstraintLocation::Step`"
         );
+        self.add_coverage_point(ConstraintKind::Lookup, name, &lookup.input_exprs());
         let lookup = match self.condition_expr_opt() {
             Some(condition) => lookup.conditional(condition),
             None => lookup,
@@ -1701,4 +1784,20 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     pub fn debug_expression<S: Into<String>>(&mut self, name: S, expr: Expression<F>) {
         self.debug_expressions.push((name.into(), expr));
     }
+
+    /// Tags a constraint or lookup with the source line that added it for the constraint
+    /// coverage
+    #[track_caller]
+    fn add_coverage_point(&mut self, kind: ConstraintKind, name: &str, exprs: &[Expression<F>]) {
+        if self.collect_coverage {
+            self.coverage_points.push(CoveragePoint {
+                kind,
+                name: name.to_string(),
+                location: Location::caller(),
+                constraint_location: self.constraints_location,
+                condition: self.condition_expr_opt(),
+                exprs: exprs.to_vec(),
+            });
+        }
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
 };
 use eth_types::Field;
 use itertools::Itertools;
+use std::collections::HashMap;
 
 type StepSize = Vec<(CellType, ColumnSize)>;
 /// Contains (width, height, num_cells)
@@ -16,6 +17,8 @@ type ColumnSize = (usize, usize, usize);
 pub struct Instrument {
     // States -> Cell Types -> (width, height, num_cells)
     states: Vec<(ExecutionState, StepSize)>,
+    // Cell Types -> max number of columns holding cells in a state
+    columns_used: HashMap<CellType, usize>,
 }
 
 impl Instrument {
@@ -35,6 +38,18 @@ impl Instrument {
             .collect::<Vec<_>>();
 
         self.states.push((execution_state, sizes));
+
+        let strategy = cb.curr.cell_manager.strategy();
+        for (cell_type, _) in self.states.last().unwrap().1.iter() {
+            let columns_used = self.columns_used.entry(*cell_type).or_insert(0);
+            *columns_used = (*columns_used).max(strategy.columns_used(cell_type));
+        }
+    }
+
+    /// Returns the max number of columns holding cells in a state, per
+    /// `CellType`. The columns after them are left empty by every state.
+    pub(crate) fn columns_used(&self) -> &HashMap<CellType, usize> {
+        &self.columns_used
     }
 
     /// Dissasembles the instrumentation data and returns a collection of
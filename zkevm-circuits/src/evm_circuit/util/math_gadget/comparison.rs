@@ -1,5 +1,5 @@
 use crate::evm_circuit::util::{
-    constraint_builder::EVMConstraintBuilder, math_gadget::*, sum, CachedRegion,
+    constraint_builder::EVMConstraintBuilder, from_u16_limbs, math_gadget::*, sum, CachedRegion,
 };
 use eth_types::Field;
 use halo2_proofs::plonk::{Error, Expression};
@@ -22,7 +22,7 @@ impl<F: Field, const N_BYTES: usize> ComparisonGadget<F, N_BYTES> {
         rhs: Expression<F>,
     ) -> Self {
         let lt = LtGadget::<F, N_BYTES>::construct(cb, lhs, rhs);
-        let eq = IsZeroGadget::<F>::construct(cb, sum::expr(&lt.diff_bytes()));
+        let eq = IsZeroGadget::<F>::construct(cb, sum::expr(&lt.diff_limbs()));
 
         Self { lt, eq }
     }
@@ -42,7 +42,13 @@ impl<F: Field, const N_BYTES: usize> ComparisonGadget<F, N_BYTES> {
         let (lt, diff) = self.lt.assign(region, offset, lhs, rhs)?;
 
         // eq
-        let eq = self.eq.assign(region, offset, sum::value(&diff))?;
+        let eq = self.eq.assign(
+            region,
+            offset,
+            from_u16_limbs::values::<F>(&diff[..N_BYTES])
+                .into_iter()
+                .sum(),
+        )?;
 
         Ok((lt, eq))
     }
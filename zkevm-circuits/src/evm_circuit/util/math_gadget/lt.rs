@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::util::{
         constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-        from_bytes, pow_of_two, transpose_val_ret, CachedRegion, Cell,
+        from_u16_limbs, pow_of_two, transpose_val_ret, CachedRegion, Cell,
     },
     util::Expr,
 };
@@ -22,7 +22,7 @@ use halo2_proofs::{
 #[derive(Clone, Debug)]
 pub struct LtGadget<F, const N_BYTES: usize> {
     lt: Cell<F>, // `1` when `lhs < rhs`, `0` otherwise.
-    diff: [Cell<F>; N_BYTES], /* The byte values of `diff`.
+    diff: Vec<Cell<F>>, /* The u16 limbs of `diff`.
                   * `diff` equals `lhs - rhs` if `lhs >= rhs`,
                   * `lhs - rhs + range` otherwise. */
     range: F, // The range of the inputs, `256**N_BYTES`
@@ -35,14 +35,14 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
         rhs: Expression<F>,
     ) -> Self {
         let lt = cb.query_bool();
-        let diff = cb.query_bytes();
+        let diff = cb.query_u16_limbs(N_BYTES);
         let range = pow_of_two(N_BYTES * 8);
 
         // The equation we require to hold: `lhs - rhs == diff - (lt * range)`.
         cb.require_equal(
             "lhs - rhs == diff - (lt ⋅ range)",
             lhs - rhs,
-            from_bytes::expr(&diff) - (lt.expr() * range),
+            from_u16_limbs::expr(&diff) - (lt.expr() * range),
         );
 
         Self { lt, diff, range }
@@ -67,21 +67,21 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
             Value::known(if lt { F::ONE } else { F::ZERO }),
         )?;
 
-        // Set the bytes of diff
+        // Set the limbs of diff
         let diff = (lhs - rhs) + (if lt { self.range } else { F::ZERO });
         let diff_bytes = diff.to_repr();
-        for (idx, diff) in self.diff.iter().enumerate() {
-            diff.assign(
-                region,
-                offset,
-                Value::known(F::from(diff_bytes[idx] as u64)),
-            )?;
+        for (diff, limb) in self
+            .diff
+            .iter()
+            .zip(from_u16_limbs::values::<F>(&diff_bytes[..N_BYTES]))
+        {
+            diff.assign(region, offset, Value::known(limb))?;
         }
 
         Ok((if lt { F::ONE } else { F::ZERO }, diff_bytes.to_vec()))
     }
 
-    pub(crate) fn diff_bytes(&self) -> Vec<Cell<F>> {
+    pub(crate) fn diff_limbs(&self) -> Vec<Cell<F>> {
         self.diff.to_vec()
     }
 
@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::util::{
         constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-        from_bytes, pow_of_two_expr, split_u256, split_u256_limb64, CachedRegion, Cell,
+        from_u16_limbs, pow_of_two_expr, split_u256, split_u256_limb64, CachedRegion, Cell,
     },
     util::{
         word::{Word32Cell, Word4, WordExpr, WordLoHi},
@@ -46,18 +46,18 @@ use halo2_proofs::{
 /// MulAddWordsGadget.
 #[derive(Clone, Debug)]
 pub(crate) struct MulAddWordsGadget<F> {
-    carry_lo: [Cell<F>; 9],
-    carry_hi: [Cell<F>; 9],
+    carry_lo: Vec<Cell<F>>,
+    carry_hi: Vec<Cell<F>>,
     overflow: Expression<F>,
 }
 
 impl<F: Field> MulAddWordsGadget<F> {
     pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, words: [&Word32Cell<F>; 4]) -> Self {
         let (a, b, c, d) = (words[0], words[1], words[2], words[3]);
-        let carry_lo = cb.query_bytes();
-        let carry_hi = cb.query_bytes();
-        let carry_lo_expr = from_bytes::expr(&carry_lo);
-        let carry_hi_expr = from_bytes::expr(&carry_hi);
+        let carry_lo = cb.query_u16_limbs(9);
+        let carry_hi = cb.query_u16_limbs(9);
+        let carry_lo_expr = from_u16_limbs::expr(&carry_lo);
+        let carry_hi_expr = from_u16_limbs::expr(&carry_hi);
 
         let mut a_limbs = vec![];
         let mut b_limbs = vec![];
@@ -132,14 +132,14 @@ impl<F: Field> MulAddWordsGadget<F> {
 
         self.carry_lo
             .iter()
-            .zip(carry_lo.to_le_bytes().iter())
-            .map(|(cell, byte)| cell.assign(region, offset, Value::known(F::from(*byte as u64))))
+            .zip(from_u16_limbs::values::<F>(&carry_lo.to_le_bytes()[..9]))
+            .map(|(cell, limb)| cell.assign(region, offset, Value::known(limb)))
             .collect::<Result<Vec<_>, _>>()?;
 
         self.carry_hi
             .iter()
-            .zip(carry_hi.to_le_bytes().iter())
-            .map(|(cell, byte)| cell.assign(region, offset, Value::known(F::from(*byte as u64))))
+            .zip(from_u16_limbs::values::<F>(&carry_hi.to_le_bytes()[..9]))
+            .map(|(cell, limb)| cell.assign(region, offset, Value::known(limb)))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(())
@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::util::{
         constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-        from_bytes, pow_of_two_expr, split_u256, split_u256_limb64, CachedRegion, Cell,
+        from_u16_limbs, pow_of_two_expr, split_u256, split_u256_limb64, CachedRegion, Cell,
     },
     util::{
         word::{Word32Cell, Word4, WordExpr},
@@ -47,9 +47,9 @@ use halo2_proofs::{
 ///   t6 + carry_2 = d_hi
 #[derive(Clone, Debug)]
 pub(crate) struct MulAddWords512Gadget<F> {
-    carry_0: [Cell<F>; 9],
-    carry_1: [Cell<F>; 9],
-    carry_2: [Cell<F>; 9],
+    carry_0: Vec<Cell<F>>,
+    carry_1: Vec<Cell<F>>,
+    carry_2: Vec<Cell<F>>,
 }
 
 impl<F: Field> MulAddWords512Gadget<F> {
@@ -60,12 +60,12 @@ impl<F: Field> MulAddWords512Gadget<F> {
         words: [&Word32Cell<F>; 4],
         addend: Option<&Word32Cell<F>>,
     ) -> Self {
-        let carry_0 = cb.query_bytes();
-        let carry_1 = cb.query_bytes();
-        let carry_2 = cb.query_bytes();
-        let carry_0_expr = from_bytes::expr(&carry_0);
-        let carry_1_expr = from_bytes::expr(&carry_1);
-        let carry_2_expr = from_bytes::expr(&carry_2);
+        let carry_0 = cb.query_u16_limbs(9);
+        let carry_1 = cb.query_u16_limbs(9);
+        let carry_2 = cb.query_u16_limbs(9);
+        let carry_0_expr = from_u16_limbs::expr(&carry_0);
+        let carry_1_expr = from_u16_limbs::expr(&carry_1);
+        let carry_2_expr = from_u16_limbs::expr(&carry_2);
 
         // Split input words in limbs
         let mut a_limbs = vec![];
@@ -178,20 +178,20 @@ impl<F: Field> MulAddWords512Gadget<F> {
 
         self.carry_0
             .iter()
-            .zip(carry_0.to_le_bytes().iter())
-            .map(|(cell, byte)| cell.assign(region, offset, Value::known(F::from(*byte as u64))))
+            .zip(from_u16_limbs::values::<F>(&carry_0.to_le_bytes()[..9]))
+            .map(|(cell, limb)| cell.assign(region, offset, Value::known(limb)))
             .collect::<Result<Vec<_>, _>>()?;
 
         self.carry_1
             .iter()
-            .zip(carry_1.to_le_bytes().iter())
-            .map(|(cell, byte)| cell.assign(region, offset, Value::known(F::from(*byte as u64))))
+            .zip(from_u16_limbs::values::<F>(&carry_1.to_le_bytes()[..9]))
+            .map(|(cell, limb)| cell.assign(region, offset, Value::known(limb)))
             .collect::<Result<Vec<_>, _>>()?;
 
         self.carry_2
             .iter()
-            .zip(carry_2.to_le_bytes().iter())
-            .map(|(cell, byte)| cell.assign(region, offset, Value::known(F::from(*byte as u64))))
+            .zip(from_u16_limbs::values::<F>(&carry_2.to_le_bytes()[..9]))
+            .map(|(cell, limb)| cell.assign(region, offset, Value::known(limb)))
             .collect::<Result<Vec<_>, _>>()?;
         Ok(())
     }
@@ -2,7 +2,7 @@ use crate::{
     evm_circuit::util::{
         self,
         constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-        from_bytes, pow_of_two_expr, split_u256, CachedRegion,
+        from_u16_limbs, pow_of_two_expr, split_u256, CachedRegion,
     },
     util::{
         word::{Word32Cell, WordExpr},
@@ -21,7 +21,7 @@ use halo2_proofs::{
 pub(crate) struct MulWordByU64Gadget<F> {
     multiplicand: Word32Cell<F>,
     product: Word32Cell<F>,
-    carry_lo: [util::Cell<F>; 8],
+    carry_lo: Vec<util::Cell<F>>,
 }
 
 impl<F: Field> MulWordByU64Gadget<F> {
@@ -33,12 +33,12 @@ impl<F: Field> MulWordByU64Gadget<F> {
         let gadget = Self {
             multiplicand,
             product: cb.query_word32(),
-            carry_lo: cb.query_bytes(),
+            carry_lo: cb.query_u16_limbs(8),
         };
         let (multiplicand_lo, multiplicand_hi) = gadget.multiplicand.to_word().to_lo_hi();
         let (product_lo, product_hi) = gadget.product.to_word().to_lo_hi();
 
-        let carry_lo = from_bytes::expr(&gadget.carry_lo[..8]);
+        let carry_lo = from_u16_limbs::expr(&gadget.carry_lo);
 
         cb.require_equal(
             "multiplicand_lo ⋅ multiplier == carry_lo ⋅ 2^128 + product_lo",
@@ -71,13 +71,12 @@ impl<F: Field> MulWordByU64Gadget<F> {
         let (product_lo, _) = split_u256(&product);
 
         let carry_lo = (multiplicand_lo * multiplier - product_lo) >> 128;
-        for (cell, byte) in self.carry_lo.iter().zip(
-            u64::try_from(carry_lo)
+        for (cell, limb) in self.carry_lo.iter().zip(from_u16_limbs::values::<F>(
+            &u64::try_from(carry_lo)
                 .map_err(|_| Error::Synthesis)?
-                .to_le_bytes()
-                .iter(),
-        ) {
-            cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+                .to_le_bytes(),
+        )) {
+            cell.assign(region, offset, Value::known(limb))?;
         }
 
         Ok(())
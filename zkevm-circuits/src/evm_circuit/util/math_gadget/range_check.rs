@@ -1,6 +1,6 @@
 use crate::evm_circuit::util::{
     constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-    from_bytes, CachedRegion, Cell,
+    from_u16_limbs, CachedRegion, Cell,
 };
 use eth_types::Field;
 use halo2_proofs::{
@@ -12,19 +12,19 @@ use halo2_proofs::{
 /// `N_BYTES` is required to be `<= MAX_N_BYTES_INTEGER`.
 #[derive(Clone, Debug)]
 pub struct RangeCheckGadget<F, const N_BYTES: usize> {
-    parts: [Cell<F>; N_BYTES],
+    parts: Vec<Cell<F>>,
 }
 
 impl<F: Field, const N_BYTES: usize> RangeCheckGadget<F, N_BYTES> {
     pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, value: Expression<F>) -> Self {
-        let parts = cb.query_bytes();
+        let parts = cb.query_u16_limbs(N_BYTES);
 
         // Require that the reconstructed value from the parts equals the
         // original value
         cb.require_equal(
-            "Constrain bytes recomposited to value",
+            "Constrain limbs recomposited to value",
             value,
-            from_bytes::expr(&parts),
+            from_u16_limbs::expr(&parts),
         );
 
         Self { parts }
@@ -37,8 +37,12 @@ impl<F: Field, const N_BYTES: usize> RangeCheckGadget<F, N_BYTES> {
         value: F,
     ) -> Result<(), Error> {
         let bytes = value.to_repr();
-        for (idx, part) in self.parts.iter().enumerate() {
-            part.assign(region, offset, Value::known(F::from(bytes[idx] as u64)))?;
+        for (part, limb) in self
+            .parts
+            .iter()
+            .zip(from_u16_limbs::values::<F>(&bytes[..N_BYTES]))
+        {
+            part.assign(region, offset, Value::known(limb))?;
         }
         Ok(())
     }
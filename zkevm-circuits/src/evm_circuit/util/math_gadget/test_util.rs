@@ -5,13 +5,10 @@ use strum::IntoEnumIterator;
 
 use crate::{
     evm_circuit::{
-        param::{MAX_STEP_HEIGHT, N_PHASE2_COLUMNS, STEP_WIDTH},
+        param::{EvmColumnLayout, MAX_STEP_HEIGHT},
         step::{ExecutionState, Step},
         table::{FixedTableTag, Table},
-        util::{
-            constraint_builder::EVMConstraintBuilder, rlc, CachedRegion, StoredExpression,
-            LOOKUP_CONFIG,
-        },
+        util::{constraint_builder::EVMConstraintBuilder, rlc, CachedRegion, StoredExpression},
         Advice, Column, Fixed,
     },
     table::LookupTable,
@@ -58,7 +55,7 @@ where
 {
     q_usable: Selector,
     fixed_table: [Column<Fixed>; 4],
-    advices: [Column<Advice>; STEP_WIDTH],
+    advices: Vec<Column<Advice>>,
     step: Step<F>,
     stored_expressions: Vec<StoredExpression<F>>,
     math_gadget_container: G,
@@ -98,25 +95,22 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
         let q_usable = meta.selector();
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
 
-        let lookup_column_count: usize = LOOKUP_CONFIG.iter().map(|(_, count)| *count).sum();
-        let advices = [(); STEP_WIDTH]
-            .iter()
-            .enumerate()
-            .map(|(n, _)| {
+        let layout = EvmColumnLayout::default();
+        let lookup_column_count = layout.lookup_columns();
+        let advices = (0..layout.step_width)
+            .map(|n| {
                 if n < lookup_column_count {
                     meta.advice_column_in(ThirdPhase)
-                } else if n < lookup_column_count + N_PHASE2_COLUMNS {
+                } else if n < lookup_column_count + layout.phase2_columns {
                     meta.advice_column_in(SecondPhase)
                 } else {
                     meta.advice_column_in(FirstPhase)
                 }
             })
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
+            .collect::<Vec<_>>();
 
-        let step_curr = Step::new(meta, advices, 0);
-        let step_next = Step::new(meta, advices, MAX_STEP_HEIGHT);
+        let step_curr = Step::new(meta, &advices, 0, &layout);
+        let step_next = Step::new(meta, &advices, MAX_STEP_HEIGHT, &layout);
         let mut cb = EVMConstraintBuilder::new(
             meta,
             step_curr.clone(),
@@ -224,7 +218,7 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
                                         | FixedTableTag::Range1024
                                 )
                             })
-                            .flat_map(|tag| tag.build()),
+                            .flat_map(|tag| tag.build(FeatureConfig::default().hardfork)),
                     )
                     .enumerate()
                 {
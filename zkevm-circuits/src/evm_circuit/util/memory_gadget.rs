@@ -1,11 +1,14 @@
 use super::{constraint_builder::ConstrainBuilderCommon, CachedRegion, MemoryAddress, WordExpr};
 use crate::{
     evm_circuit::{
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64},
+        param::{
+            N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
+        },
         util::{
             and,
             constraint_builder::EVMConstraintBuilder,
-            from_bytes,
+            from_bytes, from_u16_limbs,
             math_gadget::{
                 AddWordsGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
                 RangeCheckGadget,
@@ -14,14 +17,14 @@ use crate::{
         },
     },
     util::{
-        word::{WordLoHi, WordLoHiCell},
+        word::{Word32, WordExpr, WordLoHi, WordLoHiCell},
         Expr,
     },
 };
 use array_init::array_init;
 use eth_types::{
     evm_types::{GasCost, MAX_EXPANDED_MEMORY_ADDRESS},
-    Field, ToLittleEndian, U256,
+    Field, ToBigEndian, ToLittleEndian, Word, U256,
 };
 use gadgets::util::not;
 use halo2_proofs::{
@@ -69,11 +72,12 @@ pub(crate) trait CommonMemoryAddressGadget<F: Field> {
 
 /// Convert the dynamic memory offset and length from random linear combination
 /// to integer. It handles the "no expansion" feature by setting the
-/// `memory_offset_bytes` to zero when `memory_length` is zero. In this case,
-/// the RLC value for `memory_offset` need not match the bytes.
+/// `memory_offset_limbs` to zero when `memory_length` is zero. In this case,
+/// the RLC value for `memory_offset` need not match the limbs.
 #[derive(Clone, Debug)]
 pub(crate) struct MemoryAddressGadget<F> {
-    memory_offset_bytes: MemoryAddress<F>,
+    /// The u16 limbs of the offset, see `from_u16_limbs`.
+    memory_offset_limbs: Vec<Cell<F>>,
     memory_offset: WordLoHiCell<F>,
     memory_length: MemoryAddress<F>,
     memory_length_is_zero: IsZeroGadget<F>,
@@ -86,19 +90,19 @@ impl<F: Field> MemoryAddressGadget<F> {
         memory_length: MemoryAddress<F>,
     ) -> Self {
         let memory_length_is_zero = IsZeroGadget::construct(cb, memory_length.sum_expr());
-        let memory_offset_bytes = cb.query_memory_address();
+        let memory_offset_limbs = cb.query_u16_limbs(N_BYTES_MEMORY_ADDRESS);
 
         let has_length = 1.expr() - memory_length_is_zero.expr();
         cb.condition(has_length, |cb| {
             cb.require_equal_word(
                 "Offset decomposition into 5 bytes",
-                WordLoHi::from_lo_unchecked(memory_offset_bytes.expr()),
+                WordLoHi::from_lo_unchecked(from_u16_limbs::expr(&memory_offset_limbs)),
                 memory_offset.to_word(),
             );
         });
 
         Self {
-            memory_offset_bytes,
+            memory_offset_limbs,
             memory_offset,
             memory_length,
             memory_length_is_zero,
@@ -112,7 +116,7 @@ impl<F: Field> MemoryAddressGadget<F> {
     // offset is the valid offset. It might not equal the offset pop from stack if
     // `self.has_length()` is zero
     pub(crate) fn offset(&self) -> Expression<F> {
-        self.has_length() * self.memory_offset_bytes.expr()
+        self.has_length() * from_u16_limbs::expr(&self.memory_offset_limbs)
     }
 }
 
@@ -133,17 +137,14 @@ impl<F: Field> CommonMemoryAddressGadget<F> for MemoryAddressGadget<F> {
         let memory_offset_bytes = memory_offset.to_le_bytes();
         let memory_length_bytes = memory_length.to_le_bytes();
         let memory_length_is_zero = memory_length.is_zero();
-        self.memory_offset_bytes.assign(
-            region,
-            offset,
-            if memory_length_is_zero {
-                Some([0; 5])
-            } else {
-                memory_offset_bytes[..N_BYTES_MEMORY_ADDRESS]
-                    .try_into()
-                    .ok()
-            },
-        )?;
+        let memory_offset_limbs = from_u16_limbs::values::<F>(if memory_length_is_zero {
+            &[0; N_BYTES_MEMORY_ADDRESS][..]
+        } else {
+            &memory_offset_bytes[..N_BYTES_MEMORY_ADDRESS]
+        });
+        for (cell, limb) in self.memory_offset_limbs.iter().zip(memory_offset_limbs) {
+            cell.assign(region, offset, Value::known(limb))?;
+        }
         self.memory_offset
             .assign_u256(region, offset, memory_offset)?;
         self.memory_length
@@ -687,6 +688,165 @@ impl<F: Field, const MAX_BYTES: usize, const ADDR_SIZE_IN_BYTES: usize>
     }
 }
 
+/// Memory words gadget relates the bytes at a memory address to the `N_WORDS`
+/// 32-byte aligned words holding them, which are the values of the memory
+/// lookups.
+#[derive(Clone, Debug)]
+pub(crate) struct MemoryWordsGadget<F, const N_WORDS: usize> {
+    /// One-hot encoding of the offset of the address in its word.
+    shift_bits: [Cell<F>; N_BYTES_WORD],
+    /// The bytes of the words.
+    bytes: [[Cell<F>; N_BYTES_WORD]; N_WORDS],
+    /// The bytes of the words before they are updated.
+    bytes_prev: Option<[[Cell<F>; N_BYTES_WORD]; N_WORDS]>,
+    /// The address of the first word.
+    word_address: Expression<F>,
+}
+
+impl<F: Field, const N_WORDS: usize> MemoryWordsGadget<F, N_WORDS> {
+    /// Read the words holding the bytes at `address`.
+    pub(crate) fn construct_read(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>) -> Self {
+        Self::construct(cb, address, false)
+    }
+
+    /// Update the words holding the bytes at `address`, see
+    /// [`Self::require_update`].
+    pub(crate) fn construct_update(
+        cb: &mut EVMConstraintBuilder<F>,
+        address: Expression<F>,
+    ) -> Self {
+        Self::construct(cb, address, true)
+    }
+
+    fn construct(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>, update: bool) -> Self {
+        let shift_bits = array_init(|_| cb.query_bool());
+        cb.require_equal(
+            "exactly one shift bit is set",
+            sum::expr(&shift_bits),
+            1.expr(),
+        );
+        let bytes = array_init(|_| cb.query_bytes());
+        let bytes_prev = update.then(|| array_init(|_| cb.query_bytes()));
+
+        // The memory lookups only accept 32-byte aligned word addresses, which leaves a single
+        // valid shift.
+        let shift = sum::expr(
+            shift_bits
+                .iter()
+                .enumerate()
+                .map(|(index, bit)| bit.expr() * index.expr()),
+        );
+
+        Self {
+            shift_bits,
+            bytes,
+            bytes_prev,
+            word_address: address - shift,
+        }
+    }
+
+    /// The address of the word at `index`.
+    pub(crate) fn word_address(&self, index: usize) -> Expression<F> {
+        self.word_address.clone() + (index * N_BYTES_WORD).expr()
+    }
+
+    /// The word at `index`.
+    pub(crate) fn word(&self, index: usize) -> WordLoHi<Expression<F>> {
+        Self::to_word(&self.bytes[index])
+    }
+
+    /// The word at `index` before the update, which is the word itself for a
+    /// read.
+    pub(crate) fn word_prev(&self, index: usize) -> WordLoHi<Expression<F>> {
+        Self::to_word(&self.bytes_prev.as_ref().unwrap_or(&self.bytes)[index])
+    }
+
+    /// The byte at `address + index`.
+    pub(crate) fn byte(&self, index: usize) -> Expression<F> {
+        sum::expr(
+            self.shift_bits
+                .iter()
+                .enumerate()
+                .filter_map(|(shift, bit)| {
+                    let position = shift + index;
+                    (position < N_WORDS * N_BYTES_WORD).then(|| {
+                        bit.expr()
+                            * self.bytes[position / N_BYTES_WORD][position % N_BYTES_WORD].expr()
+                    })
+                }),
+        )
+    }
+
+    /// Require the bytes at `address` to be updated to `values`, and the
+    /// other bytes of the words to be unchanged.
+    pub(crate) fn require_update(
+        &self,
+        cb: &mut EVMConstraintBuilder<F>,
+        values: &[Expression<F>],
+    ) {
+        let bytes_prev = self
+            .bytes_prev
+            .as_ref()
+            .expect("MemoryWordsGadget is not constructed for an update");
+        for position in 0..N_WORDS * N_BYTES_WORD {
+            let byte = self.bytes[position / N_BYTES_WORD][position % N_BYTES_WORD].expr();
+            let byte_prev = bytes_prev[position / N_BYTES_WORD][position % N_BYTES_WORD].expr();
+            // The byte changes to the value written at its position, if any.
+            let delta = sum::expr(
+                self.shift_bits
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(shift, bit)| {
+                        position
+                            .checked_sub(shift)
+                            .and_then(|index| values.get(index))
+                            .map(|value| bit.expr() * (value.clone() - byte_prev.clone()))
+                    }),
+            );
+            cb.require_equal(
+                "memory word byte is updated at the address only",
+                byte - byte_prev,
+                delta,
+            );
+        }
+    }
+
+    fn to_word(bytes: &[Cell<F>; N_BYTES_WORD]) -> WordLoHi<Expression<F>> {
+        // Word32 takes little-endian bytes.
+        Word32::new(array_init(|index| bytes[N_BYTES_WORD - 1 - index].expr())).to_word()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        address: u64,
+        words: [Word; N_WORDS],
+        words_prev: [Word; N_WORDS],
+    ) -> Result<(), Error> {
+        let shift = (address % N_BYTES_WORD as u64) as usize;
+        for (index, bit) in self.shift_bits.iter().enumerate() {
+            bit.assign(
+                region,
+                offset,
+                Value::known(F::from((index == shift) as u64)),
+            )?;
+        }
+        let mut cells = vec![(&self.bytes, words)];
+        if let Some(bytes_prev) = &self.bytes_prev {
+            cells.push((bytes_prev, words_prev));
+        }
+        for (bytes, words) in cells {
+            for (word_bytes, word) in bytes.iter().zip(words) {
+                for (byte, value) in word_bytes.iter().zip(word.to_be_bytes()) {
+                    byte.assign(region, offset, Value::known(F::from(value as u64)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test_util;
 
@@ -2,11 +2,9 @@ use std::marker::PhantomData;
 
 use crate::{
     evm_circuit::{
-        param::{MAX_STEP_HEIGHT, N_PHASE2_COLUMNS, STEP_WIDTH},
+        param::{EvmColumnLayout, MAX_STEP_HEIGHT},
         step::{ExecutionState, Step},
-        util::{
-            constraint_builder::EVMConstraintBuilder, CachedRegion, StoredExpression, LOOKUP_CONFIG,
-        },
+        util::{constraint_builder::EVMConstraintBuilder, CachedRegion, StoredExpression},
         Advice, Column,
     },
     util::Challenges,
@@ -38,7 +36,7 @@ where
     G: MemoryGadgetContainer<F>,
 {
     q_usable: Selector,
-    advices: [Column<Advice>; STEP_WIDTH],
+    advices: Vec<Column<Advice>>,
     step: Step<F>,
     stored_expressions: Vec<StoredExpression<F>>,
     memory_gadget_container: G,
@@ -77,25 +75,22 @@ impl<F: Field, G: MemoryGadgetContainer<F>> Circuit<F> for UnitTestMemoryGadgetB
 
         let q_usable = meta.selector();
 
-        let lookup_column_count: usize = LOOKUP_CONFIG.iter().map(|(_, count)| *count).sum();
-        let advices = [(); STEP_WIDTH]
-            .iter()
-            .enumerate()
-            .map(|(n, _)| {
+        let layout = EvmColumnLayout::default();
+        let lookup_column_count = layout.lookup_columns();
+        let advices = (0..layout.step_width)
+            .map(|n| {
                 if n < lookup_column_count {
                     meta.advice_column_in(ThirdPhase)
-                } else if n < lookup_column_count + N_PHASE2_COLUMNS {
+                } else if n < lookup_column_count + layout.phase2_columns {
                     meta.advice_column_in(SecondPhase)
                 } else {
                     meta.advice_column_in(FirstPhase)
                 }
             })
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
+            .collect::<Vec<_>>();
 
-        let step_curr = Step::new(meta, advices, 0);
-        let step_next = Step::new(meta, advices, MAX_STEP_HEIGHT);
+        let step_curr = Step::new(meta, &advices, 0, &layout);
+        let step_next = Step::new(meta, &advices, MAX_STEP_HEIGHT, &layout);
         let mut cb = EVMConstraintBuilder::new(
             meta,
             step_curr.clone(),
@@ -210,6 +210,8 @@ pub(crate) struct TxDataGadget<F> {
 
     pub(crate) gas_mul_gas_price_plus_value: Option<AddWordsGadget<F, 2, false>>,
     pub(crate) cost_sum: Option<Word32Cell<F>>,
+
+    init_code_word_gas: u64,
 }
 
 impl<F: Field> TxDataGadget<F> {
@@ -269,6 +271,7 @@ impl<F: Field> TxDataGadget<F> {
             callee_address,
             gas_mul_gas_price_plus_value,
             cost_sum,
+            init_code_word_gas: cb.feature_config.hardfork.init_code_word_gas(),
         }
     }
 
@@ -276,8 +279,7 @@ impl<F: Field> TxDataGadget<F> {
         // Calculate gas cost of init code for EIP-3860.
         let init_code_gas_cost = select::expr(
             self.is_create.expr(),
-            self.call_data_word_length.quotient().expr()
-                * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
+            self.call_data_word_length.quotient().expr() * self.init_code_word_gas.expr(),
             0.expr(),
         );
 
@@ -1,10 +1,16 @@
 //! The instance definition.
 
 use bus_mapping::circuit_input_builder::Withdrawal;
-use eth_types::{geth_types::BlockConstants, BigEndianHash, Field, Keccak};
+use eth_types::{
+    geth_types::BlockConstants,
+    keccak256,
+    trie::{ordered_trie_nodes, ordered_trie_root},
+    BigEndianHash, Field, Keccak,
+};
+use ethers_core::{types::Bloom, utils::rlp::RlpStream};
 use std::{iter, ops::Deref};
 
-use eth_types::{geth_types::Transaction, Address, ToBigEndian, Word, H256};
+use eth_types::{geth_types::Transaction, Address, Bytes, ToBigEndian, Word, H256, H64, U64};
 use itertools::Itertools;
 
 use crate::{util::word::WordLoHi, witness::Block};
@@ -69,6 +75,44 @@ pub struct ExtraValues {
     pub state_root: H256,
     /// prev_state_root
     pub prev_state_root: H256,
+    /// transactions_root
+    pub transactions_root: H256,
+}
+
+/// Block header fields that are not part of the block table but are needed to
+/// reconstruct the block hash.
+#[derive(Clone, Default, Debug)]
+pub struct HeaderValues {
+    /// parent_hash
+    pub parent_hash: H256,
+    /// ommers_hash
+    pub ommers_hash: H256,
+    /// receipts_root
+    pub receipts_root: H256,
+    /// logs_bloom
+    pub logs_bloom: Bloom,
+    /// difficulty as found in the header (zero after the merge, where the
+    /// block table difficulty holds the PREVRANDAO value instead)
+    pub difficulty: Word,
+    /// gas_used
+    pub gas_used: Word,
+    /// extra_data
+    pub extra_data: Bytes,
+    /// mix_hash
+    pub mix_hash: H256,
+    /// nonce
+    pub nonce: H64,
+    /// Whether the header contains the withdrawals root (Shanghai onwards)
+    pub has_withdrawals_root: bool,
+    /// blob_gas_used
+    pub blob_gas_used: u64,
+    /// excess_blob_gas
+    pub excess_blob_gas: u64,
+    /// parent_beacon_block_root
+    pub parent_beacon_block_root: H256,
+    /// Whether the header contains the blob gas used, the excess blob gas and
+    /// the parent beacon block root (Cancun onwards, EIP-4844 and EIP-4788)
+    pub has_blob_fields: bool,
 }
 
 /// PublicData contains all the values that the PiCircuit receives as input
@@ -89,10 +133,14 @@ pub struct PublicData {
     pub prev_state_root: H256,
     /// Constants related to Ethereum block
     pub block_constants: BlockConstants,
-    /// Block Hash
+    /// Block Hash as reported by the node. The hash committed in the public
+    /// inputs is recomputed from the header fields instead, see
+    /// [`PublicData::get_block_hash`].
     pub block_hash: Option<H256>,
     /// withdrawals_root
     pub withdrawals_root: H256,
+    /// Remaining block header fields
+    pub header_values: HeaderValues,
 }
 
 impl Default for PublicData {
@@ -107,6 +155,7 @@ impl Default for PublicData {
             block_constants: BlockConstants::default(),
             block_hash: None,
             withdrawals_root: H256::zero(),
+            header_values: HeaderValues::default(),
         }
     }
 }
@@ -171,10 +220,62 @@ impl PublicData {
     /// Returns struct with the extra values
     pub fn get_extra_values(&self) -> ExtraValues {
         ExtraValues {
-            block_hash: self.block_hash.unwrap_or_else(H256::zero),
+            block_hash: self.get_block_hash(),
             state_root: self.state_root,
             prev_state_root: self.prev_state_root,
+            transactions_root: self.get_transactions_root(),
+        }
+    }
+
+    /// Returns the root of the transactions trie, built from the RLP encoding
+    /// of each signed transaction keyed by its index in the block.
+    pub fn get_transactions_root(&self) -> H256 {
+        ordered_trie_root(
+            self.transactions
+                .iter()
+                .map(|tx| tx.rlp_signed(self.chain_id.as_u64())),
+        )
+    }
+
+    /// Returns the RLP encoding of the block header, with the transactions root
+    /// derived from the block transactions.
+    pub fn get_block_header_rlp(&self) -> Vec<u8> {
+        let header = &self.header_values;
+        let mut stream = RlpStream::new();
+        stream.begin_list(
+            16 + usize::from(header.has_withdrawals_root) + 3 * usize::from(header.has_blob_fields),
+        );
+        stream.append(&header.parent_hash);
+        stream.append(&header.ommers_hash);
+        stream.append(&self.block_constants.coinbase);
+        stream.append(&self.state_root);
+        stream.append(&self.get_transactions_root());
+        stream.append(&header.receipts_root);
+        stream.append(&header.logs_bloom);
+        stream.append(&header.difficulty);
+        stream.append(&self.block_constants.number);
+        stream.append(&self.block_constants.gas_limit);
+        stream.append(&header.gas_used);
+        stream.append(&self.block_constants.timestamp);
+        stream.append(&header.extra_data.to_vec());
+        stream.append(&header.mix_hash);
+        stream.append(&header.nonce);
+        stream.append(&self.block_constants.base_fee);
+        if header.has_withdrawals_root {
+            stream.append(&self.withdrawals_root);
+        }
+        if header.has_blob_fields {
+            stream.append(&header.blob_gas_used);
+            stream.append(&header.excess_blob_gas);
+            stream.append(&header.parent_beacon_block_root);
         }
+        stream.out().to_vec()
+    }
+
+    /// Returns the block hash, computed as the keccak hash of the RLP encoded
+    /// block header.
+    pub fn get_block_hash(&self) -> H256 {
+        H256(keccak256(&self.get_block_header_rlp()))
     }
 
     /// get the serialized public data bytes
@@ -208,7 +309,8 @@ impl PublicData {
         let result = result
             .chain(extra_vals.block_hash.to_fixed_bytes()) // block hash
             .chain(extra_vals.state_root.to_fixed_bytes()) // block state root
-            .chain(extra_vals.prev_state_root.to_fixed_bytes()); // previous block state root
+            .chain(extra_vals.prev_state_root.to_fixed_bytes()) // previous block state root
+            .chain(extra_vals.transactions_root.to_fixed_bytes()); // transactions root
 
         // Assign Tx table
         let tx_field_byte_fn = |tx_id: u64, index: u64, value_bytes: &[u8]| {
@@ -283,6 +385,31 @@ impl PublicData {
         result.chain(all_wd_bytes).collect_vec()
     }
 
+    /// Returns the inputs hashed by the PiCircuit: the public data bytes, the
+    /// block header and the encoded nodes of the transactions trie.
+    pub fn get_keccak_inputs(
+        &self,
+        max_txs: usize,
+        max_withdrawals: usize,
+        max_calldata: usize,
+    ) -> Vec<Vec<u8>> {
+        [
+            self.get_pi_bytes(max_txs, max_withdrawals, max_calldata),
+            self.get_block_header_rlp(),
+        ]
+        .into_iter()
+        .chain(
+            ordered_trie_nodes(
+                self.transactions
+                    .iter()
+                    .map(|tx| tx.rlp_signed(self.chain_id.as_u64())),
+            )
+            .into_iter()
+            .map(|node| node.encoded),
+        )
+        .collect()
+    }
+
     /// generate public data from validator perspective
     pub fn get_rpi_digest_word<F: Field>(
         &self,
@@ -299,6 +426,22 @@ impl PublicData {
 
 /// convert witness block to public data
 pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
+    // The Cancun header fields are not part of the ethers block type, which
+    // keeps them among its other fields.
+    let other = &block.eth_block.other;
+    let blob_gas_used = other.get_deserialized::<U64>("blobGasUsed");
+    let excess_blob_gas = other.get_deserialized::<U64>("excessBlobGas");
+    let parent_beacon_block_root = other.get_deserialized::<H256>("parentBeaconBlockRoot");
+    let blob_fields = match (blob_gas_used, excess_blob_gas, parent_beacon_block_root) {
+        (
+            Some(Ok(blob_gas_used)),
+            Some(Ok(excess_blob_gas)),
+            Some(Ok(parent_beacon_block_root)),
+        ) => Some((blob_gas_used, excess_blob_gas, parent_beacon_block_root)),
+        _ => None,
+    };
+    let (blob_gas_used, excess_blob_gas, parent_beacon_block_root) =
+        blob_fields.unwrap_or_default();
     PublicData {
         chain_id: block.context.chain_id,
         history_hashes: block.context.history_hashes.clone(),
@@ -316,5 +459,21 @@ pub fn public_data_convert<F: Field>(block: &Block<F>) -> PublicData {
             base_fee: block.context.base_fee,
         },
         withdrawals_root: block.withdrawals_root(),
+        header_values: HeaderValues {
+            parent_hash: block.eth_block.parent_hash,
+            ommers_hash: block.eth_block.uncles_hash,
+            receipts_root: block.eth_block.receipts_root,
+            logs_bloom: block.eth_block.logs_bloom.unwrap_or_default(),
+            difficulty: block.eth_block.difficulty,
+            gas_used: block.eth_block.gas_used,
+            extra_data: block.eth_block.extra_data.clone(),
+            mix_hash: block.eth_block.mix_hash.unwrap_or_default(),
+            nonce: block.eth_block.nonce.unwrap_or_default(),
+            has_withdrawals_root: block.eth_block.withdrawals_root.is_some(),
+            blob_gas_used: blob_gas_used.as_u64(),
+            excess_blob_gas: excess_blob_gas.as_u64(),
+            parent_beacon_block_root,
+            has_blob_fields: blob_fields.is_some(),
+        },
     }
 }
@@ -1061,6 +1061,15 @@ impl<F: Field> KeccakCircuit<F> {
         }
     }
 
+    /// Return the number of rows giving a capacity large enough to hash the
+    /// `inputs`, to be used as `max_keccak_rows`.
+    pub fn min_num_rows_padded(inputs: &[Vec<u8>]) -> usize {
+        // Each input is padded to a whole number of keccak_f's
+        let num_keccak_f: usize = inputs.iter().map(|bytes| bytes.len() / RATE + 1).sum();
+        // Add two for unusable rows
+        (num_keccak_f + 2) * (NUM_ROUNDS + 1) * get_num_rows_per_round()
+    }
+
     /// Sets the witness using the data to be hashed
     pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
         multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
@@ -0,0 +1,496 @@
+//! On-disk store for KZG parameters and proving/verifying keys, so that
+//! provers don't need to run the setup and keygen of a circuit on every start.
+//!
+//! Params from a trusted setup are stored as `params_k{degree}.bin`. Params
+//! from the insecure setup used for testing are only generated on request and
+//! stored as `params_k{degree}.insecure.bin`, so they are never mistaken for
+//! trusted ones.
+//!
+//! Keys are stored next to a small JSON record of the configuration that
+//! produced them (circuit name, degree and circuit parameters), of the digest
+//! of the params they were generated with and of the digest of their verifying
+//! key, and looked up by the configuration. A loaded proving key is checked
+//! against its record, so a stale or corrupted key, or a key generated with
+//! other params, is an error rather than a proof with keys of a different
+//! circuit or setup, without running the keygen of the circuit.
+
+use eth_types::keccak256;
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Circuit, ProvingKey, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    SerdeFormat,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Serialization format used for every file in the store.
+const SERDE_FORMAT: SerdeFormat = SerdeFormat::RawBytes;
+
+/// Errors of the [`KeyStore`]
+#[derive(Debug, Error)]
+pub enum KeyStoreError {
+    /// Reading or writing a file failed
+    #[error("Io({0})")]
+    Io(#[from] io::Error),
+    /// The key descriptor couldn't be (de)serialized
+    #[error("Descriptor({0})")]
+    Descriptor(#[from] serde_json::Error),
+    /// Key generation failed
+    #[error("Keygen({0:?})")]
+    Keygen(halo2_proofs::plonk::Error),
+    /// The stored key doesn't match its record
+    #[error("KeyMismatch({0:?})")]
+    KeyMismatch(KeyDescriptor),
+    /// No params from a trusted setup are stored for the degree
+    #[error("ParamsNotFound({0})")]
+    ParamsNotFound(u32),
+    /// The stored params have a different degree than the requested one
+    #[error("DegreeMismatch(expected: {expected}, found: {found})")]
+    DegreeMismatch {
+        /// The requested degree
+        expected: u32,
+        /// The degree of the stored params
+        found: u32,
+    },
+}
+
+impl From<halo2_proofs::plonk::Error> for KeyStoreError {
+    fn from(err: halo2_proofs::plonk::Error) -> Self {
+        Self::Keygen(err)
+    }
+}
+
+/// Describes the configuration a key has been generated for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyDescriptor {
+    /// Name of the circuit, e.g. `"super"` or `"root"`
+    pub circuit: String,
+    /// Degree of the circuit
+    pub degree: u32,
+    /// Debug representation of everything else that affects the circuit
+    /// layout, e.g. `FixedCParams`, `Circuit::Params` and `FeatureConfig`
+    pub config: String,
+}
+
+impl KeyDescriptor {
+    /// Create a new descriptor. `config` must capture every parameter that
+    /// changes the circuit layout.
+    pub fn new(circuit: impl Into<String>, degree: u32, config: &impl Debug) -> Self {
+        Self {
+            circuit: circuit.into(),
+            degree,
+            config: format!("{:?}", config),
+        }
+    }
+
+    /// Name of the files of this key in the store, without extension.
+    fn file_stem(&self) -> String {
+        let config_hash = keccak256(self.config.as_bytes());
+        format!(
+            "{}_k{}_{}",
+            self.circuit,
+            self.degree,
+            hex::encode(&config_hash[..8])
+        )
+    }
+}
+
+/// Record stored next to a key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct KeyRecord {
+    /// Configuration the key has been generated for
+    descriptor: KeyDescriptor,
+    /// Digest of the params the key has been generated with, empty in the
+    /// records written before it was recorded
+    #[serde(default)]
+    params_digest: String,
+    /// Transcript representation of the verifying key
+    vk_digest: String,
+}
+
+impl KeyRecord {
+    fn new(
+        descriptor: &KeyDescriptor,
+        params: &ParamsKZG<Bn256>,
+        vk: &VerifyingKey<G1Affine>,
+    ) -> Self {
+        Self {
+            descriptor: descriptor.clone(),
+            params_digest: Self::params_digest(params),
+            vk_digest: format!("{:?}", vk.transcript_repr()),
+        }
+    }
+
+    /// Digest of the points of `params` that the proofs are verified with,
+    /// where `s_g2` binds the secret of the setup. They don't depend on the
+    /// degree of the params, which is in the descriptor.
+    fn params_digest(params: &ParamsKZG<Bn256>) -> String {
+        let points = format!(
+            "{:?} {:?} {:?}",
+            params.get_g()[0],
+            params.g2(),
+            params.s_g2()
+        );
+        hex::encode(keccak256(points.as_bytes()))
+    }
+}
+
+/// Directory backed store of KZG params and circuit keys
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Open the store at `dir`, creating the directory if needed.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, KeyStoreError> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Directory of the store
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn params_path(&self, degree: u32) -> PathBuf {
+        self.dir.join(format!("params_k{}.bin", degree))
+    }
+
+    fn insecure_params_path(&self, degree: u32) -> PathBuf {
+        self.dir.join(format!("params_k{}.insecure.bin", degree))
+    }
+
+    fn key_path(&self, descriptor: &KeyDescriptor, kind: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}.bin", descriptor.file_stem(), kind))
+    }
+
+    fn record_path(&self, descriptor: &KeyDescriptor) -> PathBuf {
+        self.dir.join(format!("{}.json", descriptor.file_stem()))
+    }
+
+    /// Read the trusted params of the given degree, if present.
+    pub fn read_params(&self, degree: u32) -> Result<Option<ParamsKZG<Bn256>>, KeyStoreError> {
+        Self::read_params_file(self.params_path(degree), degree)
+    }
+
+    fn read_params_file(
+        path: PathBuf,
+        degree: u32,
+    ) -> Result<Option<ParamsKZG<Bn256>>, KeyStoreError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        let params = ParamsKZG::<Bn256>::read_custom(&mut reader, SERDE_FORMAT)?;
+        if params.k() != degree {
+            return Err(KeyStoreError::DegreeMismatch {
+                expected: degree,
+                found: params.k(),
+            });
+        }
+        Ok(Some(params))
+    }
+
+    /// Write params from a trusted setup to the store, replacing the ones of
+    /// the same degree.
+    pub fn write_params(&self, params: &ParamsKZG<Bn256>) -> Result<(), KeyStoreError> {
+        let mut writer = BufWriter::new(File::create(self.params_path(params.k()))?);
+        params.write_custom(&mut writer, SERDE_FORMAT)?;
+        Ok(())
+    }
+
+    /// Read the trusted params of the given degree, which must be present.
+    pub fn params(&self, degree: u32) -> Result<ParamsKZG<Bn256>, KeyStoreError> {
+        self.read_params(degree)?
+            .ok_or(KeyStoreError::ParamsNotFound(degree))
+    }
+
+    /// Read the trusted params of the given degree, or else the insecure
+    /// params generated for testing, running the insecure setup with `rng` and
+    /// storing its result if needed. Never use the result in production.
+    pub fn params_or_insecure_setup(
+        &self,
+        degree: u32,
+        rng: impl RngCore,
+    ) -> Result<ParamsKZG<Bn256>, KeyStoreError> {
+        if let Some(params) = self.read_params(degree)? {
+            return Ok(params);
+        }
+        let path = self.insecure_params_path(degree);
+        log::warn!("using the insecure params {:?}, for testing only", path);
+        if let Some(params) = Self::read_params_file(path.clone(), degree)? {
+            return Ok(params);
+        }
+        let params = ParamsKZG::<Bn256>::setup(degree, rng);
+        let mut writer = BufWriter::new(File::create(path)?);
+        params.write_custom(&mut writer, SERDE_FORMAT)?;
+        Ok(params)
+    }
+
+    fn read_record(&self, descriptor: &KeyDescriptor) -> Result<Option<KeyRecord>, KeyStoreError> {
+        let path = self.record_path(descriptor);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let reader = BufReader::new(File::open(path)?);
+        Ok(Some(serde_json::from_reader(reader)?))
+    }
+
+    fn write_record(&self, record: &KeyRecord) -> Result<(), KeyStoreError> {
+        let writer = BufWriter::new(File::create(self.record_path(&record.descriptor))?);
+        serde_json::to_writer_pretty(writer, record)?;
+        Ok(())
+    }
+
+    /// Read the verifying key of `descriptor`, if present. The key is not
+    /// checked against the circuit.
+    pub fn read_verifying_key<C: Circuit<Fr>>(
+        &self,
+        descriptor: &KeyDescriptor,
+        circuit_params: C::Params,
+    ) -> Result<Option<VerifyingKey<G1Affine>>, KeyStoreError> {
+        let path = self.key_path(descriptor, "vk");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        let vk = VerifyingKey::read::<_, C>(&mut reader, SERDE_FORMAT, circuit_params)?;
+        Ok(Some(vk))
+    }
+
+    /// Read the proving key of `descriptor`, if present. The key is not
+    /// checked against the circuit, see [`KeyStore::proving_key`].
+    pub fn read_proving_key<C: Circuit<Fr>>(
+        &self,
+        descriptor: &KeyDescriptor,
+        circuit_params: C::Params,
+    ) -> Result<Option<ProvingKey<G1Affine>>, KeyStoreError> {
+        let path = self.key_path(descriptor, "pk");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        let pk = ProvingKey::read::<_, C>(&mut reader, SERDE_FORMAT, circuit_params)?;
+        Ok(Some(pk))
+    }
+
+    /// Write the proving key of `descriptor`, generated with `params`,
+    /// together with its verifying key and its record.
+    pub fn write_proving_key(
+        &self,
+        descriptor: &KeyDescriptor,
+        params: &ParamsKZG<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+    ) -> Result<(), KeyStoreError> {
+        let mut writer = BufWriter::new(File::create(self.key_path(descriptor, "vk"))?);
+        pk.get_vk().write(&mut writer, SERDE_FORMAT)?;
+        let mut writer = BufWriter::new(File::create(self.key_path(descriptor, "pk"))?);
+        pk.write(&mut writer, SERDE_FORMAT)?;
+        self.write_record(&KeyRecord::new(descriptor, params, pk.get_vk()))
+    }
+
+    /// Read the proving key of `descriptor`, or generate it for `circuit` and
+    /// store it. A stored key without a record, or whose record was written
+    /// for another descriptor, other params or another verifying key, is a
+    /// [`KeyStoreError::KeyMismatch`].
+    pub fn proving_key<C: Circuit<Fr>>(
+        &self,
+        descriptor: &KeyDescriptor,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+    ) -> Result<ProvingKey<G1Affine>, KeyStoreError> {
+        if params.k() != descriptor.degree {
+            return Err(KeyStoreError::DegreeMismatch {
+                expected: descriptor.degree,
+                found: params.k(),
+            });
+        }
+        if let Some(pk) = self.read_proving_key::<C>(descriptor, circuit.params())? {
+            let record = self.read_record(descriptor)?;
+            if record != Some(KeyRecord::new(descriptor, params, pk.get_vk())) {
+                return Err(KeyStoreError::KeyMismatch(descriptor.clone()));
+            }
+            return Ok(pk);
+        }
+        let vk = keygen_vk(params, circuit)?;
+        let pk = keygen_pk(params, vk, circuit)?;
+        self.write_proving_key(descriptor, params, &pk)?;
+        Ok(pk)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+        poly::Rotation,
+    };
+    use rand::rngs::OsRng;
+
+    /// Squares the witness and adds the constant `c` of its params
+    #[derive(Clone, Default)]
+    struct SquareCircuit {
+        x: Value<Fr>,
+        c: u64,
+    }
+
+    impl Circuit<Fr> for SquareCircuit {
+        type Config = (Column<Advice>, Column<Fixed>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = u64;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                c: self.c,
+                ..Default::default()
+            }
+        }
+
+        fn params(&self) -> Self::Params {
+            self.c
+        }
+
+        fn configure_with_params(meta: &mut ConstraintSystem<Fr>, _: Self::Params) -> Self::Config {
+            Self::configure(meta)
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let advice = meta.advice_column();
+            let fixed = meta.fixed_column();
+            meta.create_gate("next = cur * cur + c", |meta| {
+                let cur = meta.query_advice(advice, Rotation::cur());
+                let next = meta.query_advice(advice, Rotation::next());
+                let c = meta.query_fixed(fixed, Rotation::cur());
+                vec![next - cur.clone() * cur - c]
+            });
+            (advice, fixed)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, fixed): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "square",
+                |mut region| {
+                    let c = Value::known(Fr::from(self.c));
+                    region.assign_fixed(|| "c", fixed, 0, || c)?;
+                    region.assign_advice(|| "x", advice, 0, || self.x)?;
+                    region.assign_advice(|| "x^2 + c", advice, 1, || self.x * self.x + c)?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn temp_store(name: &str) -> KeyStore {
+        let dir = std::env::temp_dir().join(format!("key_store_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        KeyStore::new(dir).unwrap()
+    }
+
+    #[test]
+    fn key_store_roundtrip() {
+        let store = temp_store("roundtrip");
+        let degree = 4;
+        assert!(matches!(
+            store.params(degree),
+            Err(KeyStoreError::ParamsNotFound(4))
+        ));
+        let params = store.params_or_insecure_setup(degree, OsRng).unwrap();
+        // The insecure params are not read as trusted ones
+        assert!(store.read_params(degree).unwrap().is_none());
+        let read_params = store.params_or_insecure_setup(degree, OsRng).unwrap();
+        assert_eq!(params.s_g2(), read_params.s_g2());
+        store.write_params(&params).unwrap();
+        assert_eq!(params.k(), store.params(degree).unwrap().k());
+
+        let circuit = SquareCircuit::default();
+        let descriptor = KeyDescriptor::new("square", degree, &circuit.params());
+        let pk = store.proving_key(&descriptor, &params, &circuit).unwrap();
+        let read_pk = store
+            .read_proving_key::<SquareCircuit>(&descriptor, circuit.params())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            pk.get_vk().transcript_repr(),
+            read_pk.get_vk().transcript_repr()
+        );
+        let read_vk = store
+            .read_verifying_key::<SquareCircuit>(&descriptor, circuit.params())
+            .unwrap()
+            .unwrap();
+        assert_eq!(pk.get_vk().transcript_repr(), read_vk.transcript_repr());
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn key_store_key_mismatch() {
+        let store = temp_store("mismatch");
+        let degree = 4;
+        let params = store.params_or_insecure_setup(degree, OsRng).unwrap();
+        let circuit = SquareCircuit::default();
+        let descriptor = KeyDescriptor::new("square", degree, &circuit.params());
+        store.proving_key(&descriptor, &params, &circuit).unwrap();
+
+        // A stale key stored for a circuit with a different fixed column,
+        // without and then with its record
+        let other = SquareCircuit {
+            c: 1,
+            ..Default::default()
+        };
+        let other_descriptor = KeyDescriptor::new("square", degree, &other.params());
+        for kind in ["pk", "vk"] {
+            fs::copy(
+                store.key_path(&descriptor, kind),
+                store.key_path(&other_descriptor, kind),
+            )
+            .unwrap();
+        }
+        for _ in 0..2 {
+            assert!(matches!(
+                store.proving_key(&other_descriptor, &params, &other),
+                Err(KeyStoreError::KeyMismatch(_))
+            ));
+            fs::copy(
+                store.record_path(&descriptor),
+                store.record_path(&other_descriptor),
+            )
+            .unwrap();
+        }
+
+        // A stored key used with other params of the same degree
+        let other_params = ParamsKZG::<Bn256>::setup(degree, OsRng);
+        assert!(matches!(
+            store.proving_key(&descriptor, &other_params, &circuit),
+            Err(KeyStoreError::KeyMismatch(_))
+        ));
+
+        // A stored key whose record was written for another verifying key
+        let mut record = store.read_record(&descriptor).unwrap().unwrap();
+        record.vk_digest = "0x0".to_string();
+        store.write_record(&record).unwrap();
+        assert!(matches!(
+            store.proving_key(&descriptor, &params, &circuit),
+            Err(KeyStoreError::KeyMismatch(_))
+        ));
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+}
@@ -29,6 +29,8 @@ pub mod copy_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
 pub mod keccak_circuit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod key_store;
 #[allow(dead_code, reason = "under active development")]
 pub mod mpt_circuit;
 pub mod pi_circuit;
@@ -1,5 +1,8 @@
 //! Public Input Circuit implementation
+mod header;
 mod param;
+mod rlp_list;
+mod tx_trie;
 
 #[cfg(any(test, feature = "test-circuits"))]
 mod dev;
@@ -13,8 +16,10 @@ pub use PiCircuit as TestPiCircuit;
 use bus_mapping::circuit_input_builder::Withdrawal;
 use eth_types::{self, Field, ToLittleEndian};
 use halo2_proofs::plonk::{Expression, Instance, SecondPhase};
+use header::{HeaderCells, HeaderConfig};
 use itertools::Itertools;
 use param::*;
+use tx_trie::{TxCells, TxTrieConfig};
 
 use crate::{
     evm_circuit::{
@@ -46,6 +51,15 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+/// Rows of the public input digest limbs (lo, hi) in the PiCircuit instance
+/// column
+pub const RPI_DIGEST_INSTANCE_ROWS: [usize; 2] = [0, 1];
+/// Rows of the state root limbs (lo, hi) in the PiCircuit instance column
+pub const STATE_ROOT_INSTANCE_ROWS: [usize; 2] = [2, 3];
+/// Rows of the previous block state root limbs (lo, hi) in the PiCircuit
+/// instance column
+pub const PREV_STATE_ROOT_INSTANCE_ROWS: [usize; 2] = [4, 5];
+
 /// Config for PiCircuit
 #[derive(Clone, Debug)]
 pub struct PiCircuitConfig<F: Field> {
@@ -84,6 +98,10 @@ pub struct PiCircuitConfig<F: Field> {
     tx_id_diff_inv: Column<Advice>,
     fixed_u16: Column<Fixed>,
     calldata_gas_cost: Column<Advice>,
+    // calldata_rlc: RLC of the calldata bytes of the tx so far, by the keccak challenge
+    calldata_rlc: Column<Advice>,
+    // calldata_pow: keccak challenge to the power of the calldata bytes of the tx so far
+    calldata_pow: Column<Advice>,
     is_final: Column<Advice>,
 
     // rpi_bytes: raw public input bytes laid vertically
@@ -101,7 +119,14 @@ pub struct PiCircuitConfig<F: Field> {
 
     q_rpi_byte_enable: Selector,
 
-    pi_instance: Column<Instance>, // keccak_digest_hi, keccak_digest_lo
+    // keccak_digest_lo, keccak_digest_hi, state_root_lo, state_root_hi,
+    // prev_state_root_lo, prev_state_root_hi
+    pi_instance: Column<Instance>,
+
+    // RLP encoding of the txs and trie of the encodings, bound to the transactions root
+    tx_trie: TxTrieConfig<F>,
+    // RLP encoding of the block header, bound to the block hash
+    header: HeaderConfig,
 
     _marker: PhantomData<F>,
     // External tables
@@ -166,6 +191,8 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         // 2^8 transfer transactions which have 21000*2^8 (~ 5.376M) gas.
         let fixed_u16 = meta.fixed_column();
         let calldata_gas_cost = meta.advice_column_in(SecondPhase);
+        let calldata_rlc = meta.advice_column_in(SecondPhase);
+        let calldata_pow = meta.advice_column_in(SecondPhase);
         let is_final = meta.advice_column();
 
         let q_digest_last = meta.complex_selector();
@@ -380,6 +407,13 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             let gas_cost_next = meta.query_advice(calldata_gas_cost, Rotation::next());
             let is_final = meta.query_advice(is_final, Rotation::cur());
 
+            let value_lo = meta.query_advice(tx_value.lo(), Rotation::cur());
+            let rlc = meta.query_advice(calldata_rlc, Rotation::cur());
+            let rlc_next = meta.query_advice(calldata_rlc, Rotation::next());
+            let pow = meta.query_advice(calldata_pow, Rotation::cur());
+            let pow_next = meta.query_advice(calldata_pow, Rotation::next());
+            let keccak_rand = challenges.keccak_input();
+
             let is_tx_id_nonzero = not::expr(tx_id_is_zero_config.expr());
             let is_tx_id_next_nonzero = tx_idx_next.expr() * tx_idx_inv_next.expr();
 
@@ -421,13 +455,27 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
                 * (tx_idx_next.expr() - tx_idx.expr())
                 * (gas_cost_next.expr() - gas_next.expr());
 
+            // calldata_rlc and calldata_pow accumulate the calldata of each tx
+            let rlc_of_same_tx_constraint = tx_id_equal_to_next.clone()
+                * (rlc_next.expr() - rlc.expr() * keccak_rand.expr() - value_next_lo.expr());
+            let pow_of_same_tx_constraint =
+                tx_id_equal_to_next.clone() * (pow_next.expr() - pow.expr() * keccak_rand.expr());
+            let rlc_of_next_tx_constraint = is_tx_id_next_nonzero.expr()
+                * (tx_idx_next.expr() - tx_idx.expr())
+                * (rlc_next - value_next_lo.expr());
+            let pow_of_next_tx_constraint = is_tx_id_next_nonzero.expr()
+                * (tx_idx_next.expr() - tx_idx.expr())
+                * (pow_next - keccak_rand.expr());
+
             let is_final_of_same_tx_constraint = tx_id_equal_to_next * is_final.expr();
             let is_final_of_next_tx_constraint =
                 (tx_idx_next.expr() - tx_idx.expr()) * (is_final.expr() - 1.expr());
 
             // if tx_id != 0 then
-            //    1. q_calldata_start * (index - 0) == 0 and
-            //    2. q_calldata_start * (gas_cost - gas) == 0.
+            //    1. q_calldata_start * (index - 0) == 0,
+            //    2. q_calldata_start * (gas_cost - gas) == 0,
+            //    3. q_calldata_start * (calldata_rlc - value) == 0 and
+            //    4. q_calldata_start * (calldata_pow - keccak_rand) == 0.
 
             vec![
                 q_is_calldata.expr() * default_calldata_row_constraint1,
@@ -440,8 +488,14 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
                 q_is_calldata.expr() * is_tx_id_nonzero.expr() * gas_cost_of_next_tx_constraint,
                 q_is_calldata.expr() * is_tx_id_nonzero.expr() * is_final_of_same_tx_constraint,
                 q_is_calldata.expr() * is_tx_id_nonzero.expr() * is_final_of_next_tx_constraint,
+                q_is_calldata.expr() * is_tx_id_nonzero.expr() * rlc_of_same_tx_constraint,
+                q_is_calldata.expr() * is_tx_id_nonzero.expr() * pow_of_same_tx_constraint,
+                q_is_calldata.expr() * is_tx_id_nonzero.expr() * rlc_of_next_tx_constraint,
+                q_is_calldata.expr() * is_tx_id_nonzero.expr() * pow_of_next_tx_constraint,
                 q_calldata_start.expr() * is_tx_id_nonzero.expr() * (idx - 0.expr()),
                 q_calldata_start.expr() * is_tx_id_nonzero.expr() * (gas_cost - gas),
+                q_calldata_start.expr() * is_tx_id_nonzero.expr() * (rlc - value_lo),
+                q_calldata_start.expr() * is_tx_id_nonzero.expr() * (pow - keccak_rand),
             ]
         });
 
@@ -496,6 +550,32 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             ]
         });
 
+        let tx_trie = TxTrieConfig::configure(meta, &keccak_table, fixed_u16, &challenges);
+        let header = HeaderConfig::configure(meta);
+
+        // The calldata spliced in the encoding of each tx is the calldata of
+        // the tx in the tx table
+        meta.lookup_any("calldata in tx encoding", |meta| {
+            let condition = meta.query_fixed(tx_trie.q_tx_data, Rotation::cur())
+                * tx_trie.rlp.splice_nonzero(meta);
+            let input = [
+                meta.query_fixed(tx_trie.tx_id, Rotation::cur()),
+                1.expr(),
+                meta.query_advice(tx_trie.rlp.item_len, Rotation::cur()) - 1.expr(),
+                meta.query_advice(tx_trie.rlp.splice_rlc, Rotation::cur()),
+                meta.query_advice(tx_trie.rlp.splice_pow, Rotation::cur()),
+            ];
+            let q_tx_calldata = meta.query_selector(q_tx_calldata);
+            let table = [tx_id, is_final, index, calldata_rlc, calldata_pow]
+                .map(|column| q_tx_calldata.expr() * meta.query_advice(column, Rotation::cur()));
+
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (condition.expr() * input, table))
+                .collect()
+        });
+
         Self {
             max_txs,
             max_withdrawals,
@@ -517,6 +597,8 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             tx_id_diff_inv,
             fixed_u16,
             calldata_gas_cost,
+            calldata_rlc,
+            calldata_pow,
             is_final,
             rpi_bytes,
             rpi_bytes_keccak_rlc,
@@ -525,6 +607,8 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             rpi_digest_bytes_limbs,
             q_rpi_byte_enable,
             pi_instance,
+            tx_trie,
+            header,
             _marker: PhantomData,
         }
     }
@@ -539,7 +623,7 @@ impl<F: Field> PiCircuitConfig<F> {
 
     /// Return the number of rows for txs and calldata
     #[inline]
-    fn circuit_len_all(txs: usize, wds: usize, calldata: usize) -> usize {
+    pub(crate) fn circuit_len_all(txs: usize, wds: usize, calldata: usize) -> usize {
         N_BYTES_ONE
             + N_BYTES_BLOCK
             + N_BYTES_EXTRA_VALUE
@@ -599,6 +683,18 @@ impl<F: Field> PiCircuitConfig<F> {
             offset,
             || Value::known(F::ZERO),
         )?;
+        region.assign_advice(
+            || "calldata_rlc",
+            self.calldata_rlc,
+            offset,
+            || Value::known(F::ZERO),
+        )?;
+        region.assign_advice(
+            || "calldata_pow",
+            self.calldata_pow,
+            offset,
+            || Value::known(F::ZERO),
+        )?;
         region.assign_advice(
             || "tx_id",
             self.tx_table.tx_id,
@@ -674,7 +770,7 @@ impl<F: Field> PiCircuitConfig<F> {
     }
 
     /// Assigns a tx_table row and stores the values in a vec for the
-    /// raw_public_inputs column
+    /// raw_public_inputs column. Returns the assigned tx_table value.
     #[allow(clippy::too_many_arguments)]
     fn assign_tx_row(
         &self,
@@ -689,7 +785,7 @@ impl<F: Field> PiCircuitConfig<F> {
         current_rpi_offset: &mut usize,
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
-    ) -> Result<(), Error> {
+    ) -> Result<WordLoHi<AssignedCell<F, F>>, Error> {
         // tx_id_inv = (tag - CallDataLength)^(-1)
         let tx_id_inv = if tag != TxFieldTag::CallDataLength {
             let x = F::from(tag as u64) - F::from(TxFieldTag::CallDataLength as u64);
@@ -784,7 +880,7 @@ impl<F: Field> PiCircuitConfig<F> {
             || tx_value_inv.lo(),
         )?;
 
-        Ok(())
+        Ok(tx_value_assignedcell)
     }
 
     /// Assigns one calldata row
@@ -803,6 +899,8 @@ impl<F: Field> PiCircuitConfig<F> {
         rpi_bytes: &mut [u8],
         is_final: bool,
         gas_cost: F,
+        calldata_rlc: Value<F>,
+        calldata_pow: Value<F>,
         zero_cell: AssignedCell<F, F>,
     ) -> Result<AssignedCell<F, F>, Error> {
         let tx_id = F::from(tx_id as u64);
@@ -865,6 +963,18 @@ impl<F: Field> PiCircuitConfig<F> {
             offset,
             || Value::known(gas_cost),
         )?;
+        region.assign_advice(
+            || "calldata_rlc",
+            self.calldata_rlc,
+            offset,
+            || calldata_rlc,
+        )?;
+        region.assign_advice(
+            || "calldata_pow",
+            self.calldata_pow,
+            offset,
+            || calldata_pow,
+        )?;
 
         let (rpi_bytes_keccakrlc_cell, rpi_value_lc_cell) = self.assign_raw_bytes(
             region,
@@ -1107,7 +1217,8 @@ impl<F: Field> PiCircuitConfig<F> {
 
     /// Assigns the values for block table in the block_table column
     /// and rpi_bytes columns. Copy constraints will be enable
-    /// to assure block_table value cell equal with respective rpi_byte_rlc cell
+    /// to assure block_table value cell equal with respective rpi_byte_rlc cell.
+    /// Returns the assigned block table values.
     #[allow(clippy::too_many_arguments)]
     fn assign_block_table(
         &self,
@@ -1119,7 +1230,7 @@ impl<F: Field> PiCircuitConfig<F> {
         current_rpi_offset: &mut usize,
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
-    ) -> Result<(), Error> {
+    ) -> Result<Vec<WordLoHi<AssignedCell<F, F>>>, Error> {
         let mut block_copy_cells = vec![];
 
         // coinbase
@@ -1327,14 +1438,19 @@ impl<F: Field> PiCircuitConfig<F> {
             Ok::<(), Error>(())
         })?;
 
-        Ok(())
+        Ok(block_copy_cells
+            .into_iter()
+            .map(|(block_value, _)| block_value)
+            .collect())
     }
 
     /// Assigns the extra fields (not in block or tx tables):
     ///   - block hash
     ///   - state root
     ///   - previous block state root
-    /// to the rpi_byte column
+    ///   - transactions root
+    /// to the rpi_byte column.
+    /// Returns the assigned words, in the same order.
     #[allow(clippy::too_many_arguments)]
     fn assign_extra_fields(
         &self,
@@ -1345,9 +1461,9 @@ impl<F: Field> PiCircuitConfig<F> {
         current_rpi_offset: &mut usize,
         rpi_bytes: &mut [u8],
         zero_cell: AssignedCell<F, F>,
-    ) -> Result<(), Error> {
+    ) -> Result<[WordLoHi<AssignedCell<F, F>>; 4], Error> {
         // block hash
-        self.assign_raw_bytes(
+        let (_, block_hash) = self.assign_raw_bytes(
             region,
             &extra
                 .block_hash
@@ -1364,7 +1480,7 @@ impl<F: Field> PiCircuitConfig<F> {
         )?;
 
         // block state root
-        self.assign_raw_bytes(
+        let (_, state_root) = self.assign_raw_bytes(
             region,
             &extra
                 .state_root
@@ -1381,7 +1497,7 @@ impl<F: Field> PiCircuitConfig<F> {
         )?;
 
         // previous block state root
-        self.assign_raw_bytes(
+        let (_, prev_state_root) = self.assign_raw_bytes(
             region,
             &extra
                 .prev_state_root
@@ -1394,10 +1510,27 @@ impl<F: Field> PiCircuitConfig<F> {
             rpi_bytes,
             current_rpi_offset,
             challenges,
+            zero_cell.clone(),
+        )?;
+
+        // transactions root
+        let (_, transactions_root) = self.assign_raw_bytes(
+            region,
+            &extra
+                .transactions_root
+                .to_fixed_bytes()
+                .iter()
+                .copied()
+                .rev()
+                .collect_vec(),
+            rpi_bytes_keccak_rlc,
+            rpi_bytes,
+            current_rpi_offset,
+            challenges,
             zero_cell,
         )?;
 
-        Ok(())
+        Ok([block_hash, state_root, prev_state_root, transactions_root])
     }
 
     /// Assign digest word
@@ -1423,6 +1556,12 @@ impl<F: Field> PiCircuitConfig<F> {
 }
 
 /// Public Inputs Circuit
+///
+/// Its single instance column holds 6 values: the lo, hi limbs of the public
+/// input digest, of the state root and of the previous block state root, at
+/// [`RPI_DIGEST_INSTANCE_ROWS`], [`STATE_ROOT_INSTANCE_ROWS`] and
+/// [`PREV_STATE_ROOT_INSTANCE_ROWS`]. The state roots are exposed next to the
+/// digest so that the proofs of consecutive blocks can be chained.
 #[derive(Clone, Default, Debug)]
 pub struct PiCircuit<F: Field> {
     max_txs: usize,
@@ -1430,6 +1569,10 @@ pub struct PiCircuit<F: Field> {
     max_calldata: usize,
     /// PublicInputs data known by the verifier
     pub public_data: PublicData,
+    /// Public data laid out in the block header region instead of
+    /// `public_data`, to check that the header binds the block table
+    #[cfg(test)]
+    header_data: Option<PublicData>,
     _marker: PhantomData<F>,
 }
 
@@ -1446,9 +1589,20 @@ impl<F: Field> PiCircuit<F> {
             max_withdrawals,
             max_calldata,
             public_data,
+            #[cfg(test)]
+            header_data: None,
             _marker: PhantomData,
         }
     }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(txs: usize, wds: usize, calldata: usize) -> usize {
+        // The tx trie and block header regions share their own columns, so they
+        // are laid out one after the other alongside the public inputs region.
+        PiCircuitConfig::<F>::circuit_len_all(txs, wds, calldata)
+            .max(tx_trie::circuit_len(txs) + header::circuit_len())
+    }
 }
 
 impl<F: Field> SubCircuit<F> for PiCircuit<F> {
@@ -1474,8 +1628,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
         let calldata_len = block.txs.iter().map(|tx| tx.call_data.len()).sum();
         (
-            Self::Config::circuit_len_all(block.txs.len(), block.withdrawals().len(), calldata_len),
-            Self::Config::circuit_len_all(
+            Self::min_num_rows(block.txs.len(), block.withdrawals().len(), calldata_len),
+            Self::min_num_rows(
                 block.circuits_params.max_txs,
                 block.circuits_params.max_withdrawals,
                 block.circuits_params.max_calldata,
@@ -1491,7 +1645,17 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             self.max_calldata,
         );
 
-        vec![vec![rpi_digest_byte_field.lo(), rpi_digest_byte_field.hi()]]
+        let state_root = WordLoHi::<F>::from(self.public_data.state_root);
+        let prev_state_root = WordLoHi::<F>::from(self.public_data.prev_state_root);
+
+        vec![vec![
+            rpi_digest_byte_field.lo(),
+            rpi_digest_byte_field.hi(),
+            state_root.lo(),
+            state_root.hi(),
+            prev_state_root.lo(),
+            prev_state_root.hi(),
+        ]]
     }
 
     /// Make the assignments to the PiCircuit
@@ -1516,7 +1680,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 Ok(())
             },
         )?;
-        let digest_word_assigned = layouter.assign_region(
+        let (digest_word_assigned, roots, tx_cells, header_cells) = layouter.assign_region(
             || "region 0",
             |mut region| {
                 // Annotate columns
@@ -1538,6 +1702,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
                 region.name_column(|| "fixed_u16", config.fixed_u16);
                 region.name_column(|| "calldata_gas_cost", config.calldata_gas_cost);
+                region.name_column(|| "calldata_rlc", config.calldata_rlc);
+                region.name_column(|| "calldata_pow", config.calldata_pow);
                 region.name_column(|| "is_final", config.is_final);
 
                 region.name_column(|| "Public_Inputs", config.pi_instance);
@@ -1581,7 +1747,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                     zero_cell.clone(),
                 )?;
                 block_table_offset += 1;
-                config.assign_block_table(
+                let block_values_assigned = config.assign_block_table(
                     &mut region,
                     &mut block_table_offset,
                     block_values,
@@ -1598,15 +1764,16 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
                 // Assign extra fields
                 let extra_vals = self.public_data.get_extra_values();
-                config.assign_extra_fields(
-                    &mut region,
-                    extra_vals,
-                    &mut rpi_bytes_keccak_rlc,
-                    challenges,
-                    &mut current_rpi_offset,
-                    &mut rpi_bytes,
-                    zero_cell,
-                )?;
+                let [block_hash, state_root, prev_state_root, tx_root] = config
+                    .assign_extra_fields(
+                        &mut region,
+                        extra_vals,
+                        &mut rpi_bytes_keccak_rlc,
+                        challenges,
+                        &mut current_rpi_offset,
+                        &mut rpi_bytes,
+                        zero_cell,
+                    )?;
                 assert_eq!(
                     start_offset - current_rpi_offset,
                     N_BYTES_ONE + N_BYTES_BLOCK + N_BYTES_EXTRA_VALUE
@@ -1640,11 +1807,13 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 )?;
                 tx_table_offset += 1;
 
+                let mut tx_cells = Vec::with_capacity(config.max_txs);
                 iter::empty()
                     .chain(&txs)
                     .chain((0..(config.max_txs - txs.len())).map(|_| &tx_default))
                     .enumerate()
                     .try_for_each(|(i, tx)| -> Result<(), Error> {
+                        let mut values = Vec::with_capacity(TX_LEN);
                         for (tag, value_bytes) in &[
                             (TxFieldTag::Nonce, tx.nonce.to_le_bytes().to_vec()),
                             (TxFieldTag::Gas, tx.gas_limit.to_le_bytes().to_vec()),
@@ -1682,7 +1851,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                         ] {
                             let i: u64 = i.try_into().unwrap();
                             // assign tx field
-                            config.assign_tx_row(
+                            let value = config.assign_tx_row(
                                 &mut region,
                                 tx_table_offset,
                                 i + 1,
@@ -1695,8 +1864,22 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                                 &mut rpi_bytes,
                                 zero_cell.clone(),
                             )?;
+                            values.push(value);
                             tx_table_offset += 1;
                         }
+                        // values are in the order of the tags above
+                        let mut values = values.into_iter();
+                        let mut next = || values.next().unwrap();
+                        tx_cells.push(TxCells {
+                            nonce: next(),
+                            gas: next(),
+                            gas_price: next(),
+                            caller: next(),
+                            callee: next(),
+                            is_create: next(),
+                            value: next(),
+                            call_data_length: next(),
+                        });
                         Ok(())
                     })?;
                 assert_eq!(
@@ -1721,6 +1904,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 for (i, tx) in self.public_data.transactions.iter().enumerate() {
                     let call_data_length = tx.call_data.0.len();
                     let mut gas_cost = F::ZERO;
+                    let mut calldata_rlc = Value::known(F::ZERO);
+                    let mut calldata_pow = Value::known(F::ONE);
                     for (index, byte) in tx.call_data.0.iter().enumerate() {
                         assert!(calldata_count < config.max_calldata);
                         let is_final = index == call_data_length - 1;
@@ -1729,6 +1914,9 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                         } else {
                             F::from(NONZERO_BYTE_GAS_COST)
                         };
+                        calldata_rlc = calldata_rlc * challenges.keccak_input()
+                            + Value::known(F::from(*byte as u64));
+                        calldata_pow = calldata_pow * challenges.keccak_input();
                         let tx_id_next = if is_final {
                             let mut j = i + 1;
                             while j < txs.len() && txs[j].call_data.0.is_empty() {
@@ -1756,6 +1944,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                             &mut rpi_bytes,
                             is_final,
                             gas_cost,
+                            calldata_rlc,
+                            calldata_pow,
                             zero_cell.clone(),
                         )?;
                         call_data_offset += 1;
@@ -1777,6 +1967,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                         &mut rpi_bytes,
                         false,
                         F::ZERO,
+                        Value::known(F::ZERO),
+                        Value::known(F::ZERO),
                         zero_cell.clone(),
                     )?;
                     call_data_offset += 1;
@@ -1835,13 +2027,72 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 // keccak lookup occur on offset 0
                 config.q_rpi_keccak_lookup.enable(&mut region, 0)?;
 
-                Ok(digest_word_assigned)
+                // block table values, in the order of assign_block_table
+                let block_value = |index: usize| block_values_assigned[index].clone();
+                let header_cells = HeaderCells {
+                    coinbase: block_value(0),
+                    chain_id: block_value(6),
+                    gas_limit: block_value(1),
+                    number: block_value(2),
+                    timestamp: block_value(3),
+                    difficulty: block_value(4),
+                    base_fee: block_value(5),
+                    withdrawals_root: block_value(7),
+                    state_root: state_root.clone(),
+                    transactions_root: tx_root,
+                    block_hash,
+                };
+
+                Ok((
+                    digest_word_assigned,
+                    [state_root, prev_state_root],
+                    tx_cells,
+                    header_cells,
+                ))
+            },
+        )?;
+
+        config.tx_trie.load(layouter)?;
+        layouter.assign_region(
+            || "tx trie",
+            |mut region| {
+                config.tx_trie.assign(
+                    &mut region,
+                    config.max_txs,
+                    self.public_data.chain_id.as_u64(),
+                    &self.public_data.transactions,
+                    &tx_cells,
+                    &header_cells,
+                    challenges,
+                )
+            },
+        )?;
+
+        layouter.assign_region(
+            || "block header",
+            |mut region| {
+                #[cfg(test)]
+                let header_data = self.header_data.as_ref().unwrap_or(&self.public_data);
+                #[cfg(not(test))]
+                let header_data = &self.public_data;
+                config.header.assign(
+                    &mut region,
+                    &config.tx_trie.rlp,
+                    header_data,
+                    &header_cells,
+                    challenges,
+                )
             },
         )?;
 
         // Constrain raw_public_input cells to public inputs
-        layouter.constrain_instance(digest_word_assigned.lo().cell(), config.pi_instance, 0)?;
-        layouter.constrain_instance(digest_word_assigned.hi().cell(), config.pi_instance, 1)?;
+        for (row, cell) in iter::once(&digest_word_assigned)
+            .chain(roots.iter())
+            .flat_map(|word| [word.lo(), word.hi()])
+            .enumerate()
+        {
+            layouter.constrain_instance(cell.cell(), config.pi_instance, row)?;
+        }
 
         Ok(())
     }
@@ -75,14 +75,14 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
     ) -> Result<(), Error> {
         let challenges = challenges.values(&mut layouter);
         // assign keccak table
-        let rpi_bytes = self.public_data.get_pi_bytes(
+        let keccak_inputs = self.public_data.get_keccak_inputs(
             config.max_txs,
             config.max_withdrawals,
             config.max_calldata,
         );
         config
             .keccak_table
-            .dev_load(&mut layouter, vec![&rpi_bytes], &challenges)?;
+            .dev_load(&mut layouter, &keccak_inputs, &challenges)?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
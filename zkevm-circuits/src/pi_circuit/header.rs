@@ -0,0 +1,273 @@
+//! Block header of the PiCircuit.
+//!
+//! The RLP encoding of the block header is laid out and hashed through the
+//! keccak table. Its hash is the block hash of the public inputs, and the
+//! header fields found in the block table or in the public inputs are bound to
+//! them. The remaining fields are only bound through the hash.
+
+use eth_types::{Field, H256};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    rlp_list::{RlpItem, RlpListConfig, RlpListLayout},
+    tx_trie::word_bytes,
+};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    instance::PublicData,
+    util::{word::WordLoHi, Challenges},
+};
+
+/// Items of a block header: parent hash, ommers hash, coinbase, state root,
+/// transactions root, receipts root, logs bloom, difficulty, number, gas
+/// limit, gas used, timestamp, extra data, mix hash, nonce, base fee, the
+/// withdrawals root, which is absent before Shanghai, and the blob gas used,
+/// the excess blob gas and the parent beacon block root, which are absent
+/// before Cancun.
+///
+/// Every item is laid out byte by byte, so that the header hash binds all of
+/// them. The extra data is at most 32 bytes by consensus.
+const HEADER_ITEMS: [RlpItem; 20] = [
+    RlpItem::Fixed(32),
+    RlpItem::Fixed(32),
+    RlpItem::Fixed(20),
+    RlpItem::Fixed(32),
+    RlpItem::Fixed(32),
+    RlpItem::Fixed(32),
+    RlpItem::Fixed(256),
+    RlpItem::Short(32),
+    RlpItem::Short(8),
+    RlpItem::Short(8),
+    RlpItem::Short(32),
+    RlpItem::Short(8),
+    RlpItem::Short(32),
+    RlpItem::Fixed(32),
+    RlpItem::Fixed(8),
+    RlpItem::Short(32),
+    RlpItem::Fixed(32),
+    RlpItem::Short(8),
+    RlpItem::Short(8),
+    RlpItem::Fixed(32),
+];
+const COINBASE_ITEM: usize = 2;
+const STATE_ROOT_ITEM: usize = 3;
+const TX_ROOT_ITEM: usize = 4;
+const DIFFICULTY_ITEM: usize = 7;
+const NUMBER_ITEM: usize = 8;
+const GAS_LIMIT_ITEM: usize = 9;
+const TIMESTAMP_ITEM: usize = 11;
+const MIX_HASH_ITEM: usize = 13;
+const BASE_FEE_ITEM: usize = 15;
+const WITHDRAWALS_ROOT_ITEM: usize = 16;
+
+fn header_layout() -> RlpListLayout {
+    RlpListLayout::new(HEADER_ITEMS.to_vec())
+}
+
+/// Number of rows of the block header region
+pub(super) fn circuit_len() -> usize {
+    // zero row, header and difficulty row
+    1 + header_layout().height() + 1
+}
+
+/// Cells of the block table and of the public inputs bound to the header, and
+/// the chain id of the block table bound to the typed transactions
+pub(super) struct HeaderCells<F: Field> {
+    pub(super) coinbase: WordLoHi<AssignedCell<F, F>>,
+    pub(super) chain_id: WordLoHi<AssignedCell<F, F>>,
+    pub(super) gas_limit: WordLoHi<AssignedCell<F, F>>,
+    pub(super) number: WordLoHi<AssignedCell<F, F>>,
+    pub(super) timestamp: WordLoHi<AssignedCell<F, F>>,
+    pub(super) difficulty: WordLoHi<AssignedCell<F, F>>,
+    pub(super) base_fee: WordLoHi<AssignedCell<F, F>>,
+    pub(super) withdrawals_root: WordLoHi<AssignedCell<F, F>>,
+    pub(super) state_root: WordLoHi<AssignedCell<F, F>>,
+    pub(super) transactions_root: WordLoHi<AssignedCell<F, F>>,
+    pub(super) block_hash: WordLoHi<AssignedCell<F, F>>,
+}
+
+/// Config of the block header
+#[derive(Clone, Debug)]
+pub(super) struct HeaderConfig {
+    /// Enables the difficulty row
+    q_difficulty: Selector,
+    /// Difficulty found in the header
+    difficulty: WordLoHi<Column<Advice>>,
+    /// Length of the difficulty found in the header, zero when it is zero
+    difficulty_len: Column<Advice>,
+    difficulty_len_inv: Column<Advice>,
+    /// Mix hash found in the header
+    mix_hash: WordLoHi<Column<Advice>>,
+    /// Difficulty of the block table
+    block_difficulty: WordLoHi<Column<Advice>>,
+}
+
+impl HeaderConfig {
+    /// Configure the block header, whose encoding is laid out in the columns
+    /// of an [`RlpListConfig`].
+    pub(super) fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let config = Self {
+            q_difficulty: meta.selector(),
+            difficulty: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            difficulty_len: meta.advice_column(),
+            difficulty_len_inv: meta.advice_column(),
+            mix_hash: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            block_difficulty: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+        };
+        for column in [
+            config.difficulty.lo(),
+            config.difficulty.hi(),
+            config.difficulty_len,
+            config.mix_hash.lo(),
+            config.mix_hash.hi(),
+            config.block_difficulty.lo(),
+            config.block_difficulty.hi(),
+        ] {
+            meta.enable_equality(column);
+        }
+
+        // After the merge the header difficulty is zero and the block table
+        // difficulty holds the PREVRANDAO value, found in the mix hash.
+        meta.create_gate("header difficulty", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let difficulty = config.difficulty.query_advice(meta, Rotation::cur());
+            let mix_hash = config.mix_hash.query_advice(meta, Rotation::cur());
+            let block_difficulty = config.block_difficulty.query_advice(meta, Rotation::cur());
+            let len = meta.query_advice(config.difficulty_len, Rotation::cur());
+            let len_inv = meta.query_advice(config.difficulty_len_inv, Rotation::cur());
+            let is_zero = not::expr(len.expr() * len_inv);
+
+            cb.require_zero("difficulty_len_inv is the inverse", len * is_zero.expr());
+            cb.require_equal(
+                "block difficulty lo",
+                block_difficulty.lo(),
+                is_zero.expr() * mix_hash.lo() + not::expr(is_zero.expr()) * difficulty.lo(),
+            );
+            cb.require_equal(
+                "block difficulty hi",
+                block_difficulty.hi(),
+                is_zero.expr() * mix_hash.hi() + not::expr(is_zero) * difficulty.hi(),
+            );
+
+            cb.gate(meta.query_selector(config.q_difficulty))
+        });
+
+        config
+    }
+
+    /// Assign the block header region, laying out the header in the columns
+    /// of `rlp` and binding it to `cells`.
+    pub(super) fn assign<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        rlp: &RlpListConfig,
+        public_data: &PublicData,
+        cells: &HeaderCells<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let layout = header_layout();
+        let header = &public_data.header_values;
+        let constants = &public_data.block_constants;
+        let h256 = |hash: H256| Some(hash.0.to_vec());
+        let values = vec![
+            h256(header.parent_hash),
+            h256(header.ommers_hash),
+            Some(constants.coinbase.0.to_vec()),
+            h256(public_data.state_root),
+            h256(public_data.get_transactions_root()),
+            h256(header.receipts_root),
+            Some(header.logs_bloom.0.to_vec()),
+            Some(word_bytes(header.difficulty)),
+            Some(word_bytes(constants.number.as_u64().into())),
+            Some(word_bytes(constants.gas_limit)),
+            Some(word_bytes(header.gas_used)),
+            Some(word_bytes(constants.timestamp)),
+            Some(header.extra_data.to_vec()),
+            h256(header.mix_hash),
+            Some(header.nonce.0.to_vec()),
+            Some(word_bytes(constants.base_fee)),
+            header
+                .has_withdrawals_root
+                .then(|| public_data.withdrawals_root.0.to_vec()),
+            header
+                .has_blob_fields
+                .then(|| word_bytes(header.blob_gas_used.into())),
+            header
+                .has_blob_fields
+                .then(|| word_bytes(header.excess_blob_gas.into())),
+            header
+                .has_blob_fields
+                .then(|| header.parent_beacon_block_root.0.to_vec()),
+        ];
+
+        // header, after a row of zeros queried by the first row of the list
+        rlp.assign_zero_row(region, 0)?;
+        let list = rlp.assign(region, 1, &layout, &values, true, challenges)?;
+        debug_assert_eq!(list.bytes, public_data.get_block_header_rlp());
+
+        for (left, right) in [
+            (&list.items[COINBASE_ITEM].chunks[0], &cells.coinbase),
+            (&list.items[STATE_ROOT_ITEM].chunks[0], &cells.state_root),
+            (
+                &list.items[TX_ROOT_ITEM].chunks[0],
+                &cells.transactions_root,
+            ),
+            (&list.items[NUMBER_ITEM].chunks[0], &cells.number),
+            (&list.items[GAS_LIMIT_ITEM].chunks[0], &cells.gas_limit),
+            (&list.items[TIMESTAMP_ITEM].chunks[0], &cells.timestamp),
+            (&list.items[BASE_FEE_ITEM].chunks[0], &cells.base_fee),
+            (
+                &list.items[WITHDRAWALS_ROOT_ITEM].chunks[0],
+                &cells.withdrawals_root,
+            ),
+            (&list.hash, &cells.block_hash),
+        ] {
+            region.constrain_equal(left.lo().cell(), right.lo().cell())?;
+            region.constrain_equal(left.hi().cell(), right.hi().cell())?;
+        }
+        // difficulty
+        let offset = 1 + layout.height();
+        self.q_difficulty.enable(region, offset)?;
+        let difficulty_len = word_bytes(header.difficulty).len();
+        let difficulty_len_cell = region.assign_advice(
+            || "difficulty_len",
+            self.difficulty_len,
+            offset,
+            || Value::known(F::from(difficulty_len as u64)),
+        )?;
+        region.assign_advice(
+            || "difficulty_len_inv",
+            self.difficulty_len_inv,
+            offset,
+            || Value::known(F::from(difficulty_len as u64).invert().unwrap_or(F::ZERO)),
+        )?;
+        let difficulty = WordLoHi::<F>::from(header.difficulty)
+            .into_value()
+            .assign_advice(region, || "difficulty", self.difficulty, offset)?;
+        let mix_hash = WordLoHi::<F>::from(header.mix_hash)
+            .into_value()
+            .assign_advice(region, || "mix_hash", self.mix_hash, offset)?;
+        let block_difficulty = WordLoHi::<F>::from(constants.difficulty)
+            .into_value()
+            .assign_advice(region, || "block_difficulty", self.block_difficulty, offset)?;
+        region.constrain_equal(
+            difficulty_len_cell.cell(),
+            list.items[DIFFICULTY_ITEM].item_len.cell(),
+        )?;
+        for (left, right) in [
+            (&difficulty, &list.items[DIFFICULTY_ITEM].chunks[0]),
+            (&mix_hash, &list.items[MIX_HASH_ITEM].chunks[0]),
+            (&block_difficulty, &cells.difficulty),
+        ] {
+            region.constrain_equal(left.lo().cell(), right.lo().cell())?;
+            region.constrain_equal(left.hi().cell(), right.hi().cell())?;
+        }
+
+        Ok(())
+    }
+}
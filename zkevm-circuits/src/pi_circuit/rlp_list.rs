@@ -0,0 +1,1138 @@
+//! RLP encoding of lists, laid out one byte per row and optionally hashed
+//! through the keccak table.
+//!
+//! Each item of a list sits in a window of rows whose position only depends on
+//! the list layout, so that item values can be bound to other cells with copy
+//! constraints. Window rows that are not part of the encoding are marked as
+//! padding and skipped by the accumulators.
+//!
+//! Only the consistency of the encoding with the item values is constrained:
+//! non-canonical encodings are not rejected, as a list whose bytes are all laid
+//! out and that is not canonically encoded can't hash to the value it is
+//! checked against.
+//!
+//! The content of a `Spliced` or `Raw` item is not laid out: its RLC, power of
+//! the challenge and length are free witnesses of the splice row, so the hash
+//! of a list does not bind them. Users of these items must bind them on their
+//! own, as the calldata of a transaction is bound to the tx table, the value of
+//! a trie leaf to the encoding of the transaction it holds and the access list
+//! of a transaction to the empty list.
+
+use eth_types::{keccak256, Field, H256};
+use gadgets::util::{not, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::KeccakTable,
+    util::{word::WordLoHi, Challenges},
+};
+
+/// Bytes of each limb of the linear combination of a chunk
+const N_BYTES_LIMB: usize = 16;
+/// Bytes of each chunk of a `Fixed` item
+const N_BYTES_CHUNK: usize = 32;
+/// Rows holding the length bytes of a list or of a `Spliced` item
+const N_LEN_ROWS: usize = 3;
+
+/// An item of an RLP list layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RlpItem {
+    /// Byte string of up to `n <= 32` bytes, right aligned in its window.
+    /// Used for integers and hex-prefix encoded paths.
+    Short(usize),
+    /// Byte string of exactly `n` bytes, or the empty string.
+    Fixed(usize),
+    /// Byte string of any length, whose content is spliced in as an RLC that
+    /// the list leaves unconstrained.
+    Spliced,
+    /// RLP encoding of any length, such as a nested list, spliced in as is
+    /// like the content of a `Spliced` item.
+    Raw,
+}
+
+impl RlpItem {
+    fn header(&self) -> Vec<u8> {
+        match self {
+            Self::Fixed(n) if *n < 56 => vec![0x80 + *n as u8],
+            Self::Fixed(n) => {
+                let len = be_bytes(*n as u64);
+                [vec![0xb7 + len.len() as u8], len].concat()
+            }
+            Self::Short(_) => vec![0],
+            Self::Spliced => vec![0; 1 + N_LEN_ROWS],
+            Self::Raw => vec![0],
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Self::Short(n) | Self::Fixed(n) => self.header().len() + n,
+            Self::Spliced | Self::Raw => self.header().len() + 1,
+        }
+    }
+
+    fn chunk_sizes(&self) -> Vec<usize> {
+        match self {
+            Self::Short(n) => vec![*n],
+            Self::Fixed(n) => iter_chunk_sizes(*n),
+            Self::Spliced | Self::Raw => vec![],
+        }
+    }
+}
+
+fn iter_chunk_sizes(n: usize) -> Vec<usize> {
+    let mut sizes = vec![];
+    if n % N_BYTES_CHUNK != 0 {
+        sizes.push(n % N_BYTES_CHUNK);
+    }
+    sizes.extend(std::iter::repeat(N_BYTES_CHUNK).take(n / N_BYTES_CHUNK));
+    sizes
+}
+
+/// Minimal big-endian bytes of `value`
+fn be_bytes(value: u64) -> Vec<u8> {
+    value
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect()
+}
+
+/// Row layout of an RLP list, relative to the first row of the list.
+///
+/// The list starts with its prefix and length rows, followed by the item
+/// windows, and ends with a row holding the accumulated encoding and its hash.
+#[derive(Clone, Debug)]
+pub(crate) struct RlpListLayout {
+    items: Vec<RlpItem>,
+    item_offsets: Vec<usize>,
+    height: usize,
+}
+
+impl RlpListLayout {
+    /// Lay out a list of `items`
+    pub(crate) fn new(items: Vec<RlpItem>) -> Self {
+        let mut item_offsets = vec![];
+        let mut offset = 1 + N_LEN_ROWS;
+        for item in items.iter() {
+            if let RlpItem::Short(n) = item {
+                assert!(*n > 0 && *n <= N_BYTES_CHUNK);
+            }
+            item_offsets.push(offset);
+            offset += item.height();
+        }
+        Self {
+            items,
+            item_offsets,
+            height: offset + 1,
+        }
+    }
+
+    /// Number of rows of the list
+    pub(crate) fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Row holding the accumulated encoding and its hash
+    pub(crate) fn end(&self) -> usize {
+        self.height - 1
+    }
+
+    /// First row of the item, holding its prefix
+    pub(crate) fn prefix_row(&self, item: usize) -> usize {
+        self.item_offsets[item]
+    }
+
+    /// Row where the content of a `Spliced` or `Raw` item is spliced in
+    pub(crate) fn splice_row(&self, item: usize) -> usize {
+        assert!(matches!(self.items[item], RlpItem::Spliced | RlpItem::Raw));
+        self.window_start(item)
+    }
+
+    fn window_start(&self, item: usize) -> usize {
+        self.item_offsets[item] + self.items[item].header().len()
+    }
+
+    /// Last row of each chunk of a `Short` or `Fixed` item, where the limbs of
+    /// the linear combination of the chunk bytes are complete.
+    pub(crate) fn chunk_ends(&self, item: usize) -> Vec<usize> {
+        self.items[item]
+            .chunk_sizes()
+            .into_iter()
+            .scan(self.window_start(item), |end, size| {
+                *end += size;
+                Some(*end - 1)
+            })
+            .collect()
+    }
+
+    /// Encode the list from the item values, where `None` leaves the item out
+    /// of the list. `Fixed` items take either `n` bytes or none.
+    pub(crate) fn encode(&self, values: &[Option<Vec<u8>>]) -> Vec<u8> {
+        let rows = self.rows(values);
+        rows.iter()
+            .flat_map(|row| match &row.splice {
+                Some(content) => content.clone(),
+                None if !row.pad => vec![row.byte],
+                None => vec![],
+            })
+            .collect()
+    }
+
+    fn rows(&self, values: &[Option<Vec<u8>>]) -> Vec<RlpRow> {
+        assert_eq!(values.len(), self.items.len());
+        let mut rows = vec![
+            RlpRow {
+                pad: true,
+                ..Default::default()
+            };
+            self.height
+        ];
+
+        for (index, (item, value)) in self.items.iter().zip(values.iter()).enumerate() {
+            let offset = self.item_offsets[index];
+            let item_rows = &mut rows[offset..offset + item.height()];
+            for row in item_rows.iter_mut() {
+                row.q_short = matches!(item, RlpItem::Short(_));
+                row.q_fixed = matches!(item, RlpItem::Fixed(_));
+                row.q_spliced = matches!(item, RlpItem::Spliced);
+                row.q_raw = matches!(item, RlpItem::Raw);
+                row.pad = true;
+                row.absent = value.is_none();
+            }
+            item_rows[0].q_prefix = true;
+            let value = value.clone().unwrap_or_default();
+            let present = !item_rows[0].absent;
+            let single = present && value.len() == 1 && value[0] < 0x80;
+
+            match item {
+                RlpItem::Short(n) => {
+                    assert!(value.len() <= *n, "item {} too long", index);
+                    let window = &mut item_rows[1..];
+                    window[n - value.len()..]
+                        .iter_mut()
+                        .zip(value.iter())
+                        .for_each(|(row, byte)| {
+                            row.pad = false;
+                            row.byte = *byte;
+                        });
+                    for row in item_rows.iter_mut() {
+                        row.item_len = value.len() as u64;
+                        row.single = single;
+                    }
+                    let prefix = &mut item_rows[0];
+                    prefix.count = value.len() as u64;
+                    if present && !single {
+                        prefix.pad = false;
+                        prefix.byte = 0x80 + value.len() as u8;
+                    }
+                    // count down the emitted bytes
+                    let mut count = value.len() as u64;
+                    for row in item_rows[1..].iter_mut() {
+                        count -= u64::from(!row.pad);
+                        row.count = count;
+                        row.q_value = true;
+                    }
+                    item_rows[1].q_lc_reset = true;
+                    item_rows[*n].q_count_end = true;
+                    for row in item_rows[1..]
+                        .iter_mut()
+                        .take(n.saturating_sub(N_BYTES_LIMB))
+                    {
+                        row.q_hi = true;
+                    }
+                }
+                RlpItem::Fixed(n) => {
+                    let header = item.header();
+                    let empty = present && value.is_empty();
+                    assert!(
+                        value.is_empty() || value.len() == *n,
+                        "item {} length",
+                        index
+                    );
+                    for (i, row) in item_rows.iter_mut().enumerate() {
+                        row.empty = empty;
+                        row.item_len = value.len() as u64;
+                        if i < header.len() {
+                            row.q_header = true;
+                            row.header_byte = header[i] as u64;
+                        } else {
+                            row.q_value = true;
+                        }
+                    }
+                    if empty {
+                        item_rows[0].pad = false;
+                        item_rows[0].byte = 0x80;
+                    } else if present {
+                        for (row, byte) in
+                            item_rows.iter_mut().zip(header.iter().chain(value.iter()))
+                        {
+                            row.pad = false;
+                            row.byte = *byte;
+                        }
+                    }
+                    let mut start = header.len();
+                    for size in item.chunk_sizes() {
+                        item_rows[start].q_lc_reset = true;
+                        for row in item_rows[start..]
+                            .iter_mut()
+                            .take(size - N_BYTES_LIMB.min(size))
+                        {
+                            row.q_hi = true;
+                        }
+                        start += size;
+                    }
+                }
+                RlpItem::Spliced => {
+                    let long = value.len() >= 56;
+                    let len_bytes = if long {
+                        be_bytes(value.len() as u64)
+                    } else {
+                        vec![]
+                    };
+                    for row in item_rows.iter_mut() {
+                        row.item_len = value.len() as u64;
+                        row.single = single;
+                        row.long = long;
+                    }
+                    let prefix = &mut item_rows[0];
+                    prefix.count = len_bytes.len() as u64;
+                    if present && !single {
+                        prefix.pad = false;
+                        prefix.byte = if long {
+                            0xb7 + len_bytes.len() as u8
+                        } else {
+                            0x80 + value.len() as u8
+                        };
+                    }
+                    assign_len_rows(&mut item_rows[1..1 + N_LEN_ROWS], &len_bytes);
+                    let splice = &mut item_rows[1 + N_LEN_ROWS];
+                    splice.q_splice = true;
+                    splice.splice = Some(value);
+                }
+                RlpItem::Raw => {
+                    for row in item_rows.iter_mut() {
+                        row.item_len = value.len() as u64;
+                    }
+                    let splice = &mut item_rows[1];
+                    splice.q_splice = true;
+                    splice.splice = Some(value);
+                }
+            }
+        }
+
+        // list header
+        let payload_len = self.encode_items(&rows) as u64;
+        let long = payload_len >= 56;
+        let len_bytes = if long { be_bytes(payload_len) } else { vec![] };
+        for row in rows.iter_mut() {
+            row.list_len = payload_len;
+            row.hdr_len = 1 + len_bytes.len() as u64;
+        }
+        for row in rows[..1 + N_LEN_ROWS].iter_mut() {
+            row.long = long;
+            row.pad = true;
+        }
+        rows[0].q_first = true;
+        rows[0].pad = false;
+        rows[0].count = len_bytes.len() as u64;
+        rows[0].byte = if long {
+            0xf7 + len_bytes.len() as u8
+        } else {
+            0xc0 + payload_len as u8
+        };
+        assign_len_rows(&mut rows[1..1 + N_LEN_ROWS], &len_bytes);
+
+        // the end row carries the flags of the last item
+        let end = self.end();
+        rows[end] = RlpRow {
+            q_end: true,
+            pad: true,
+            ..rows[end - 1].clone()
+        }
+        .end_row();
+
+        rows
+    }
+
+    fn encode_items(&self, rows: &[RlpRow]) -> usize {
+        rows[1 + N_LEN_ROWS..]
+            .iter()
+            .map(|row| match &row.splice {
+                Some(content) => content.len(),
+                None => usize::from(!row.pad),
+            })
+            .sum()
+    }
+}
+
+/// Fill the length rows, right aligning the length bytes
+fn assign_len_rows(rows: &mut [RlpRow], len_bytes: &[u8]) {
+    let mut count = len_bytes.len() as u64;
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.q_len = true;
+        row.q_lc_reset = i == 0;
+        row.q_count_end = i == N_LEN_ROWS - 1;
+        row.pad = true;
+        if i >= N_LEN_ROWS - len_bytes.len() {
+            row.pad = false;
+            row.byte = len_bytes[i + len_bytes.len() - N_LEN_ROWS];
+            count -= 1;
+        }
+        row.count = count;
+    }
+}
+
+/// Values of a row of a list, before accumulation
+#[derive(Clone, Debug, Default)]
+struct RlpRow {
+    q_first: bool,
+    q_end: bool,
+    q_prefix: bool,
+    q_len: bool,
+    q_count_end: bool,
+    q_short: bool,
+    q_fixed: bool,
+    q_spliced: bool,
+    q_raw: bool,
+    q_header: bool,
+    header_byte: u64,
+    q_value: bool,
+    q_lc_reset: bool,
+    q_hi: bool,
+    q_splice: bool,
+
+    byte: u8,
+    pad: bool,
+    count: u64,
+    item_len: u64,
+    absent: bool,
+    empty: bool,
+    single: bool,
+    long: bool,
+    list_len: u64,
+    hdr_len: u64,
+    splice: Option<Vec<u8>>,
+}
+
+impl RlpRow {
+    /// Keep only what the end row carries over from the row before it
+    fn end_row(self) -> Self {
+        Self {
+            q_end: self.q_end,
+            pad: self.pad,
+            item_len: self.item_len,
+            absent: self.absent,
+            empty: self.empty,
+            single: self.single,
+            long: self.long,
+            list_len: self.list_len,
+            hdr_len: self.hdr_len,
+            ..Default::default()
+        }
+    }
+}
+
+/// Assigned cells of an item
+#[derive(Clone, Debug)]
+pub(crate) struct AssignedRlpItem<F: Field> {
+    /// Whether the item is left out of the list
+    pub(crate) absent: AssignedCell<F, F>,
+    /// Whether a `Fixed` item is encoded as the empty string
+    pub(crate) empty: AssignedCell<F, F>,
+    /// Length of the item value
+    pub(crate) item_len: AssignedCell<F, F>,
+    /// Linear combination of each chunk of a `Short` or `Fixed` item
+    pub(crate) chunks: Vec<WordLoHi<AssignedCell<F, F>>>,
+}
+
+/// Assigned cells of a list
+#[derive(Clone, Debug)]
+pub(crate) struct AssignedRlpList<F: Field> {
+    /// Assigned items
+    pub(crate) items: Vec<AssignedRlpItem<F>>,
+    /// Keccak hash of the encoding, zero when the list is not hashed
+    pub(crate) hash: WordLoHi<AssignedCell<F, F>>,
+    /// Whether the encoding is looked up in the keccak table
+    pub(crate) is_hashed: AssignedCell<F, F>,
+    /// The encoding
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// Config of the RLP list gadget
+#[derive(Clone, Debug)]
+pub(crate) struct RlpListConfig {
+    q_enable: Selector,
+    q_first: Column<Fixed>,
+    pub(crate) q_end: Column<Fixed>,
+    pub(crate) q_prefix: Column<Fixed>,
+    q_len: Column<Fixed>,
+    q_count_end: Column<Fixed>,
+    q_short: Column<Fixed>,
+    q_fixed: Column<Fixed>,
+    q_spliced: Column<Fixed>,
+    q_raw: Column<Fixed>,
+    q_header: Column<Fixed>,
+    header_byte: Column<Fixed>,
+    q_value: Column<Fixed>,
+    q_lc_reset: Column<Fixed>,
+    q_hi: Column<Fixed>,
+    pub(crate) q_splice: Column<Fixed>,
+
+    byte: Column<Advice>,
+    pad: Column<Advice>,
+    /// RLC of the encoding so far, by the keccak challenge
+    pub(crate) rlc: Column<Advice>,
+    /// Length of the encoding so far
+    pub(crate) len: Column<Advice>,
+    /// Power of the keccak challenge matching `len`
+    pub(crate) pow: Column<Advice>,
+    /// Linear combination of a chunk or of the length bytes; holds the hash of
+    /// the list on its end row
+    pub(crate) lc: WordLoHi<Column<Advice>>,
+    count: Column<Advice>,
+    pub(crate) item_len: Column<Advice>,
+    pub(crate) absent: Column<Advice>,
+    pub(crate) empty: Column<Advice>,
+    single: Column<Advice>,
+    long: Column<Advice>,
+    list_len: Column<Advice>,
+    hdr_len: Column<Advice>,
+    pub(crate) splice_rlc: Column<Advice>,
+    pub(crate) splice_pow: Column<Advice>,
+    splice_inv: Column<Advice>,
+    pub(crate) is_hashed: Column<Advice>,
+}
+
+impl RlpListConfig {
+    /// Configure the gadget, range checking against the `u16` fixed table
+    pub(crate) fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: &KeccakTable,
+        fixed_u16: Column<Fixed>,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let config = Self {
+            q_enable: meta.selector(),
+            q_first: meta.fixed_column(),
+            q_end: meta.fixed_column(),
+            q_prefix: meta.fixed_column(),
+            q_len: meta.fixed_column(),
+            q_count_end: meta.fixed_column(),
+            q_short: meta.fixed_column(),
+            q_fixed: meta.fixed_column(),
+            q_spliced: meta.fixed_column(),
+            q_raw: meta.fixed_column(),
+            q_header: meta.fixed_column(),
+            header_byte: meta.fixed_column(),
+            q_value: meta.fixed_column(),
+            q_lc_reset: meta.fixed_column(),
+            q_hi: meta.fixed_column(),
+            q_splice: meta.fixed_column(),
+            byte: meta.advice_column(),
+            pad: meta.advice_column(),
+            rlc: meta.advice_column_in(SecondPhase),
+            len: meta.advice_column(),
+            pow: meta.advice_column_in(SecondPhase),
+            lc: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            count: meta.advice_column(),
+            item_len: meta.advice_column(),
+            absent: meta.advice_column(),
+            empty: meta.advice_column(),
+            single: meta.advice_column(),
+            long: meta.advice_column(),
+            list_len: meta.advice_column(),
+            hdr_len: meta.advice_column(),
+            splice_rlc: meta.advice_column_in(SecondPhase),
+            splice_pow: meta.advice_column_in(SecondPhase),
+            splice_inv: meta.advice_column(),
+            is_hashed: meta.advice_column(),
+        };
+        for column in [
+            config.lc.lo(),
+            config.lc.hi(),
+            config.item_len,
+            config.absent,
+            config.empty,
+            config.is_hashed,
+        ] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("rlp list", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let r = challenges.keccak_input();
+
+            let fixed =
+                |meta: &mut VirtualCells<F>, column| meta.query_fixed(column, Rotation::cur());
+            let q_first = fixed(meta, config.q_first);
+            let q_end = fixed(meta, config.q_end);
+            let q_prefix = fixed(meta, config.q_prefix);
+            let q_len = fixed(meta, config.q_len);
+            let q_count_end = fixed(meta, config.q_count_end);
+            let q_short = fixed(meta, config.q_short);
+            let q_fixed = fixed(meta, config.q_fixed);
+            let q_spliced = fixed(meta, config.q_spliced);
+            let q_raw = fixed(meta, config.q_raw);
+            let q_header = fixed(meta, config.q_header);
+            let header_byte = fixed(meta, config.header_byte);
+            let q_value = fixed(meta, config.q_value);
+            let q_lc_reset = fixed(meta, config.q_lc_reset);
+            let q_hi = fixed(meta, config.q_hi);
+            let q_splice = fixed(meta, config.q_splice);
+
+            let cur =
+                |meta: &mut VirtualCells<F>, column| meta.query_advice(column, Rotation::cur());
+            let prev =
+                |meta: &mut VirtualCells<F>, column| meta.query_advice(column, Rotation::prev());
+            let byte = cur(meta, config.byte);
+            let pad = cur(meta, config.pad);
+            let emit = not::expr(pad.expr());
+            let count = cur(meta, config.count);
+            let item_len = cur(meta, config.item_len);
+            let absent = cur(meta, config.absent);
+            let empty = cur(meta, config.empty);
+            let single = cur(meta, config.single);
+            let long = cur(meta, config.long);
+            let list_len = cur(meta, config.list_len);
+            let hdr_len = cur(meta, config.hdr_len);
+            let splice_rlc = cur(meta, config.splice_rlc);
+            let splice_pow = cur(meta, config.splice_pow);
+            let splice_inv = cur(meta, config.splice_inv);
+            let is_hashed = cur(meta, config.is_hashed);
+            let [rlc, len, pow] = [config.rlc, config.len, config.pow].map(|c| cur(meta, c));
+            let [rlc_prev, len_prev, pow_prev] =
+                [config.rlc, config.len, config.pow].map(|c| prev(meta, c));
+            let [lc_lo, lc_hi] = [config.lc.lo(), config.lc.hi()].map(|c| cur(meta, c));
+            let [lc_lo_prev, lc_hi_prev] = [config.lc.lo(), config.lc.hi()].map(|c| prev(meta, c));
+            let pad_prev = prev(meta, config.pad);
+            let count_prev = prev(meta, config.count);
+
+            for (name, flag) in [
+                ("pad is boolean", pad.expr()),
+                ("absent is boolean", absent.expr()),
+                ("empty is boolean", empty.expr()),
+                ("single is boolean", single.expr()),
+                ("long is boolean", long.expr()),
+                ("is_hashed is boolean", is_hashed.expr()),
+            ] {
+                cb.require_boolean(name, flag);
+            }
+            cb.require_zero("padding is zero", pad.expr() * byte.expr());
+
+            cb.condition(q_first.expr(), |cb| {
+                cb.require_equal("rlc starts with the list prefix", rlc.expr(), byte.expr());
+                cb.require_equal("len starts at 1", len.expr(), 1.expr());
+                cb.require_equal("pow starts at r", pow.expr(), r.expr());
+                cb.require_zero("list prefix is emitted", pad.expr());
+                cb.require_equal(
+                    "list prefix",
+                    byte.expr(),
+                    long.expr() * (0xf7.expr() + count.expr())
+                        + not::expr(long.expr()) * (0xc0.expr() + list_len.expr()),
+                );
+            });
+
+            cb.condition(not::expr(q_first.expr()), |cb| {
+                let not_splice = not::expr(q_splice.expr());
+                cb.require_zero(
+                    "rlc accumulates the emitted byte",
+                    not_splice.expr()
+                        * (rlc.expr()
+                            - rlc_prev.expr()
+                            - emit.expr()
+                                * (rlc_prev.expr() * (r.expr() - 1.expr()) + byte.expr())),
+                );
+                cb.require_zero(
+                    "len counts the emitted byte",
+                    not_splice.expr() * (len.expr() - len_prev.expr() - emit.expr()),
+                );
+                cb.require_zero(
+                    "pow follows len",
+                    not_splice.expr()
+                        * (pow.expr()
+                            - pow_prev.expr()
+                            - emit.expr() * pow_prev.expr() * (r.expr() - 1.expr())),
+                );
+                cb.require_zero(
+                    "rlc accumulates the spliced content",
+                    q_splice.expr()
+                        * (rlc.expr() - rlc_prev.expr() * splice_pow.expr() - splice_rlc.expr()),
+                );
+                cb.require_zero(
+                    "len counts the spliced content",
+                    q_splice.expr() * (len.expr() - len_prev.expr() - item_len.expr()),
+                );
+                cb.require_zero(
+                    "pow follows the spliced content",
+                    q_splice.expr() * (pow.expr() - pow_prev.expr() * splice_pow.expr()),
+                );
+                cb.require_zero("splice row is not emitted", q_splice.expr() * emit.expr());
+
+                for (name, column) in [
+                    ("list_len is constant", config.list_len),
+                    ("hdr_len is constant", config.hdr_len),
+                ] {
+                    cb.require_equal(name, cur(meta, column), prev(meta, column));
+                }
+                for (name, column) in [
+                    ("item_len is constant", config.item_len),
+                    ("absent is constant", config.absent),
+                    ("empty is constant", config.empty),
+                    ("single is constant", config.single),
+                    ("long is constant", config.long),
+                ] {
+                    cb.require_zero(
+                        name,
+                        not::expr(q_prefix.expr()) * (cur(meta, column) - prev(meta, column)),
+                    );
+                }
+            });
+
+            // Length bytes of the list and of spliced items
+            cb.condition(q_len.expr(), |cb| {
+                cb.require_equal(
+                    "count down the length bytes",
+                    count.expr(),
+                    count_prev.expr() - emit.expr(),
+                );
+                cb.require_zero(
+                    "no length bytes in short form",
+                    not::expr(long.expr()) * emit.expr(),
+                );
+                cb.require_zero(
+                    "length bytes are right aligned",
+                    not::expr(q_lc_reset.expr()) * pad.expr() * not::expr(pad_prev.expr()),
+                );
+                let length = q_spliced.expr() * item_len.expr()
+                    + not::expr(q_spliced.expr()) * list_len.expr();
+                cb.require_zero(
+                    "all length bytes emitted",
+                    q_count_end.expr() * count.expr(),
+                );
+                cb.require_zero(
+                    "at least one length byte in long form",
+                    q_count_end.expr() * long.expr() * pad.expr(),
+                );
+                cb.require_zero(
+                    "length bytes match the length",
+                    q_count_end.expr() * long.expr() * (lc_lo.expr() - length),
+                );
+                cb.require_zero(
+                    "hdr_len is the length of the list header",
+                    q_count_end.expr()
+                        * not::expr(q_spliced.expr())
+                        * (hdr_len.expr() - len.expr()),
+                );
+            });
+
+            // Linear combination of the chunks and of the length bytes
+            cb.condition(q_value.expr() + q_len.expr(), |cb| {
+                let lo = not::expr(q_lc_reset.expr()) * lc_lo_prev;
+                let hi = not::expr(q_lc_reset.expr()) * lc_hi_prev;
+                cb.require_equal(
+                    "lc_lo",
+                    lc_lo.expr(),
+                    lo.expr() + not::expr(q_hi.expr()) * (lo.expr() * 255.expr() + byte.expr()),
+                );
+                cb.require_equal(
+                    "lc_hi",
+                    lc_hi.expr(),
+                    hi.expr() + q_hi.expr() * (hi.expr() * 255.expr() + byte.expr()),
+                );
+            });
+
+            let q_item = q_short.expr() + q_fixed.expr() + q_spliced.expr() + q_raw.expr();
+            cb.condition(q_item, |cb| {
+                cb.require_zero("absent item is not emitted", absent.expr() * emit.expr());
+            });
+
+            cb.condition(q_short.expr(), |cb| {
+                cb.require_zero(
+                    "short prefix",
+                    q_prefix.expr() * emit.expr() * (byte.expr() - 0x80.expr() - count.expr()),
+                );
+                cb.require_zero(
+                    "short prefix is emitted unless absent or single",
+                    q_prefix.expr() * (pad.expr() - absent.expr() - single.expr()),
+                );
+                cb.require_zero(
+                    "short item length",
+                    q_prefix.expr() * (item_len.expr() - count.expr()),
+                );
+                cb.require_zero(
+                    "single byte item",
+                    q_prefix.expr() * single.expr() * (count.expr() - 1.expr()),
+                );
+                cb.require_zero(
+                    "count down the short item bytes",
+                    q_value.expr() * (count.expr() - count_prev.expr() + emit.expr()),
+                );
+                cb.require_zero(
+                    "short item bytes are right aligned",
+                    q_value.expr()
+                        * not::expr(q_lc_reset.expr())
+                        * pad.expr()
+                        * not::expr(pad_prev.expr()),
+                );
+                cb.require_zero(
+                    "all short item bytes emitted",
+                    q_count_end.expr() * count.expr(),
+                );
+            });
+
+            cb.condition(q_fixed.expr(), |cb| {
+                cb.require_zero(
+                    "fixed item is absent or empty",
+                    q_prefix.expr() * absent.expr() * empty.expr(),
+                );
+                cb.require_zero(
+                    "fixed item header padding",
+                    q_header.expr()
+                        * (pad.expr() - absent.expr() - not::expr(q_prefix.expr()) * empty.expr()),
+                );
+                cb.require_zero(
+                    "fixed item header",
+                    q_header.expr()
+                        * emit.expr()
+                        * (byte.expr()
+                            - header_byte.expr()
+                            - empty.expr() * (0x80.expr() - header_byte.expr())),
+                );
+                cb.require_zero(
+                    "fixed item padding",
+                    q_value.expr() * (pad.expr() - absent.expr() - empty.expr()),
+                );
+            });
+
+            cb.condition(q_spliced.expr(), |cb| {
+                cb.require_zero(
+                    "spliced prefix is emitted unless absent or single",
+                    q_prefix.expr() * (pad.expr() - absent.expr() - single.expr()),
+                );
+                cb.require_zero(
+                    "spliced prefix",
+                    q_prefix.expr()
+                        * emit.expr()
+                        * (byte.expr()
+                            - long.expr() * (0xb7.expr() + count.expr())
+                            - not::expr(long.expr()) * (0x80.expr() + item_len.expr())),
+                );
+                cb.require_zero(
+                    "absent spliced item is empty",
+                    q_prefix.expr() * absent.expr() * (item_len.expr() + long.expr()),
+                );
+                cb.require_zero(
+                    "single byte spliced item",
+                    q_prefix.expr() * single.expr() * (item_len.expr() - 1.expr() + long.expr()),
+                );
+            });
+
+            cb.condition(q_raw.expr(), |cb| {
+                cb.require_equal("raw item has no header", pad.expr(), 1.expr());
+                cb.require_zero(
+                    "absent raw item is empty",
+                    q_prefix.expr() * absent.expr() * item_len.expr(),
+                );
+            });
+
+            cb.condition(q_splice.expr(), |cb| {
+                let nonzero = item_len.expr() * splice_inv.expr();
+                cb.require_zero(
+                    "splice_inv is the inverse of item_len",
+                    item_len.expr() * not::expr(nonzero.expr()),
+                );
+                cb.require_zero(
+                    "empty splice rlc",
+                    not::expr(nonzero.expr()) * splice_rlc.expr(),
+                );
+                cb.require_zero(
+                    "empty splice pow",
+                    not::expr(nonzero) * (splice_pow.expr() - 1.expr()),
+                );
+            });
+
+            cb.condition(q_end.expr(), |cb| {
+                cb.require_equal("end row is not emitted", pad.expr(), 1.expr());
+                cb.require_equal("list length", len.expr(), list_len.expr() + hdr_len.expr());
+                cb.require_zero(
+                    "hash_lo is zero when not hashed",
+                    not::expr(is_hashed.expr()) * lc_lo.expr(),
+                );
+                cb.require_zero(
+                    "hash_hi is zero when not hashed",
+                    not::expr(is_hashed.expr()) * lc_hi,
+                );
+            });
+
+            cb.gate(meta.query_selector(config.q_enable))
+        });
+
+        meta.lookup_any("rlp list range checks", |meta| {
+            let fixed =
+                |meta: &mut VirtualCells<F>, column| meta.query_fixed(column, Rotation::cur());
+            let cur =
+                |meta: &mut VirtualCells<F>, column| meta.query_advice(column, Rotation::cur());
+            let single = cur(meta, config.single);
+            let long = cur(meta, config.long);
+
+            // single bytes are below 0x80 and short forms are at most 55 bytes
+            let value = fixed(meta, config.q_short)
+                * fixed(meta, config.q_count_end)
+                * single.expr()
+                * (0x7f.expr() - cur(meta, config.byte))
+                + fixed(meta, config.q_splice)
+                    * single.expr()
+                    * (0x7f.expr() - cur(meta, config.splice_rlc))
+                + fixed(meta, config.q_spliced)
+                    * fixed(meta, config.q_prefix)
+                    * not::expr(long.expr())
+                    * not::expr(single)
+                    * (55.expr() - cur(meta, config.item_len))
+                + fixed(meta, config.q_first)
+                    * not::expr(long)
+                    * (55.expr() - cur(meta, config.list_len));
+
+            vec![(value, meta.query_fixed(fixed_u16, Rotation::cur()))]
+        });
+
+        meta.lookup_any("rlp list keccak", |meta| {
+            let condition = meta.query_fixed(config.q_end, Rotation::cur())
+                * meta.query_advice(config.is_hashed, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(config.rlc, Rotation::cur()),
+                meta.query_advice(config.len, Rotation::cur()),
+                meta.query_advice(config.lc.lo(), Rotation::cur()),
+                meta.query_advice(config.lc.hi(), Rotation::cur()),
+            ];
+            let table = [
+                keccak_table.is_enabled,
+                keccak_table.input_rlc,
+                keccak_table.input_len,
+                keccak_table.output.lo(),
+                keccak_table.output.hi(),
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (condition.expr() * input, table))
+                .collect()
+        });
+
+        config
+    }
+
+    /// Whether the content spliced in on the current row is not empty
+    pub(crate) fn splice_nonzero<F: Field>(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
+        meta.query_advice(self.item_len, Rotation::cur())
+            * meta.query_advice(self.splice_inv, Rotation::cur())
+    }
+
+    /// Assign a row of zeros before the first list of a region, as the first
+    /// row of a list queries the previous one.
+    pub(crate) fn assign_zero_row<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+    ) -> Result<(), Error> {
+        for column in [
+            self.byte,
+            self.pad,
+            self.rlc,
+            self.len,
+            self.pow,
+            self.lc.lo(),
+            self.lc.hi(),
+            self.count,
+            self.item_len,
+            self.absent,
+            self.empty,
+            self.single,
+            self.long,
+            self.list_len,
+            self.hdr_len,
+            self.splice_rlc,
+            self.splice_pow,
+            self.splice_inv,
+            self.is_hashed,
+        ] {
+            region.assign_advice(
+                || "rlp list zero row",
+                column,
+                offset,
+                || Value::known(F::ZERO),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Assign the list laid out by `layout` at `offset`, from the item values
+    /// (see [`RlpListLayout::encode`]).
+    pub(crate) fn assign<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        layout: &RlpListLayout,
+        values: &[Option<Vec<u8>>],
+        is_hashed: bool,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedRlpList<F>, Error> {
+        let r = challenges.keccak_input();
+        let rows = layout.rows(values);
+        let bytes = layout.encode(values);
+        let hash = if is_hashed {
+            WordLoHi::from(H256(keccak256(&bytes)))
+        } else {
+            WordLoHi::new([F::ZERO, F::ZERO])
+        };
+
+        let bool_value = |flag: bool| Value::known(if flag { F::ONE } else { F::ZERO });
+        let mut rlc_acc = Value::known(F::ZERO);
+        let mut pow_acc = Value::known(F::ONE);
+        let mut len_acc = 0u64;
+        let mut lc = [F::ZERO, F::ZERO];
+        let mut cells = vec![];
+        let mut items = vec![];
+        for (i, row) in rows.iter().enumerate() {
+            let offset = offset + i;
+            self.q_enable.enable(region, offset)?;
+            for (column, value) in [
+                (self.q_first, row.q_first as u64),
+                (self.q_end, row.q_end as u64),
+                (self.q_prefix, row.q_prefix as u64),
+                (self.q_len, row.q_len as u64),
+                (self.q_count_end, row.q_count_end as u64),
+                (self.q_short, row.q_short as u64),
+                (self.q_fixed, row.q_fixed as u64),
+                (self.q_spliced, row.q_spliced as u64),
+                (self.q_raw, row.q_raw as u64),
+                (self.q_header, row.q_header as u64),
+                (self.header_byte, row.header_byte),
+                (self.q_value, row.q_value as u64),
+                (self.q_lc_reset, row.q_lc_reset as u64),
+                (self.q_hi, row.q_hi as u64),
+                (self.q_splice, row.q_splice as u64),
+            ] {
+                region.assign_fixed(
+                    || "rlp list fixed",
+                    column,
+                    offset,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+
+            // accumulators
+            let (splice_rlc, splice_pow, splice_inv) = match &row.splice {
+                Some(content) => {
+                    let splice_rlc = r.map(|r| rlc::value(content.iter().rev(), r));
+                    let splice_pow = r.map(|r| (0..content.len()).fold(F::ONE, |acc, _| acc * r));
+                    rlc_acc = rlc_acc * splice_pow + splice_rlc;
+                    pow_acc = pow_acc * splice_pow;
+                    len_acc += content.len() as u64;
+                    let inv = F::from(content.len() as u64).invert().unwrap_or(F::ZERO);
+                    (splice_rlc, splice_pow, inv)
+                }
+                None => {
+                    if !row.pad {
+                        rlc_acc = rlc_acc * r + Value::known(F::from(row.byte as u64));
+                        pow_acc = pow_acc * r;
+                        len_acc += 1;
+                    }
+                    (Value::known(F::ZERO), Value::known(F::ZERO), F::ZERO)
+                }
+            };
+            if row.q_value || row.q_len {
+                if row.q_lc_reset {
+                    lc = [F::ZERO, F::ZERO];
+                }
+                let limb = if row.q_hi { 1 } else { 0 };
+                lc[limb] = lc[limb] * F::from(256) + F::from(row.byte as u64);
+            } else if row.q_end {
+                lc = [hash.lo(), hash.hi()];
+            } else {
+                lc = [F::ZERO, F::ZERO];
+            }
+
+            for (column, value) in [
+                (self.byte, Value::known(F::from(row.byte as u64))),
+                (self.pad, bool_value(row.pad)),
+                (self.rlc, rlc_acc),
+                (self.len, Value::known(F::from(len_acc))),
+                (self.pow, pow_acc),
+                (self.count, Value::known(F::from(row.count))),
+                (self.single, bool_value(row.single)),
+                (self.long, bool_value(row.long)),
+                (self.list_len, Value::known(F::from(row.list_len))),
+                (self.hdr_len, Value::known(F::from(row.hdr_len))),
+                (self.splice_rlc, splice_rlc),
+                (self.splice_pow, splice_pow),
+                (self.splice_inv, Value::known(splice_inv)),
+            ] {
+                region.assign_advice(|| "rlp list", column, offset, || value)?;
+            }
+            let lc = WordLoHi::new(lc).into_value().assign_advice(
+                region,
+                || "rlp list lc",
+                self.lc,
+                offset,
+            )?;
+            let absent = region.assign_advice(
+                || "absent",
+                self.absent,
+                offset,
+                || bool_value(row.absent),
+            )?;
+            let empty =
+                region.assign_advice(|| "empty", self.empty, offset, || bool_value(row.empty))?;
+            let item_len = region.assign_advice(
+                || "item_len",
+                self.item_len,
+                offset,
+                || Value::known(F::from(row.item_len)),
+            )?;
+            let is_hashed_cell = region.assign_advice(
+                || "is_hashed",
+                self.is_hashed,
+                offset,
+                || bool_value(row.q_end && is_hashed),
+            )?;
+            cells.push((lc, absent, empty, item_len, is_hashed_cell));
+        }
+
+        for item in 0..values.len() {
+            let (_, absent, empty, item_len, _) = cells[layout.prefix_row(item)].clone();
+            items.push(AssignedRlpItem {
+                absent,
+                empty,
+                item_len,
+                chunks: layout
+                    .chunk_ends(item)
+                    .into_iter()
+                    .map(|row| cells[row].0.clone())
+                    .collect(),
+            });
+        }
+        let (hash, _, _, _, is_hashed) = cells[layout.end()].clone();
+
+        Ok(AssignedRlpList {
+            items,
+            hash,
+            is_hashed,
+            bytes,
+        })
+    }
+}
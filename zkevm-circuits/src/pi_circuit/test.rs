@@ -6,10 +6,16 @@ use super::*;
 use bus_mapping::{
     circuit_input_builder::FixedCParams, mock::BlockData, state_db::EMPTY_CODE_HASH_LE,
 };
-use eth_types::{bytecode, geth_types::GethData, Address, Word, H160, H256};
+use eth_types::{
+    bytecode,
+    geth_types::{GethData, Transaction},
+    trie::EMPTY_TRIE_ROOT,
+    Address, Word, H160, H256,
+};
+use ethers_core::types::Bloom;
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
-    dev::{MockProver, VerifyFailure},
+    dev::{FailureLocation, MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
 };
 use mock::{eth, TestContext, TestContext2, CORRECT_MOCK_TXS, MOCK_ACCOUNTS, MOCK_CHAIN_ID};
@@ -63,6 +69,51 @@ fn test_default_pi() {
     );
 }
 
+#[test]
+fn test_pi_instance() {
+    let (max_txs, max_withdrawals, max_calldata) = (2, 2, 8);
+    let mut public_data = PublicData::default();
+    public_data.chain_id = *MOCK_CHAIN_ID;
+    public_data.state_root = H256::from_low_u64_be(0x1111);
+    public_data.prev_state_root = H256::from_low_u64_be(0x2222);
+
+    let circuit = PiCircuit::<Fr>::new(max_txs, max_withdrawals, max_calldata, public_data);
+    let instance = circuit.instance();
+    assert_eq!(instance.len(), 1);
+    assert_eq!(instance[0].len(), 6);
+
+    let digest =
+        circuit
+            .public_data
+            .get_rpi_digest_word::<Fr>(max_txs, max_withdrawals, max_calldata);
+    let state_root = WordLoHi::<Fr>::from(circuit.public_data.state_root);
+    let prev_state_root = WordLoHi::<Fr>::from(circuit.public_data.prev_state_root);
+    for (rows, word) in [
+        (RPI_DIGEST_INSTANCE_ROWS, digest),
+        (STATE_ROOT_INSTANCE_ROWS, state_root),
+        (PREV_STATE_ROOT_INSTANCE_ROWS, prev_state_root),
+    ] {
+        assert_eq!(
+            [instance[0][rows[0]], instance[0][rows[1]]],
+            [word.lo(), word.hi()]
+        );
+    }
+
+    let prover = MockProver::run(17, &circuit, instance.clone()).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Swapping the state roots must be rejected
+    let mut swapped = instance;
+    for (from, to) in STATE_ROOT_INSTANCE_ROWS
+        .into_iter()
+        .zip(PREV_STATE_ROOT_INSTANCE_ROWS)
+    {
+        swapped[0].swap(from, to);
+    }
+    let prover = MockProver::run(17, &circuit, swapped).unwrap();
+    assert!(prover.verify().is_err());
+}
+
 #[test]
 fn test_simple_pi() {
     let max_txs = 8;
@@ -86,6 +137,144 @@ fn test_simple_pi() {
     );
 }
 
+#[test]
+fn test_tx_trie_pi() {
+    let max_txs = 3;
+    let max_withdrawals = 1;
+    let max_calldata = 200;
+
+    // a single leaf, a branch of leaves, and padding tx slots
+    for n_tx in 1..=max_txs {
+        let mut public_data = PublicData::default();
+        for tx in CORRECT_MOCK_TXS.iter().take(n_tx) {
+            public_data.transactions.push(tx.clone().into());
+        }
+
+        let k = 17;
+        assert_eq!(
+            run::<Fr>(k, max_txs, max_withdrawals, max_calldata, public_data),
+            Ok(()),
+            "{} txs",
+            n_tx
+        );
+    }
+}
+
+fn header_public_data(difficulty: Word, mix_hash: H256, block_difficulty: Word) -> PublicData {
+    let mut public_data = PublicData::default();
+    public_data.block_constants.coinbase = H160([1u8; 20]);
+    public_data.block_constants.number = 0xcafe.into();
+    public_data.block_constants.difficulty = block_difficulty;
+    public_data.withdrawals_root = H256::from_low_u64_be(0xabcd);
+    public_data.header_values.difficulty = difficulty;
+    public_data.header_values.mix_hash = mix_hash;
+    public_data.header_values.extra_data = vec![0xaa; 32].into();
+    public_data.header_values.has_withdrawals_root = true;
+    public_data
+        .transactions
+        .push(CORRECT_MOCK_TXS[0].clone().into());
+    public_data
+}
+
+#[test]
+fn test_header_pi() {
+    let prevrandao = H256::from_low_u64_be(0x1234);
+    for public_data in [
+        // before the merge
+        header_public_data(0x20000.into(), H256::zero(), 0x20000.into()),
+        // after the merge
+        header_public_data(
+            Word::zero(),
+            prevrandao,
+            Word::from_big_endian(prevrandao.as_bytes()),
+        ),
+    ] {
+        assert_eq!(run::<Fr>(17, 2, 1, 200, public_data), Ok(()));
+    }
+}
+
+#[test]
+fn test_header_pi_difficulty_mismatch() {
+    let prevrandao = H256::from_low_u64_be(0x1234);
+    for public_data in [
+        header_public_data(0x20000.into(), prevrandao, 0x20001.into()),
+        header_public_data(
+            Word::zero(),
+            prevrandao,
+            Word::from_big_endian(prevrandao.as_bytes()) + 1,
+        ),
+    ] {
+        assert!(run::<Fr>(17, 2, 1, 200, public_data).is_err());
+    }
+}
+
+#[test]
+fn test_header_pi_extra_data() {
+    // empty, single byte and short byte strings
+    for extra_data in [vec![], vec![0x7f], vec![0x80], vec![0x00, 0x01]] {
+        let mut public_data = header_public_data(0x20000.into(), H256::zero(), 0x20000.into());
+        public_data.header_values.extra_data = extra_data.clone().into();
+        assert_eq!(
+            run::<Fr>(17, 2, 1, 200, public_data),
+            Ok(()),
+            "extra data {:?}",
+            extra_data
+        );
+    }
+}
+
+#[test]
+fn test_header_pi_blob_fields() {
+    // zero, single byte and multi byte blob gas values
+    for (blob_gas_used, excess_blob_gas) in [(0, 0), (0x7f, 0x80), (0x60000, 0x1234_5678)] {
+        let mut public_data = header_public_data(Word::zero(), H256::zero(), Word::zero());
+        public_data.header_values.blob_gas_used = blob_gas_used;
+        public_data.header_values.excess_blob_gas = excess_blob_gas;
+        public_data.header_values.parent_beacon_block_root = H256::from_low_u64_be(0xbeac);
+        public_data.header_values.has_blob_fields = true;
+        assert_eq!(
+            ethers_core::utils::rlp::Rlp::new(&public_data.get_block_header_rlp()).item_count(),
+            Ok(20)
+        );
+        assert_eq!(
+            run::<Fr>(17, 2, 1, 200, public_data),
+            Ok(()),
+            "blob gas used {}, excess blob gas {}",
+            blob_gas_used,
+            excess_blob_gas
+        );
+    }
+}
+
+#[test]
+fn test_header_pi_block_table_mismatch() {
+    let mut header_data = header_public_data(0x20000.into(), H256::zero(), 0x20000.into());
+    header_data.chain_id = *MOCK_CHAIN_ID;
+    let tampers: [fn(&mut PublicData); 3] = [
+        |public_data| public_data.block_constants.coinbase = H160([2u8; 20]),
+        |public_data| public_data.block_constants.timestamp += Word::one(),
+        |public_data| public_data.block_constants.base_fee += Word::one(),
+    ];
+    for tamper in tampers {
+        // The block table holds a tampered field while the header keeps the
+        // real one
+        let mut public_data = header_data.clone();
+        tamper(&mut public_data);
+        let mut circuit = PiCircuit::<Fr>::new(2, 1, 200, public_data);
+        circuit.header_data = Some(header_data.clone());
+        let prover = MockProver::run(17, &circuit, circuit.instance()).unwrap();
+
+        let failures = prover.verify().unwrap_err();
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            VerifyFailure::Permutation {
+                location: FailureLocation::InRegion { region, .. },
+                ..
+            } if region.to_string().ends_with("('block header')")
+        )));
+    }
+}
+
 #[test]
 fn test_1tx_1maxtx() {
     const MAX_TXS: usize = 1;
@@ -159,6 +348,87 @@ fn test_1tx_1maxtx() {
     assert_eq!(prover.verify(), Ok(()));
 }
 
+#[test]
+fn test_typed_txs() {
+    const MAX_TXS: usize = 1;
+    const MAX_WITHDRAWALS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+    let degree = 17;
+
+    // EIP-2930 and EIP-1559 transactions, whose effective gas price of
+    // min(2, 0 + 1) is the mock gas price
+    for transaction_type in [1, 2] {
+        let mut rng = ChaChaRng::seed_from_u64(2);
+        let wallet_a = LocalWallet::new(&mut rng).with_chain_id(MOCK_CHAIN_ID.as_u64());
+        let test_ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(wallet_a.address()).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .transaction_type(transaction_type)
+                    .max_priority_fee_per_gas(Word::one())
+                    .max_fee_per_gas(Word::from(2));
+            },
+            |block, _txs| block.chain_id(*MOCK_CHAIN_ID),
+        )
+        .unwrap();
+        let mut wallets = HashMap::new();
+        wallets.insert(wallet_a.address(), wallet_a);
+
+        let mut block: GethData = test_ctx.into();
+        block.sign(&wallets);
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            FixedCParams {
+                max_txs: MAX_TXS,
+                max_withdrawals: MAX_WITHDRAWALS,
+                max_calldata: MAX_CALLDATA,
+                max_rws: 1 << (degree - 1),
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let block = block_convert(&builder).unwrap();
+        let circuit = PiCircuit::<Fr>::new_from_block(&block);
+        let prover = MockProver::run(degree, &circuit, circuit.instance()).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "tx type {}", transaction_type);
+    }
+}
+
+#[test]
+fn test_tx_trie_pi_effective_gas_price() {
+    let mut tx: Transaction = CORRECT_MOCK_TXS[0].clone().into();
+    tx.transaction_type = 2.into();
+    tx.gas_tip_cap = Word::from(2);
+    tx.gas_fee_cap = Word::from(3);
+    tx.v = 1;
+
+    // the gas price of the tx table is min(max fee, base fee + max priority
+    // fee)
+    for (base_fee, gas_price, ok) in [(0, 2, true), (2, 3, true), (0, 3, false), (2, 4, false)] {
+        let mut public_data = PublicData::default();
+        public_data.block_constants.base_fee = Word::from(base_fee);
+        tx.gas_price = Word::from(gas_price);
+        public_data.transactions.push(tx.clone());
+
+        let result = run::<Fr>(17, 1, 1, 200, public_data);
+        assert_eq!(
+            result.is_ok(),
+            ok,
+            "base fee {base_fee}, gas price {gas_price}"
+        );
+    }
+}
+
 #[test]
 fn test_1wd_1wdmax() {
     const MAX_TXS: usize = 1;
@@ -239,6 +509,34 @@ fn test_1wd_1wdmax() {
     assert_eq!(prover.verify(), Ok(()));
 }
 
+#[test]
+fn test_block_hash_from_header() {
+    let block: GethData = TestContext::<0, 0>::new(
+        None,
+        |_| {},
+        |_, _| {},
+        |block, _| {
+            block
+                .transactions_root(EMPTY_TRIE_ROOT)
+                .logs_bloom(Bloom::zero())
+                .withdrawal_hash(Some(H256::from_low_u64_le(0xabcd)))
+                .hash()
+        },
+    )
+    .unwrap()
+    .into();
+    let mut builder =
+        BlockData::new_from_geth_data_with_params(block.clone(), FixedCParams::default())
+            .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let block = block_convert::<Fr>(&builder).unwrap();
+
+    let public_data = public_data_convert(&block);
+    assert_eq!(Some(public_data.get_block_hash()), block.eth_block.hash);
+}
+
 fn run_size_check<F: Field>(
     max_txs: usize,
     max_withdrawals: usize,
@@ -0,0 +1,1232 @@
+//! Transactions trie of the PiCircuit.
+//!
+//! The RLP encoding of every transaction is laid out with its fields bound to
+//! the tx table, and every node of the ordered trie of those encodings is laid
+//! out and hashed, from the root bound to the transactions root down to the
+//! leaves, which hold the transaction encodings: `rlp(tx)` for legacy
+//! transactions and `type || rlp(payload)` for EIP-2930 and EIP-1559 ones.
+//!
+//! The access lists of typed transactions must be empty, as the EVM circuit
+//! does not warm their entries up. The gas price of the tx table is the gas
+//! price of legacy and EIP-2930 transactions, and the effective gas price
+//! `min(max_fee, base_fee + max_priority_fee)` of EIP-1559 ones, whose fees
+//! must be below 2^128.
+//!
+//! Nodes are identified by their path from the root. The hash of every present
+//! child of a used node must be the hash of a used node at the child path, and
+//! every used node other than the root must be the child of a used node, so the
+//! used nodes are exactly the nodes of the trie with the given root. The key of
+//! each leaf is bound to the index of the transaction it holds.
+
+use std::collections::BTreeSet;
+
+use eth_types::{
+    geth_types::Transaction,
+    keccak256,
+    trie::{
+        bytes_to_nibbles, hex_prefix, ordered_trie_key, ordered_trie_nodes, TrieNode, TrieNodeKind,
+        EMPTY_TRIE_ROOT,
+    },
+    Field, ToBigEndian, Word, H256,
+};
+use ethers_core::utils::rlp;
+use gadgets::{
+    less_than::{LtChip, LtConfig, LtInstruction},
+    util::{not, Expr},
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::Itertools;
+
+use super::{
+    header::HeaderCells,
+    rlp_list::{AssignedRlpList, RlpItem, RlpListConfig, RlpListLayout},
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::KeccakTable,
+    util::{word::WordLoHi, Challenges},
+};
+
+/// Items of a transaction of any type: chain id, nonce, gas price, max
+/// priority fee, max fee, gas, to, value, data, access list, v, r and s.
+///
+/// Legacy transactions leave the chain id, the fee caps and the access list
+/// out, EIP-2930 ones the fee caps and EIP-1559 ones the gas price.
+pub(super) const TX_ITEMS: [RlpItem; 13] = [
+    RlpItem::Short(8),
+    RlpItem::Short(8),
+    RlpItem::Short(32),
+    RlpItem::Short(32),
+    RlpItem::Short(32),
+    RlpItem::Short(8),
+    RlpItem::Fixed(20),
+    RlpItem::Short(32),
+    RlpItem::Spliced,
+    RlpItem::Raw,
+    RlpItem::Short(8),
+    RlpItem::Short(32),
+    RlpItem::Short(32),
+];
+const TX_CHAIN_ID_ITEM: usize = 0;
+const TX_NONCE_ITEM: usize = 1;
+const TX_GAS_PRICE_ITEM: usize = 2;
+const TX_TIP_ITEM: usize = 3;
+const TX_FEE_CAP_ITEM: usize = 4;
+const TX_GAS_ITEM: usize = 5;
+/// Position of the callee address in [`TX_ITEMS`]
+pub(super) const TX_TO_ITEM: usize = 6;
+const TX_VALUE_ITEM: usize = 7;
+/// Position of the calldata in [`TX_ITEMS`]
+pub(super) const TX_DATA_ITEM: usize = 8;
+const TX_ACCESS_LIST_ITEM: usize = 9;
+/// RLP encoding of an empty access list
+const EMPTY_ACCESS_LIST: u8 = 0xc0;
+
+/// Bytes of the effective gas price comparison of EIP-1559 transactions, whose
+/// fees are below 2^128
+const N_BYTES_FEE: usize = 17;
+
+/// Bytes of the hex-prefix encoded path of a node, enough for the keys of up
+/// to 2^16 transactions
+const N_BYTES_HP: usize = 4;
+/// Position of the first child in the node layout
+const NODE_CHILD_ITEM: usize = 1;
+/// Position of the value in the node layout
+const NODE_VALUE_ITEM: usize = 17;
+
+fn node_layout() -> RlpListLayout {
+    RlpListLayout::new(
+        std::iter::once(RlpItem::Short(N_BYTES_HP))
+            .chain([RlpItem::Fixed(32); 16])
+            .chain(std::iter::once(RlpItem::Spliced))
+            .collect(),
+    )
+}
+
+fn tx_layout() -> RlpListLayout {
+    RlpListLayout::new(TX_ITEMS.to_vec())
+}
+
+/// Whether the transaction of type 0, 1 or 2 is typed
+fn is_typed<F: Field>(meta: &mut VirtualCells<F>, tx_type: Column<Advice>) -> Expression<F> {
+    let tx_type = meta.query_advice(tx_type, Rotation::cur());
+    tx_type.expr() * (3.expr() - tx_type) * Expression::Constant(F::from(2).invert().unwrap())
+}
+
+/// Whether the transaction of type 0, 1 or 2 is an EIP-1559 transaction
+fn is_dynamic<F: Field>(meta: &mut VirtualCells<F>, tx_type: Column<Advice>) -> Expression<F> {
+    let tx_type = meta.query_advice(tx_type, Rotation::cur());
+    tx_type.expr() * (tx_type - 1.expr()) * Expression::Constant(F::from(2).invert().unwrap())
+}
+
+/// Item values of a transaction, leaving out the items its type lacks
+fn tx_values(tx: &Transaction, chain_id: u64) -> Vec<Option<Vec<u8>>> {
+    let tx_type = tx.transaction_type.as_u64();
+    let typed = |value: Vec<u8>| (tx_type != 0).then_some(value);
+    let access_list = tx.access_list.clone().unwrap_or_default();
+    // typed txs carry the y parity, which may be given as an EIP-155 `v`
+    let v = if tx_type != 0 && tx.v > 1 {
+        tx.v - 35 - chain_id * 2
+    } else {
+        tx.v
+    };
+    vec![
+        typed(word_bytes(chain_id.into())),
+        Some(word_bytes(tx.nonce.as_u64().into())),
+        (tx_type != 2).then(|| word_bytes(tx.gas_price)),
+        (tx_type == 2).then(|| word_bytes(tx.gas_tip_cap)),
+        (tx_type == 2).then(|| word_bytes(tx.gas_fee_cap)),
+        Some(word_bytes(tx.gas_limit.as_u64().into())),
+        Some(tx.to.map(|to| to.0.to_vec()).unwrap_or_default()),
+        Some(word_bytes(tx.value)),
+        Some(tx.call_data.to_vec()),
+        typed(rlp::encode(&access_list).to_vec()),
+        Some(word_bytes(v.into())),
+        Some(word_bytes(tx.r)),
+        Some(word_bytes(tx.s)),
+    ]
+}
+
+/// Path nibbles packed as a base 16 number
+fn pack(nibbles: &[u8]) -> u64 {
+    nibbles
+        .iter()
+        .fold(0, |acc, nibble| acc * 16 + *nibble as u64)
+}
+
+/// Minimal big-endian bytes of a word
+pub(super) fn word_bytes(word: Word) -> Vec<u8> {
+    word.to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect()
+}
+
+/// Big-endian linear combination of bytes
+fn lc(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |acc, byte| acc * 256 + *byte as u64)
+}
+
+/// Number of nodes of the trie holding the sorted, prefix-free nibble `keys`,
+/// as listed by [`ordered_trie_nodes`]
+fn count_nodes(keys: &[&[u8]]) -> usize {
+    if keys.len() <= 1 {
+        return keys.len();
+    }
+    let (first, last) = (keys[0], keys[keys.len() - 1]);
+    let prefix_len = first
+        .iter()
+        .zip(last.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix_len > 0 {
+        return 1 + count_nodes(&keys.iter().map(|key| &key[prefix_len..]).collect_vec());
+    }
+    1 + keys
+        .iter()
+        .group_by(|key| key[0])
+        .into_iter()
+        .map(|(_, group)| count_nodes(&group.map(|key| &key[1..]).collect_vec()))
+        .sum::<usize>()
+}
+
+/// Number of node slots needed by the trie of up to `max_txs` transactions
+pub(super) fn max_trie_nodes(max_txs: usize) -> usize {
+    let keys = (0..max_txs)
+        .map(|index| bytes_to_nibbles(&ordered_trie_key(index)))
+        .collect_vec();
+    (1..=max_txs)
+        .map(|n| {
+            let mut keys = keys[..n].iter().map(|key| key.as_slice()).collect_vec();
+            keys.sort();
+            count_nodes(&keys)
+        })
+        .max()
+        .unwrap_or(0)
+        .max(1)
+}
+
+/// Number of rows of the transactions trie region
+pub(super) fn circuit_len(max_txs: usize) -> usize {
+    1 + max_txs * tx_layout().height() + max_trie_nodes(max_txs) * node_layout().height()
+}
+
+/// Row of the key table: a leaf or an extension at `(prefix, plen)` with the
+/// hex-prefix encoded path `hp`, and the path of the extension child.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct KeyRow {
+    is_leaf: bool,
+    key_idx: u64,
+    prefix: u64,
+    plen: u64,
+    hp: u64,
+    hp_len: u64,
+    child_prefix: u64,
+    child_plen: u64,
+}
+
+fn key_rows(max_txs: usize) -> BTreeSet<KeyRow> {
+    let mut rows = BTreeSet::new();
+    for index in 0..max_txs {
+        let key = bytes_to_nibbles(&ordered_trie_key(index));
+        for p in 0..key.len() {
+            let hp = hex_prefix(&key[p..], true);
+            rows.insert(KeyRow {
+                is_leaf: true,
+                key_idx: index as u64,
+                prefix: pack(&key[..p]),
+                plen: p as u64,
+                hp: lc(&hp),
+                hp_len: hp.len() as u64,
+                child_prefix: 0,
+                child_plen: 0,
+            });
+            for q in p + 1..key.len() {
+                let hp = hex_prefix(&key[p..q], false);
+                rows.insert(KeyRow {
+                    is_leaf: false,
+                    key_idx: 0,
+                    prefix: pack(&key[..p]),
+                    plen: p as u64,
+                    hp: lc(&hp),
+                    hp_len: hp.len() as u64,
+                    child_prefix: pack(&key[..q]),
+                    child_plen: q as u64,
+                });
+            }
+        }
+    }
+    rows
+}
+
+/// Tx table value cells bound to the encoding of a transaction
+pub(super) struct TxCells<F: Field> {
+    pub(super) nonce: WordLoHi<AssignedCell<F, F>>,
+    pub(super) gas: WordLoHi<AssignedCell<F, F>>,
+    pub(super) gas_price: WordLoHi<AssignedCell<F, F>>,
+    pub(super) caller: WordLoHi<AssignedCell<F, F>>,
+    pub(super) callee: WordLoHi<AssignedCell<F, F>>,
+    pub(super) is_create: WordLoHi<AssignedCell<F, F>>,
+    pub(super) value: WordLoHi<AssignedCell<F, F>>,
+    pub(super) call_data_length: WordLoHi<AssignedCell<F, F>>,
+}
+
+/// Config of the transactions trie
+#[derive(Clone, Debug)]
+pub(super) struct TxTrieConfig<F> {
+    pub(super) rlp: RlpListConfig,
+
+    // Transactions
+    /// 1 on every row of a transaction
+    q_tx: Column<Fixed>,
+    /// 1 on the first row of a transaction
+    q_tx_first: Column<Fixed>,
+    /// 1 on the prefix row of the items of every transaction type
+    q_tx_item: Column<Fixed>,
+    /// 1 on the prefix row of the chain id and of the access list, only found
+    /// in typed transactions
+    q_tx_typed_item: Column<Fixed>,
+    /// 1 on the prefix row of the gas price, found in all but EIP-1559
+    /// transactions
+    q_tx_gas_price_item: Column<Fixed>,
+    /// 1 on the prefix row of the fee caps, only found in EIP-1559
+    /// transactions
+    q_tx_fee_cap_item: Column<Fixed>,
+    /// 1 on the splice row of the access list of a transaction
+    q_tx_access_list: Column<Fixed>,
+    /// 1 on the splice row of the calldata of a transaction
+    pub(super) q_tx_data: Column<Fixed>,
+    /// 1 on the end row of a transaction
+    q_tx_end: Column<Fixed>,
+    /// Tx id on the calldata splice row and on the end row of a transaction
+    pub(super) tx_id: Column<Fixed>,
+    /// EIP-2718 type of the transaction, 0 for legacy transactions
+    tx_type: Column<Advice>,
+    /// Whether the transaction is in the block, i.e. its caller is not zero
+    is_real: Column<Advice>,
+    caller: WordLoHi<Column<Advice>>,
+    caller_inv: Column<Advice>,
+    /// RLC, length and power of the challenge of the leaf value of the
+    /// transaction, on its end row
+    leaf_rlc: Column<Advice>,
+    leaf_len: Column<Advice>,
+    leaf_pow: Column<Advice>,
+    /// Fields of the encoding and of the block table, on the end row of a
+    /// transaction
+    chain_id_item: WordLoHi<Column<Advice>>,
+    chain_id: WordLoHi<Column<Advice>>,
+    gas_price_item: WordLoHi<Column<Advice>>,
+    tip: WordLoHi<Column<Advice>>,
+    fee_cap: WordLoHi<Column<Advice>>,
+    base_fee: WordLoHi<Column<Advice>>,
+    /// Gas price of the tx table
+    gas_price: WordLoHi<Column<Advice>>,
+    /// Whether the max fee of an EIP-1559 transaction is below the base fee
+    /// plus its max priority fee
+    fee_cap_lt: LtConfig<F, N_BYTES_FEE>,
+
+    // Nodes
+    /// 1 on every row of a node
+    q_node: Column<Fixed>,
+    /// 1 on the first row of a node
+    q_node_first: Column<Fixed>,
+    /// 1 on the end row of the root node
+    q_root_end: Column<Fixed>,
+    /// 1 on the end row of the other nodes
+    q_node_end: Column<Fixed>,
+    /// 1 on the row of the hex-prefix encoded path
+    q_hp: Column<Fixed>,
+    /// 1 on the last row of each child
+    q_child: Column<Fixed>,
+    /// 1 on the last row of the first child
+    q_first_child: Column<Fixed>,
+    /// Nibble of the child on the last row of each child
+    nibble: Column<Fixed>,
+    /// 1 on the splice row of the value
+    q_value: Column<Fixed>,
+    is_branch: Column<Advice>,
+    is_ext: Column<Advice>,
+    is_leaf: Column<Advice>,
+    /// Path of the node, packed as a base 16 number
+    prefix: Column<Advice>,
+    /// Number of nibbles of the path of the node
+    plen: Column<Advice>,
+    /// Index of the transaction held by a leaf
+    key_idx: Column<Advice>,
+    /// Path of the child of an extension
+    child_prefix: Column<Advice>,
+    child_plen: Column<Advice>,
+    /// Path of the child, on the last row of each child
+    ref_prefix: Column<Advice>,
+    ref_plen: Column<Advice>,
+    /// Transactions root, on the end row of the root node
+    root: WordLoHi<Column<Advice>>,
+
+    // Key table
+    key_is_leaf: Column<Fixed>,
+    key_idx_table: Column<Fixed>,
+    key_prefix: Column<Fixed>,
+    key_plen: Column<Fixed>,
+    key_hp: Column<Fixed>,
+    key_hp_len: Column<Fixed>,
+    key_child_prefix: Column<Fixed>,
+    key_child_plen: Column<Fixed>,
+}
+
+impl<F: Field> TxTrieConfig<F> {
+    /// Configure the transactions trie
+    pub(super) fn configure(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: &KeccakTable,
+        fixed_u16: Column<Fixed>,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let rlp = RlpListConfig::configure(meta, keccak_table, fixed_u16, challenges);
+        let q_tx_end = meta.fixed_column();
+        let tx_type = meta.advice_column();
+        let [tip, fee_cap, base_fee] =
+            [(); 3].map(|_| WordLoHi::new([meta.advice_column(), meta.advice_column()]));
+        let fee_cap_lt = LtChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_tx_end, Rotation::cur()) * is_dynamic(meta, tx_type),
+            |meta| meta.query_advice(fee_cap.lo(), Rotation::cur()),
+            |meta| {
+                meta.query_advice(base_fee.lo(), Rotation::cur())
+                    + meta.query_advice(tip.lo(), Rotation::cur())
+            },
+        );
+        let config = Self {
+            rlp,
+            q_tx: meta.fixed_column(),
+            q_tx_first: meta.fixed_column(),
+            q_tx_item: meta.fixed_column(),
+            q_tx_typed_item: meta.fixed_column(),
+            q_tx_gas_price_item: meta.fixed_column(),
+            q_tx_fee_cap_item: meta.fixed_column(),
+            q_tx_access_list: meta.fixed_column(),
+            q_tx_data: meta.fixed_column(),
+            q_tx_end,
+            tx_id: meta.fixed_column(),
+            tx_type,
+            is_real: meta.advice_column(),
+            caller: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            caller_inv: meta.advice_column(),
+            leaf_rlc: meta.advice_column_in(SecondPhase),
+            leaf_len: meta.advice_column(),
+            leaf_pow: meta.advice_column_in(SecondPhase),
+            chain_id_item: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            chain_id: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            gas_price_item: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            tip,
+            fee_cap,
+            base_fee,
+            gas_price: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            fee_cap_lt,
+            q_node: meta.fixed_column(),
+            q_node_first: meta.fixed_column(),
+            q_root_end: meta.fixed_column(),
+            q_node_end: meta.fixed_column(),
+            q_hp: meta.fixed_column(),
+            q_child: meta.fixed_column(),
+            q_first_child: meta.fixed_column(),
+            nibble: meta.fixed_column(),
+            q_value: meta.fixed_column(),
+            is_branch: meta.advice_column(),
+            is_ext: meta.advice_column(),
+            is_leaf: meta.advice_column(),
+            prefix: meta.advice_column(),
+            plen: meta.advice_column(),
+            key_idx: meta.advice_column(),
+            child_prefix: meta.advice_column(),
+            child_plen: meta.advice_column(),
+            ref_prefix: meta.advice_column(),
+            ref_plen: meta.advice_column(),
+            root: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            key_is_leaf: meta.fixed_column(),
+            key_idx_table: meta.fixed_column(),
+            key_prefix: meta.fixed_column(),
+            key_plen: meta.fixed_column(),
+            key_hp: meta.fixed_column(),
+            key_hp_len: meta.fixed_column(),
+            key_child_prefix: meta.fixed_column(),
+            key_child_plen: meta.fixed_column(),
+        };
+        for word in [
+            config.caller,
+            config.chain_id_item,
+            config.chain_id,
+            config.gas_price_item,
+            config.tip,
+            config.fee_cap,
+            config.base_fee,
+            config.gas_price,
+            config.root,
+        ] {
+            meta.enable_equality(word.lo());
+            meta.enable_equality(word.hi());
+        }
+        let rlp = &config.rlp;
+
+        meta.create_gate("tx encoding", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let fixed =
+                |meta: &mut VirtualCells<F>, column| meta.query_fixed(column, Rotation::cur());
+            let cur =
+                |meta: &mut VirtualCells<F>, column| meta.query_advice(column, Rotation::cur());
+            let word = |meta: &mut VirtualCells<F>, word: WordLoHi<Column<Advice>>| {
+                word.query_advice(meta, Rotation::cur())
+            };
+            let q_tx_first = fixed(meta, config.q_tx_first);
+            let q_tx_item = fixed(meta, config.q_tx_item);
+            let q_tx_typed_item = fixed(meta, config.q_tx_typed_item);
+            let q_tx_gas_price_item = fixed(meta, config.q_tx_gas_price_item);
+            let q_tx_fee_cap_item = fixed(meta, config.q_tx_fee_cap_item);
+            let q_tx_access_list = fixed(meta, config.q_tx_access_list);
+            let q_tx_end = fixed(meta, config.q_tx_end);
+            let tx_type = cur(meta, config.tx_type);
+            let is_typed = is_typed(meta, config.tx_type);
+            let is_dynamic = is_dynamic(meta, config.tx_type);
+            let absent = cur(meta, rlp.absent);
+            let is_real = cur(meta, config.is_real);
+            let caller = word(meta, config.caller).compress();
+            let caller_inv = cur(meta, config.caller_inv);
+            let r = challenges.keccak_input();
+
+            cb.require_zero(
+                "tx_type is 0, 1 or 2",
+                tx_type.expr() * (tx_type.expr() - 1.expr()) * (tx_type.expr() - 2.expr()),
+            );
+            cb.require_zero(
+                "tx_type is constant",
+                not::expr(q_tx_first)
+                    * (tx_type.expr() - meta.query_advice(config.tx_type, Rotation::prev())),
+            );
+            cb.require_zero("tx fields are present", q_tx_item * absent.expr());
+            cb.require_zero(
+                "chain id and access list only in typed txs",
+                q_tx_typed_item * (absent.expr() - not::expr(is_typed.expr())),
+            );
+            cb.require_zero(
+                "gas price in all but EIP-1559 txs",
+                q_tx_gas_price_item * (absent.expr() - is_dynamic.expr()),
+            );
+            cb.require_zero(
+                "fee caps only in EIP-1559 txs",
+                q_tx_fee_cap_item * (absent.expr() - not::expr(is_dynamic.expr())),
+            );
+            cb.condition(q_tx_access_list * not::expr(absent), |cb| {
+                cb.require_equal("access list is empty", cur(meta, rlp.item_len), 1.expr());
+                cb.require_equal(
+                    "access list is the empty list",
+                    cur(meta, rlp.splice_rlc),
+                    EMPTY_ACCESS_LIST.expr(),
+                );
+                cb.require_equal("access list pow", cur(meta, rlp.splice_pow), r.expr());
+            });
+
+            cb.condition(q_tx_end.expr(), |cb| {
+                cb.require_boolean("is_real is boolean", is_real.expr());
+                cb.require_equal(
+                    "is_real when the caller is not zero",
+                    is_real.expr(),
+                    caller.expr() * caller_inv,
+                );
+                cb.require_zero("caller is zero unless real", caller * not::expr(is_real));
+
+                // typed txs are prefixed with their type
+                cb.require_equal(
+                    "leaf rlc",
+                    cur(meta, config.leaf_rlc),
+                    tx_type.expr() * cur(meta, rlp.pow) + cur(meta, rlp.rlc),
+                );
+                cb.require_equal(
+                    "leaf len",
+                    cur(meta, config.leaf_len),
+                    cur(meta, rlp.len) + is_typed.expr(),
+                );
+                cb.require_equal(
+                    "leaf pow",
+                    cur(meta, config.leaf_pow),
+                    cur(meta, rlp.pow) * (1.expr() + is_typed.expr() * (r.expr() - 1.expr())),
+                );
+            });
+
+            let chain_id_item = word(meta, config.chain_id_item);
+            let chain_id = word(meta, config.chain_id);
+            cb.condition(q_tx_end.expr() * is_typed, |cb| {
+                cb.require_equal_word("chain id of typed txs", chain_id_item, chain_id);
+            });
+
+            let gas_price = word(meta, config.gas_price);
+            let gas_price_item = word(meta, config.gas_price_item);
+            cb.condition(q_tx_end.expr() * not::expr(is_dynamic.expr()), |cb| {
+                cb.require_equal_word("gas price", gas_price.clone(), gas_price_item);
+            });
+            let [tip, fee_cap, base_fee] =
+                [config.tip, config.fee_cap, config.base_fee].map(|w| word(meta, w));
+            let fee_cap_lt = config.fee_cap_lt.is_lt(meta, None);
+            cb.condition(q_tx_end * is_dynamic, |cb| {
+                for (name, hi) in [
+                    ("max priority fee is below 2^128", tip.hi()),
+                    ("max fee is below 2^128", fee_cap.hi()),
+                    ("base fee is below 2^128", base_fee.hi()),
+                    ("effective gas price is below 2^128", gas_price.hi()),
+                ] {
+                    cb.require_zero(name, hi);
+                }
+                cb.require_equal(
+                    "effective gas price is min(max fee, base fee + max priority fee)",
+                    gas_price.lo(),
+                    fee_cap_lt.expr() * fee_cap.lo()
+                        + not::expr(fee_cap_lt) * (base_fee.lo() + tip.lo()),
+                );
+            });
+
+            cb.gate(fixed(meta, config.q_tx))
+        });
+
+        meta.create_gate("trie node", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let fixed =
+                |meta: &mut VirtualCells<F>, column| meta.query_fixed(column, Rotation::cur());
+            let cur =
+                |meta: &mut VirtualCells<F>, column| meta.query_advice(column, Rotation::cur());
+            let q_node_first = fixed(meta, config.q_node_first);
+            let q_root_end = fixed(meta, config.q_root_end);
+            let q_node_end = fixed(meta, config.q_node_end);
+            let q_hp = fixed(meta, config.q_hp);
+            let q_child = fixed(meta, config.q_child);
+            let q_first_child = fixed(meta, config.q_first_child);
+            let nibble = fixed(meta, config.nibble);
+            let q_value = fixed(meta, config.q_value);
+
+            let is_branch = cur(meta, config.is_branch);
+            let is_ext = cur(meta, config.is_ext);
+            let is_leaf = cur(meta, config.is_leaf);
+            let used = is_branch.expr() + is_ext.expr() + is_leaf.expr();
+            let prefix = cur(meta, config.prefix);
+            let plen = cur(meta, config.plen);
+            let absent = cur(meta, rlp.absent);
+            let empty = cur(meta, rlp.empty);
+            let item_len = cur(meta, rlp.item_len);
+            let is_hashed = cur(meta, rlp.is_hashed);
+            let lc = rlp.lc.map(|column| cur(meta, column));
+            let root = config.root.map(|column| cur(meta, column));
+
+            cb.require_boolean("is_branch is boolean", is_branch.expr());
+            cb.require_boolean("is_ext is boolean", is_ext.expr());
+            cb.require_boolean("is_leaf is boolean", is_leaf.expr());
+            cb.require_boolean("at most one node kind", used.expr());
+
+            cb.condition(not::expr(q_node_first), |cb| {
+                for (name, column) in [
+                    ("is_branch is constant", config.is_branch),
+                    ("is_ext is constant", config.is_ext),
+                    ("is_leaf is constant", config.is_leaf),
+                    ("prefix is constant", config.prefix),
+                    ("plen is constant", config.plen),
+                    ("key_idx is constant", config.key_idx),
+                    ("child_prefix is constant", config.child_prefix),
+                    ("child_plen is constant", config.child_plen),
+                ] {
+                    cb.require_equal(
+                        name,
+                        cur(meta, column),
+                        meta.query_advice(column, Rotation::prev()),
+                    );
+                }
+            });
+
+            // Branch: [child; 16] + empty value, extension: [path, child],
+            // leaf: [path, value]
+            cb.require_zero(
+                "path only in extensions and leaves",
+                q_hp * (absent.expr() - not::expr(is_ext.expr() + is_leaf.expr())),
+            );
+            cb.require_zero(
+                "children only in branches and extensions",
+                q_child.expr()
+                    * (absent.expr()
+                        - not::expr(is_branch.expr() + q_first_child.expr() * is_ext.expr())),
+            );
+            cb.require_zero(
+                "extension child is not empty",
+                q_first_child * is_ext.expr() * empty,
+            );
+            cb.require_zero(
+                "value only in branches and leaves",
+                q_value.expr() * (absent - not::expr(is_branch.expr() + is_leaf.expr())),
+            );
+            cb.require_zero(
+                "branch value is empty",
+                q_value * is_branch.expr() * item_len,
+            );
+            cb.require_zero(
+                "child path",
+                q_child.expr()
+                    * (cur(meta, config.ref_prefix)
+                        - is_branch.expr() * (prefix.expr() * 16.expr() + nibble)
+                        - is_ext.expr() * cur(meta, config.child_prefix)),
+            );
+            cb.require_zero(
+                "child path length",
+                q_child
+                    * (cur(meta, config.ref_plen)
+                        - is_branch.expr() * (plen.expr() + 1.expr())
+                        - is_ext * cur(meta, config.child_plen)),
+            );
+
+            cb.require_zero(
+                "used nodes are hashed",
+                (q_root_end.expr() + q_node_end) * (is_hashed - used.expr()),
+            );
+            cb.condition(q_root_end, |cb| {
+                cb.require_zero("root path is empty", plen);
+                cb.require_zero("root path is zero", prefix);
+                let empty_root = WordLoHi::<F>::from(EMPTY_TRIE_ROOT);
+                cb.require_equal(
+                    "transactions root lo",
+                    root.lo(),
+                    used.expr() * lc.lo() + not::expr(used.expr()) * empty_root.lo().expr(),
+                );
+                cb.require_equal(
+                    "transactions root hi",
+                    root.hi(),
+                    used.expr() * lc.hi() + not::expr(used) * empty_root.hi().expr(),
+                );
+            });
+
+            cb.gate(fixed(meta, config.q_node))
+        });
+
+        meta.lookup_any("trie leaf and extension paths", |meta| {
+            let q_hp = meta.query_fixed(config.q_hp, Rotation::cur());
+            let is_ext = meta.query_advice(config.is_ext, Rotation::cur());
+            let is_leaf = meta.query_advice(config.is_leaf, Rotation::cur());
+            let condition = q_hp * (is_ext.expr() + is_leaf.expr());
+            let input = [
+                is_leaf.expr(),
+                is_leaf * meta.query_advice(config.key_idx, Rotation::cur()),
+                meta.query_advice(config.prefix, Rotation::cur()),
+                meta.query_advice(config.plen, Rotation::cur()),
+                meta.query_advice(rlp.lc.lo(), Rotation::cur()),
+                meta.query_advice(rlp.item_len, Rotation::cur()),
+                is_ext.expr() * meta.query_advice(config.child_prefix, Rotation::cur()),
+                is_ext * meta.query_advice(config.child_plen, Rotation::cur()),
+            ];
+            let table = [
+                config.key_is_leaf,
+                config.key_idx_table,
+                config.key_prefix,
+                config.key_plen,
+                config.key_hp,
+                config.key_hp_len,
+                config.key_child_prefix,
+                config.key_child_plen,
+            ]
+            .map(|column| meta.query_fixed(column, Rotation::cur()));
+
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (condition.expr() * input, table))
+                .collect()
+        });
+
+        // (path, path length, hash) of present children and of used nodes
+        let child = |meta: &mut VirtualCells<F>| {
+            let condition = meta.query_fixed(config.q_child, Rotation::cur())
+                * not::expr(
+                    meta.query_advice(rlp.absent, Rotation::cur())
+                        + meta.query_advice(rlp.empty, Rotation::cur()),
+                );
+            [
+                meta.query_advice(config.ref_prefix, Rotation::cur()),
+                meta.query_advice(config.ref_plen, Rotation::cur()),
+                meta.query_advice(rlp.lc.lo(), Rotation::cur()),
+                meta.query_advice(rlp.lc.hi(), Rotation::cur()),
+            ]
+            .map(|value| condition.expr() * value)
+        };
+        let node = |meta: &mut VirtualCells<F>, include_root: bool| {
+            let q_end = meta.query_fixed(config.q_node_end, Rotation::cur())
+                + if include_root {
+                    meta.query_fixed(config.q_root_end, Rotation::cur())
+                } else {
+                    0.expr()
+                };
+            let condition = q_end
+                * (meta.query_advice(config.is_branch, Rotation::cur())
+                    + meta.query_advice(config.is_ext, Rotation::cur())
+                    + meta.query_advice(config.is_leaf, Rotation::cur()));
+            [
+                meta.query_advice(config.prefix, Rotation::cur()),
+                meta.query_advice(config.plen, Rotation::cur()),
+                meta.query_advice(rlp.lc.lo(), Rotation::cur()),
+                meta.query_advice(rlp.lc.hi(), Rotation::cur()),
+            ]
+            .map(|value| condition.expr() * value)
+        };
+        meta.lookup_any("trie children are used nodes", |meta| {
+            child(meta).into_iter().zip(node(meta, true)).collect()
+        });
+        meta.lookup_any("trie nodes are children of used nodes", |meta| {
+            node(meta, false).into_iter().zip(child(meta)).collect()
+        });
+
+        // (tx id, is real, rlc, len, pow) of leaf values and of transactions
+        let leaf = |meta: &mut VirtualCells<F>| {
+            let condition = meta.query_fixed(config.q_value, Rotation::cur())
+                * meta.query_advice(config.is_leaf, Rotation::cur());
+            [
+                meta.query_advice(config.key_idx, Rotation::cur()) + 1.expr(),
+                1.expr(),
+                meta.query_advice(rlp.splice_rlc, Rotation::cur()),
+                meta.query_advice(rlp.item_len, Rotation::cur()),
+                meta.query_advice(rlp.splice_pow, Rotation::cur()),
+            ]
+            .map(|value| condition.expr() * value)
+        };
+        let tx = |meta: &mut VirtualCells<F>, real_only: bool| {
+            let condition = meta.query_fixed(config.q_tx_end, Rotation::cur())
+                * if real_only {
+                    meta.query_advice(config.is_real, Rotation::cur())
+                } else {
+                    1.expr()
+                };
+            [
+                meta.query_fixed(config.tx_id, Rotation::cur()),
+                if real_only {
+                    1.expr()
+                } else {
+                    meta.query_advice(config.is_real, Rotation::cur())
+                },
+                meta.query_advice(config.leaf_rlc, Rotation::cur()),
+                meta.query_advice(config.leaf_len, Rotation::cur()),
+                meta.query_advice(config.leaf_pow, Rotation::cur()),
+            ]
+            .map(|value| condition.expr() * value)
+        };
+        meta.lookup_any("trie leaves are real transactions", |meta| {
+            leaf(meta).into_iter().zip(tx(meta, false)).collect()
+        });
+        meta.lookup_any("real transactions are trie leaves", |meta| {
+            tx(meta, true).into_iter().zip(leaf(meta)).collect()
+        });
+
+        config
+    }
+
+    /// Load the byte table of the effective gas price comparison
+    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        LtChip::construct(self.fee_cap_lt).load(layouter)
+    }
+
+    /// Assign the transactions trie region, binding the transaction fields to
+    /// `tx_cells` (one per transaction slot), the chain id and base fee of
+    /// typed transactions and the root to the cells of `header_cells`.
+    pub(super) fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        max_txs: usize,
+        chain_id: u64,
+        txs: &[Transaction],
+        tx_cells: &[TxCells<F>],
+        header_cells: &HeaderCells<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        assert!(
+            max_txs <= 1 << 16,
+            "too many transactions for the trie paths"
+        );
+        assert_eq!(tx_cells.len(), max_txs);
+        assert!(
+            txs.iter().all(|tx| tx.transaction_type.as_u64() <= 2),
+            "only legacy, EIP-2930 and EIP-1559 transactions are encoded"
+        );
+        let fee_cap_lt = LtChip::construct(self.fee_cap_lt);
+        let tx_layout = tx_layout();
+        let node_layout = node_layout();
+        let bool_value = |flag: bool| Value::known(if flag { F::ONE } else { F::ZERO });
+
+        // key table
+        for (offset, row) in key_rows(max_txs).into_iter().enumerate() {
+            for (column, value) in [
+                (self.key_is_leaf, row.is_leaf as u64),
+                (self.key_idx_table, row.key_idx),
+                (self.key_prefix, row.prefix),
+                (self.key_plen, row.plen),
+                (self.key_hp, row.hp),
+                (self.key_hp_len, row.hp_len),
+                (self.key_child_prefix, row.child_prefix),
+                (self.key_child_plen, row.child_plen),
+            ] {
+                region.assign_fixed(
+                    || "tx trie key table",
+                    column,
+                    offset,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+        }
+
+        // transactions, after a row of zeros queried by the first list
+        self.rlp.assign_zero_row(region, 0)?;
+        let mut offset = 1;
+        let mut tx_rlps = vec![];
+        for (index, cells) in tx_cells.iter().enumerate() {
+            let tx = txs.get(index);
+            let tx_type = tx
+                .map(|tx| tx.transaction_type.as_u64())
+                .unwrap_or_default();
+            let values = match tx {
+                Some(tx) => tx_values(tx, chain_id),
+                // padding transactions are legacy calls to the zero address
+                None => {
+                    let mut values = vec![Some(vec![]); TX_ITEMS.len()];
+                    values[TX_TO_ITEM] = Some(vec![0; 20]);
+                    for item in [
+                        TX_CHAIN_ID_ITEM,
+                        TX_TIP_ITEM,
+                        TX_FEE_CAP_ITEM,
+                        TX_ACCESS_LIST_ITEM,
+                    ] {
+                        values[item] = None;
+                    }
+                    values
+                }
+            };
+
+            for row in offset..offset + tx_layout.height() {
+                region.assign_fixed(|| "q_tx", self.q_tx, row, || Value::known(F::ONE))?;
+                region.assign_advice(
+                    || "tx_type",
+                    self.tx_type,
+                    row,
+                    || Value::known(F::from(tx_type)),
+                )?;
+            }
+            region.assign_fixed(
+                || "q_tx_first",
+                self.q_tx_first,
+                offset,
+                || Value::known(F::ONE),
+            )?;
+            for item in 0..TX_ITEMS.len() {
+                let q_tx_item = match item {
+                    TX_CHAIN_ID_ITEM | TX_ACCESS_LIST_ITEM => self.q_tx_typed_item,
+                    TX_GAS_PRICE_ITEM => self.q_tx_gas_price_item,
+                    TX_TIP_ITEM | TX_FEE_CAP_ITEM => self.q_tx_fee_cap_item,
+                    _ => self.q_tx_item,
+                };
+                region.assign_fixed(
+                    || "q_tx_item",
+                    q_tx_item,
+                    offset + tx_layout.prefix_row(item),
+                    || Value::known(F::ONE),
+                )?;
+            }
+            region.assign_fixed(
+                || "q_tx_access_list",
+                self.q_tx_access_list,
+                offset + tx_layout.splice_row(TX_ACCESS_LIST_ITEM),
+                || Value::known(F::ONE),
+            )?;
+            let tx_id = Value::known(F::from(index as u64 + 1));
+            let data_row = offset + tx_layout.splice_row(TX_DATA_ITEM);
+            let end_row = offset + tx_layout.end();
+            region.assign_fixed(
+                || "q_tx_data",
+                self.q_tx_data,
+                data_row,
+                || Value::known(F::ONE),
+            )?;
+            region.assign_fixed(|| "tx_id", self.tx_id, data_row, || tx_id)?;
+            region.assign_fixed(
+                || "q_tx_end",
+                self.q_tx_end,
+                end_row,
+                || Value::known(F::ONE),
+            )?;
+            region.assign_fixed(|| "tx_id", self.tx_id, end_row, || tx_id)?;
+
+            let list = self
+                .rlp
+                .assign(region, offset, &tx_layout, &values, false, challenges)?;
+            // typed txs are prefixed with their type
+            let leaf = if tx_type == 0 {
+                list.bytes.clone()
+            } else {
+                [vec![tx_type as u8], list.bytes.clone()].concat()
+            };
+            if let Some(tx) = tx {
+                debug_assert_eq!(
+                    leaf,
+                    tx.rlp_signed(chain_id).to_vec(),
+                    "tx {} encoding",
+                    index
+                );
+                tx_rlps.push(leaf.clone());
+            }
+            let r = challenges.keccak_input();
+            for (column, value) in [
+                (self.leaf_rlc, r.map(|r| rlc::value(leaf.iter().rev(), r))),
+                (self.leaf_len, Value::known(F::from(leaf.len() as u64))),
+                (
+                    self.leaf_pow,
+                    r.map(|r| (0..leaf.len()).fold(F::ONE, |acc, _| acc * r)),
+                ),
+            ] {
+                region.assign_advice(|| "tx leaf", column, end_row, || value)?;
+            }
+
+            // is_real
+            let caller = WordLoHi::<F>::from(tx.map(|tx| tx.from).unwrap_or_default());
+            let caller_cells =
+                caller
+                    .into_value()
+                    .assign_advice(region, || "caller", self.caller, end_row)?;
+            region.assign_advice(
+                || "caller_inv",
+                self.caller_inv,
+                end_row,
+                || Value::known(caller.compress_f().invert().unwrap_or(F::ZERO)),
+            )?;
+            region.assign_advice(
+                || "is_real",
+                self.is_real,
+                end_row,
+                || bool_value(tx.is_some()),
+            )?;
+            for (left, right) in [
+                (&caller_cells, &cells.caller),
+                (&list.items[TX_NONCE_ITEM].chunks[0], &cells.nonce),
+                (&list.items[TX_GAS_ITEM].chunks[0], &cells.gas),
+                (&list.items[TX_TO_ITEM].chunks[0], &cells.callee),
+                (&list.items[TX_VALUE_ITEM].chunks[0], &cells.value),
+            ] {
+                region.constrain_equal(left.lo().cell(), right.lo().cell())?;
+                region.constrain_equal(left.hi().cell(), right.hi().cell())?;
+            }
+
+            // fields bound through the gate of the end row
+            for (word, column) in [
+                (&list.items[TX_CHAIN_ID_ITEM].chunks[0], self.chain_id_item),
+                (&header_cells.chain_id, self.chain_id),
+                (
+                    &list.items[TX_GAS_PRICE_ITEM].chunks[0],
+                    self.gas_price_item,
+                ),
+                (&cells.gas_price, self.gas_price),
+                (&list.items[TX_TIP_ITEM].chunks[0], self.tip),
+                (&list.items[TX_FEE_CAP_ITEM].chunks[0], self.fee_cap),
+                (&header_cells.base_fee, self.base_fee),
+            ] {
+                word.lo()
+                    .copy_advice(|| "tx field", region, column.lo(), end_row)?;
+                word.hi()
+                    .copy_advice(|| "tx field", region, column.hi(), end_row)?;
+            }
+            let lo = |word: &WordLoHi<AssignedCell<F, F>>| word.lo().value().copied();
+            fee_cap_lt.assign(
+                region,
+                end_row,
+                lo(&list.items[TX_FEE_CAP_ITEM].chunks[0]),
+                lo(&header_cells.base_fee) + lo(&list.items[TX_TIP_ITEM].chunks[0]),
+            )?;
+            region.constrain_equal(
+                list.items[TX_TO_ITEM].empty.cell(),
+                cells.is_create.lo().cell(),
+            )?;
+            region.constrain_equal(
+                list.items[TX_DATA_ITEM].item_len.cell(),
+                cells.call_data_length.lo().cell(),
+            )?;
+
+            offset += tx_layout.height();
+        }
+
+        // nodes
+        let nodes = ordered_trie_nodes(&tx_rlps);
+        let n_nodes = max_trie_nodes(max_txs);
+        assert!(nodes.len() <= n_nodes);
+        for slot in 0..n_nodes {
+            let node = nodes.get(slot);
+            let list = self.assign_node(
+                region,
+                offset,
+                slot,
+                &node_layout,
+                node,
+                &tx_rlps,
+                challenges,
+            )?;
+            if let Some(node) = node {
+                debug_assert_eq!(list.bytes, node.encoded, "trie node {} encoding", slot);
+            }
+            if slot == 0 {
+                let root = if nodes.is_empty() {
+                    EMPTY_TRIE_ROOT
+                } else {
+                    H256(keccak256(&nodes[0].encoded))
+                };
+                let root = WordLoHi::<F>::from(root).into_value().assign_advice(
+                    region,
+                    || "transactions root",
+                    self.root,
+                    offset + node_layout.end(),
+                )?;
+                let transactions_root = &header_cells.transactions_root;
+                region.constrain_equal(root.lo().cell(), transactions_root.lo().cell())?;
+                region.constrain_equal(root.hi().cell(), transactions_root.hi().cell())?;
+            }
+            offset += node_layout.height();
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign_node(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        slot: usize,
+        layout: &RlpListLayout,
+        node: Option<&TrieNode>,
+        tx_rlps: &[Vec<u8>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedRlpList<F>, Error> {
+        let mut values = vec![None; NODE_VALUE_ITEM + 1];
+        let (mut is_branch, mut is_ext, mut is_leaf) = (false, false, false);
+        let (mut key_idx, mut child_prefix, mut child_plen) = (0, 0, 0);
+        let (prefix, plen) = node
+            .map(|node| (pack(&node.path), node.path.len() as u64))
+            .unwrap_or_default();
+        if let Some(node) = node {
+            match &node.kind {
+                TrieNodeKind::Leaf { index } => {
+                    is_leaf = true;
+                    key_idx = *index as u64;
+                    let key = bytes_to_nibbles(&ordered_trie_key(*index));
+                    values[0] = Some(hex_prefix(&key[node.path.len()..], true));
+                    values[NODE_VALUE_ITEM] = Some(tx_rlps[*index].clone());
+                }
+                TrieNodeKind::Extension { index, len, child } => {
+                    is_ext = true;
+                    let key = bytes_to_nibbles(&ordered_trie_key(*index));
+                    let end = node.path.len() + len;
+                    values[0] = Some(hex_prefix(&key[node.path.len()..end], false));
+                    values[NODE_CHILD_ITEM] = Some(child.0.to_vec());
+                    child_prefix = pack(&key[..end]);
+                    child_plen = end as u64;
+                }
+                TrieNodeKind::Branch { children } => {
+                    is_branch = true;
+                    for (nibble, child) in children.iter().enumerate() {
+                        values[NODE_CHILD_ITEM + nibble] =
+                            Some(child.map(|hash| hash.0.to_vec()).unwrap_or_default());
+                    }
+                    values[NODE_VALUE_ITEM] = Some(vec![]);
+                }
+            }
+        }
+
+        let end_row = offset + layout.end();
+        for row in offset..offset + layout.height() {
+            region.assign_fixed(|| "q_node", self.q_node, row, || Value::known(F::ONE))?;
+            for (column, value) in [
+                (self.is_branch, is_branch as u64),
+                (self.is_ext, is_ext as u64),
+                (self.is_leaf, is_leaf as u64),
+                (self.prefix, prefix),
+                (self.plen, plen),
+                (self.key_idx, key_idx),
+                (self.child_prefix, child_prefix),
+                (self.child_plen, child_plen),
+            ] {
+                region.assign_advice(
+                    || "trie node",
+                    column,
+                    row,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+        }
+        region.assign_fixed(
+            || "q_node_first",
+            self.q_node_first,
+            offset,
+            || Value::known(F::ONE),
+        )?;
+        region.assign_fixed(
+            || "q_node_end",
+            if slot == 0 {
+                self.q_root_end
+            } else {
+                self.q_node_end
+            },
+            end_row,
+            || Value::known(F::ONE),
+        )?;
+        region.assign_fixed(
+            || "q_hp",
+            self.q_hp,
+            offset + layout.chunk_ends(0)[0],
+            || Value::known(F::ONE),
+        )?;
+        for nibble in 0..16 {
+            let row = offset + layout.chunk_ends(NODE_CHILD_ITEM + nibble)[0];
+            region.assign_fixed(|| "q_child", self.q_child, row, || Value::known(F::ONE))?;
+            region.assign_fixed(
+                || "q_first_child",
+                self.q_first_child,
+                row,
+                || Value::known(F::from((nibble == 0) as u64)),
+            )?;
+            region.assign_fixed(
+                || "nibble",
+                self.nibble,
+                row,
+                || Value::known(F::from(nibble as u64)),
+            )?;
+            let (ref_prefix, ref_plen) = if is_branch {
+                (prefix * 16 + nibble as u64, plen + 1)
+            } else if is_ext {
+                (child_prefix, child_plen)
+            } else {
+                (0, 0)
+            };
+            region.assign_advice(
+                || "ref_prefix",
+                self.ref_prefix,
+                row,
+                || Value::known(F::from(ref_prefix)),
+            )?;
+            region.assign_advice(
+                || "ref_plen",
+                self.ref_plen,
+                row,
+                || Value::known(F::from(ref_plen)),
+            )?;
+        }
+        region.assign_fixed(
+            || "q_value",
+            self.q_value,
+            offset + layout.splice_row(NODE_VALUE_ITEM),
+            || Value::known(F::ONE),
+        )?;
+
+        self.rlp
+            .assign(region, offset, layout, &values, node.is_some(), challenges)
+    }
+}
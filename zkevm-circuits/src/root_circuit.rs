@@ -22,6 +22,8 @@ use snark_verifier::{
 use std::{iter, marker::PhantomData, rc::Rc};
 
 mod aggregation;
+mod aggregation_circuit;
+mod evm_verifier;
 
 #[cfg(any(test, feature = "test-circuits"))]
 mod dev;
@@ -34,9 +36,13 @@ pub use self::RootCircuit as TestRootCircuit;
 pub use dev::TestAggregationCircuit;
 
 pub use aggregation::{
-    aggregate, AggregationConfig, EccChip, Gwc, Halo2Loader, KzgDk, KzgSvk, PlonkSuccinctVerifier,
-    PlonkVerifier, PoseidonTranscript, Shplonk, Snark, SnarkWitness, BITS, LIMBS,
+    advice_commitment_index, aggregate, aggregate_linked, hash_instances, linked_degrees,
+    table_links, AggregationConfig, CommitmentLink, EccChip, Gwc, Halo2Loader, KzgDk, KzgSvk,
+    PlonkSuccinctVerifier, PlonkVerifier, PoseidonTranscript, Shplonk, Snark, SnarkWitness, BITS,
+    LIMBS,
 };
+pub use aggregation_circuit::{AggregationCircuit, InstanceIndex};
+pub use evm_verifier::{encode_calldata, evm_verify, gen_evm_verifier, gen_evm_verifier_code};
 pub use snark_verifier::{
     loader::native::NativeLoader,
     system::halo2::{compile, transcript::evm::EvmTranscript, Config},
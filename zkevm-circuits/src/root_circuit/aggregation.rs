@@ -5,7 +5,7 @@ use halo2_proofs::{
     halo2curves::{
         group::prime::PrimeCurveAffine, pairing::Engine, serde::SerdeObject, CurveAffine,
     },
-    plonk::{ConstraintSystem, Error},
+    plonk::{Advice, Column, ConstraintSystem, Error},
     poly::{commitment::ParamsProver, kzg::commitment::ParamsKZG},
 };
 use itertools::Itertools;
@@ -18,13 +18,17 @@ use snark_verifier::{
             Scalar,
         },
         native::NativeLoader,
+        EcPointLoader,
     },
     pcs::{
         kzg::*, AccumulationDecider, AccumulationScheme, AccumulationSchemeProver,
         PolynomialCommitmentScheme,
     },
     system::halo2::transcript,
-    util::arithmetic::{fe_to_limbs, FromUniformBytes, MultiMillerLoop, PrimeField},
+    util::{
+        arithmetic::{fe_to_limbs, FromUniformBytes, MultiMillerLoop, PrimeField},
+        hash::Poseidon,
+    },
     verifier::{self, plonk::PlonkProtocol, SnarkVerifier},
 };
 use std::{io, iter, rc::Rc};
@@ -42,8 +46,10 @@ pub type Shplonk<M> = KzgAs<M, Bdfg21>;
 pub type KzgSvk<M> = KzgSuccinctVerifyingKey<<M as Engine>::G1Affine>;
 /// KZG deciding key
 pub type KzgDk<M> = KzgDecidingKey<M>;
-/// Plonk succinct verifier with `KzgAs`
-pub type PlonkSuccinctVerifier<As> = verifier::plonk::PlonkSuccinctVerifier<As>;
+/// Plonk succinct verifier with `KzgAs` and `LimbsEncoding<LIMBS, BITS>`, so
+/// that the accumulators in the instance of aggregated snarks are propagated.
+pub type PlonkSuccinctVerifier<As> =
+    verifier::plonk::PlonkSuccinctVerifier<As, LimbsEncoding<LIMBS, BITS>>;
 /// Plonk verifier with `KzgAs` and `LimbsEncoding<LIMBS, BITS>`.
 pub type PlonkVerifier<As> = verifier::plonk::PlonkVerifier<As, LimbsEncoding<LIMBS, BITS>>;
 
@@ -61,10 +67,138 @@ pub type Halo2Loader<'a, C> = loader::halo2::Halo2Loader<'a, C, EccChip<C>>;
 pub type PoseidonTranscript<C, S> =
     transcript::halo2::PoseidonTranscript<C, NativeLoader, S, T, RATE, R_F, R_P>;
 
+/// Hash `values` with the Poseidon sponge of the transcripts, as
+/// [`AggregationConfig::aggregate_hashed`] does in circuit.
+pub fn hash_instances<F: Field>(values: &[F]) -> F {
+    let mut hasher = Poseidon::<F, F, T, RATE>::new(&NativeLoader, R_F, R_P);
+    hasher.update(values);
+    hasher.squeeze()
+}
+
+/// Link between the witness commitments of two aggregated snarks, as
+/// `((snark, witness), (snark, witness))` indices, asserting that they commit to
+/// the same advice column, such as a table shared by sub-circuits proven
+/// separately. See [`table_links`] to link all the columns of a table.
+///
+/// A commitment depends on the domain of the circuit and on the rows the
+/// column is assigned at, so the circuits must lay out the shared table at the
+/// same rows, and linked snarks must be proven in the same domain. Links
+/// across degrees are not supported: sub-circuits linked together, directly or
+/// through other sub-circuits, are all proven at the largest of their degrees,
+/// see [`linked_degrees`], and only unlinked sub-circuits keep their own degree.
+///
+/// Only first phase advice columns can be linked, the aggregation fails on a
+/// link to any other witness commitment. The second phase columns of a table
+/// hold RLCs with the challenges of each proof, so the sub-circuits sharing the
+/// keccak table (`input_rlc`) or the copy table (`rlc_acc`) can't be proven
+/// separately: `SuperCircuit::check_split` rejects such splits of the super
+/// circuit, whose lookups into these tables would not be bound.
+pub type CommitmentLink = ((usize, usize), (usize, usize));
+
+/// Returns the degree to prove each snark at, given the minimum degree of each
+/// one, so that snarks linked together, directly or through other snarks, are
+/// proven in the domain of the largest of them.
+pub fn linked_degrees(min_degrees: &[u32], links: &[CommitmentLink]) -> Vec<u32> {
+    let mut degrees = min_degrees.to_vec();
+    loop {
+        let mut changed = false;
+        for ((lhs, _), (rhs, _)) in links.iter() {
+            let k = degrees[*lhs].max(degrees[*rhs]);
+            changed |= degrees[*lhs] != k || degrees[*rhs] != k;
+            degrees[*lhs] = k;
+            degrees[*rhs] = k;
+        }
+        if !changed {
+            return degrees;
+        }
+    }
+}
+
+/// Returns why `links` can't hold between snarks of the given protocols: a
+/// link between snarks of different degrees, or to a witness that is not a
+/// first phase advice column.
+fn invalid_link<C: CurveAffine>(
+    protocols: &[&PlonkProtocol<C>],
+    links: &[CommitmentLink],
+) -> Option<String> {
+    links.iter().find_map(|((lhs, lhs_witness), (rhs, rhs_witness))| {
+        let (lhs_k, rhs_k) = (protocols[*lhs].domain.k, protocols[*rhs].domain.k);
+        if lhs_k != rhs_k {
+            return Some(format!(
+                "snarks {} and {} are linked but have degrees {} and {}, see `linked_degrees`",
+                lhs, rhs, lhs_k, rhs_k
+            ));
+        }
+        [(lhs, lhs_witness), (rhs, rhs_witness)]
+            .into_iter()
+            .find(|(snark, witness)| **witness >= protocols[**snark].num_witness[0])
+            .map(|(snark, witness)| {
+                format!(
+                    "commitment {} of snark {} is not a first phase advice column and can't be linked",
+                    witness, snark
+                )
+            })
+    })
+}
+
+/// Returns the index of the commitment of an advice column among the witness
+/// commitments of a proof, which are ordered by phase and then by column index.
+pub fn advice_commitment_index<F: Field>(
+    cs: &ConstraintSystem<F>,
+    column: Column<Advice>,
+) -> usize {
+    let phases = cs.advice_column_phase();
+    let phase = phases[column.index()];
+    phases
+        .iter()
+        .enumerate()
+        .filter(|(index, p)| **p < phase || (**p == phase && *index < column.index()))
+        .count()
+}
+
+/// Returns the links between the commitments of a table shared by snarks `lhs`
+/// and `rhs`, given the constraint system of each snark and the advice columns
+/// of the table in it, in the same order. Fails if a column of the table is not
+/// in the first phase, since it can't be linked, see [`CommitmentLink`].
+pub fn table_links<F: Field>(
+    (lhs, lhs_cs, lhs_columns): (usize, &ConstraintSystem<F>, &[Column<Advice>]),
+    (rhs, rhs_cs, rhs_columns): (usize, &ConstraintSystem<F>, &[Column<Advice>]),
+) -> Result<Vec<CommitmentLink>, Error> {
+    assert_eq!(lhs_columns.len(), rhs_columns.len());
+    let (lhs_phases, rhs_phases) = (lhs_cs.advice_column_phase(), rhs_cs.advice_column_phase());
+    if let Some((lhs_column, rhs_column)) =
+        lhs_columns
+            .iter()
+            .zip(rhs_columns.iter())
+            .find(|(lhs_column, rhs_column)| {
+                lhs_phases[lhs_column.index()] != 0 || rhs_phases[rhs_column.index()] != 0
+            })
+    {
+        log::error!(
+            "advice columns {} of snark {} and {} of snark {} can't be linked, only first phase columns can",
+            lhs_column.index(),
+            lhs,
+            rhs_column.index(),
+            rhs
+        );
+        return Err(Error::Synthesis);
+    }
+    Ok(lhs_columns
+        .iter()
+        .zip(rhs_columns.iter())
+        .map(|(lhs_column, rhs_column)| {
+            (
+                (lhs, advice_commitment_index(lhs_cs, *lhs_column)),
+                (rhs, advice_commitment_index(rhs_cs, *rhs_column)),
+            )
+        })
+        .collect())
+}
+
 /// Snark contains the minimal information for verification
 #[derive(Clone, Copy)]
 pub struct Snark<'a, C: CurveAffine> {
-    protocol: &'a PlonkProtocol<C>,
+    pub(crate) protocol: &'a PlonkProtocol<C>,
     pub(crate) instances: &'a Vec<Vec<C::Scalar>>,
     proof: &'a [u8],
 }
@@ -219,6 +353,118 @@ impl AggregationConfig {
         ),
         Error,
     >
+    where
+        M: MultiMillerLoop,
+        M::Fr: Field,
+        M::G1Affine: CurveAffine<ScalarExt = M::Fr>,
+        for<'b> As: PolynomialCommitmentScheme<
+                M::G1Affine,
+                Rc<Halo2Loader<'b, M::G1Affine>>,
+                VerifyingKey = KzgSvk<M>,
+                Output = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+            > + AccumulationScheme<
+                M::G1Affine,
+                Rc<Halo2Loader<'b, M::G1Affine>>,
+                Accumulator = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+                VerifyingKey = KzgAsVerifyingKey,
+            >,
+    {
+        self.aggregate_linked::<M, As>(layouter, svk, snarks, &[])
+    }
+
+    /// Same as [`Self::aggregate`], also constraining the witness
+    /// commitments of the snarks to match for each of the `links`.
+    #[allow(clippy::type_complexity)]
+    pub fn aggregate_linked<'a, M, As>(
+        &self,
+        layouter: &mut impl Layouter<M::Fr>,
+        svk: &KzgSvk<M>,
+        snarks: impl IntoIterator<Item = SnarkWitness<'a, M::G1Affine>>,
+        links: &[CommitmentLink],
+    ) -> Result<
+        (
+            Vec<Vec<Vec<AssignedCell<M::Fr, M::Fr>>>>,
+            Vec<AssignedCell<M::Fr, M::Fr>>,
+        ),
+        Error,
+    >
+    where
+        M: MultiMillerLoop,
+        M::Fr: Field,
+        M::G1Affine: CurveAffine<ScalarExt = M::Fr>,
+        for<'b> As: PolynomialCommitmentScheme<
+                M::G1Affine,
+                Rc<Halo2Loader<'b, M::G1Affine>>,
+                VerifyingKey = KzgSvk<M>,
+                Output = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+            > + AccumulationScheme<
+                M::G1Affine,
+                Rc<Halo2Loader<'b, M::G1Affine>>,
+                Accumulator = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+                VerifyingKey = KzgAsVerifyingKey,
+            >,
+    {
+        self.aggregate_inner::<M, As>(layouter, svk, snarks, links, None)
+            .map(|(instances, _, accumulator_limbs)| (instances, accumulator_limbs))
+    }
+
+    /// Same as [`Self::aggregate_linked`], also hashing the instances at
+    /// `indices[i]` of each snark `i` in circuit, see [`hash_instances`].
+    /// Returns the assigned hash along with the instances and limbs.
+    #[allow(clippy::type_complexity)]
+    pub fn aggregate_hashed<'a, M, As>(
+        &self,
+        layouter: &mut impl Layouter<M::Fr>,
+        svk: &KzgSvk<M>,
+        snarks: impl IntoIterator<Item = SnarkWitness<'a, M::G1Affine>>,
+        links: &[CommitmentLink],
+        indices: &[Vec<(usize, usize)>],
+    ) -> Result<
+        (
+            Vec<Vec<Vec<AssignedCell<M::Fr, M::Fr>>>>,
+            AssignedCell<M::Fr, M::Fr>,
+            Vec<AssignedCell<M::Fr, M::Fr>>,
+        ),
+        Error,
+    >
+    where
+        M: MultiMillerLoop,
+        M::Fr: Field,
+        M::G1Affine: CurveAffine<ScalarExt = M::Fr>,
+        for<'b> As: PolynomialCommitmentScheme<
+                M::G1Affine,
+                Rc<Halo2Loader<'b, M::G1Affine>>,
+                VerifyingKey = KzgSvk<M>,
+                Output = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+            > + AccumulationScheme<
+                M::G1Affine,
+                Rc<Halo2Loader<'b, M::G1Affine>>,
+                Accumulator = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+                VerifyingKey = KzgAsVerifyingKey,
+            >,
+    {
+        self.aggregate_inner::<M, As>(layouter, svk, snarks, links, Some(indices))
+            .map(|(instances, hash, accumulator_limbs)| {
+                (instances, hash.unwrap(), accumulator_limbs)
+            })
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn aggregate_inner<'a, M, As>(
+        &self,
+        layouter: &mut impl Layouter<M::Fr>,
+        svk: &KzgSvk<M>,
+        snarks: impl IntoIterator<Item = SnarkWitness<'a, M::G1Affine>>,
+        links: &[CommitmentLink],
+        hashed_indices: Option<&[Vec<(usize, usize)>]>,
+    ) -> Result<
+        (
+            Vec<Vec<Vec<AssignedCell<M::Fr, M::Fr>>>>,
+            Option<AssignedCell<M::Fr, M::Fr>>,
+            Vec<AssignedCell<M::Fr, M::Fr>>,
+        ),
+        Error,
+    >
     where
         M: MultiMillerLoop,
         M::Fr: Field,
@@ -238,6 +484,13 @@ impl AggregationConfig {
         type PoseidonTranscript<'a, C, S> =
             transcript::halo2::PoseidonTranscript<C, Rc<Halo2Loader<'a, C>>, S, T, RATE, R_F, R_P>;
         let snarks = snarks.into_iter().collect_vec();
+        if let Some(err) = invalid_link(
+            &snarks.iter().map(|snark| snark.protocol()).collect_vec(),
+            links,
+        ) {
+            log::error!("{}", err);
+            return Err(Error::Synthesis);
+        }
         layouter.assign_region(
             || "Aggregate snarks",
             |mut region| {
@@ -254,6 +507,7 @@ impl AggregationConfig {
 
                 // Verify the cheap part and get accumulator (left-hand and right-hand side of
                 // pairing) of individual proof.
+                let mut witnesses = Vec::with_capacity(snarks.len());
                 let (instances, accumulators) = snarks
                     .iter()
                     .map(|snark| {
@@ -270,12 +524,22 @@ impl AggregationConfig {
                         let accumulators =
                             PlonkSuccinctVerifier::verify(svk, &protocol, &instances, &proof)
                                 .unwrap();
+                        witnesses.push(proof.witnesses);
                         (instances, accumulators)
                     })
                     .collect_vec()
                     .into_iter()
                     .unzip::<_, _, Vec<_>, Vec<_>>();
 
+                // Constrain linked commitments to be equal.
+                for ((lhs_snark, lhs_witness), (rhs_snark, rhs_witness)) in links.iter() {
+                    loader.ec_point_assert_eq(
+                        "commitment link",
+                        &witnesses[*lhs_snark][*lhs_witness],
+                        &witnesses[*rhs_snark][*rhs_witness],
+                    );
+                }
+
                 // Verify proof for accumulation of all accumulators into new one.
                 let accumulator = {
                     let as_vk = Default::default();
@@ -292,6 +556,20 @@ impl AggregationConfig {
                     <As as AccumulationScheme<_, _>>::verify(&as_vk, &accumulators, &proof).unwrap()
                 };
 
+                // Hash the selected instances with the loaded Poseidon sponge.
+                let hash = hashed_indices.map(|hashed_indices| {
+                    let mut hasher = Poseidon::<M::Fr, _, T, RATE>::new(&loader, R_F, R_P);
+                    for (instances, indices) in instances.iter().zip(hashed_indices) {
+                        hasher.update(
+                            &indices
+                                .iter()
+                                .map(|(column, row)| instances[*column][*row].clone())
+                                .collect_vec(),
+                        );
+                    }
+                    hasher.squeeze().assigned().to_owned()
+                });
+
                 let instances = instances
                     .iter()
                     .map(|instances| {
@@ -318,7 +596,7 @@ impl AggregationConfig {
                     .flatten()
                     .collect();
 
-                Ok((instances, accumulator_limbs))
+                Ok((instances, hash, accumulator_limbs))
             },
         )
     }
@@ -331,6 +609,33 @@ pub fn aggregate<'a, M, As>(
     params: &ParamsKZG<M>,
     snarks: impl IntoIterator<Item = Snark<'a, M::G1Affine>>,
 ) -> Result<[M::Fr; 4 * LIMBS], snark_verifier::Error>
+where
+    M: MultiMillerLoop,
+    M::Fr: PrimeField + FromUniformBytes<64>,
+    M::G1: CurveExt<AffineExt = M::G1Affine, ScalarExt = M::Fr>,
+    M::G1Affine: SerdeObject + CurveAffine<ScalarExt = M::Fr, CurveExt = M::G1>,
+    M::G2Affine: SerdeObject + CurveAffine,
+    for<'b> As: PolynomialCommitmentScheme<
+            M::G1Affine,
+            NativeLoader,
+            VerifyingKey = KzgSvk<M>,
+            Output = KzgAccumulator<M::G1Affine, NativeLoader>,
+        > + AccumulationSchemeProver<
+            M::G1Affine,
+            Accumulator = KzgAccumulator<M::G1Affine, NativeLoader>,
+            ProvingKey = KzgAsProvingKey<M::G1Affine>,
+        > + AccumulationDecider<M::G1Affine, NativeLoader, DecidingKey = KzgDecidingKey<M>>,
+{
+    aggregate_linked::<M, As>(params, snarks, &[])
+}
+
+/// Same as [`aggregate`], also checking that the witness commitments of the
+/// snarks match for each of the `links`.
+pub fn aggregate_linked<'a, M, As>(
+    params: &ParamsKZG<M>,
+    snarks: impl IntoIterator<Item = Snark<'a, M::G1Affine>>,
+    links: &[CommitmentLink],
+) -> Result<[M::Fr; 4 * LIMBS], snark_verifier::Error>
 where
     M: MultiMillerLoop,
     M::Fr: PrimeField + FromUniformBytes<64>,
@@ -351,8 +656,17 @@ where
     let svk = KzgSvk::<M>::new(params.get_g()[0]);
     let dk = KzgDk::new(svk, params.g2(), params.s_g2());
 
+    let snarks = snarks.into_iter().collect_vec();
+    if let Some(err) = invalid_link(
+        &snarks.iter().map(|snark| snark.protocol).collect_vec(),
+        links,
+    ) {
+        return Err(snark_verifier::Error::AssertionFailure(err));
+    }
+
     // Use the same logic of verifier to get accumulator (left-hand and right-hand
     // side of pairing) of individual proof.
+    let mut witnesses = Vec::new();
     let accumulators = snarks
         .into_iter()
         .map(|snark| {
@@ -375,13 +689,25 @@ where
                     ));
                 }
             }
-            PlonkSuccinctVerifier::verify(&svk, snark.protocol, snark.instances, &proof)
+            let accumulators =
+                PlonkSuccinctVerifier::verify(&svk, snark.protocol, snark.instances, &proof)?;
+            witnesses.push(proof.witnesses);
+            Ok(accumulators)
         })
         .try_collect::<_, Vec<_>, _>()?
         .into_iter()
         .flatten()
         .collect_vec();
 
+    for ((lhs_snark, lhs_witness), (rhs_snark, rhs_witness)) in links.iter() {
+        if witnesses[*lhs_snark][*lhs_witness] != witnesses[*rhs_snark][*rhs_witness] {
+            return Err(snark_verifier::Error::AssertionFailure(format!(
+                "commitment {} of snark {} does not match commitment {} of snark {}",
+                lhs_witness, lhs_snark, rhs_witness, rhs_snark
+            )));
+        }
+    }
+
     // Create proof for accumulation of all accumulators into new one.
     // In our case (KZG without zero-knowledge), it's no-ops since the verifier
     // could add up the accumulators into the new one itself in circuit.
@@ -447,9 +773,9 @@ pub mod test {
 
     #[derive(Clone)]
     pub struct SnarkOwned<C: CurveAffine> {
-        protocol: PlonkProtocol<C>,
-        instances: Vec<Vec<C::Scalar>>,
-        proof: Vec<u8>,
+        pub(crate) protocol: PlonkProtocol<C>,
+        pub(crate) instances: Vec<Vec<C::Scalar>>,
+        pub(crate) proof: Vec<u8>,
     }
 
     impl<C: CurveAffine> SnarkOwned<C> {
@@ -479,6 +805,9 @@ pub mod test {
     pub struct StandardPlonkConfig {
         selectors: [Column<Fixed>; 5],
         wires: [Column<Advice>; 3],
+        /// Unblinded column holding the instance, like a table shared with
+        /// other circuits
+        pub table: Column<Advice>,
     }
 
     impl StandardPlonkConfig {
@@ -486,8 +815,9 @@ pub mod test {
         pub fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
             let [w_l, w_r, w_o] = [(); 3].map(|_| meta.advice_column());
             let [q_l, q_r, q_o, q_m, q_c] = [(); 5].map(|_| meta.fixed_column());
+            let table = meta.unblinded_advice_column();
             let pi = meta.instance_column();
-            [w_l, w_r, w_o].map(|column| meta.enable_equality(column));
+            [w_l, w_r, w_o, table].map(|column| meta.enable_equality(column));
             meta.create_gate(
                 "q_l·w_l + q_r·w_r + q_o·w_o + q_m·w_l·w_r + q_c + pi = 0",
                 |meta| {
@@ -509,6 +839,7 @@ pub mod test {
             StandardPlonkConfig {
                 selectors: [q_l, q_r, q_o, q_m, q_c],
                 wires: [w_l, w_r, w_o],
+                table,
             }
         }
     }
@@ -559,6 +890,7 @@ pub mod test {
                     region.assign_fixed(|| "", q_l, 0, || Value::known(-F::ONE))?;
                     a.copy_advice(|| "", &mut region, w_r, 1)?;
                     a.copy_advice(|| "", &mut region, w_o, 2)?;
+                    a.copy_advice(|| "", &mut region, config.table, 0)?;
                     region.assign_advice(|| "", w_l, 3, || Value::known(-F::from(5)))?;
                     for (column, idx) in [q_l, q_r, q_o, q_m, q_c].iter().zip(1..) {
                         region.assign_fixed(|| "", *column, 3, || Value::known(F::from(idx)))?;
@@ -573,6 +905,18 @@ pub mod test {
     pub fn rand_standard_plonk_snarks(
         params: &ParamsKZG<Bn256>,
         n: usize,
+    ) -> Vec<SnarkOwned<G1Affine>> {
+        standard_plonk_snarks(
+            params,
+            iter::repeat_with(|| StandardPlonk::<Fr>::rand(OsRng).0).take(n),
+        )
+    }
+
+    /// Create `StandardPlonk` with given instances and returns `Snark`s for
+    /// them.
+    pub fn standard_plonk_snarks(
+        params: &ParamsKZG<Bn256>,
+        instances: impl IntoIterator<Item = Fr>,
     ) -> Vec<SnarkOwned<G1Affine>> {
         // Preprocess
         let (pk, protocol) = {
@@ -594,26 +938,27 @@ pub mod test {
         };
 
         // Create snarks
-        iter::repeat_with(move || {
-            let standard_plonk = StandardPlonk::<Fr>::rand(OsRng);
-            let instances = standard_plonk.instances();
-            let proof = {
-                let mut transcript = PoseidonTranscript::new(Vec::new());
-                create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
-                    params,
-                    &pk,
-                    &[standard_plonk],
-                    &[&instances.iter().map(Vec::as_slice).collect_vec()],
-                    OsRng,
-                    &mut transcript,
-                )
-                .unwrap();
-                transcript.finalize()
-            };
-            SnarkOwned::new(protocol.clone(), instances, proof)
-        })
-        .take(n)
-        .collect()
+        instances
+            .into_iter()
+            .map(move |instance| {
+                let standard_plonk = StandardPlonk(instance);
+                let instances = standard_plonk.instances();
+                let proof = {
+                    let mut transcript = PoseidonTranscript::new(Vec::new());
+                    create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+                        params,
+                        &pk,
+                        &[standard_plonk],
+                        &[&instances.iter().map(Vec::as_slice).collect_vec()],
+                        OsRng,
+                        &mut transcript,
+                    )
+                    .unwrap();
+                    transcript.finalize()
+                };
+                SnarkOwned::new(protocol.clone(), instances, proof)
+            })
+            .collect()
     }
 
     #[test]
@@ -0,0 +1,296 @@
+//! Aggregation of a batch of snarks, such as the `SuperCircuit` proofs of
+//! consecutive blocks, into one proof with a constant size instance.
+use super::{
+    aggregate_linked, hash_instances, AggregationConfig, CommitmentLink, Halo2Loader, KzgSvk,
+    Snark, SnarkWitness, LIMBS,
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    halo2curves::{ff::Field as Halo2Field, serde::SerdeObject, CurveAffine, CurveExt},
+    plonk::{Circuit, ConstraintSystem, Error},
+    poly::{commitment::ParamsProver, kzg::commitment::ParamsKZG},
+};
+use itertools::Itertools;
+use maingate::MainGateInstructions;
+use snark_verifier::{
+    loader::native::NativeLoader,
+    pcs::{
+        kzg::*, AccumulationDecider, AccumulationScheme, AccumulationSchemeProver,
+        PolynomialCommitmentScheme,
+    },
+    util::arithmetic::MultiMillerLoop,
+    verifier::plonk::PlonkProtocol,
+};
+use std::{iter, marker::PhantomData, rc::Rc};
+
+/// Index of an instance value as `(column, row)`.
+pub type InstanceIndex = (usize, usize);
+
+/// Returns the indices of the instance of a snark that are not accumulator
+/// limbs, in column major order.
+fn public_instance_indices<C: CurveAffine>(protocol: &PlonkProtocol<C>) -> Vec<InstanceIndex> {
+    let accumulator_indices = protocol.accumulator_indices.iter().flatten().collect_vec();
+    protocol
+        .num_instance
+        .iter()
+        .enumerate()
+        .flat_map(|(column, num_instance)| (0..*num_instance).map(move |row| (column, row)))
+        .filter(|index| !accumulator_indices.contains(&index))
+        .collect()
+}
+
+/// AggregationCircuit for aggregating any number of snarks into a single
+/// proof, such as the `SuperCircuit` proofs of consecutive blocks of a batch.
+///
+/// Snarks compiled with accumulator indices, like `RootCircuit` or other
+/// `AggregationCircuit` proofs, have their accumulator folded into the new one,
+/// which allows building proof trees.
+///
+/// Consecutive snarks are chained: for each `(from, to)` pair of the
+/// chaining, the instance at `from` of a snark must equal the instance at `to`
+/// of the next one. For `SuperCircuit` proofs, this is the state root of a
+/// block and the previous state root of the next one, see
+/// `SuperCircuit::state_root_chaining`.
+///
+/// Snarks can also be linked, by constraining some of their witness
+/// commitments to be equal, see [`CommitmentLink`]. This allows to prove the
+/// sub-circuits of a block separately and check in the aggregation that the
+/// tables they share are the same.
+///
+/// The instance layout doesn't depend on the number of snarks:
+/// - for each `(from, to)` pair of the chaining, the instance at `to` of the first snark, e.g. the
+///   previous state root of the batch;
+/// - for each `(from, to)` pair of the chaining, the instance at `from` of the last snark, e.g. the
+///   state root of the batch;
+/// - the [`hash_instances`] of the instance of all snarks in order, without their accumulator
+///   limbs, which commits to the public inputs of each block;
+/// - the `4 * LIMBS` limbs of the aggregated accumulator.
+#[derive(Clone)]
+pub struct AggregationCircuit<'a, M: MultiMillerLoop, As>
+where
+    M::G1Affine: CurveAffine,
+{
+    svk: KzgSvk<M>,
+    snarks: Vec<SnarkWitness<'a, M::G1Affine>>,
+    chaining: Vec<(InstanceIndex, InstanceIndex)>,
+    links: Vec<CommitmentLink>,
+    instance: Vec<M::Fr>,
+    _marker: PhantomData<As>,
+}
+
+impl<'a, M, As> AggregationCircuit<'a, M, As>
+where
+    M: MultiMillerLoop,
+    M::Fr: Field,
+    M::G1: CurveExt<AffineExt = M::G1Affine, ScalarExt = M::Fr>,
+    M::G1Affine: SerdeObject + CurveAffine<ScalarExt = M::Fr, CurveExt = M::G1>,
+    M::G2Affine: SerdeObject + CurveAffine,
+    for<'b> As: PolynomialCommitmentScheme<
+            M::G1Affine,
+            NativeLoader,
+            VerifyingKey = KzgSvk<M>,
+            Output = KzgAccumulator<M::G1Affine, NativeLoader>,
+        > + AccumulationSchemeProver<
+            M::G1Affine,
+            Accumulator = KzgAccumulator<M::G1Affine, NativeLoader>,
+            ProvingKey = KzgAsProvingKey<M::G1Affine>,
+        > + AccumulationDecider<M::G1Affine, NativeLoader, DecidingKey = KzgDecidingKey<M>>,
+{
+    /// Create an `AggregationCircuit` with accumulator computed given the
+    /// snarks to aggregate and the chaining between consecutive ones.
+    /// Returns an error if there is no snark, if any given snark is invalid
+    /// or if consecutive snarks are not chained.
+    pub fn new(
+        params: &ParamsKZG<M>,
+        snarks: impl IntoIterator<Item = Snark<'a, M::G1Affine>>,
+        chaining: Vec<(InstanceIndex, InstanceIndex)>,
+    ) -> Result<Self, snark_verifier::Error> {
+        Self::new_linked(params, snarks, chaining, Vec::new())
+    }
+
+    /// Same as [`Self::new`], also linking the witness commitments of the
+    /// snarks. Returns an error if any of the `links` doesn't hold.
+    pub fn new_linked(
+        params: &ParamsKZG<M>,
+        snarks: impl IntoIterator<Item = Snark<'a, M::G1Affine>>,
+        chaining: Vec<(InstanceIndex, InstanceIndex)>,
+        links: Vec<CommitmentLink>,
+    ) -> Result<Self, snark_verifier::Error> {
+        let snarks = snarks.into_iter().collect_vec();
+
+        for (idx, (prev, next)) in snarks.iter().tuple_windows().enumerate() {
+            for ((from_column, from_row), (to_column, to_row)) in chaining.iter() {
+                if prev.instances[*from_column][*from_row] != next.instances[*to_column][*to_row] {
+                    return Err(snark_verifier::Error::AssertionFailure(format!(
+                        "snarks {} and {} are not chained",
+                        idx,
+                        idx + 1
+                    )));
+                }
+            }
+        }
+
+        if snarks.is_empty() {
+            return Err(snark_verifier::Error::AssertionFailure(
+                "no snark to aggregate".to_string(),
+            ));
+        }
+
+        let accumulator_limbs = aggregate_linked::<M, As>(params, snarks.clone(), &links)?;
+        let (first, last) = (snarks.first().unwrap(), snarks.last().unwrap());
+        let instance = iter::empty()
+            // Chained instance at the boundaries of the batch
+            .chain(
+                chaining
+                    .iter()
+                    .map(|(_, (column, row))| first.instances[*column][*row]),
+            )
+            .chain(
+                chaining
+                    .iter()
+                    .map(|((column, row), _)| last.instances[*column][*row]),
+            )
+            // Commit to aggregated snarks' instance
+            .chain([hash_instances(
+                &snarks
+                    .iter()
+                    .flat_map(|snark| {
+                        public_instance_indices(snark.protocol)
+                            .into_iter()
+                            .map(|(column, row)| snark.instances[column][row])
+                    })
+                    .collect_vec(),
+            )])
+            // Output aggregated accumulator limbs
+            .chain(accumulator_limbs)
+            .collect_vec();
+
+        Ok(Self {
+            svk: KzgSvk::<M>::new(params.get_g()[0]),
+            snarks: snarks.into_iter().map_into().collect(),
+            chaining,
+            links,
+            instance,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns accumulator indices in instance columns, which will be in
+    /// the last `4 * LIMBS` rows of instance column in `MainGate`.
+    pub fn accumulator_indices(&self) -> Vec<(usize, usize)> {
+        (self.instance.len() - 4 * LIMBS..)
+            .map(|idx| (0, idx))
+            .take(4 * LIMBS)
+            .collect()
+    }
+
+    /// Returns number of instance
+    pub fn num_instance(&self) -> Vec<usize> {
+        vec![self.instance.len()]
+    }
+
+    /// Returns instance
+    pub fn instance(&self) -> Vec<Vec<M::Fr>> {
+        vec![self.instance.clone()]
+    }
+}
+
+impl<'a, M, As> Circuit<M::Fr> for AggregationCircuit<'a, M, As>
+where
+    M: MultiMillerLoop,
+    M::Fr: Field,
+    M::G1Affine: CurveAffine<ScalarExt = M::Fr>,
+    for<'b> As: PolynomialCommitmentScheme<
+            M::G1Affine,
+            Rc<Halo2Loader<'b, M::G1Affine>>,
+            VerifyingKey = KzgSvk<M>,
+            Output = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+        > + AccumulationScheme<
+            M::G1Affine,
+            Rc<Halo2Loader<'b, M::G1Affine>>,
+            Accumulator = KzgAccumulator<M::G1Affine, Rc<Halo2Loader<'b, M::G1Affine>>>,
+            VerifyingKey = KzgAsVerifyingKey,
+        >,
+{
+    type Config = AggregationConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            svk: self.svk,
+            snarks: self
+                .snarks
+                .iter()
+                .map(SnarkWitness::without_witnesses)
+                .collect(),
+            chaining: self.chaining.clone(),
+            links: self.links.clone(),
+            instance: vec![M::Fr::ZERO; self.instance.len()],
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<M::Fr>) -> Self::Config {
+        AggregationConfig::configure::<M::G1Affine>(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<M::Fr>,
+    ) -> Result<(), Error> {
+        config.load_table(&mut layouter)?;
+        let (instances, hash, accumulator_limbs) = config.aggregate_hashed::<M, As>(
+            &mut layouter,
+            &self.svk,
+            self.snarks.clone(),
+            &self.links,
+            &self
+                .snarks
+                .iter()
+                .map(|snark| public_instance_indices(snark.protocol()))
+                .collect_vec(),
+        )?;
+
+        // Constrain chaining between consecutive snarks
+        layouter.assign_region(
+            || "Chain snarks",
+            |mut region| {
+                for (prev, next) in instances.iter().tuple_windows() {
+                    for ((from_column, from_row), (to_column, to_row)) in self.chaining.iter() {
+                        region.constrain_equal(
+                            prev[*from_column][*from_row].cell(),
+                            next[*to_column][*to_row].cell(),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        // Constrain equality to instance values
+        let main_gate = config.main_gate();
+        let (first, last) = (instances.first().unwrap(), instances.last().unwrap());
+        for (row, limb) in iter::empty()
+            .chain(
+                self.chaining
+                    .iter()
+                    .map(|(_, (column, row))| first[*column][*row].clone()),
+            )
+            .chain(
+                self.chaining
+                    .iter()
+                    .map(|((column, row), _)| last[*column][*row].clone()),
+            )
+            .chain([hash])
+            .chain(accumulator_limbs)
+            .enumerate()
+        {
+            main_gate.expose_public(layouter.namespace(|| ""), limb, row)?;
+        }
+
+        Ok(())
+    }
+}
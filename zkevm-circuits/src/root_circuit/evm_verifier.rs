@@ -0,0 +1,99 @@
+//! EVM verifier of `RootCircuit` proofs.
+//!
+//! The verifier is generated by running the `PlonkVerifier` over the
+//! `EvmLoader`, which records the verification as Solidity code. The proof to
+//! verify must be created with `EvmTranscript`, and the accumulator left in the
+//! instance by the aggregation is checked with a pairing by the contract.
+use super::{KzgDk, KzgSvk, PlonkVerifier};
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fq, Fr, G1Affine},
+    plonk::VerifyingKey,
+    poly::{commitment::ParamsProver, kzg::commitment::ParamsKZG},
+};
+use snark_verifier::{
+    loader::evm::{self, EvmLoader},
+    system::halo2::{compile, transcript::evm::EvmTranscript, Config},
+    verifier::{plonk::PlonkProtocol, SnarkVerifier},
+};
+use std::rc::Rc;
+
+pub use snark_verifier::loader::evm::encode_calldata;
+
+/// Generate the Solidity code of a contract verifying proofs of the circuit
+/// with verifying key `vk`, given the number of instance of each instance
+/// column and the indices of the accumulator limbs in them, as returned by
+/// `RootCircuit::num_instance` and `RootCircuit::accumulator_indices`. Circuits
+/// that don't carry an accumulator pass `None`.
+pub fn gen_evm_verifier_code<As>(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    num_instance: Vec<usize>,
+    accumulator_indices: Option<Vec<(usize, usize)>>,
+) -> String
+where
+    PlonkVerifier<As>: SnarkVerifier<
+        G1Affine,
+        Rc<EvmLoader>,
+        VerifyingKey = KzgDk<Bn256>,
+        Protocol = PlonkProtocol<G1Affine, Rc<EvmLoader>>,
+    >,
+{
+    let protocol = compile(
+        params,
+        vk,
+        Config::kzg()
+            .with_num_instance(num_instance.clone())
+            .with_accumulator_indices(accumulator_indices),
+    );
+    let svk = KzgSvk::<Bn256>::new(params.get_g()[0]);
+    let dk = KzgDk::new(svk, params.g2(), params.s_g2());
+
+    let loader = EvmLoader::new::<Fq, Fr>();
+    let protocol = protocol.loaded(&loader);
+    let mut transcript = EvmTranscript::<_, Rc<EvmLoader>, _, _>::new(&loader);
+
+    let instances = transcript.load_instances(num_instance);
+    let proof = PlonkVerifier::<As>::read_proof(&dk, &protocol, &instances, &mut transcript)
+        .expect("EvmLoader never fails to read a proof");
+    PlonkVerifier::<As>::verify(&dk, &protocol, &instances, &proof)
+        .expect("EvmLoader never fails to verify a proof");
+
+    loader.solidity_code()
+}
+
+/// Generate the deployment code of a contract verifying proofs of the circuit
+/// with verifying key `vk`. See [`gen_evm_verifier_code`].
+///
+/// Compiling the verifier requires `solc` to be available in `PATH`.
+pub fn gen_evm_verifier<As>(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    num_instance: Vec<usize>,
+    accumulator_indices: Option<Vec<(usize, usize)>>,
+) -> Vec<u8>
+where
+    PlonkVerifier<As>: SnarkVerifier<
+        G1Affine,
+        Rc<EvmLoader>,
+        VerifyingKey = KzgDk<Bn256>,
+        Protocol = PlonkProtocol<G1Affine, Rc<EvmLoader>>,
+    >,
+{
+    evm::compile_solidity(&gen_evm_verifier_code::<As>(
+        params,
+        vk,
+        num_instance,
+        accumulator_indices,
+    ))
+}
+
+/// Deploy the verifier with an in-process EVM (`revm`) and call it with the calldata
+/// encoding `instances` and `proof`. Returns the gas used by the call, or an
+/// error when deployment fails or the proof is rejected.
+pub fn evm_verify(
+    deployment_code: Vec<u8>,
+    instances: &[Vec<Fr>],
+    proof: &[u8],
+) -> Result<u64, String> {
+    evm::deploy_and_call(deployment_code, encode_calldata(instances, proof))
+}
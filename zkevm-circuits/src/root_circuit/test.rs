@@ -1,16 +1,39 @@
 use crate::{
-    root_circuit::{compile, Config, Gwc, PoseidonTranscript, RootCircuit},
-    super_circuit::{test::block_1tx, SuperCircuit},
+    evm_circuit::EvmCircuit,
+    root_circuit::{
+        advice_commitment_index,
+        aggregation::test::{
+            rand_standard_plonk_snarks, standard_plonk_snarks, SnarkOwned, StandardPlonk,
+        },
+        compile, evm_verify, gen_evm_verifier, hash_instances, linked_degrees, table_links,
+        AggregationCircuit, Config, EvmTranscript, Gwc, NativeLoader, PoseidonTranscript,
+        RootCircuit, LIMBS,
+    },
+    state_circuit::StateCircuit,
+    super_circuit::{test::block_1tx, SubCircuitKind, SuperCircuit},
+    table::LookupTable,
+    util::{log2_ceil, SubCircuit},
+    witness::block_convert,
 };
 use bus_mapping::circuit_input_builder::FixedCParams;
+#[cfg(feature = "split-proof")]
+use bus_mapping::mock::BlockData;
+#[cfg(feature = "split-proof")]
+use halo2_proofs::halo2curves::bn256::G1Affine;
 use halo2_proofs::{
     circuit::Value,
-    dev::MockProver,
-    halo2curves::bn256::Bn256,
-    plonk::{create_proof, keygen_pk, keygen_vk},
-    poly::kzg::{
-        commitment::{KZGCommitmentScheme, ParamsKZG},
-        multiopen::ProverGWC,
+    dev::{CellValue, MockProver},
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        ff::Field,
+    },
+    plonk::{create_proof, keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem},
+    poly::{
+        commitment::Params,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::ProverGWC,
+        },
     },
 };
 use itertools::Itertools;
@@ -77,3 +100,556 @@ fn test_root_circuit() {
         Ok(())
     );
 }
+
+#[ignore = "Requires solc and high memory"]
+#[test]
+fn test_root_circuit_evm_verifier() {
+    // Aggregate a small circuit so that the root circuit fits in a low degree
+    let params = ParamsKZG::<Bn256>::setup(21, OsRng);
+    let inner_params = {
+        let mut params = params.clone();
+        params.downsize(8);
+        params
+    };
+    let snark = rand_standard_plonk_snarks(&inner_params, 1).remove(0);
+    let root_circuit = RootCircuit::<Bn256, Gwc<_>>::new(
+        &inner_params,
+        &snark.protocol,
+        Value::known(&snark.instances),
+        Value::known(&snark.proof),
+    )
+    .unwrap();
+    let instance = root_circuit.instance();
+
+    let pk = keygen_pk(
+        &params,
+        keygen_vk(&params, &root_circuit).unwrap(),
+        &root_circuit,
+    )
+    .unwrap();
+    let proof = {
+        let mut transcript = EvmTranscript::<_, NativeLoader, _, _>::new(Vec::new());
+        create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+            &params,
+            &pk,
+            &[root_circuit.clone()],
+            &[&instance.iter().map(Vec::as_slice).collect_vec()],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        transcript.finalize()
+    };
+
+    let deployment_code = gen_evm_verifier::<Gwc<Bn256>>(
+        &params,
+        pk.get_vk(),
+        root_circuit.num_instance(),
+        Some(root_circuit.accumulator_indices()),
+    );
+    let gas_used = evm_verify(deployment_code.clone(), &instance, &proof).unwrap();
+    log::info!("root circuit proof verified on EVM with {} gas", gas_used);
+
+    // A proof with a tampered instance must be rejected
+    let mut tampered = instance;
+    tampered[0][0] += Fr::ONE;
+    assert!(evm_verify(deployment_code, &tampered, &proof).is_err());
+}
+
+#[test]
+fn test_evm_verifier_standard_plonk() {
+    // Same flow as the root circuit verifier at a degree small enough for CI
+    let params = ParamsKZG::<Bn256>::setup(8, OsRng);
+    let circuit = StandardPlonk::<Fr>::rand(OsRng);
+    let instance = circuit.instances();
+
+    let pk = keygen_pk(&params, keygen_vk(&params, &circuit).unwrap(), &circuit).unwrap();
+    let proof = {
+        let mut transcript = EvmTranscript::<_, NativeLoader, _, _>::new(Vec::new());
+        create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+            &params,
+            &pk,
+            &[circuit],
+            &[&instance.iter().map(Vec::as_slice).collect_vec()],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        transcript.finalize()
+    };
+
+    let deployment_code = gen_evm_verifier::<Gwc<Bn256>>(
+        &params,
+        pk.get_vk(),
+        instance.iter().map(Vec::len).collect(),
+        None,
+    );
+    assert!(evm_verify(deployment_code.clone(), &instance, &proof).is_ok());
+
+    let mut tampered = instance;
+    tampered[0][0] += Fr::ONE;
+    assert!(evm_verify(deployment_code, &tampered, &proof).is_err());
+}
+
+#[test]
+fn test_aggregation_circuit_chaining() {
+    let params = ParamsKZG::<Bn256>::setup(8, OsRng);
+    let value = Fr::from(42);
+    let snarks = standard_plonk_snarks(&params, [value, value, value]);
+
+    let aggregation = AggregationCircuit::<Bn256, Gwc<_>>::new(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![((0, 0), (0, 0))],
+    )
+    .unwrap();
+    let instance = aggregation.instance();
+    // Chained instance of the first and last snarks, hash of the instance of
+    // each snark, then the accumulator limbs
+    assert_eq!(
+        &instance[0][..3],
+        &[value, value, hash_instances(&[value, value, value])]
+    );
+    assert_eq!(
+        aggregation.accumulator_indices(),
+        (3..instance[0].len()).map(|row| (0, row)).collect_vec()
+    );
+    // The instance layout doesn't depend on the number of snarks
+    let pair = AggregationCircuit::<Bn256, Gwc<_>>::new(
+        &params,
+        snarks[..2].iter().map(SnarkOwned::as_snark),
+        vec![((0, 0), (0, 0))],
+    )
+    .unwrap();
+    assert_eq!(pair.num_instance(), aggregation.num_instance());
+    assert_eq!(
+        MockProver::run(21, &aggregation, instance)
+            .unwrap()
+            .verify(),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_aggregation_circuit_unchained() {
+    let params = ParamsKZG::<Bn256>::setup(8, OsRng);
+    let snarks = standard_plonk_snarks(&params, [Fr::from(1), Fr::from(2)]);
+
+    assert!(AggregationCircuit::<Bn256, Gwc<_>>::new(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![((0, 0), (0, 0))],
+    )
+    .is_err());
+}
+
+/// Returns the witness index of the table commitment of `StandardPlonk`.
+fn standard_plonk_table_index() -> usize {
+    let mut cs = ConstraintSystem::default();
+    let config = StandardPlonk::<Fr>::configure(&mut cs);
+    advice_commitment_index(&cs, config.table)
+}
+
+#[test]
+fn test_aggregation_circuit_linked() {
+    let params = ParamsKZG::<Bn256>::setup(8, OsRng);
+    let snarks = standard_plonk_snarks(&params, [Fr::from(7), Fr::from(7)]);
+    let table = standard_plonk_table_index();
+
+    let aggregation = AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        vec![((0, table), (1, table))],
+    )
+    .unwrap();
+    let instance = aggregation.instance();
+    assert_eq!(
+        MockProver::run(21, &aggregation, instance)
+            .unwrap()
+            .verify(),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_aggregation_circuit_linked_degree_mismatch() {
+    let params = ParamsKZG::<Bn256>::setup(9, OsRng);
+    let small_params = {
+        let mut params = params.clone();
+        params.downsize(8);
+        params
+    };
+    let table = standard_plonk_table_index();
+    let snarks = standard_plonk_snarks(&small_params, [Fr::from(7)])
+        .into_iter()
+        .chain(standard_plonk_snarks(&params, [Fr::from(7)]))
+        .collect_vec();
+
+    // The commitments of a same table differ with the degree, see
+    // `linked_degrees`
+    assert!(AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        vec![((0, table), (1, table))],
+    )
+    .is_err());
+}
+
+#[test]
+fn test_aggregation_circuit_linked_degrees() {
+    // Snarks 0 and 2 are linked through snark 1, snark 3 is not linked
+    let table = standard_plonk_table_index();
+    let links = vec![((0, table), (1, table)), ((1, table), (2, table))];
+    let degrees = linked_degrees(&[8, 10, 9, 8], &links);
+    assert_eq!(degrees, [10, 10, 10, 8]);
+
+    let params = ParamsKZG::<Bn256>::setup(10, OsRng);
+    let snarks = degrees
+        .iter()
+        .flat_map(|k| standard_plonk_snarks(&downsized(&params, *k), [Fr::from(7)]))
+        .collect_vec();
+    let aggregation = AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        links,
+    )
+    .unwrap();
+    let instance = aggregation.instance();
+    assert_eq!(
+        MockProver::run(21, &aggregation, instance)
+            .unwrap()
+            .verify(),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_aggregation_circuit_unlinked() {
+    let params = ParamsKZG::<Bn256>::setup(8, OsRng);
+    let snarks = standard_plonk_snarks(&params, [Fr::from(7), Fr::from(8)]);
+    let table = standard_plonk_table_index();
+
+    assert!(AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        vec![((0, table), (1, table))],
+    )
+    .is_err());
+}
+
+#[test]
+fn test_aggregation_circuit_linked_not_first_phase() {
+    let params = ParamsKZG::<Bn256>::setup(8, OsRng);
+    let snarks = standard_plonk_snarks(&params, [Fr::from(7), Fr::from(7)]);
+    let table = standard_plonk_table_index();
+
+    // Only the commitments of first phase advice columns can be linked
+    let witness = snarks[1].protocol.num_witness[0];
+    assert!(AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        vec![((0, table), (1, witness))],
+    )
+    .is_err());
+}
+
+#[ignore = "Due to high memory requirement"]
+#[test]
+fn test_aggregation_circuit_nested() {
+    let params = ParamsKZG::<Bn256>::setup(21, OsRng);
+    let inner_params = {
+        let mut params = params.clone();
+        params.downsize(8);
+        params
+    };
+    let value = Fr::from(42);
+    let snarks = standard_plonk_snarks(&inner_params, [value, value]);
+
+    // Prove the aggregation of two snarks
+    let inner = AggregationCircuit::<Bn256, Gwc<_>>::new(
+        &inner_params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![((0, 0), (0, 0))],
+    )
+    .unwrap();
+    let inner_instance = inner.instance();
+    let inner_public = inner_instance[0][..3].to_vec();
+    let pk = keygen_pk(&params, keygen_vk(&params, &inner).unwrap(), &inner).unwrap();
+    let protocol = compile(
+        &params,
+        pk.get_vk(),
+        Config::kzg()
+            .with_num_instance(inner.num_instance())
+            .with_accumulator_indices(Some(inner.accumulator_indices())),
+    );
+    let proof = {
+        let mut transcript = PoseidonTranscript::new(Vec::new());
+        create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+            &params,
+            &pk,
+            &[inner],
+            &[&inner_instance.iter().map(Vec::as_slice).collect_vec()],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        transcript.finalize()
+    };
+    let snark = SnarkOwned::new(protocol, inner_instance, proof);
+
+    // Aggregate the aggregation proof, folding its accumulator
+    let outer =
+        AggregationCircuit::<Bn256, Gwc<_>>::new(&params, [snark.as_snark()], vec![]).unwrap();
+    let instance = outer.instance();
+    // Hash of the public instance of the inner aggregation, without the inner
+    // accumulator limbs
+    assert_eq!(instance[0].len(), 1 + 4 * LIMBS);
+    assert_eq!(instance[0][0], hash_instances(&inner_public));
+    assert_eq!(
+        MockProver::run(21, &outer, instance).unwrap().verify(),
+        Ok(())
+    );
+
+    // An inner accumulator that doesn't match its proof must be rejected
+    let (column, row) = snark.protocol.accumulator_indices[0][0];
+    let mut tampered = snark;
+    tampered.instances[column][row] += Fr::ONE;
+    assert!(
+        AggregationCircuit::<Bn256, Gwc<_>>::new(&params, [tampered.as_snark()], vec![]).is_err()
+    );
+}
+
+/// Returns `params` downsized to degree `k`.
+fn downsized(params: &ParamsKZG<Bn256>, k: u32) -> ParamsKZG<Bn256> {
+    let mut params = params.clone();
+    params.downsize(k);
+    params
+}
+
+/// Proves `circuit` and returns its snark.
+#[cfg(feature = "split-proof")]
+fn sub_circuit_snark<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    circuit: C,
+    instance: Vec<Vec<Fr>>,
+) -> SnarkOwned<G1Affine> {
+    let pk = keygen_pk(params, keygen_vk(params, &circuit).unwrap(), &circuit).unwrap();
+    let protocol = compile(
+        params,
+        pk.get_vk(),
+        Config::kzg().with_num_instance(instance.iter().map(|instance| instance.len()).collect()),
+    );
+    let proof = {
+        let mut transcript = PoseidonTranscript::new(Vec::new());
+        create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+            params,
+            &pk,
+            &[circuit],
+            &[&instance.iter().map(Vec::as_slice).collect_vec()],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        transcript.finalize()
+    };
+    SnarkOwned::new(protocol, instance, proof)
+}
+
+#[cfg(feature = "split-proof")]
+#[ignore = "Due to high memory requirement"]
+#[test]
+fn test_aggregation_circuit_sub_circuits() {
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_withdrawals: 5,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+    };
+    let geth_data = block_1tx();
+    let mut builder = BlockData::new_from_geth_data_with_params(geth_data.clone(), circuits_params)
+        .new_circuit_input_builder();
+    builder
+        .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+        .unwrap();
+    let block = block_convert::<Fr>(&builder).unwrap();
+
+    let params = ParamsKZG::<Bn256>::setup(21, OsRng);
+    let evm_circuit = EvmCircuit::<Fr>::new_from_block(&block);
+    let state_circuit = StateCircuit::<Fr>::new_from_block(&block);
+
+    let mut evm_cs = ConstraintSystem::default();
+    let (evm_config, _) =
+        EvmCircuit::<Fr>::configure_with_params(&mut evm_cs, evm_circuit.params());
+    let mut state_cs = ConstraintSystem::default();
+    let (state_config, _) = StateCircuit::<Fr>::configure(&mut state_cs);
+    let links = table_links(
+        (0, &evm_cs, &evm_config.rw_table.advice_columns()),
+        (1, &state_cs, &state_config.rw_table.advice_columns()),
+    )
+    .unwrap();
+
+    // Both circuits lay out the rw table at the same rows, and are proven in
+    // the larger of their degrees so that its commitments match.
+    let degrees = linked_degrees(
+        &[
+            block.get_test_degree(),
+            // The state circuit loads the u16 table
+            log2_ceil((1 << 16) + StateCircuit::<Fr>::unusable_rows()),
+        ],
+        &links,
+    );
+    assert_eq!(degrees[0], degrees[1]);
+    let sub_params = downsized(&params, degrees[0]);
+    let snarks = [
+        sub_circuit_snark(&sub_params, evm_circuit.clone(), evm_circuit.instance()),
+        sub_circuit_snark(&sub_params, state_circuit.clone(), state_circuit.instance()),
+    ];
+
+    let aggregation = AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        links,
+    )
+    .unwrap();
+    let instance = aggregation.instance();
+    assert_eq!(
+        MockProver::run(21, &aggregation, instance)
+            .unwrap()
+            .verify(),
+        Ok(())
+    );
+
+    // Linking different columns of the rw table must be rejected
+    assert!(AggregationCircuit::<Bn256, Gwc<_>>::new_linked(
+        &params,
+        snarks.iter().map(SnarkOwned::as_snark),
+        vec![],
+        vec![(links[0].0, links[1].1)],
+    )
+    .is_err());
+}
+
+/// Returns the values assigned to `column` in the first `rows` rows, as they
+/// are committed to.
+fn committed_values(prover: &MockProver<Fr>, column: Column<Advice>, rows: usize) -> Vec<Fr> {
+    prover.advice_values(column)[..rows]
+        .iter()
+        .map(|value| match value {
+            CellValue::Assigned(value) => *value,
+            _ => Fr::ZERO,
+        })
+        .collect()
+}
+
+#[test]
+fn test_split_super_circuit() {
+    let circuits_params = FixedCParams {
+        max_txs: 1,
+        max_withdrawals: 5,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+    };
+    let (_, circuit, _, builder) =
+        SuperCircuit::<Fr>::build(block_1tx(), circuits_params, Fr::from(0x100)).unwrap();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    let (evm_circuit, state_circuit) = (circuit.evm_circuit, circuit.state_circuit);
+
+    let mut evm_cs = ConstraintSystem::default();
+    let (evm_config, _) =
+        EvmCircuit::<Fr>::configure_with_params(&mut evm_cs, evm_circuit.params());
+    let mut state_cs = ConstraintSystem::default();
+    let (state_config, _) = StateCircuit::<Fr>::configure(&mut state_cs);
+    let (evm_rw_table, state_rw_table) = (
+        evm_config.rw_table.advice_columns(),
+        state_config.rw_table.advice_columns(),
+    );
+    let links = table_links((0, &evm_cs, &evm_rw_table), (1, &state_cs, &state_rw_table)).unwrap();
+
+    // The copy and keccak tables have second phase columns, so the
+    // sub-circuits sharing them can't be proven separately.
+    for table in [
+        evm_config.copy_table.advice_columns(),
+        evm_config.keccak_table.advice_columns(),
+    ] {
+        assert!(table_links((0, &evm_cs, &table), (1, &evm_cs, &table)).is_err());
+    }
+    let other_circuits = [
+        SubCircuitKind::Evm,
+        SubCircuitKind::Copy,
+        SubCircuitKind::Keccak,
+        SubCircuitKind::Tx,
+        SubCircuitKind::Pi,
+        SubCircuitKind::Bytecode,
+        SubCircuitKind::Exp,
+    ];
+    assert!(SuperCircuit::<Fr>::check_split(&[&other_circuits, &[SubCircuitKind::State]]).is_ok());
+    let state_circuit_with = |circuits: &[SubCircuitKind]| {
+        circuits
+            .iter()
+            .copied()
+            .chain([SubCircuitKind::State])
+            .collect_vec()
+    };
+    for (lhs, rhs) in [
+        // the EVM circuit looks up the copy and keccak tables of the other snark
+        (
+            &other_circuits[..1],
+            state_circuit_with(&other_circuits[1..]),
+        ),
+        // the EVM circuit looks up the keccak table of the other snark
+        (
+            &other_circuits[..2],
+            state_circuit_with(&other_circuits[2..]),
+        ),
+    ] {
+        assert!(SuperCircuit::<Fr>::check_split(&[lhs, &rhs[..]]).is_err());
+    }
+    // the rw table looked up by the EVM circuit is constrained by no snark
+    assert!(SuperCircuit::<Fr>::check_split(&[&other_circuits]).is_err());
+
+    // Prove the state circuit of the super circuit separately from the other
+    // sub-circuits, at the larger of their degrees. The EVM circuit stands
+    // for the snark of the other sub-circuits, whose tables besides the rw
+    // table are not shared with the state circuit.
+    let degrees = linked_degrees(
+        &[
+            block.get_test_degree(),
+            // The state circuit loads the u16 table
+            log2_ceil((1 << 16) + StateCircuit::<Fr>::unusable_rows()),
+        ],
+        &links,
+    );
+    assert_eq!(degrees[0], degrees[1]);
+    let k = degrees[0];
+    let evm_prover = MockProver::run(k, &evm_circuit, evm_circuit.instance()).unwrap();
+    assert_eq!(evm_prover.verify(), Ok(()));
+    let state_prover = MockProver::run(k, &state_circuit, state_circuit.instance()).unwrap();
+    assert_eq!(state_prover.verify(), Ok(()));
+
+    // The unblinded rw table columns are committed to at the same degree, so
+    // the linked commitments match if both circuits assign the same values at
+    // the same rows.
+    let rows = (1 << k) - (evm_cs.blinding_factors().max(state_cs.blinding_factors()) + 1);
+    for (evm_column, state_column) in evm_rw_table.into_iter().zip(state_rw_table) {
+        assert_eq!(
+            committed_values(&evm_prover, evm_column, rows),
+            committed_values(&state_prover, state_column, rows)
+        );
+    }
+}
@@ -14,11 +14,13 @@ use bus_mapping::operation::Target;
 pub use dev::StateCircuit as TestStateCircuit;
 
 use self::{
-    constraint_builder::{MptUpdateTableQueries, RwTableQueries},
+    constraint_builder::{MptUpdateTableQueries, NextCumulativeGasUsedQueries, RwTableQueries},
     lexicographic_ordering::LimbIndex,
 };
 use crate::{
-    table::{AccountFieldTag, LookupTable, MPTProofType, MptTable, RwTable, UXTable},
+    table::{
+        AccountFieldTag, LookupTable, MPTProofType, MptTable, RwTable, TxReceiptFieldTag, UXTable,
+    },
     util::{word::WordLoHi, Challenges, Expr, SubCircuit, SubCircuitConfig},
     witness::{self, MptUpdates, Rw, RwMap},
 };
@@ -51,7 +53,7 @@ pub struct StateCircuitConfig<F> {
     // Figure out why you get errors when this is Selector.
     selector: Column<Fixed>,
     // https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/407
-    rw_table: RwTable,
+    pub(crate) rw_table: RwTable,
     sort_keys: SortKeysConfig,
     // Assigned value at the start of the block. For Rw::Account and
     // Rw::AccountStorage rows this is the committed value in the MPT, for
@@ -66,6 +68,9 @@ pub struct StateCircuitConfig<F> {
     state_root: WordLoHi<Column<Advice>>,
     lexicographic_ordering: LexicographicOrderingConfig,
     not_first_access: Column<Advice>,
+    // For the Rw::TxReceipt CumulativeGasUsed reads, the 16-bit limbs of the gas used by the
+    // next tx, which is the increase of CumulativeGasUsed to the next tx receipt.
+    gas_used_limbs: [Column<Advice>; N_LIMBS_GAS_USED],
     lookups: LookupsConfig,
     // External tables
     mpt_table: MptTable,
@@ -165,6 +170,7 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
             state_root,
             lexicographic_ordering,
             not_first_access: meta.advice_column(),
+            gas_used_limbs: [(); N_LIMBS_GAS_USED].map(|_| meta.advice_column()),
             lookups,
             rw_table,
             mpt_table,
@@ -349,6 +355,27 @@ impl<F: Field> StateCircuitConfig<F> {
                 || Value::known(F::from(mpt_proof_type)),
             )?;
 
+            let gas_used = match row {
+                Rw::TxReceipt {
+                    is_write: false,
+                    field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+                    value,
+                    ..
+                } => rows
+                    .get(offset + NEXT_CUMULATIVE_GAS_USED_ROTATION as usize)
+                    .and_then(|next| next.value_assignment().low_u64().checked_sub(*value))
+                    .unwrap_or_default(),
+                _ => 0,
+            };
+            for (index, column) in self.gas_used_limbs.iter().enumerate() {
+                region.assign_advice(
+                    || "gas_used_limb",
+                    *column,
+                    offset,
+                    || Value::known(F::from((gas_used >> (16 * index)) & 0xffff)),
+                )?;
+            }
+
             // TODO: Switch from Rw::Start -> Rw::Padding to simplify this logic.
             // State root assignment is at previous row (offset - 1) because the state root
             // changes on the last access row.
@@ -396,6 +423,9 @@ impl<F: Field> StateCircuitConfig<F> {
         region.name_column(|| "STATE_initial_value lo", self.initial_value.lo());
         region.name_column(|| "STATE_initial_value hi", self.initial_value.hi());
         region.name_column(|| "STATE_mpt_proof_type", self.mpt_proof_type);
+        for (index, column) in self.gas_used_limbs.iter().enumerate() {
+            region.name_column(|| format!("STATE_gas_used_limb{}", index), *column);
+        }
         region.name_column(|| "STATE_state_root lo", self.state_root.lo());
         region.name_column(|| "STATE_state_root hi", self.state_root.hi());
     }
@@ -531,6 +561,10 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
     let first_different_limb = c.lexicographic_ordering.first_different_limb;
     let final_bits_sum = meta.query_advice(first_different_limb.bits[3], Rotation::cur())
         + meta.query_advice(first_different_limb.bits[4], Rotation::cur());
+    let first_different_limb_bits_sum =
+        first_different_limb.bits.iter().fold(0.expr(), |acc, bit| {
+            acc + meta.query_advice(*bit, Rotation::cur())
+        });
     let mpt_update_table_expressions = c.mpt_table.table_exprs(meta);
     assert_eq!(mpt_update_table_expressions.len(), 12);
 
@@ -603,6 +637,8 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
                 + meta.query_advice(first_different_limb.bits[1], Rotation::cur())
                 + meta.query_advice(first_different_limb.bits[2], Rotation::cur()))
             + final_bits_sum.clone() * (1.expr() - final_bits_sum),
+        // this isn't binary either! only 0 if all the bits are 0, i.e. the tag changed.
+        is_tag_unchanged: first_different_limb_bits_sum,
         address: MpiQueries::new(meta, c.sort_keys.address),
         storage_key: MpiQueries::new(meta, c.sort_keys.storage_key),
         initial_value: meta_query_word(meta, c.initial_value, Rotation::cur()),
@@ -616,5 +652,21 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
         last_access: 1.expr() - meta.query_advice(c.not_first_access, Rotation::next()),
         state_root: meta_query_word(meta, c.state_root, Rotation::cur()),
         state_root_prev: meta_query_word(meta, c.state_root, Rotation::prev()),
+        next_cumulative_gas_used: NextCumulativeGasUsedQueries {
+            tag: meta.query_advice(c.rw_table.tag, Rotation(NEXT_CUMULATIVE_GAS_USED_ROTATION)),
+            id: meta.query_advice(c.rw_table.id, Rotation(NEXT_CUMULATIVE_GAS_USED_ROTATION)),
+            field_tag: meta.query_advice(
+                c.rw_table.field_tag,
+                Rotation(NEXT_CUMULATIVE_GAS_USED_ROTATION),
+            ),
+            value: meta_query_word(
+                meta,
+                c.rw_table.value,
+                Rotation(NEXT_CUMULATIVE_GAS_USED_ROTATION),
+            ),
+        },
+        gas_used_limbs: c
+            .gas_used_limbs
+            .map(|limb| meta.query_advice(limb, Rotation::cur())),
     }
 }
@@ -3,7 +3,7 @@ use super::{
 };
 use crate::{
     evm_circuit::util::{math_gadget::generate_lagrange_base_polynomial, not},
-    table::{AccountFieldTag, MPTProofType},
+    table::{AccountFieldTag, MPTProofType, TxReceiptFieldTag},
     util::{word, Expr},
 };
 use bus_mapping::operation::Target;
@@ -41,6 +41,14 @@ pub struct MptUpdateTableQueries<F: Field> {
     pub old_value: WordLoHi<Expression<F>>,
 }
 
+#[derive(Clone)]
+pub struct NextCumulativeGasUsedQueries<F: Field> {
+    pub tag: Expression<F>,
+    pub id: Expression<F>,
+    pub field_tag: Expression<F>,
+    pub value: WordLoHi<Expression<F>>,
+}
+
 #[derive(Clone)]
 pub struct Queries<F: Field> {
     pub selector: Expression<F>,
@@ -51,6 +59,7 @@ pub struct Queries<F: Field> {
     pub tag_bits: [Expression<F>; 4],
     pub id: MpiQueries<F, N_LIMBS_ID>,
     pub is_tag_and_id_unchanged: Expression<F>,
+    pub is_tag_unchanged: Expression<F>,
     pub address: MpiQueries<F, N_LIMBS_ACCOUNT_ADDRESS>,
     pub storage_key: MpiQueries<F, N_LIMBS_WORD>,
     pub initial_value: WordLoHi<Expression<F>>,
@@ -63,6 +72,8 @@ pub struct Queries<F: Field> {
     pub last_access: Expression<F>,
     pub state_root: WordLoHi<Expression<F>>,
     pub state_root_prev: WordLoHi<Expression<F>>,
+    pub next_cumulative_gas_used: NextCumulativeGasUsedQueries<F>,
+    pub gas_used_limbs: [Expression<F>; N_LIMBS_GAS_USED],
 }
 
 type Constraint<F> = (&'static str, Expression<F>);
@@ -143,6 +154,9 @@ impl<F: Field> ConstraintBuilder<F> {
         self.condition(q.tag_matches(Target::CallContext), |cb| {
             cb.build_call_context_constraints(q)
         });
+        self.condition(q.tag_matches(Target::TxReceipt), |cb| {
+            cb.build_tx_receipt_constraints(q)
+        });
         self.condition(q.tag_matches(Target::TxLog), |cb| {
             cb.build_tx_log_constraints(q)
         });
@@ -248,12 +262,15 @@ impl<F: Field> ConstraintBuilder<F> {
         for limb in &q.address.limbs[2..] {
             self.require_zero("memory address fits into 2 limbs", limb.clone());
         }
-        // 2.3. value is a byte
+        // 2.3. mem_addr is a word address. limb0 / 32 only fits into 16 bits when limb0 is a
+        // multiple of 32, and 2^16 is a multiple of 32 so the limbs above don't matter.
         self.add_lookup(
-            "memory value is a byte (lo is u8)",
-            vec![(q.rw_table.value.lo(), q.lookups.u8.clone())],
+            "memory address is 32-byte aligned",
+            vec![(
+                q.address.limbs[0].clone() * Expression::Constant(F::from(32).invert().unwrap()),
+                q.lookups.u16.clone(),
+            )],
         );
-        self.require_zero("memory value is a byte (hi is 0)", q.rw_table.value.hi());
         // 2.4. Start initial value is 0
         self.require_word_zero("initial Memory value is 0", q.initial_value());
         // 2.5. state root does not change
@@ -262,11 +279,14 @@ impl<F: Field> ConstraintBuilder<F> {
             q.state_root(),
             q.state_root_prev(),
         );
-        self.require_word_equal(
-            "value_prev column equals initial_value for Memory",
-            q.value_prev_column(),
-            q.initial_value(),
-        );
+        // 2.6. value_prev is the value of the previous access to the word
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_word_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
     }
 
     fn build_stack_constraints(&mut self, q: &Queries<F>) {
@@ -529,6 +549,90 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
+    fn build_tx_receipt_constraints(&mut self, q: &Queries<F>) {
+        // 11.0. Unused keys are 0
+        self.require_zero("address is 0 for TxReceipt", q.rw_table.address.clone());
+        self.require_word_zero(
+            "storage_key is 0 for TxReceipt",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_in_set(
+            "field_tag in TxReceiptFieldTag range",
+            q.field_tag(),
+            set::<F, TxReceiptFieldTag>(),
+        );
+        self.require_zero("TxReceipt value fits into 128 bits", q.value().hi());
+        // 11.1. tx_id increases by 0 or 1 between TxReceipt rows
+        self.condition(q.is_tag_unchanged.clone(), |cb| {
+            cb.require_boolean(
+                "if previous row is also TxReceipt, tx_id change is 0 or 1",
+                q.id() - q.rw_table.prev_id.clone(),
+            );
+        });
+        // 11.2. Every field of a receipt is written once, by the EndTx of its tx. The only
+        // reads are the ones of the CumulativeGasUsed of the previous tx by the next EndTx.
+        self.require_zero(
+            "first access to a TxReceipt field is a write",
+            q.first_access() * q.is_read(),
+        );
+        self.require_zero(
+            "TxReceipt field is written once",
+            q.not_first_access.clone() * q.is_write(),
+        );
+        self.require_zero(
+            "TxReceipt reads are of CumulativeGasUsed",
+            q.is_read() * (q.field_tag() - TxReceiptFieldTag::CumulativeGasUsed.expr()),
+        );
+        // 11.3. CumulativeGasUsed doesn't decrease from a tx to the next one. Every tx but the
+        // first reads the CumulativeGasUsed of the previous tx, so the check is done on the read
+        // against the CumulativeGasUsed row of the next tx.
+        self.condition(q.is_read(), |cb| {
+            let next = &q.next_cumulative_gas_used;
+            cb.require_equal(
+                "row of the next CumulativeGasUsed is TxReceipt",
+                next.tag.clone(),
+                Target::TxReceipt.expr(),
+            );
+            cb.require_equal(
+                "row of the next CumulativeGasUsed is of the next tx",
+                next.id.clone(),
+                q.id() + 1.expr(),
+            );
+            cb.require_equal(
+                "row of the next CumulativeGasUsed is CumulativeGasUsed",
+                next.field_tag.clone(),
+                TxReceiptFieldTag::CumulativeGasUsed.expr(),
+            );
+            cb.require_equal(
+                "CumulativeGasUsed increases by the gas used by the next tx",
+                next.value.lo() - q.value().lo(),
+                q.gas_used_limbs
+                    .iter()
+                    .rev()
+                    .fold(0.expr(), |acc, limb| acc * (1 << 16).expr() + limb.clone()),
+            );
+            for limb in &q.gas_used_limbs {
+                cb.add_lookup(
+                    "gas used by the next tx fits into 64 bits",
+                    vec![(limb.clone(), q.lookups.u16.clone())],
+                );
+            }
+        });
+        // 11.4. TxReceipt initial value is 0
+        self.require_word_zero("initial TxReceipt value is 0", q.initial_value());
+        self.require_word_equal(
+            "value_prev column equals initial_value for TxReceipt",
+            q.value_prev_column(),
+            q.initial_value(),
+        );
+        // 11.5. state root does not change
+        self.require_word_equal(
+            "state_root is unchanged for TxReceipt",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+    }
+
     fn require_zero(&mut self, name: &'static str, e: Expression<F>) {
         self.constraints.push((name, self.condition.clone() * e));
     }
@@ -2,3 +2,8 @@ pub(super) const N_LIMBS_RW_COUNTER: usize = 2;
 pub(super) const N_LIMBS_ACCOUNT_ADDRESS: usize = 10;
 pub(super) const N_LIMBS_ID: usize = 2;
 pub(super) const N_LIMBS_WORD: usize = 16;
+pub(super) const N_LIMBS_GAS_USED: usize = 4;
+// Rows from the CumulativeGasUsed read of a tx receipt to the CumulativeGasUsed write of the
+// next tx receipt, which has its PostStateOrStatus row and the LogLength row of the read receipt
+// in between.
+pub(super) const NEXT_CUMULATIVE_GAS_USED_ROTATION: i32 = 3;
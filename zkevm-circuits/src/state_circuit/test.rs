@@ -5,7 +5,7 @@ use crate::{
     witness::{MptUpdates, Rw, RwMap},
 };
 use bus_mapping::operation::{
-    MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW,
+    MemoryWordOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW,
 };
 use eth_types::{
     address,
@@ -35,7 +35,7 @@ fn state_circuit_unusable_rows() {
 }
 
 fn test_state_circuit_ok(
-    memory_ops: Vec<Operation<MemoryOp>>,
+    memory_ops: Vec<Operation<MemoryWordOp>>,
     stack_ops: Vec<Operation<StackOp>>,
     storage_ops: Vec<Operation<StorageOp>>,
 ) {
@@ -71,7 +71,7 @@ fn verifying_key_independent_of_rw_length() {
             memory: vec![Operation::new(
                 RWCounter::from(1),
                 RW::WRITE,
-                MemoryOp::new(1, MemoryAddress::from(0), 32),
+                MemoryWordOp::new(1, MemoryAddress::from(0), Word::from(32), Word::zero()),
             )],
             ..Default::default()
         }),
@@ -95,23 +95,23 @@ fn state_circuit_simple_2() {
     let memory_op_0 = Operation::new(
         RWCounter::from(12),
         RW::WRITE,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new(1, MemoryAddress::from(0), Word::from(32), Word::zero()),
     );
     let memory_op_1 = Operation::new(
         RWCounter::from(24),
         RW::READ,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new_read(1, MemoryAddress::from(0), Word::from(32)),
     );
 
     let memory_op_2 = Operation::new(
         RWCounter::from(17),
         RW::WRITE,
-        MemoryOp::new(1, MemoryAddress::from(1), 32),
+        MemoryWordOp::new(1, MemoryAddress::from(32), Word::from(32), Word::zero()),
     );
     let memory_op_3 = Operation::new(
         RWCounter::from(87),
         RW::READ,
-        MemoryOp::new(1, MemoryAddress::from(1), 32),
+        MemoryWordOp::new_read(1, MemoryAddress::from(32), Word::from(32)),
     );
 
     let stack_op_0 = Operation::new(
@@ -174,12 +174,12 @@ fn state_circuit_simple_6() {
     let memory_op_0 = Operation::new(
         RWCounter::from(12),
         RW::WRITE,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new(1, MemoryAddress::from(0), Word::from(32), Word::zero()),
     );
     let memory_op_1 = Operation::new(
         RWCounter::from(13),
         RW::READ,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new_read(1, MemoryAddress::from(0), Word::from(32)),
     );
     let storage_op_2 = Operation::new(
         RWCounter::from(19),
@@ -201,7 +201,7 @@ fn lexicographic_ordering_test_1() {
     let memory_op = Operation::new(
         RWCounter::from(12),
         RW::WRITE,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new(1, MemoryAddress::from(0), Word::from(32), Word::zero()),
     );
     let storage_op = Operation::new(
         RWCounter::from(19),
@@ -223,12 +223,12 @@ fn lexicographic_ordering_test_2() {
     let memory_op_0 = Operation::new(
         RWCounter::from(12),
         RW::WRITE,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new(1, MemoryAddress::from(0), Word::from(32), Word::zero()),
     );
     let memory_op_1 = Operation::new(
         RWCounter::from(13),
         RW::WRITE,
-        MemoryOp::new(1, MemoryAddress::from(0), 32),
+        MemoryWordOp::new(1, MemoryAddress::from(0), Word::from(32), Word::from(32)),
     );
     test_state_circuit_ok(vec![memory_op_0, memory_op_1], vec![], vec![]);
 }
@@ -433,8 +433,9 @@ fn nonlexicographic_order_tag() {
         rw_counter: 1,
         is_write: true,
         call_id: 1,
-        memory_address: 10,
-        byte: 12,
+        memory_address: 32,
+        value: Word::from(12),
+        value_prev: Word::zero(),
     };
     let second = Rw::CallContext {
         rw_counter: 2,
@@ -631,15 +632,17 @@ fn read_inconsistency() {
             rw_counter: 10,
             is_write: false,
             call_id: 1,
-            memory_address: 10,
-            byte: 0,
+            memory_address: 32,
+            value: Word::zero(),
+            value_prev: Word::zero(),
         },
         Rw::Memory {
             rw_counter: 40,
             is_write: false,
             call_id: 1,
-            memory_address: 10,
-            byte: 200,
+            memory_address: 32,
+            value: Word::from(200),
+            value_prev: Word::zero(),
         },
     ];
 
@@ -676,7 +679,8 @@ fn invalid_memory_address() {
         is_write: true,
         call_id: 1,
         memory_address: 1u64 << 32,
-        byte: 12,
+        value: Word::from(12),
+        value_prev: Word::zero(),
     }];
 
     assert_error_matches(verify(rows), "memory address fits into 2 limbs");
@@ -688,8 +692,9 @@ fn bad_initial_memory_value() {
         rw_counter: 1,
         is_write: true,
         call_id: 1,
-        memory_address: 10,
-        byte: 0,
+        memory_address: 32,
+        value: Word::zero(),
+        value_prev: Word::zero(),
     }];
 
     let v = Fr::from(200);
@@ -710,24 +715,47 @@ fn bad_initial_memory_value() {
 }
 
 #[test]
-fn invalid_memory_value() {
+fn unaligned_memory_address() {
     let rows = vec![Rw::Memory {
         rw_counter: 1,
         is_write: true,
         call_id: 1,
         memory_address: 10,
-        byte: 1,
+        value: Word::from(12),
+        value_prev: Word::zero(),
     }];
-    let v = Fr::from(256);
-    let overrides = HashMap::from([
-        ((AdviceColumn::ValueHi, 0), Fr::ZERO),
-        ((AdviceColumn::ValueLo, 0), v),
-        ((AdviceColumn::NonEmptyWitness, 0), v.invert().unwrap()),
-    ]);
+
+    assert_error_matches(verify(rows), "memory address is 32-byte aligned");
+}
+
+#[test]
+fn memory_value_prev_mismatch() {
+    let rows = vec![
+        Rw::Memory {
+            rw_counter: 1,
+            is_write: true,
+            call_id: 1,
+            memory_address: 32,
+            value: Word::from(12),
+            value_prev: Word::zero(),
+        },
+        Rw::Memory {
+            rw_counter: 2,
+            is_write: true,
+            call_id: 1,
+            memory_address: 32,
+            value: Word::from(13),
+            value_prev: Word::from(12),
+        },
+    ];
+    let overrides = HashMap::from([((AdviceColumn::ValuePrevLo, 1), Fr::from(11))]);
 
     let result = verify_with_overrides(rows, overrides);
 
-    assert_error_matches(result, "memory value is a byte (lo is u8)");
+    assert_error_matches(
+        result,
+        "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+    );
 }
 
 #[test]
@@ -967,21 +995,20 @@ fn variadic_size_check() {
 }
 
 #[test]
-#[ignore = "TxReceipt constraints not yet implemented"]
 fn bad_initial_tx_receipt_value() {
     let rows = vec![Rw::TxReceipt {
         rw_counter: 1,
-        is_write: false,
+        is_write: true,
         tx_id: 3421,
         field_tag: TxReceiptFieldTag::CumulativeGasUsed,
         value: 0,
     }];
 
     let overrides = HashMap::from([
-        ((AdviceColumn::ValueHi, 0), Fr::ZERO),
-        ((AdviceColumn::ValueLo, 0), Fr::from(1900)),
         ((AdviceColumn::InitialValueHi, 0), Fr::ZERO),
         ((AdviceColumn::InitialValueLo, 0), Fr::from(1900)),
+        ((AdviceColumn::ValuePrevHi, 0), Fr::ZERO),
+        ((AdviceColumn::ValuePrevLo, 0), Fr::from(1900)),
     ]);
 
     assert_error_matches(
@@ -990,6 +1017,80 @@ fn bad_initial_tx_receipt_value() {
     );
 }
 
+// Sorted TxReceipt rows of EndTx steps taking 4 rws each: PostStateOrStatus and LogLength writes,
+// the read of the CumulativeGasUsed of the previous tx and the CumulativeGasUsed write.
+fn tx_receipt_rows(cumulative_gas_used: &[u64]) -> Vec<Rw> {
+    let receipt = |rw_counter, is_write, tx_id, field_tag, value| Rw::TxReceipt {
+        rw_counter,
+        is_write,
+        tx_id,
+        field_tag,
+        value,
+    };
+    let mut rows = vec![];
+    for (index, value) in cumulative_gas_used.iter().enumerate() {
+        let (tx_id, rw_counter) = (index + 1, 4 * index);
+        rows.push(receipt(
+            rw_counter + 1,
+            true,
+            tx_id,
+            TxReceiptFieldTag::PostStateOrStatus,
+            1,
+        ));
+        rows.push(receipt(
+            rw_counter + 4,
+            true,
+            tx_id,
+            TxReceiptFieldTag::CumulativeGasUsed,
+            *value,
+        ));
+        if index + 1 < cumulative_gas_used.len() {
+            rows.push(receipt(
+                rw_counter + 7,
+                false,
+                tx_id,
+                TxReceiptFieldTag::CumulativeGasUsed,
+                *value,
+            ));
+        }
+        rows.push(receipt(
+            rw_counter + 2,
+            true,
+            tx_id,
+            TxReceiptFieldTag::LogLength,
+            0,
+        ));
+    }
+    rows
+}
+
+#[test]
+fn tx_receipts() {
+    assert_eq!(verify(tx_receipt_rows(&[21000, 21000, 100000])), Ok(()));
+}
+
+#[test]
+fn tx_receipt_written_twice() {
+    let mut rows = tx_receipt_rows(&[21000]);
+    rows.push(Rw::TxReceipt {
+        rw_counter: 5,
+        is_write: true,
+        tx_id: 1,
+        field_tag: TxReceiptFieldTag::LogLength,
+        value: 3,
+    });
+
+    assert_error_matches(verify(rows), "TxReceipt field is written once");
+}
+
+#[test]
+fn decreasing_cumulative_gas_used() {
+    assert_error_matches(
+        verify(tx_receipt_rows(&[100000, 21000])),
+        "CumulativeGasUsed increases by the gas used by the next tx",
+    );
+}
+
 fn prover(rows: Vec<Rw>, overrides: HashMap<(AdviceColumn, isize), Fr>) -> MockProver<Fr> {
     let updates = MptUpdates::mock_from(&rows);
     let circuit = StateCircuit::<Fr> {
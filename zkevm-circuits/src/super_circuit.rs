@@ -50,23 +50,33 @@
 //!   - [x] Tx Circuit
 //!   - [ ] MPT Circuit
 
+mod sizing;
+mod split;
 #[cfg(test)]
 pub(crate) mod test;
 
+pub use sizing::{SubCircuitKind, SubCircuitOverflow};
+
 use crate::{
     bytecode_circuit::{BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs},
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
-    evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
+    evm_circuit::{
+        param::{step_height_map, EvmColumnLayout},
+        EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs,
+    },
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
-    pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
+    pi_circuit::{
+        PiCircuit, PiCircuitConfig, PiCircuitConfigArgs, PREV_STATE_ROOT_INSTANCE_ROWS,
+        STATE_ROOT_INSTANCE_ROWS,
+    },
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
         BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, MptTable, RwTable, TxTable,
         UXTable, WdTable,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
-    util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
+    util::{cell_manager::CellPlacementOrder, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
     witness::{block_convert, Block, MptUpdates},
 };
 use bus_mapping::{
@@ -111,6 +121,8 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             max_calldata,
             mock_randomness,
             feature_config,
+            evm_layout,
+            evm_cell_placement,
         }: Self::ConfigArgs,
     ) -> Self {
         let tx_table = TxTable::construct(meta);
@@ -182,6 +194,7 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 bytecode_table: bytecode_table.clone(),
                 copy_table,
                 q_enable: q_copy_table,
+                u8_table,
                 challenges: challenges.clone(),
             },
         );
@@ -211,6 +224,9 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 u8_table,
                 u16_table,
                 feature_config,
+                layout: evm_layout,
+                cell_placement: evm_cell_placement,
+                constraint_coverage: false,
             },
         );
 
@@ -260,13 +276,27 @@ pub struct SuperCircuit<F: Field> {
 }
 
 impl<F: Field> SuperCircuit<F> {
-    /// Return the number of rows required to verify a given block
+    /// Return the number of rows required to verify a given block with the
+    /// default EVM circuit column layout
     pub fn get_num_rows_required(block: &Block<F>) -> usize {
-        let num_rows_evm_circuit = EvmCircuit::<F>::get_num_rows_required(block);
+        let num_rows_evm_circuit =
+            EvmCircuit::<F>::get_num_rows_required(block, &step_height_map(block.feature_config));
         let num_rows_tx_circuit =
             TxCircuitConfig::<F>::get_num_rows_required(block.circuits_params.max_txs);
         num_rows_evm_circuit.max(num_rows_tx_circuit)
     }
+
+    /// Return the pairs of `(column, row)` instance indices chaining the
+    /// proofs of consecutive blocks: the state root of a block is the
+    /// previous state root of the next one.
+    pub fn state_root_chaining() -> Vec<((usize, usize), (usize, usize))> {
+        // The PiCircuit has the only instance column
+        STATE_ROOT_INSTANCE_ROWS
+            .into_iter()
+            .zip(PREV_STATE_ROOT_INSTANCE_ROWS)
+            .map(|(from, to)| ((0, from), (0, to)))
+            .collect()
+    }
 }
 
 // Eventhough the SuperCircuit is not a subcircuit we implement the SubCircuit
@@ -377,13 +407,15 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
 }
 
 /// Super Circuit configuration parameters
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct SuperCircuitParams<F: Field> {
     max_txs: usize,
     max_withdrawals: usize,
     max_calldata: usize,
     mock_randomness: F,
     feature_config: FeatureConfig,
+    evm_layout: EvmColumnLayout,
+    evm_cell_placement: CellPlacementOrder,
 }
 
 impl<F: Field> Circuit<F> for SuperCircuit<F> {
@@ -402,6 +434,8 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
             max_calldata: self.circuits_params.max_calldata,
             mock_randomness: self.mock_randomness,
             feature_config: self.feature_config,
+            evm_layout: self.evm_circuit.layout().clone(),
+            evm_cell_placement: self.evm_circuit.cell_placement(),
         }
     }
 
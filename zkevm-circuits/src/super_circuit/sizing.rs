@@ -0,0 +1,281 @@
+//! Sizing of the `SuperCircuit` parameters for a target degree.
+
+use super::SuperCircuit;
+use crate::{
+    evm_circuit::{detect_fixed_table_tags, step::ExecutionState, EvmCircuit},
+    exp_circuit::param::{OFFSET_INCREMENT, UNUSABLE_EXP_ROWS},
+    keccak_circuit::KeccakCircuit,
+    pi_circuit::PiCircuit,
+    tx_circuit::TxCircuit,
+    util::SubCircuit,
+    witness::Block,
+};
+use bus_mapping::{circuit_input_builder::FixedCParams, operation::Target};
+use eth_types::Field;
+use std::{collections::HashMap, fmt};
+
+/// Sub-circuits of the `SuperCircuit` whose number of rows depends on the
+/// circuit parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubCircuitKind {
+    /// EVM Circuit
+    Evm,
+    /// State Circuit
+    State,
+    /// Tx Circuit
+    Tx,
+    /// Public Input Circuit
+    Pi,
+    /// Bytecode Circuit
+    Bytecode,
+    /// Copy Circuit
+    Copy,
+    /// Exp Circuit
+    Exp,
+    /// Keccak Circuit
+    Keccak,
+}
+
+/// A sub-circuit needing more rows than available for a degree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubCircuitOverflow {
+    /// The overflowing sub-circuit
+    pub circuit: SubCircuitKind,
+    /// Rows required by the sub-circuit
+    pub rows: usize,
+    /// Usable rows of the circuit
+    pub usable_rows: usize,
+}
+
+impl fmt::Display for SubCircuitOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} circuit requires {} rows but only {} are usable",
+            self.circuit, self.rows, self.usable_rows
+        )
+    }
+}
+
+impl<F: Field> SuperCircuit<F> {
+    /// Return the number of usable rows of the circuit with degree `k`.
+    pub fn usable_rows(k: u32) -> usize {
+        (1usize << k).saturating_sub(Self::unusable_rows())
+    }
+
+    /// Return the number of rows of each sub-circuit padded to `params`,
+    /// which must have non zero `max_evm_rows` and `max_keccak_rows`.
+    pub fn sub_circuit_rows(params: &FixedCParams) -> Vec<(SubCircuitKind, usize)> {
+        vec![
+            // The EVM circuit has at least one unused row
+            (SubCircuitKind::Evm, params.max_evm_rows + 1),
+            (SubCircuitKind::State, params.max_rws),
+            (
+                SubCircuitKind::Tx,
+                TxCircuit::<F>::min_num_rows(params.max_txs, params.max_calldata),
+            ),
+            (
+                SubCircuitKind::Pi,
+                PiCircuit::<F>::min_num_rows(
+                    params.max_txs,
+                    params.max_withdrawals,
+                    params.max_calldata,
+                ),
+            ),
+            (SubCircuitKind::Bytecode, params.max_bytecode),
+            (SubCircuitKind::Copy, params.max_copy_rows),
+            (
+                SubCircuitKind::Exp,
+                params.max_exp_steps * OFFSET_INCREMENT + UNUSABLE_EXP_ROWS,
+            ),
+            (SubCircuitKind::Keccak, params.max_keccak_rows),
+        ]
+    }
+
+    /// Return the smallest circuit parameters able to prove the block,
+    /// independently of the parameters it was built with, given the height of
+    /// each execution state of the EVM circuit (see
+    /// [`EvmCircuit::step_heights`]).
+    pub fn required_circuits_params(
+        block: &Block<F>,
+        evm_step_heights: &HashMap<ExecutionState, usize>,
+    ) -> FixedCParams {
+        let num_rws = block
+            .rws
+            .0
+            .iter()
+            .filter(|(target, _)| **target != Target::Start)
+            .map(|(_, rws)| rws.len())
+            .sum::<usize>();
+        FixedCParams {
+            // At least one Start row
+            max_rws: num_rws + 1,
+            max_txs: block.txs.len(),
+            max_withdrawals: block.withdrawals().len(),
+            max_calldata: block.txs.iter().map(|tx| tx.call_data.len()).sum(),
+            // Two rows per copied byte, plus the disabled and unused rows
+            max_copy_rows: block
+                .copy_events
+                .iter()
+                .map(|c| c.steps().len() * 2)
+                .sum::<usize>()
+                + 4,
+            max_exp_steps: block.exp_events.iter().map(|e| e.steps.len()).sum(),
+            max_bytecode: block.bytecodes.num_rows_required_for_bytecode_table(),
+            // Without the unused row
+            max_evm_rows: EvmCircuit::<F>::get_min_num_rows_required(block, evm_step_heights) - 1,
+            max_keccak_rows: KeccakCircuit::<F>::min_num_rows_padded(&block.keccak_inputs),
+        }
+    }
+
+    /// Check that the block can be proven with degree `k`, returning the
+    /// sub-circuits that overflow otherwise.
+    pub fn check_block(
+        block: &Block<F>,
+        k: u32,
+        evm_step_heights: &HashMap<ExecutionState, usize>,
+    ) -> Result<(), Vec<SubCircuitOverflow>> {
+        Self::check_circuits_params(
+            &Self::required_circuits_params(block, evm_step_heights),
+            Self::fixed_table_rows(block),
+            k,
+        )
+    }
+
+    /// Derive the largest circuit parameters that fit in degree `k` and can
+    /// prove each of the sample `blocks`. Returns the sub-circuits that
+    /// overflow when the samples can't fit together.
+    ///
+    /// The parameters with a dedicated sub-circuit are sized to fill all the
+    /// usable rows. The Tx and PI circuits share `max_txs` and `max_calldata`:
+    /// the withdrawals are sized to the samples, the transactions grow with the
+    /// largest calldata per transaction of the samples, and the calldata fills
+    /// the rows left.
+    pub fn fit_circuits_params(
+        blocks: &[Block<F>],
+        k: u32,
+        evm_step_heights: &HashMap<ExecutionState, usize>,
+    ) -> Result<FixedCParams, Vec<SubCircuitOverflow>> {
+        let required = blocks
+            .iter()
+            .map(|block| Self::required_circuits_params(block, evm_step_heights))
+            .fold(
+                FixedCParams {
+                    max_rws: 0,
+                    max_txs: 0,
+                    max_withdrawals: 0,
+                    max_calldata: 0,
+                    max_copy_rows: 0,
+                    max_exp_steps: 0,
+                    max_bytecode: 0,
+                    max_evm_rows: 0,
+                    max_keccak_rows: 0,
+                },
+                |acc, params| FixedCParams {
+                    max_rws: acc.max_rws.max(params.max_rws),
+                    max_txs: acc.max_txs.max(params.max_txs),
+                    max_withdrawals: acc.max_withdrawals.max(params.max_withdrawals),
+                    max_calldata: acc.max_calldata.max(params.max_calldata),
+                    max_copy_rows: acc.max_copy_rows.max(params.max_copy_rows),
+                    max_exp_steps: acc.max_exp_steps.max(params.max_exp_steps),
+                    max_bytecode: acc.max_bytecode.max(params.max_bytecode),
+                    max_evm_rows: acc.max_evm_rows.max(params.max_evm_rows),
+                    max_keccak_rows: acc.max_keccak_rows.max(params.max_keccak_rows),
+                },
+            );
+        let fixed_table_rows = blocks
+            .iter()
+            .map(Self::fixed_table_rows)
+            .max()
+            .unwrap_or_default();
+        Self::check_circuits_params(&required, fixed_table_rows, k)?;
+
+        let usable_rows = Self::usable_rows(k);
+        let max_withdrawals = required.max_withdrawals;
+        let tx_rows =
+            |max_txs: usize, max_calldata: usize| {
+                TxCircuit::<F>::min_num_rows(max_txs, max_calldata).max(
+                    PiCircuit::<F>::min_num_rows(max_txs, max_withdrawals, max_calldata),
+                )
+            };
+        let calldata_per_tx = blocks
+            .iter()
+            .filter(|block| !block.txs.is_empty())
+            .map(|block| {
+                let calldata: usize = block.txs.iter().map(|tx| tx.call_data.len()).sum();
+                (calldata + block.txs.len() - 1) / block.txs.len()
+            })
+            .max()
+            .unwrap_or_default();
+        let calldata_for = |max_txs: usize| required.max_calldata.max(max_txs * calldata_per_tx);
+        let max_txs = Self::largest_fitting(required.max_txs, usable_rows, |max_txs| {
+            tx_rows(max_txs, calldata_for(max_txs)) <= usable_rows
+        });
+        let max_calldata =
+            Self::largest_fitting(calldata_for(max_txs), usable_rows, |max_calldata| {
+                tx_rows(max_txs, max_calldata) <= usable_rows
+            });
+
+        Ok(FixedCParams {
+            max_rws: usable_rows,
+            max_txs,
+            max_withdrawals,
+            max_calldata,
+            max_copy_rows: usable_rows,
+            max_exp_steps: (usable_rows - UNUSABLE_EXP_ROWS) / OFFSET_INCREMENT,
+            max_bytecode: usable_rows,
+            max_evm_rows: usable_rows - 1,
+            max_keccak_rows: usable_rows,
+        })
+    }
+
+    /// Check that the sub-circuits padded to `params`, and the fixed tables of
+    /// the EVM circuit, fit in degree `k`.
+    fn check_circuits_params(
+        params: &FixedCParams,
+        fixed_table_rows: usize,
+        k: u32,
+    ) -> Result<(), Vec<SubCircuitOverflow>> {
+        let usable_rows = Self::usable_rows(k);
+        let overflows: Vec<_> = Self::sub_circuit_rows(params)
+            .into_iter()
+            .map(|(circuit, rows)| match circuit {
+                SubCircuitKind::Evm => (circuit, rows.max(fixed_table_rows)),
+                _ => (circuit, rows),
+            })
+            .filter(|(_, rows)| *rows > usable_rows)
+            .map(|(circuit, rows)| SubCircuitOverflow {
+                circuit,
+                rows,
+                usable_rows,
+            })
+            .collect();
+        if overflows.is_empty() {
+            Ok(())
+        } else {
+            Err(overflows)
+        }
+    }
+
+    fn fixed_table_rows(block: &Block<F>) -> usize {
+        detect_fixed_table_tags(block)
+            .iter()
+            .map(|tag| tag.build::<F>(block.feature_config.hardfork).count())
+            .sum()
+    }
+
+    /// Return the largest value in `[min, max]` satisfying `fits`, which must
+    /// hold for `min` and be monotonic.
+    fn largest_fitting(min: usize, max: usize, fits: impl Fn(usize) -> bool) -> usize {
+        let (mut low, mut high) = (min, max.max(min));
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if fits(mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+}
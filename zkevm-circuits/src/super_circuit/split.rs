@@ -0,0 +1,131 @@
+//! Split of the `SuperCircuit` into sub-circuits proven separately.
+
+use super::{SubCircuitKind, SuperCircuit};
+use crate::table::{
+    BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
+};
+use eth_types::Field;
+use halo2_proofs::plonk::{ConstraintSystem, Error};
+
+/// Tables that a sub-circuit of the `SuperCircuit` looks up and another one
+/// constrains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SharedTable {
+    Tx,
+    Rw,
+    Bytecode,
+    Block,
+    Copy,
+    Keccak,
+    Exp,
+}
+
+impl SharedTable {
+    /// Sub-circuit constraining the table.
+    fn loader(self) -> SubCircuitKind {
+        match self {
+            Self::Tx => SubCircuitKind::Tx,
+            Self::Rw => SubCircuitKind::State,
+            Self::Bytecode => SubCircuitKind::Bytecode,
+            Self::Block => SubCircuitKind::Pi,
+            Self::Copy => SubCircuitKind::Copy,
+            Self::Keccak => SubCircuitKind::Keccak,
+            Self::Exp => SubCircuitKind::Exp,
+        }
+    }
+
+    /// Whether all the advice columns of the table are in the first phase, so
+    /// that its commitments can be linked across proofs.
+    fn is_linkable<F: Field>(self) -> bool {
+        let mut meta = ConstraintSystem::<F>::default();
+        let columns = match self {
+            Self::Tx => LookupTable::<F>::advice_columns(&TxTable::construct(&mut meta)),
+            Self::Rw => LookupTable::<F>::advice_columns(&RwTable::construct(&mut meta)),
+            Self::Bytecode => {
+                LookupTable::<F>::advice_columns(&BytecodeTable::construct(&mut meta))
+            }
+            Self::Block => LookupTable::<F>::advice_columns(&BlockTable::construct(&mut meta)),
+            Self::Copy => {
+                let q_enable = meta.fixed_column();
+                LookupTable::<F>::advice_columns(&CopyTable::construct(&mut meta, q_enable))
+            }
+            Self::Keccak => LookupTable::<F>::advice_columns(&KeccakTable::construct(&mut meta)),
+            Self::Exp => LookupTable::<F>::advice_columns(&ExpTable::construct(&mut meta)),
+        };
+        let phases = meta.advice_column_phase();
+        columns.iter().all(|column| phases[column.index()] == 0)
+    }
+}
+
+impl SubCircuitKind {
+    /// Tables looked up by the sub-circuit and constrained by another one.
+    fn looked_up_tables(self) -> &'static [SharedTable] {
+        match self {
+            Self::Evm => &[
+                SharedTable::Tx,
+                SharedTable::Rw,
+                SharedTable::Bytecode,
+                SharedTable::Block,
+                SharedTable::Copy,
+                SharedTable::Keccak,
+                SharedTable::Exp,
+            ],
+            Self::Copy => &[SharedTable::Tx, SharedTable::Rw, SharedTable::Bytecode],
+            Self::Tx | Self::Bytecode => &[SharedTable::Keccak],
+            Self::Pi => &[SharedTable::Tx, SharedTable::Keccak],
+            Self::State | Self::Exp | Self::Keccak => &[],
+        }
+    }
+}
+
+impl<F: Field> SuperCircuit<F> {
+    /// Check that the sub-circuits can be proven split into snarks, each one
+    /// proving the given sub-circuits, with the tables shared across snarks
+    /// linked by `root_circuit::table_links`.
+    ///
+    /// Every table looked up by a sub-circuit must be constrained by a
+    /// sub-circuit of some snark. A table shared across snarks must only have
+    /// first phase advice columns: the second phase ones, such as the
+    /// `rlc_acc` of the copy table or the `input_rlc` of the keccak table, hold
+    /// RLCs with the challenges of each proof and can't be linked, so the
+    /// lookups into them would not be bound to the sub-circuit constraining
+    /// them. The MPT table is not constrained by any of these sub-circuits and
+    /// is left out.
+    pub fn check_split(snarks: &[&[SubCircuitKind]]) -> Result<(), Error> {
+        let snark_of = |circuit: SubCircuitKind| {
+            snarks
+                .iter()
+                .position(|circuits| circuits.contains(&circuit))
+        };
+        for (snark, circuits) in snarks.iter().enumerate() {
+            for circuit in circuits.iter() {
+                for table in circuit.looked_up_tables() {
+                    match snark_of(table.loader()) {
+                        None => {
+                            log::error!(
+                                "{:?} circuit of snark {} looks up the {:?} table, which the {:?} circuit of no snark constrains",
+                                circuit,
+                                snark,
+                                table,
+                                table.loader()
+                            );
+                            return Err(Error::Synthesis);
+                        }
+                        Some(loader) if loader != snark && !table.is_linkable::<F>() => {
+                            log::error!(
+                                "{:?} circuit of snark {} looks up the {:?} table of snark {}, which has second phase columns and can't be linked",
+                                circuit,
+                                snark,
+                                table,
+                                loader
+                            );
+                            return Err(Error::Synthesis);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -18,6 +18,8 @@ fn super_circuit_degree() {
         max_calldata: 32,
         mock_randomness: Fr::from(0x100),
         feature_config: FeatureConfig::default(),
+        evm_layout: EvmColumnLayout::default(),
+        evm_cell_placement: Default::default(),
     };
     SuperCircuit::configure_with_params(&mut cs, params);
     log::info!("super circuit degree: {}", cs.degree());
@@ -177,3 +179,43 @@ fn serial_test_super_circuit_2tx_2max_tx() {
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
+
+#[test]
+fn super_circuit_fit_circuits_params() {
+    let (_, circuit, _, builder) = SuperCircuit::<Fr>::build(
+        block_1tx(),
+        FixedCParams::default(),
+        Fr::from(TEST_MOCK_RANDOMNESS),
+    )
+    .unwrap();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    let step_heights = circuit.evm_circuit.step_heights();
+    let required = SuperCircuit::<Fr>::required_circuits_params(&block, &step_heights);
+
+    let k = 20;
+    let usable_rows = SuperCircuit::<Fr>::usable_rows(k);
+    assert_eq!(
+        SuperCircuit::<Fr>::check_block(&block, k, &step_heights),
+        Ok(())
+    );
+    let params =
+        SuperCircuit::<Fr>::fit_circuits_params(&[block.clone()], k, &step_heights).unwrap();
+    assert_eq!(params.max_rws, usable_rows);
+    assert!(params.max_txs >= required.max_txs);
+    assert!(params.max_calldata >= required.max_calldata);
+    assert!(params.max_withdrawals >= required.max_withdrawals);
+    for (circuit, rows) in SuperCircuit::<Fr>::sub_circuit_rows(&params) {
+        assert!(rows <= usable_rows, "{:?} overflows", circuit);
+    }
+    // The sub-circuits agree with the fitted parameters
+    let mut fitted = block.clone();
+    fitted.circuits_params = params;
+    assert!(PiCircuit::<Fr>::min_num_rows_block(&fitted).1 <= usable_rows);
+
+    // The fixed tables of the EVM circuit don't fit in a small degree
+    let overflows = SuperCircuit::<Fr>::check_block(&block, 8, &step_heights).unwrap_err();
+    assert!(overflows
+        .iter()
+        .any(|overflow| overflow.circuit == SubCircuitKind::Evm));
+    assert!(SuperCircuit::<Fr>::fit_circuits_params(&[block], 8, &step_heights).is_err());
+}
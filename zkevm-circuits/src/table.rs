@@ -1,4 +1,11 @@
 //! Table definitions used cross-circuits
+//!
+//! With the `split-proof` feature, the first phase advice columns of the tables
+//! are unblinded, so that a table shared by sub-circuits proven separately has
+//! the same commitment in each proof, which the aggregation can check. The
+//! second phase columns, such as the `rlc_acc` of the copy table or the
+//! `input_rlc` of the keccak table, hold RLCs with the challenges of each
+//! proof, so their commitments differ across proofs and they can't be linked.
 
 use crate::{
     copy_circuit::util::number_or_hash_to_word,
@@ -7,7 +14,7 @@ use crate::{
     util::{build_tx_log_address, keccak, word::WordLoHi, Challenges},
     witness::{Block, BlockContext, MptUpdateRow, MptUpdates, Rw, RwMap, RwRow, Transaction},
 };
-use bus_mapping::circuit_input_builder::{CopyDataType, CopyEvent, CopyStep};
+use bus_mapping::circuit_input_builder::{CopyDataType, CopyEvent};
 use core::iter::once;
 use eth_types::{Field, ToScalar, U256};
 use gadgets::{
@@ -58,6 +65,21 @@ pub(crate) use tx_table::{
 };
 pub(crate) use wd_table::WdTable;
 
+/// Create a first phase advice column of a table, unblinded with the
+/// `split-proof` feature. Its commitment is the same in the proofs of the
+/// sub-circuits sharing the table only if they assign it at the same rows and
+/// are proven at the same degree, see `root_circuit::CommitmentLink`. The
+/// second phase columns of a table are not created with it, and
+/// `root_circuit::table_links` rejects them, as does
+/// `SuperCircuit::check_split` for the tables shared across snarks.
+fn table_advice_column<F: Field>(meta: &mut ConstraintSystem<F>) -> Column<Advice> {
+    if cfg!(feature = "split-proof") {
+        meta.unblinded_advice_column()
+    } else {
+        meta.advice_column()
+    }
+}
+
 /// Trait used to define lookup tables
 pub trait LookupTable<F: Field> {
     /// Returns the list of ALL the table columns following the table order.
@@ -45,7 +45,7 @@ impl BlockTable {
         Self {
             tag: meta.fixed_column(),
             index: meta.fixed_column(),
-            value: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            value: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
         }
     }
 
@@ -29,8 +29,8 @@ pub struct BytecodeTable {
 impl BytecodeTable {
     /// Construct a new BytecodeTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
-        let [tag, index, is_code, value] = array::from_fn(|_| meta.advice_column());
-        let code_hash = WordLoHi::new([meta.advice_column(), meta.advice_column()]);
+        let [tag, index, is_code, value] = array::from_fn(|_| table_advice_column(meta));
+        let code_hash = WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]);
         Self {
             code_hash,
             tag,
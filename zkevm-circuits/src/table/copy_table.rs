@@ -1,7 +1,7 @@
 use super::*;
 
 type CopyTableRow<F> = [(Value<F>, &'static str); 9];
-type CopyCircuitRow<F> = [(Value<F>, &'static str); 5];
+type CopyCircuitRow<F> = [(Value<F>, &'static str); 16];
 
 /// Copy Table, used to verify copies of byte chunks between Memory, Bytecode,
 /// TxLogs and TxCallData.
@@ -44,16 +44,16 @@ impl CopyTable {
     /// Construct a new CopyTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>, q_enable: Column<Fixed>) -> Self {
         Self {
-            is_first: meta.advice_column(),
-            id: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            is_first: table_advice_column(meta),
+            id: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
             q_enable,
             tag: BinaryNumberChip::configure(meta, q_enable, None),
-            addr: meta.advice_column(),
-            src_addr_end: meta.advice_column(),
-            bytes_left: meta.advice_column(),
+            addr: table_advice_column(meta),
+            src_addr_end: table_advice_column(meta),
+            bytes_left: table_advice_column(meta),
             rlc_acc: meta.advice_column_in(SecondPhase),
-            rw_counter: meta.advice_column(),
-            rwc_inc_left: meta.advice_column(),
+            rw_counter: table_advice_column(meta),
+            rwc_inc_left: table_advice_column(meta),
         }
     }
 
@@ -74,35 +74,26 @@ impl CopyTable {
                 .keccak_input()
                 .map(|keccak_input| rlc::value(values.iter().rev(), keccak_input))
         };
-        let mut value_acc = Value::known(F::ZERO);
-        for (step_idx, (is_read_step, copy_step)) in copy_event
-            .bytes
+        let steps = copy_event.steps();
+        let rw_counter_delta = copy_event.rw_counter_delta();
+        let mut rw_counter_increase = 0;
+        // The copied bytes are accumulated separately by the read and the write rows.
+        let mut bytes_left = [copy_event.bytes.len() as u64; 2];
+        let mut value_acc = [Value::known(F::ZERO); 2];
+        let mut value_word = [WordLoHi::new([F::ZERO; 2]); 2];
+        let mut value_word_prev = [WordLoHi::new([F::ZERO; 2]); 2];
+        for (step_idx, (is_read_step, copy_step)) in steps
             .iter()
-            .flat_map(|(value, is_code)| {
-                let read_step = CopyStep {
-                    value: *value,
-                    is_code: if copy_event.src_type == CopyDataType::Bytecode {
-                        Some(*is_code)
-                    } else {
-                        None
-                    },
-                };
-                let write_step = CopyStep {
-                    value: *value,
-                    is_code: if copy_event.dst_type == CopyDataType::Bytecode {
-                        Some(*is_code)
-                    } else {
-                        None
-                    },
-                };
+            .flat_map(|(read_step, write_step)| {
                 once((true, read_step)).chain(once((false, write_step)))
             })
             .enumerate()
         {
+            let side = step_idx % 2;
             // is_first
             let is_first = Value::known(if step_idx == 0 { F::ONE } else { F::ZERO });
             // is last
-            let is_last = if step_idx == copy_event.bytes.len() * 2 - 1 {
+            let is_last = if step_idx == steps.len() * 2 - 1 {
                 Value::known(F::ONE)
             } else {
                 Value::known(F::ZERO)
@@ -123,17 +114,10 @@ impl CopyTable {
             };
 
             // addr
-            let copy_step_addr: u64 =
-                if is_read_step {
-                    copy_event.src_addr
-                } else {
-                    copy_event.dst_addr
-                } + (u64::try_from(step_idx).unwrap() - if is_read_step { 0 } else { 1 }) / 2u64;
-
             let addr = if tag == CopyDataType::TxLog {
                 Value::known(
                     build_tx_log_address(
-                        copy_step_addr,
+                        copy_step.addr,
                         TxLogFieldTag::Data,
                         copy_event.log_id.unwrap(),
                     )
@@ -141,24 +125,42 @@ impl CopyTable {
                     .unwrap(),
                 )
             } else {
-                Value::known(F::from(copy_step_addr))
+                Value::known(F::from(copy_step.addr))
             };
 
-            // bytes_left
-            let bytes_left = u64::try_from(copy_event.bytes.len() * 2 - step_idx).unwrap() / 2;
             // value
             let value = Value::known(F::from(copy_step.value as u64));
-            // value_acc
-            if is_read_step {
-                value_acc = value_acc * challenges.keccak_input() + value;
+            // value_acc, only accumulating the copied bytes
+            if !copy_step.mask {
+                value_acc[side] = value_acc[side] * challenges.keccak_input() + value;
+            }
+            // value_word, accumulating the hi half and then the lo half of the word
+            let (word_addr, word_index) = copy_step.word.unwrap_or_default();
+            if copy_step.word.is_some() {
+                for (word, byte) in [
+                    (&mut value_word[side], copy_step.value),
+                    (&mut value_word_prev[side], copy_step.value_prev),
+                ] {
+                    let (hi, lo) = match word_index {
+                        0 => (F::ZERO, F::ZERO),
+                        _ => (word.hi(), word.lo()),
+                    };
+                    let byte = F::from(byte as u64);
+                    *word = if word_index < 16 {
+                        WordLoHi::new([lo, hi * F::from(256) + byte])
+                    } else {
+                        WordLoHi::new([lo * F::from(256) + byte, hi])
+                    };
+                }
+            } else {
+                value_word[side] = WordLoHi::new([F::ZERO; 2]);
+                value_word_prev[side] = WordLoHi::new([F::ZERO; 2]);
             }
-            // is_pad
-            let is_pad = Value::known(F::from(
-                (is_read_step && copy_step_addr >= copy_event.src_addr_end) as u64,
-            ));
 
-            // is_code
-            let is_code = Value::known(copy_step.is_code.map_or(F::ZERO, |v| F::from(v as u64)));
+            // rw_counter
+            let rw_counter = copy_event.rw_counter_start.0 as u64 + rw_counter_increase;
+            let rwc_inc_left = rw_counter_delta - rw_counter_increase;
+            rw_counter_increase += copy_step.has_rw as u64;
 
             assignments.push((
                 tag,
@@ -171,7 +173,7 @@ impl CopyTable {
                         Value::known(F::from(copy_event.src_addr_end)),
                         "src_addr_end",
                     ),
-                    (Value::known(F::from(bytes_left)), "bytes_left"),
+                    (Value::known(F::from(bytes_left[side])), "bytes_left"),
                     (
                         match (copy_event.src_type, copy_event.dst_type) {
                             (CopyDataType::Memory, CopyDataType::Bytecode) => rlc_acc,
@@ -181,23 +183,52 @@ impl CopyTable {
                         },
                         "rlc_acc",
                     ),
-                    (
-                        Value::known(F::from(copy_event.rw_counter(step_idx))),
-                        "rw_counter",
-                    ),
-                    (
-                        Value::known(F::from(copy_event.rw_counter_increase_left(step_idx))),
-                        "rwc_inc_left",
-                    ),
+                    (Value::known(F::from(rw_counter)), "rw_counter"),
+                    (Value::known(F::from(rwc_inc_left)), "rwc_inc_left"),
                 ],
                 [
                     (is_last, "is_last"),
                     (value, "value"),
-                    (value_acc, "value_acc"),
-                    (is_pad, "is_pad"),
-                    (is_code, "is_code"),
+                    (
+                        Value::known(F::from(copy_step.value_prev as u64)),
+                        "value_prev",
+                    ),
+                    (value_acc[side], "value_acc"),
+                    (Value::known(F::from(copy_step.is_pad as u64)), "is_pad"),
+                    (
+                        Value::known(copy_step.is_code.map_or(F::ZERO, |v| F::from(v as u64))),
+                        "is_code",
+                    ),
+                    (Value::known(F::from(copy_step.mask as u64)), "mask"),
+                    (
+                        Value::known(F::from(copy_step.front_mask as u64)),
+                        "front_mask",
+                    ),
+                    (
+                        Value::known(F::from(copy_step.word.is_some() as u64)),
+                        "is_word",
+                    ),
+                    (Value::known(F::from(word_index as u64)), "word_index"),
+                    (
+                        Value::known(F::from((word_index >= 16) as u64)),
+                        "is_word_lo",
+                    ),
+                    (Value::known(F::from(word_addr)), "word_addr"),
+                    (Value::known(value_word[side].lo()), "value_word_lo"),
+                    (Value::known(value_word[side].hi()), "value_word_hi"),
+                    (
+                        Value::known(value_word_prev[side].lo()),
+                        "value_word_prev_lo",
+                    ),
+                    (
+                        Value::known(value_word_prev[side].hi()),
+                        "value_word_prev_hi",
+                    ),
                 ],
             ));
+
+            // bytes_left, counting down the copied bytes
+            bytes_left[side] -= !copy_step.mask as u64;
         }
         assignments
     }
@@ -32,11 +32,11 @@ impl ExpTable {
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
             is_step: meta.fixed_column(),
-            identifier: meta.advice_column(),
-            is_last: meta.advice_column(),
-            base_limb: meta.advice_column(),
-            exponent_lo_hi: meta.advice_column(),
-            exponentiation_lo_hi: meta.advice_column(),
+            identifier: table_advice_column(meta),
+            is_last: table_advice_column(meta),
+            base_limb: table_advice_column(meta),
+            exponent_lo_hi: table_advice_column(meta),
+            exponentiation_lo_hi: table_advice_column(meta),
         }
     }
 
@@ -39,10 +39,10 @@ impl KeccakTable {
     /// Construct a new KeccakTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            is_enabled: meta.advice_column(),
+            is_enabled: table_advice_column(meta),
             input_rlc: meta.advice_column_in(SecondPhase),
-            input_len: meta.advice_column(),
-            output: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            input_len: table_advice_column(meta),
+            output: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
         }
     }
 
@@ -102,13 +102,13 @@ impl MptTable {
     /// Construct a new MptTable
     pub(crate) fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            address: meta.advice_column(),
-            storage_key: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            proof_type: meta.advice_column(),
-            new_root: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            old_root: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            new_value: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            old_value: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            address: table_advice_column(meta),
+            storage_key: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            proof_type: table_advice_column(meta),
+            new_root: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            old_root: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            new_value: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            old_value: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
         }
     }
 
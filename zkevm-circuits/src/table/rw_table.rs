@@ -69,16 +69,16 @@ impl RwTable {
     /// Construct a new RwTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            rw_counter: meta.advice_column(),
-            is_write: meta.advice_column(),
-            tag: meta.advice_column(),
-            id: meta.advice_column(),
-            address: meta.advice_column(),
-            field_tag: meta.advice_column(),
-            storage_key: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            value: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            value_prev: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            init_val: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            rw_counter: table_advice_column(meta),
+            is_write: table_advice_column(meta),
+            tag: table_advice_column(meta),
+            id: table_advice_column(meta),
+            address: table_advice_column(meta),
+            field_tag: table_advice_column(meta),
+            storage_key: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            value: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            value_prev: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            init_val: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
         }
     }
     fn assign<F: Field>(
@@ -76,10 +76,10 @@ impl TxTable {
     /// Construct a new TxTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            tx_id: meta.advice_column(),
+            tx_id: table_advice_column(meta),
             tag: meta.fixed_column(),
-            index: meta.advice_column(),
-            value: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
+            index: table_advice_column(meta),
+            value: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
         }
     }
 
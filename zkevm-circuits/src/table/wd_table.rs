@@ -19,10 +19,10 @@ impl WdTable {
     /// Construct a new WdTable
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
         Self {
-            id: meta.advice_column(),
-            validator_id: meta.advice_column(),
-            address: WordLoHi::new([meta.advice_column(), meta.advice_column()]),
-            amount: meta.advice_column(),
+            id: table_advice_column(meta),
+            validator_id: table_advice_column(meta),
+            address: WordLoHi::new([table_advice_column(meta), table_advice_column(meta)]),
+            amount: table_advice_column(meta),
         }
     }
 
@@ -1,7 +1,9 @@
 //! Testing utilities
 
 use crate::{
-    evm_circuit::{cached::EvmCircuitCached, EvmCircuit},
+    evm_circuit::{
+        cached::EvmCircuitCached, coverage::flush_constraint_coverage, EvmCircuit, EvmCircuitParams,
+    },
     state_circuit::StateCircuit,
     util::SubCircuit,
     witness::{Block, Rw},
@@ -12,7 +14,7 @@ use bus_mapping::{
 };
 use eth_types::geth_types::GethData;
 use itertools::all;
-use std::cmp;
+use std::{cmp, path::PathBuf};
 use thiserror::Error;
 
 use crate::util::log2_ceil;
@@ -22,6 +24,11 @@ use halo2_proofs::{
 };
 use mock::TestContext;
 
+mod failure_explainer;
+mod witness_fuzzer;
+pub use failure_explainer::{FailureContext, FailureExplainer, StepLocation};
+pub use witness_fuzzer::{FuzzReport, MutationTarget, WitnessFuzzer};
+
 #[cfg(test)]
 #[ctor::ctor]
 fn init_env_logger() {
@@ -87,6 +94,7 @@ pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
     feature_config: Option<FeatureConfig>,
     block: Option<Block<Fr>>,
     block_modifiers: Vec<Box<dyn Fn(&mut Block<Fr>)>>,
+    constraint_coverage: Option<PathBuf>,
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
@@ -98,6 +106,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             feature_config: None,
             block: None,
             block_modifiers: vec![],
+            constraint_coverage: None,
         }
     }
 
@@ -154,6 +163,14 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self.block_modifiers.push(modifier);
         self
     }
+
+    /// Collects the constraint coverage of the execution gadgets of the EVM
+    /// circuit test, and merges it into the file at `path`. See
+    /// [`crate::evm_circuit::coverage`].
+    pub fn constraint_coverage(mut self, path: impl Into<PathBuf>) -> Self {
+        self.constraint_coverage = Some(path.into());
+        self
+    }
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
@@ -183,18 +200,29 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         Ok(block)
     }
 
-    fn run_evm_circuit_test(&self, block: Block<Fr>) -> Result<(), CircuitTestError> {
+    fn run_evm_circuit_test(&self, block: &Block<Fr>) -> Result<(), CircuitTestError> {
         let k = block.get_test_degree();
 
-        let (active_gate_rows, active_lookup_rows) = EvmCircuit::<Fr>::get_active_rows(&block);
+        // The test circuits have the default column layout
+        let step_heights = EvmCircuitParams {
+            feature_config: block.feature_config,
+            ..Default::default()
+        }
+        .step_heights();
+        let (active_gate_rows, active_lookup_rows) =
+            EvmCircuit::<Fr>::get_active_rows(block, &step_heights);
 
         // Mainnet EVM circuit constraints can be cached for test performance.
-        // No cache for EVM circuit with customized features
-        let prover = if block.feature_config.is_mainnet() {
-            let circuit = EvmCircuitCached::get_test_circuit_from_block(block);
+        // No cache for EVM circuit with customized features or collecting the coverage
+        let prover = if self.constraint_coverage.is_some() {
+            let circuit =
+                EvmCircuit::get_test_circuit_from_block(block.clone()).with_constraint_coverage();
+            MockProver::<Fr>::run(k, &circuit, vec![])
+        } else if block.feature_config.is_mainnet() {
+            let circuit = EvmCircuitCached::get_test_circuit_from_block(block.clone());
             MockProver::<Fr>::run(k, &circuit, vec![])
         } else {
-            let circuit = EvmCircuit::get_test_circuit_from_block(block);
+            let circuit = EvmCircuit::get_test_circuit_from_block(block.clone());
             MockProver::<Fr>::run(k, &circuit, vec![])
         };
 
@@ -202,15 +230,22 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             circuit: Circuit::EVM,
             reason: err,
         })?;
+        if let Some(path) = &self.constraint_coverage {
+            flush_constraint_coverage(path).expect("failed to write the EVM constraint coverage");
+        }
 
         prover
             .verify_at_rows(
                 active_gate_rows.iter().cloned(),
                 active_lookup_rows.iter().cloned(),
             )
-            .map_err(|err| CircuitTestError::VerificationFailed {
-                circuit: Circuit::EVM,
-                reasons: err,
+            .map_err(|err| {
+                let explainer = FailureExplainer::evm(block, &step_heights);
+                log::error!("{}", explainer.report(&err));
+                CircuitTestError::VerificationFailed {
+                    circuit: Circuit::EVM,
+                    reasons: err,
+                }
             })
     }
     // TODO: use randomness as one of the circuit public input, since randomness in
@@ -219,7 +254,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         let rows_needed = StateCircuit::<Fr>::min_num_rows_block(&block).1;
         let k = cmp::max(log2_ceil(rows_needed + NUM_BLINDING_ROWS), 18);
         let max_rws = block.circuits_params.max_rws;
-        let state_circuit = StateCircuit::<Fr>::new(block.rws, max_rws);
+        let state_circuit = StateCircuit::<Fr>::new(block.rws.clone(), max_rws);
         let instance = state_circuit.instance();
         let prover = MockProver::<Fr>::run(k, &state_circuit, instance).map_err(|err| {
             CircuitTestError::SynthesisFailure {
@@ -235,8 +270,10 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             .count();
         let rows = max_rws - non_start_rows_len..max_rws;
         prover.verify_at_rows(rows.clone(), rows).map_err(|err| {
+            let explainer = FailureExplainer::state(&block);
+            log::error!("{}", explainer.report(&err));
             CircuitTestError::VerificationFailed {
-                circuit: Circuit::EVM,
+                circuit: Circuit::State,
                 reasons: err,
             }
         })
@@ -247,7 +284,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
     pub fn run_with_result(self) -> Result<(), CircuitTestError> {
         let block = self.build_block()?;
 
-        self.run_evm_circuit_test(block.clone())?;
+        self.run_evm_circuit_test(&block)?;
         self.run_state_circuit_test(block)
     }
 
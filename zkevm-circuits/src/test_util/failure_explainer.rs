@@ -0,0 +1,373 @@
+//! Explanation of MockProver failures in terms of the block witness.
+//!
+//! A [`VerifyFailure`] only tells the region and offset of the failing row. A
+//! [`FailureExplainer`] maps the rows of the EVM circuit back to the execution
+//! step assigned there, with the steps before and after it, and the rows of the
+//! State circuit back to their [`Rw`].
+
+use super::Circuit;
+use crate::{
+    evm_circuit::step::ExecutionState,
+    witness::{Block, Rw, RwMap},
+};
+use eth_types::evm_types::OpcodeId;
+use halo2_proofs::{
+    dev::{FailureLocation, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+use std::{collections::HashMap, fmt};
+
+/// Name of the region assigning the execution steps
+const EVM_REGION: &str = "Execution step";
+/// Name of the region assigning the RwTable rows
+const STATE_REGION: &str = "state circuit";
+
+/// Execution step assigned to a range of rows of the EVM circuit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StepLocation {
+    /// Index of the transaction, `None` for the EndBlock steps
+    pub tx_index: Option<usize>,
+    /// Index of the step in the transaction, `None` for the EndBlock steps
+    pub step_index: Option<usize>,
+    /// Execution state of the step
+    pub execution_state: ExecutionState,
+    /// Opcode of the step, if any
+    pub opcode: Option<OpcodeId>,
+    /// Program counter
+    pub pc: u64,
+    /// First row of the step
+    pub first_row: usize,
+    /// Number of rows of the step
+    pub height: usize,
+}
+
+impl fmt::Display for StepLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (Some(tx_index), Some(step_index)) = (self.tx_index, self.step_index) {
+            write!(f, "tx {} step {}: ", tx_index, step_index)?;
+        }
+        write!(f, "{:?}", self.execution_state)?;
+        if let Some(opcode) = self.opcode {
+            write!(f, " ({:?} at pc {})", opcode, self.pc)?;
+        }
+        write!(
+            f,
+            ", rows {}..{}",
+            self.first_row,
+            self.first_row + self.height
+        )
+    }
+}
+
+/// Part of the witness assigned to the row of a failure
+#[derive(Clone, Debug)]
+pub enum FailureContext {
+    /// Row of an execution step in the EVM circuit
+    Step {
+        /// Row of the failure
+        row: usize,
+        /// The step assigned at the row
+        step: StepLocation,
+        /// The previous step
+        prev: Option<StepLocation>,
+        /// The next step
+        next: Option<StepLocation>,
+    },
+    /// Row of the RwTable in the State circuit
+    Rw {
+        /// Row of the failure
+        row: usize,
+        /// The read-write record assigned at the row
+        rw: Rw,
+        /// The record of the previous row
+        prev: Option<Rw>,
+        /// The record of the next row
+        next: Option<Rw>,
+    },
+    /// The failure isn't in the region of the execution steps or of the
+    /// RwTable
+    Unknown,
+}
+
+impl fmt::Display for FailureContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn show<T>(value: &Option<T>, describe: impl Fn(&T) -> String) -> String {
+            value.as_ref().map_or_else(|| "-".to_string(), describe)
+        }
+        match self {
+            Self::Step {
+                row,
+                step,
+                prev,
+                next,
+            } => {
+                writeln!(f, "  at row {} of {}", row, step)?;
+                writeln!(f, "  previous step: {}", show(prev, ToString::to_string))?;
+                write!(f, "  next step: {}", show(next, ToString::to_string))
+            }
+            Self::Rw {
+                row,
+                rw,
+                prev,
+                next,
+            } => {
+                writeln!(f, "  at row {}: {:?}", row, rw)?;
+                writeln!(
+                    f,
+                    "  previous row: {}",
+                    show(prev, |rw| format!("{:?}", rw))
+                )?;
+                write!(f, "  next row: {}", show(next, |rw| format!("{:?}", rw)))
+            }
+            Self::Unknown => write!(f, "  at no known row"),
+        }
+    }
+}
+
+enum Rows {
+    Evm(Vec<StepLocation>),
+    State(Vec<Rw>),
+}
+
+/// Maps the rows of the failures of the EVM or State circuit MockProver to the
+/// witness of the block.
+///
+/// ```ignore
+/// let explainer = FailureExplainer::evm(&block, &circuit.step_heights());
+/// if let Err(failures) = prover.verify() {
+///     println!("{}", explainer.report(&failures));
+/// }
+/// ```
+pub struct FailureExplainer {
+    circuit: Circuit,
+    rows: Rows,
+}
+
+impl FailureExplainer {
+    /// Explainer of the EVM circuit of `block`, given the height of each
+    /// execution state with the layout of the circuit.
+    pub fn evm(block: &Block<Fr>, step_heights: &HashMap<ExecutionState, usize>) -> Self {
+        let mut steps = Vec::new();
+        let mut first_row = 0;
+        for (tx_index, tx) in block.txs.iter().enumerate() {
+            for (step_index, step) in tx.steps().iter().enumerate() {
+                let execution_state = ExecutionState::from(step);
+                let height = step_heights[&execution_state];
+                steps.push(StepLocation {
+                    tx_index: Some(tx_index),
+                    step_index: Some(step_index),
+                    execution_state,
+                    opcode: step.opcode(),
+                    pc: step.pc,
+                    first_row,
+                    height,
+                });
+                first_row += height;
+            }
+        }
+        // The EndBlock steps fill the rows up to the last one
+        steps.push(StepLocation {
+            tx_index: None,
+            step_index: None,
+            execution_state: ExecutionState::EndBlock,
+            opcode: None,
+            pc: 0,
+            first_row,
+            height: block
+                .circuits_params
+                .max_evm_rows
+                .saturating_sub(first_row)
+                .max(1),
+        });
+
+        Self {
+            circuit: Circuit::EVM,
+            rows: Rows::Evm(steps),
+        }
+    }
+
+    /// Explainer of the State circuit of `block`.
+    pub fn state(block: &Block<Fr>) -> Self {
+        let (rows, _) = RwMap::table_assignments_prepad(
+            &block.rws.table_assignments(),
+            block.circuits_params.max_rws,
+        );
+        Self {
+            circuit: Circuit::State,
+            rows: Rows::State(rows),
+        }
+    }
+
+    /// Returns the part of the witness assigned to the row of `failure`.
+    pub fn explain(&self, failure: &VerifyFailure) -> FailureContext {
+        let region = match self.rows {
+            Rows::Evm(_) => EVM_REGION,
+            Rows::State(_) => STATE_REGION,
+        };
+        let row = match failure_row(failure, region) {
+            Some(row) => row,
+            None => return FailureContext::Unknown,
+        };
+
+        match &self.rows {
+            Rows::Evm(steps) => {
+                let index = steps
+                    .partition_point(|step| step.first_row <= row)
+                    .saturating_sub(1);
+                FailureContext::Step {
+                    row,
+                    step: steps[index].clone(),
+                    prev: index.checked_sub(1).map(|index| steps[index].clone()),
+                    next: steps.get(index + 1).cloned(),
+                }
+            }
+            Rows::State(rows) => match rows.get(row) {
+                Some(rw) => FailureContext::Rw {
+                    row,
+                    rw: *rw,
+                    prev: row.checked_sub(1).map(|row| rows[row]),
+                    next: rows.get(row + 1).copied(),
+                },
+                None => FailureContext::Unknown,
+            },
+        }
+    }
+
+    /// Formats each failure followed by its context.
+    pub fn report(&self, failures: &[VerifyFailure]) -> String {
+        let mut report = format!(
+            "{} failures in the {:?} circuit",
+            failures.len(),
+            self.circuit
+        );
+        for failure in failures {
+            report += &format!("\n{}\n{}", failure, self.explain(failure));
+        }
+        report
+    }
+}
+
+/// Returns the offset of the failure in the region named `region`.  The
+/// offsets in the regions of the execution steps and of the RwTable index the
+/// witness wherever the floor planner placed them.  A failure outside of any
+/// region only has an absolute row, which can't be mapped back without knowing
+/// the first row of the region, so it's left unexplained.
+fn failure_row(failure: &VerifyFailure, region: &str) -> Option<usize> {
+    // The fields of halo2_proofs::dev::metadata::Region aren't public, so we
+    // have to match off of its format string.
+    let in_region = |failure_region: &dyn fmt::Display| {
+        failure_region
+            .to_string()
+            .ends_with(&format!("('{}')", region))
+    };
+    match failure {
+        VerifyFailure::ConstraintNotSatisfied { location, .. }
+        | VerifyFailure::Lookup { location, .. }
+        | VerifyFailure::Permutation { location, .. }
+        | VerifyFailure::Shuffle { location, .. } => match location {
+            FailureLocation::InRegion {
+                region: failure_region,
+                offset,
+            } => in_region(failure_region).then_some(*offset),
+            FailureLocation::OutsideRegion { .. } => None,
+        },
+        VerifyFailure::CellNotAssigned {
+            region: failure_region,
+            offset,
+            ..
+        } => in_region(failure_region)
+            .then(|| usize::try_from(*offset).ok())
+            .flatten(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{evm_circuit::param::EXECUTION_STATE_HEIGHT_MAP, test_util::CircuitTestBuilder};
+    use eth_types::bytecode;
+    use halo2_proofs::plonk::Any;
+    use mock::TestContext;
+
+    fn permutation_failure(location: FailureLocation) -> VerifyFailure {
+        VerifyFailure::Permutation {
+            column: (Any::advice(), 0).into(),
+            location,
+        }
+    }
+
+    #[test]
+    fn explain_evm_and_state_failures() {
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        })
+        .unwrap();
+        let block = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block()
+            .unwrap();
+
+        // A row in the middle of the ADD step
+        let explainer = FailureExplainer::evm(&block, &EXECUTION_STATE_HEIGHT_MAP);
+        let first_row: usize = block.txs[0].steps()[..3]
+            .iter()
+            .map(|step| ExecutionState::from(step).get_step_height())
+            .sum();
+        let failure = permutation_failure(FailureLocation::InRegion {
+            region: (10, EVM_REGION).into(),
+            offset: first_row + 1,
+        });
+        match explainer.explain(&failure) {
+            FailureContext::Step {
+                row,
+                step,
+                prev,
+                next,
+            } => {
+                assert_eq!(row, first_row + 1);
+                assert_eq!(step.tx_index, Some(0));
+                assert_eq!(step.step_index, Some(3));
+                assert_eq!(step.execution_state, ExecutionState::ADD_SUB);
+                assert_eq!(step.opcode, Some(OpcodeId::ADD));
+                assert_eq!(step.pc, 4);
+                assert_eq!(step.first_row, first_row);
+                assert_eq!(prev.unwrap().opcode, Some(OpcodeId::PUSH1));
+                assert_eq!(next.unwrap().opcode, Some(OpcodeId::STOP));
+            }
+            context => panic!("unexpected context {:?}", context),
+        }
+        let failure = permutation_failure(FailureLocation::InRegion {
+            region: (1, "tx table").into(),
+            offset: first_row,
+        });
+        assert!(matches!(
+            explainer.explain(&failure),
+            FailureContext::Unknown
+        ));
+
+        let failure = permutation_failure(FailureLocation::OutsideRegion { row: first_row });
+        assert!(matches!(
+            explainer.explain(&failure),
+            FailureContext::Unknown
+        ));
+
+        // The last row of the RwTable
+        let explainer = FailureExplainer::state(&block);
+        let row = block.circuits_params.max_rws - 1;
+        let failure = permutation_failure(FailureLocation::InRegion {
+            region: (3, STATE_REGION).into(),
+            offset: row,
+        });
+        match explainer.explain(&failure) {
+            FailureContext::Rw { rw, prev, next, .. } => {
+                assert!(!matches!(rw, Rw::Start { .. }));
+                assert!(prev.is_some());
+                assert!(next.is_none());
+            }
+            context => panic!("unexpected context {:?}", context),
+        }
+    }
+}
@@ -0,0 +1,478 @@
+//! Soundness fuzzing of the sub-circuits by mutation of valid block witnesses.
+//!
+//! A [`WitnessFuzzer`] tampers with one field of the RwTable rows, the execution steps, the
+//! copy events or the transactions of a block that verifies, and checks that the MockProver of
+//! at least one of the sub-circuits rejects the mutated block. A mutation that still verifies is
+//! reported as a potential under-constraint.
+
+use super::NUM_BLINDING_ROWS;
+use crate::{
+    util::{log2_ceil, SubCircuit},
+    witness::{Block, Rw},
+};
+use eth_types::{Word, U64};
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr, plonk::Circuit};
+use itertools::Itertools;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::{
+    cmp,
+    panic::{self, AssertUnwindSafe},
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Part of the block witness tampered with by a mutation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum MutationTarget {
+    /// rw_counter, is_write or value of an RwTable row
+    Rw,
+    /// Step state field of an execution step
+    Step,
+    /// Byte, address or rw_counter of a copy event
+    CopyEvent,
+    /// Field of a transaction
+    Tx,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verdict {
+    Verified,
+    Rejected,
+    /// The witness can't be assigned
+    Panicked,
+}
+
+type Check = Box<dyn Fn(&Block<Fr>, Option<u32>) -> Verdict>;
+
+/// Outcome of a [`WitnessFuzzer`] run
+#[derive(Clone, Debug, Default)]
+pub struct FuzzReport {
+    /// Number of mutations rejected by a sub-circuit
+    pub rejected: usize,
+    /// Mutations that panicked during the synthesis of a sub-circuit
+    pub panicked: Vec<String>,
+    /// Mutations that every sub-circuit verified, potential under-constraints
+    pub survivors: Vec<String>,
+}
+
+impl FuzzReport {
+    /// Panics when any mutation verified.
+    pub fn assert_no_survivors(&self) {
+        assert!(
+            self.survivors.is_empty(),
+            "{} mutations verified, potential under-constraints:\n{}",
+            self.survivors.len(),
+            self.survivors.join("\n")
+        );
+    }
+
+    /// Panics when a mutation that isn't listed in `known` verified.
+    pub fn assert_survivors_within(&self, known: &[&str]) {
+        let unknown = self
+            .survivors
+            .iter()
+            .filter(|survivor| !known.contains(&survivor.as_str()))
+            .collect_vec();
+        assert!(
+            unknown.is_empty(),
+            "{} unknown mutations verified, potential under-constraints:\n{}",
+            unknown.len(),
+            unknown.iter().join("\n")
+        );
+    }
+}
+
+/// Mutates a valid block witness and checks that the sub-circuits reject the mutations.
+///
+/// ```ignore
+/// WitnessFuzzer::new(block)
+///     .check::<EvmCircuit<Fr>>()
+///     .check::<StateCircuit<Fr>>()
+///     .targets(&[MutationTarget::Rw, MutationTarget::Step])
+///     .run()
+///     .assert_no_survivors();
+/// ```
+pub struct WitnessFuzzer {
+    block: Block<Fr>,
+    checks: Vec<Check>,
+    targets: Vec<MutationTarget>,
+    mutations_per_target: usize,
+    seed: u64,
+    k: Option<u32>,
+}
+
+impl WitnessFuzzer {
+    /// Fuzzer of a block that verifies, with every target and 8 mutations per
+    /// target.
+    pub fn new(block: Block<Fr>) -> Self {
+        Self {
+            block,
+            checks: Vec::new(),
+            targets: MutationTarget::iter().collect(),
+            mutations_per_target: 8,
+            seed: 0,
+            k: None,
+        }
+    }
+
+    /// Verifies the mutated blocks with the sub-circuit `C`. A mutation is
+    /// rejected when any of the sub-circuits rejects it.
+    pub fn check<C: SubCircuit<Fr> + Circuit<Fr> + 'static>(mut self) -> Self {
+        self.checks.push(Box::new(verify::<C>));
+        self
+    }
+
+    /// Only mutate these parts of the witness.
+    pub fn targets(mut self, targets: &[MutationTarget]) -> Self {
+        self.targets = targets.to_vec();
+        self
+    }
+
+    /// Number of mutations of each target.
+    pub fn mutations_per_target(mut self, mutations_per_target: usize) -> Self {
+        self.mutations_per_target = mutations_per_target;
+        self
+    }
+
+    /// Seed of the mutations, so that a run can be reproduced.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Degree of the sub-circuits instead of the one derived from the block.
+    pub fn k(mut self, k: u32) -> Self {
+        self.k = Some(k);
+        self
+    }
+
+    /// Verifies every mutation, panics if the block to mutate doesn't verify.
+    pub fn run(&self) -> FuzzReport {
+        assert!(!self.checks.is_empty(), "no sub-circuit to check");
+        assert_eq!(
+            self.verify(&self.block),
+            Verdict::Verified,
+            "the block to mutate must verify"
+        );
+
+        let mut rng = ChaCha20Rng::seed_from_u64(self.seed);
+        let mut report = FuzzReport::default();
+        for target in &self.targets {
+            for _ in 0..self.mutations_per_target {
+                let mut block = self.block.clone();
+                let description = match mutate(&mut block, *target, &mut rng) {
+                    Some(description) => description,
+                    // The block has nothing to mutate for this target
+                    None => break,
+                };
+                match self.verify(&block) {
+                    Verdict::Verified => {
+                        log::warn!("potential under-constraint, mutation verified: {description}");
+                        report.survivors.push(description);
+                    }
+                    Verdict::Rejected => report.rejected += 1,
+                    Verdict::Panicked => report.panicked.push(description),
+                }
+            }
+        }
+        report
+    }
+
+    fn verify(&self, block: &Block<Fr>) -> Verdict {
+        let mut verdict = Verdict::Verified;
+        for check in &self.checks {
+            match check(block, self.k) {
+                Verdict::Verified => {}
+                Verdict::Rejected => return Verdict::Rejected,
+                Verdict::Panicked => verdict = Verdict::Panicked,
+            }
+        }
+        verdict
+    }
+}
+
+fn verify<C: SubCircuit<Fr> + Circuit<Fr>>(block: &Block<Fr>, k: Option<u32>) -> Verdict {
+    let k = k.unwrap_or_else(|| {
+        let (rows, padded_rows) = C::min_num_rows_block(block);
+        let rows_needed = cmp::max(rows, padded_rows) + NUM_BLINDING_ROWS;
+        cmp::max(block.get_test_degree(), log2_ceil(rows_needed))
+    });
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let circuit = C::new_from_block(block);
+        let instance = circuit.instance();
+        MockProver::<Fr>::run(k, &circuit, instance).map(|prover| prover.verify_par().is_ok())
+    }));
+    match result {
+        Ok(Ok(true)) => Verdict::Verified,
+        Ok(Ok(false)) | Ok(Err(_)) => Verdict::Rejected,
+        Err(_) => Verdict::Panicked,
+    }
+}
+
+/// Mutates one field of the target in the block, and returns the description
+/// of the mutation, or `None` if the block has no such witness.
+fn mutate(block: &mut Block<Fr>, target: MutationTarget, rng: &mut impl Rng) -> Option<String> {
+    match target {
+        MutationTarget::Rw => mutate_rw(block, rng),
+        MutationTarget::Step => mutate_step(block, rng),
+        MutationTarget::CopyEvent => mutate_copy_event(block, rng),
+        MutationTarget::Tx => mutate_tx(block, rng),
+    }
+}
+
+fn mutate_rw(block: &mut Block<Fr>, rng: &mut impl Rng) -> Option<String> {
+    // The map iteration order isn't deterministic
+    let tags = block
+        .rws
+        .0
+        .iter()
+        .filter(|(_, rows)| !rows.is_empty())
+        .map(|(tag, _)| *tag)
+        .sorted_by_key(|tag| *tag as usize)
+        .collect_vec();
+    if tags.is_empty() {
+        return None;
+    }
+    let rows = block
+        .rws
+        .0
+        .get_mut(&tags[rng.gen_range(0..tags.len())])
+        .unwrap();
+    let index = rng.gen_range(0..rows.len());
+    let row = &mut rows[index];
+    let before = *row;
+
+    match row {
+        Rw::Start { rw_counter } => *rw_counter += 1,
+        Rw::TxAccessListAccount {
+            rw_counter,
+            is_write,
+            is_warm,
+            ..
+        }
+        | Rw::TxAccessListAccountStorage {
+            rw_counter,
+            is_write,
+            is_warm,
+            ..
+        } => mutate_fields(rng, rw_counter, is_write, || *is_warm = !*is_warm),
+        Rw::TxRefund {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        }
+        | Rw::TxReceipt {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        } => mutate_fields(rng, rw_counter, is_write, || *value ^= 1),
+        Rw::Account {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        }
+        | Rw::AccountStorage {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        }
+        | Rw::CallContext {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        }
+        | Rw::Stack {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        }
+        | Rw::TxLog {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        }
+        | Rw::Memory {
+            rw_counter,
+            is_write,
+            value,
+            ..
+        } => mutate_fields(rng, rw_counter, is_write, || *value ^= Word::one()),
+    }
+    Some(format!("rw {:?} mutated to {:?}", before, row))
+}
+
+fn mutate_fields(
+    rng: &mut impl Rng,
+    rw_counter: &mut usize,
+    is_write: &mut bool,
+    flip_value: impl FnOnce(),
+) {
+    match rng.gen_range(0..3) {
+        0 => *rw_counter += 1,
+        1 => *is_write = !*is_write,
+        _ => flip_value(),
+    }
+}
+
+fn mutate_step(block: &mut Block<Fr>, rng: &mut impl Rng) -> Option<String> {
+    let txs = (0..block.txs.len())
+        .filter(|index| !block.txs[*index].steps().is_empty())
+        .collect_vec();
+    if txs.is_empty() {
+        return None;
+    }
+    let tx_index = txs[rng.gen_range(0..txs.len())];
+    let steps = block.txs[tx_index].steps_mut();
+    let step_index = rng.gen_range(0..steps.len());
+    let step = &mut steps[step_index];
+
+    // Only the fields assigned to the step state
+    let field = match rng.gen_range(0..7) {
+        0 => {
+            step.pc += 1;
+            "pc + 1"
+        }
+        1 => {
+            step.stack_size += 1;
+            "stack_size + 1"
+        }
+        2 => {
+            step.memory_size += 32;
+            "memory_size + 32"
+        }
+        3 => {
+            step.gas_left += 1;
+            "gas_left + 1"
+        }
+        4 => {
+            step.rwc.0 += 1;
+            "rwc + 1"
+        }
+        5 => {
+            step.reversible_write_counter += 1;
+            "reversible_write_counter + 1"
+        }
+        _ => {
+            step.log_id += 1;
+            "log_id + 1"
+        }
+    };
+    Some(format!(
+        "tx {} step {} ({:?}): {}",
+        tx_index, step_index, step.exec_state, field
+    ))
+}
+
+fn mutate_copy_event(block: &mut Block<Fr>, rng: &mut impl Rng) -> Option<String> {
+    if block.copy_events.is_empty() {
+        return None;
+    }
+    let event_index = rng.gen_range(0..block.copy_events.len());
+    let event = &mut block.copy_events[event_index];
+
+    let field = match rng.gen_range(0..4) {
+        0 if !event.bytes.is_empty() => {
+            let index = rng.gen_range(0..event.bytes.len());
+            event.bytes[index].0 ^= 1;
+            format!("byte {} ^ 1", index)
+        }
+        1 => {
+            event.src_addr += 1;
+            "src_addr + 1".to_string()
+        }
+        2 => {
+            event.dst_addr += 1;
+            "dst_addr + 1".to_string()
+        }
+        _ => {
+            event.rw_counter_start.0 += 1;
+            "rw_counter_start + 1".to_string()
+        }
+    };
+    Some(format!(
+        "copy event {} ({:?} -> {:?}): {}",
+        event_index, event.src_type, event.dst_type, field
+    ))
+}
+
+fn mutate_tx(block: &mut Block<Fr>, rng: &mut impl Rng) -> Option<String> {
+    if block.txs.is_empty() {
+        return None;
+    }
+    let tx_index = rng.gen_range(0..block.txs.len());
+    let tx = block.txs[tx_index].tx_mut();
+
+    let field = match rng.gen_range(0..5) {
+        0 if !tx.call_data.is_empty() => {
+            let index = rng.gen_range(0..tx.call_data.len());
+            let mut call_data = tx.call_data.to_vec();
+            call_data[index] ^= 1;
+            tx.call_data = call_data.into();
+            format!("call_data byte {} ^ 1", index)
+        }
+        1 => {
+            tx.nonce += U64::one();
+            "nonce + 1".to_string()
+        }
+        2 => {
+            tx.gas_limit += U64::one();
+            "gas_limit + 1".to_string()
+        }
+        3 => {
+            tx.gas_price ^= Word::one();
+            "gas_price ^ 1".to_string()
+        }
+        _ => {
+            tx.value ^= Word::one();
+            "value ^ 1".to_string()
+        }
+    };
+    Some(format!("tx {}: {}", tx_index, field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        evm_circuit::EvmCircuit, state_circuit::StateCircuit, test_util::CircuitTestBuilder,
+    };
+    use eth_types::bytecode;
+    use mock::TestContext;
+
+    #[test]
+    fn witness_fuzzer_rw_mutations() {
+        let bytecode = bytecode! {
+            PUSH1(0x10)
+            PUSH1(0x20)
+            ADD
+            PUSH1(0x00)
+            MSTORE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+        let block = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block()
+            .unwrap();
+
+        let report = WitnessFuzzer::new(block)
+            .check::<EvmCircuit<Fr>>()
+            .check::<StateCircuit<Fr>>()
+            .targets(&[MutationTarget::Rw])
+            .mutations_per_target(4)
+            .run();
+
+        assert_eq!(
+            report.rejected + report.panicked.len() + report.survivors.len(),
+            4
+        );
+        report.assert_no_survivors();
+    }
+}
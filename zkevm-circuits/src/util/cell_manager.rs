@@ -406,4 +406,8 @@ impl<Stats, S: CellPlacementStrategy<Stats = Stats>> CellManager<S> {
     pub fn get_strategy(&mut self) -> &mut S {
         &mut self.strategy
     }
+
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
 }
@@ -25,19 +25,41 @@ impl CMFixedWidthStrategyDistribution {
     }
 }
 
+/// Order in which CMFixedWidthStrategy fills the columns of a CellType.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellPlacementOrder {
+    /// Fill the rows one after the other, so that the columns keep the same height, which gives
+    /// the lowest height.
+    #[default]
+    RowMajor,
+    /// Fill the columns one after the other up to the max height, which uses the fewest columns.
+    /// The columns left empty can then be dropped, see `CMFixedWidthStrategy::columns_used`.
+    ColumnMajor,
+}
+
 /// CMFixedWidthStrategy is a Cell Placement strategy that places the cells in the column that has
 /// less height for a given CellType.
 /// When a cell is queried for a CellType the strategy will find the column of that Cell Type that
 /// has a lower height and add it there.
+/// With the ColumnMajor order, the cells are instead placed in the first column of that Cell Type
+/// that isn't full.
 #[derive(Clone, Debug)]
 pub(crate) struct CMFixedWidthStrategy {
     advices: CMFixedWidthStrategyDistribution,
     height_offset: usize,
 
     next: HashMap<CellType, (usize, usize)>,
+    cells: HashMap<CellType, usize>,
+
+    order: CellPlacementOrder,
+    // First row of the columns filled in ColumnMajor order, per Cell Type
+    start_row: HashMap<CellType, usize>,
+    // Number of columns holding cells, per Cell Type
+    columns_used: HashMap<CellType, usize>,
 
     perm_substitution: bool,
     max_height: usize,
+    column_overflow: bool,
 }
 
 impl CMFixedWidthStrategy {
@@ -53,8 +75,13 @@ impl CMFixedWidthStrategy {
             advices,
             height_offset,
             next: HashMap::default(),
+            cells: HashMap::default(),
+            order: CellPlacementOrder::RowMajor,
+            start_row: HashMap::default(),
+            columns_used: HashMap::default(),
             perm_substitution: false,
             max_height: usize::max_value(),
+            column_overflow: false,
         }
     }
 
@@ -74,6 +101,35 @@ impl CMFixedWidthStrategy {
         self
     }
 
+    /// Lets the ColumnMajor order place the cells of a Cell Type out of columns below the max
+    /// height in its last column instead of panicking, so that the heights over the max height
+    /// tell the Cell Types out of columns.
+    pub fn with_column_overflow(mut self) -> Self {
+        self.column_overflow = true;
+
+        self
+    }
+
+    /// Sets the order of the cells placed from now on. The cells already placed keep their
+    /// position, and the ColumnMajor order starts below them.
+    /// The ColumnMajor order fills the columns up to the max height.
+    pub fn set_order(&mut self, order: CellPlacementOrder) {
+        if order == CellPlacementOrder::ColumnMajor && self.order == CellPlacementOrder::RowMajor {
+            for (cell_type, (column_idx, row)) in self.next.clone() {
+                let start_row = if column_idx == 0 { row } else { row + 1 };
+                self.start_row.insert(cell_type, start_row);
+                self.set_next(&cell_type, 0, start_row);
+            }
+        }
+        self.order = order;
+    }
+
+    /// Returns the number of columns of a Cell Type holding cells. The columns after them are
+    /// left empty.
+    pub(crate) fn columns_used(&self, cell_type: &CellType) -> usize {
+        *self.columns_used.get(cell_type).unwrap_or(&0)
+    }
+
     fn get_next(&self, cell_type: &CellType) -> (usize, usize) {
         *self.next.get(cell_type).unwrap_or(&(0, 0))
     }
@@ -82,25 +138,19 @@ impl CMFixedWidthStrategy {
         self.next.insert(*cell_type, (column_idx, row));
     }
 
-    fn cells_used(&self, cell_type: &CellType, columns: &CellManagerColumns) -> usize {
-        let (next_column_idx, next_row) = self.get_next(cell_type);
-        let current_row = if next_column_idx == 0 {
-            if next_row == 0 {
-                return 0;
-            }
-
-            next_row - 1
-        } else {
-            next_row
-        };
-
-        let filled_rows_cells = if current_row == 0 {
-            0
-        } else {
-            (current_row - 1) * columns.get_cell_type_width(*cell_type)
-        };
+    fn cells_used(&self, cell_type: &CellType) -> usize {
+        *self.cells.get(cell_type).unwrap_or(&0)
+    }
 
-        filled_rows_cells + next_column_idx
+    fn cell_type_height(&self, cell_type: &CellType) -> usize {
+        let (column_idx, row) = self.get_next(cell_type);
+        match self.order {
+            CellPlacementOrder::RowMajor if column_idx == 0 => row,
+            CellPlacementOrder::RowMajor => row + 1,
+            // The columns before the next one are full
+            CellPlacementOrder::ColumnMajor if column_idx == 0 => row,
+            CellPlacementOrder::ColumnMajor => self.max_height.max(row),
+        }
     }
 }
 
@@ -123,14 +173,17 @@ impl CellPlacementStrategy for CMFixedWidthStrategy {
         cell_type: CellType,
     ) -> CellPlacement {
         let (mut column_idx, mut row) = self.get_next(&cell_type);
-        if self.perm_substitution && cell_type == CellType::StoragePhase1 {
+        if self.perm_substitution
+            && self.order == CellPlacementOrder::RowMajor
+            && cell_type == CellType::StoragePhase1
+        {
             let (_, row_perm) = self.get_next(&CellType::StoragePermutation);
             if row_perm < row {
                 return self.place_cell(columns, meta, CellType::StoragePermutation);
             }
         }
 
-        if row > self.max_height {
+        if row > self.max_height && !self.column_overflow {
             panic!(
                 "CMFixedWidthStrategy: max_height reached ({})",
                 self.max_height
@@ -145,14 +198,32 @@ impl CellPlacementStrategy for CMFixedWidthStrategy {
             column: column.clone(),
             rotation,
         };
-
-        column_idx += 1;
-        if column_idx >= columns.get_cell_type_width(cell_type) {
-            column_idx = 0;
-            row += 1;
+        let columns_used = self.columns_used.entry(cell_type).or_insert(0);
+        *columns_used = (*columns_used).max(column_idx + 1);
+
+        match self.order {
+            CellPlacementOrder::RowMajor => {
+                column_idx += 1;
+                if column_idx >= columns.get_cell_type_width(cell_type) {
+                    column_idx = 0;
+                    row += 1;
+                }
+            }
+            CellPlacementOrder::ColumnMajor => {
+                row += 1;
+                let width = columns.get_cell_type_width(cell_type);
+                if row >= self.max_height && (column_idx + 1 < width || !self.column_overflow) {
+                    column_idx += 1;
+                    row = *self.start_row.get(&cell_type).unwrap_or(&0);
+                    if column_idx >= width {
+                        panic!("CMFixedWidthStrategy: no column left for {:?}", cell_type)
+                    }
+                }
+            }
         }
 
         self.set_next(&cell_type, column_idx, row);
+        *self.cells.entry(cell_type).or_insert(0) += 1;
 
         placement
     }
@@ -160,14 +231,7 @@ impl CellPlacementStrategy for CMFixedWidthStrategy {
     fn get_height(&self) -> usize {
         self.next
             .keys()
-            .map(|cell_type| {
-                let next = self.get_next(cell_type);
-                if next.0 == 0 {
-                    next.1
-                } else {
-                    next.1 + 1
-                }
-            })
+            .map(|cell_type| self.cell_type_height(cell_type))
             .max()
             .unwrap_or(0)
     }
@@ -175,14 +239,12 @@ impl CellPlacementStrategy for CMFixedWidthStrategy {
     fn get_stats(&self, columns: &CellManagerColumns) -> Self::Stats {
         let mut data = BTreeMap::new();
         for cell_type in self.next.keys() {
-            let next = self.get_next(cell_type);
-            let height = if next.0 == 0 { next.1 } else { next.1 + 1 };
             data.insert(
                 *cell_type,
                 (
                     columns.get_cell_type_width(*cell_type),
-                    height,
-                    self.cells_used(cell_type, columns),
+                    self.cell_type_height(cell_type),
+                    self.cells_used(cell_type),
                 ),
             );
         }
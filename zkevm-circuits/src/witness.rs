@@ -4,6 +4,11 @@
 
 mod block;
 pub use block::{block_convert, Block, BlockContext};
+mod dump;
+pub use dump::{
+    CopyEventDump, ExpEventDump, RwDump, StepDump, TxDump, WitnessDiff, WitnessDump,
+    WitnessDumpError,
+};
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates};
 mod rw;
@@ -1,6 +1,6 @@
 use super::{ExecStep, Rw, RwMap, Transaction};
 use crate::{
-    evm_circuit::{detect_fixed_table_tags, EvmCircuit},
+    evm_circuit::{detect_fixed_table_tags, param::step_height_map, EvmCircuit},
     exp_circuit::param::OFFSET_INCREMENT,
     instance::public_data_convert,
     table::BlockContextFieldTag,
@@ -78,6 +78,28 @@ impl<F: Field> Block<F> {
         self.rws[step.rw_index(index)]
     }
 
+    /// Get the copy event starting at the read-write record `index` of the
+    /// step.
+    pub(crate) fn get_copy_event(&self, step: &ExecStep, index: usize) -> &CopyEvent {
+        let rw_counter = self.get_rws(step, index).rw_counter();
+        self.copy_events
+            .iter()
+            .find(|copy_event| {
+                copy_event.rw_counter_start.0 == rw_counter && copy_event.rw_counter_delta() > 0
+            })
+            .expect("copy event not found")
+    }
+
+    /// Get the bytes of the copy event starting at the read-write record
+    /// `index` of the step.
+    pub(crate) fn get_copy_bytes(&self, step: &ExecStep, index: usize) -> Vec<u8> {
+        self.get_copy_event(step, index)
+            .bytes
+            .iter()
+            .map(|(byte, _)| *byte)
+            .collect()
+    }
+
     /// Return the list of withdrawals of this block.
     pub fn withdrawals(&self) -> Vec<Withdrawal> {
         let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap_or_default();
@@ -107,16 +129,16 @@ impl<F: Field> Block<F> {
     /// `ConstraintSystem`.
     pub fn get_test_degree(&self) -> u32 {
         let num_rows_required_for_execution_steps: usize =
-            EvmCircuit::<F>::get_num_rows_required(self);
+            EvmCircuit::<F>::get_num_rows_required(self, &step_height_map(self.feature_config));
         let num_rows_required_for_rw_table: usize = self.circuits_params.max_rws;
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(self)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| tag.build::<F>(self.feature_config.hardfork).count())
             .sum();
         let num_rows_required_for_bytecode_table =
             self.bytecodes.num_rows_required_for_bytecode_table();
         let num_rows_required_for_copy_table: usize =
-            self.copy_events.iter().map(|c| c.bytes.len() * 2).sum();
+            self.copy_events.iter().map(|c| c.steps().len() * 2).sum();
         let num_rows_required_for_keccak_table: usize = self.keccak_inputs.len();
         let num_rows_required_for_tx_table: usize =
             self.txs.iter().map(|tx| 9 + tx.call_data.len()).sum();
@@ -297,12 +319,11 @@ pub fn block_convert<F: Field>(
         eth_block: block.eth_block.clone(),
     };
     let public_data = public_data_convert(&block);
-    let rpi_bytes = public_data.get_pi_bytes(
+    // PI Circuit
+    block.keccak_inputs.extend(public_data.get_keccak_inputs(
         block.circuits_params.max_txs,
         block.circuits_params.max_withdrawals,
         block.circuits_params.max_calldata,
-    );
-    // PI Circuit
-    block.keccak_inputs.extend_from_slice(&[rpi_bytes]);
+    ));
     Ok(block)
 }
@@ -0,0 +1,439 @@
+//! Human readable dump of a witness [`Block`], and the differences between two
+//! dumps.
+//!
+//! A dump keeps the parts of the witness that are useful to find why a block
+//! stopped being provable after a change: the steps of each transaction with
+//! their read-write records, the tx table, and the copy and exponentiation
+//! events.  Dumps are saved as JSON so that a dump taken before a change can
+//! be compared with one taken after it:
+//!
+//! ```text
+//! cargo run --bin stats --features stats -- witness bundle.json before.json
+//! cargo run --bin stats --features stats -- witness-diff before.json after.json
+//! ```
+
+use super::{Block, ExecStep, Rw};
+use crate::evm_circuit::step::ExecutionState;
+use eth_types::{Address, Bytes, Field, ToWord, Word};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeSet, fmt, fs, io, path::Path};
+use thiserror::Error;
+
+/// Errors when reading or writing a witness dump
+#[derive(Debug, Error)]
+pub enum WitnessDumpError {
+    /// The dump file can't be read or written
+    #[error("witness dump file: {0}")]
+    Io(#[from] io::Error),
+    /// The dump file isn't valid
+    #[error("invalid witness dump file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Read-write record of a step, with the columns of the rw table that apply
+/// to its target
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RwDump {
+    /// Read-write counter
+    pub rw_counter: usize,
+    /// Whether the record is a write
+    pub is_write: bool,
+    /// Target of the record
+    pub tag: String,
+    /// Transaction id or call id
+    pub id: Option<usize>,
+    /// Account address, stack pointer, memory address or packed log address
+    pub address: Option<Word>,
+    /// Field tag of an account, call context, log or receipt record
+    pub field_tag: Option<String>,
+    /// Storage key
+    pub storage_key: Option<Word>,
+    /// Value
+    pub value: Word,
+    /// Previous value
+    pub value_prev: Option<Word>,
+    /// Committed value of a storage slot
+    pub committed_value: Option<Word>,
+}
+
+impl From<&Rw> for RwDump {
+    fn from(rw: &Rw) -> Self {
+        let field_tag = match rw {
+            Rw::Account { field_tag, .. } => Some(format!("{:?}", field_tag)),
+            Rw::CallContext { field_tag, .. } => Some(format!("{:?}", field_tag)),
+            Rw::TxLog { field_tag, .. } => Some(format!("{:?}", field_tag)),
+            Rw::TxReceipt { field_tag, .. } => Some(format!("{:?}", field_tag)),
+            _ => None,
+        };
+        Self {
+            rw_counter: rw.rw_counter(),
+            is_write: rw.is_write(),
+            tag: format!("{:?}", rw.tag()),
+            id: rw.id(),
+            address: rw.address().map(|address| address.to_word()),
+            field_tag,
+            storage_key: rw.storage_key(),
+            value: rw.value_assignment(),
+            value_prev: rw.value_prev_assignment(),
+            committed_value: rw.committed_value_assignment(),
+        }
+    }
+}
+
+/// Execution step and its read-write records
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepDump {
+    /// Execution state of the step
+    pub execution_state: String,
+    /// Read-write counter at the beginning of the step
+    pub rw_counter: usize,
+    /// Call index within the transaction
+    pub call_index: usize,
+    /// Program counter
+    pub pc: u64,
+    /// Stack size
+    pub stack_size: usize,
+    /// Memory size in bytes
+    pub memory_size: usize,
+    /// Gas left
+    pub gas_left: u64,
+    /// Gas cost of the step
+    pub gas_cost: u64,
+    /// Reversible write counter at the beginning of the step
+    pub reversible_write_counter: usize,
+    /// Log index
+    pub log_id: usize,
+    /// Error of the step
+    pub error: Option<String>,
+    /// Read-write records of the step, in order
+    pub rws: Vec<RwDump>,
+}
+
+/// Transaction with its tx table fields and its steps
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxDump {
+    /// Transaction id, which is the transaction index plus one
+    pub id: u64,
+    /// Nonce
+    pub nonce: u64,
+    /// Gas limit
+    pub gas: u64,
+    /// Gas price
+    pub gas_price: Word,
+    /// Caller address
+    pub caller_address: Address,
+    /// Callee address, or address of the created contract
+    pub callee_address: Address,
+    /// Whether the transaction creates a contract
+    pub is_create: bool,
+    /// Value
+    pub value: Word,
+    /// Call data
+    pub call_data: Bytes,
+    /// Gas cost of the call data
+    pub call_data_gas_cost: u64,
+    /// Execution steps
+    pub steps: Vec<StepDump>,
+}
+
+/// Copy event of the copy circuit
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyEventDump {
+    /// Source type
+    pub src_type: String,
+    /// Source id
+    pub src_id: String,
+    /// Start address at the source
+    pub src_addr: u64,
+    /// End address at the source
+    pub src_addr_end: u64,
+    /// Destination type
+    pub dst_type: String,
+    /// Destination id
+    pub dst_id: String,
+    /// Start address at the destination
+    pub dst_addr: u64,
+    /// Log id when the destination is a log
+    pub log_id: Option<u64>,
+    /// Read-write counter at the beginning of the copy
+    pub rw_counter_start: usize,
+    /// Number of read-write records of the copy
+    pub rw_counter_delta: u64,
+    /// Copied bytes
+    pub bytes: Bytes,
+}
+
+/// Exponentiation event of the exponentiation circuit
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpEventDump {
+    /// Identifier of the event, the read-write counter of its step
+    pub identifier: usize,
+    /// Base
+    pub base: Word,
+    /// Exponent
+    pub exponent: Word,
+    /// Result of the exponentiation
+    pub exponentiation: Word,
+}
+
+/// Human readable dump of a witness [`Block`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessDump {
+    /// Transactions
+    pub txs: Vec<TxDump>,
+    /// Last EndBlock step
+    pub end_block: StepDump,
+    /// Copy events
+    pub copy_events: Vec<CopyEventDump>,
+    /// Exponentiation events
+    pub exp_events: Vec<ExpEventDump>,
+}
+
+/// Field in which two witness dumps differ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WitnessDiff {
+    /// Path to the field, e.g. `txs[0].steps[3].gas_left`
+    pub path: String,
+    /// Value in the first dump, `None` when it's absent
+    pub left: Option<Value>,
+    /// Value in the second dump, `None` when it's absent
+    pub right: Option<Value>,
+}
+
+impl fmt::Display for WitnessDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "<absent>".to_string(),
+        };
+        write!(
+            f,
+            "{}: {} != {}",
+            self.path,
+            show(&self.left),
+            show(&self.right)
+        )
+    }
+}
+
+impl WitnessDump {
+    /// Dump a witness block.
+    pub fn new<F: Field>(block: &Block<F>) -> Self {
+        let dump_step = |step: &ExecStep| StepDump {
+            execution_state: format!("{:?}", ExecutionState::from(step)),
+            rw_counter: step.rwc.0,
+            call_index: step.call_index,
+            pc: step.pc,
+            stack_size: step.stack_size,
+            memory_size: step.memory_size,
+            gas_left: step.gas_left,
+            gas_cost: step.gas_cost,
+            reversible_write_counter: step.reversible_write_counter,
+            log_id: step.log_id,
+            error: step.error.as_ref().map(|error| format!("{:?}", error)),
+            rws: (0..step.bus_mapping_instance.len())
+                .map(|index| RwDump::from(&block.get_rws(step, index)))
+                .collect(),
+        };
+
+        Self {
+            txs: block
+                .txs
+                .iter()
+                .map(|tx| TxDump {
+                    id: tx.id,
+                    nonce: tx.nonce.as_u64(),
+                    gas: tx.gas(),
+                    gas_price: tx.gas_price,
+                    caller_address: tx.from,
+                    callee_address: tx.to_or_contract_addr(),
+                    is_create: tx.is_create(),
+                    value: tx.value,
+                    call_data: tx.call_data.clone(),
+                    call_data_gas_cost: tx.call_data_gas_cost(),
+                    steps: tx.steps().iter().map(dump_step).collect(),
+                })
+                .collect(),
+            end_block: dump_step(&block.end_block_last),
+            copy_events: block
+                .copy_events
+                .iter()
+                .map(|event| CopyEventDump {
+                    src_type: format!("{:?}", event.src_type),
+                    src_id: format!("{:?}", event.src_id),
+                    src_addr: event.src_addr,
+                    src_addr_end: event.src_addr_end,
+                    dst_type: format!("{:?}", event.dst_type),
+                    dst_id: format!("{:?}", event.dst_id),
+                    dst_addr: event.dst_addr,
+                    log_id: event.log_id,
+                    rw_counter_start: event.rw_counter_start.0,
+                    rw_counter_delta: event.rw_counter_delta(),
+                    bytes: event
+                        .bytes
+                        .iter()
+                        .map(|(byte, _)| *byte)
+                        .collect::<Vec<_>>()
+                        .into(),
+                })
+                .collect(),
+            exp_events: block
+                .exp_events
+                .iter()
+                .map(|event| ExpEventDump {
+                    identifier: event.identifier,
+                    base: event.base,
+                    exponent: event.exponent,
+                    exponentiation: event.exponentiation,
+                })
+                .collect(),
+        }
+    }
+
+    /// Reads a dump file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WitnessDumpError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the dump to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), WitnessDumpError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Returns the fields in which `self` and `other` differ: those of the
+    /// transactions first, step by step, then those of the copy and
+    /// exponentiation events and of the EndBlock step.  The first difference
+    /// is usually the cause of the following ones.
+    pub fn diff(&self, other: &Self) -> Vec<WitnessDiff> {
+        fn value<T: Serialize>(section: &T) -> Value {
+            serde_json::to_value(section).expect("the dump serializes")
+        }
+
+        let mut diffs = Vec::new();
+        for (path, left, right) in [
+            ("txs", value(&self.txs), value(&other.txs)),
+            (
+                "copy_events",
+                value(&self.copy_events),
+                value(&other.copy_events),
+            ),
+            (
+                "exp_events",
+                value(&self.exp_events),
+                value(&other.exp_events),
+            ),
+            ("end_block", value(&self.end_block), value(&other.end_block)),
+        ] {
+            diff_values(path.to_string(), &left, &right, &mut diffs);
+        }
+        diffs
+    }
+}
+
+fn diff_values(path: String, left: &Value, right: &Value, diffs: &mut Vec<WitnessDiff>) {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            let keys: BTreeSet<_> = left.keys().chain(right.keys()).collect();
+            for key in keys {
+                let path = format!("{}.{}", path, key);
+                match (left.get(key), right.get(key)) {
+                    (Some(left), Some(right)) => diff_values(path, left, right, diffs),
+                    (left, right) => diffs.push(WitnessDiff {
+                        path,
+                        left: left.cloned(),
+                        right: right.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for index in 0..left.len().max(right.len()) {
+                let path = format!("{}[{}]", path, index);
+                match (left.get(index), right.get(index)) {
+                    (Some(left), Some(right)) => diff_values(path, left, right, diffs),
+                    (left, right) => diffs.push(WitnessDiff {
+                        path,
+                        left: left.cloned(),
+                        right: right.cloned(),
+                    }),
+                }
+            }
+        }
+        (left, right) if left != right => diffs.push(WitnessDiff {
+            path,
+            left: Some(left.clone()),
+            right: Some(right.clone()),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::TestContext;
+
+    #[test]
+    fn witness_dump_and_diff() {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! {
+            PUSH1(0x02)
+            PUSH1(0x03)
+            EXP
+            STOP
+        })
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let dump = WitnessDump::new(&block_convert::<Fr>(&builder).unwrap());
+
+        assert_eq!(
+            dump.txs[0]
+                .steps
+                .iter()
+                .map(|step| step.execution_state.as_str())
+                .collect::<Vec<_>>(),
+            ["BeginTx", "PUSH", "PUSH", "EXP", "STOP", "EndTx"]
+        );
+        // The first PUSH writes 0x02 at the top of the stack of the root call
+        let push = &dump.txs[0].steps[1].rws[0];
+        assert_eq!(
+            (push.tag.as_str(), push.is_write, push.id, push.address),
+            ("Stack", true, Some(1), Some(Word::from(1023)))
+        );
+        assert_eq!((push.value, push.value_prev), (Word::from(2), None));
+        assert_eq!(dump.exp_events.len(), 1);
+        let json = serde_json::to_string(&dump).unwrap();
+        assert_eq!(serde_json::from_str::<WitnessDump>(&json).unwrap(), dump);
+        assert!(dump.diff(&dump).is_empty());
+
+        let mut other = dump.clone();
+        other.txs[0].steps[3].gas_left += 1;
+        other.txs[0].steps.pop();
+        other.exp_events[0].exponent = Word::from(4);
+        let end_tx = serde_json::to_value(&dump.txs[0].steps[5]).unwrap();
+        assert_eq!(
+            dump.diff(&other)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                format!(
+                    "txs[0].steps[3].gas_left: {} != {}",
+                    dump.txs[0].steps[3].gas_left,
+                    dump.txs[0].steps[3].gas_left + 1
+                ),
+                format!("txs[0].steps[5]: {} != <absent>", end_tx),
+                "exp_events[0].exponent: \"0x3\" != \"0x4\"".to_string(),
+            ]
+        );
+    }
+}
@@ -225,13 +225,14 @@ pub enum Rw {
         stack_pointer: usize,
         value: Word,
     },
-    /// Memory
+    /// Memory word at a 32-byte aligned address
     Memory {
         rw_counter: usize,
         is_write: bool,
         call_id: usize,
         memory_address: u64,
-        byte: u8,
+        value: Word,
+        value_prev: Word,
     },
     /// TxLog
     TxLog {
@@ -447,9 +448,11 @@ impl Rw {
         }
     }
 
-    pub(crate) fn memory_value(&self) -> u8 {
+    pub(crate) fn memory_word_pair(&self) -> (Word, Word) {
         match self {
-            Self::Memory { byte, .. } => *byte,
+            Self::Memory {
+                value, value_prev, ..
+            } => (*value, *value_prev),
             _ => unreachable!(),
         }
     }
@@ -607,19 +610,19 @@ impl Rw {
             | Self::Account { value, .. }
             | Self::AccountStorage { value, .. }
             | Self::Stack { value, .. }
+            | Self::Memory { value, .. }
             | Self::TxLog { value, .. } => *value,
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => U256::from(*is_warm as u64),
-            Self::Memory { byte, .. } => U256::from(u64::from(*byte)),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => U256::from(*value),
         }
     }
 
     pub(crate) fn value_prev_assignment(&self) -> Option<Word> {
         match self {
-            Self::Account { value_prev, .. } | Self::AccountStorage { value_prev, .. } => {
-                Some(*value_prev)
-            }
+            Self::Account { value_prev, .. }
+            | Self::AccountStorage { value_prev, .. }
+            | Self::Memory { value_prev, .. } => Some(*value_prev),
             Self::TxAccessListAccount { is_warm_prev, .. }
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(U256::from(*is_warm_prev as u64))
@@ -627,14 +630,13 @@ impl Rw {
             Self::TxRefund { value_prev, .. } => Some(U256::from(*value_prev)),
             Self::Start { .. }
             | Self::Stack { .. }
-            | Self::Memory { .. }
             | Self::CallContext { .. }
             | Self::TxLog { .. }
             | Self::TxReceipt { .. } => None,
         }
     }
 
-    fn committed_value_assignment(&self) -> Option<Word> {
+    pub(crate) fn committed_value_assignment(&self) -> Option<Word> {
         match self {
             Self::AccountStorage {
                 committed_value, ..
@@ -813,7 +815,8 @@ impl From<&operation::OperationContainer> for RwMap {
                     memory_address: u64::from_le_bytes(
                         op.op().address().to_le_bytes()[..8].try_into().unwrap(),
                     ),
-                    byte: op.op().value(),
+                    value: *op.op().value(),
+                    value_prev: *op.op().value_prev(),
                 })
                 .collect(),
         );